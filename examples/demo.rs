@@ -76,7 +76,7 @@ fn main() -> Result<(), LvError> {
             i = 0;
         }
         let val = CString::new(format!("21:{:02}", i)).unwrap();
-        let _ = time.set_text(&val);
+        let _ = time.set_text(val.as_c_str());
         i = 1 + i;
 
         lvgl::task_handler();
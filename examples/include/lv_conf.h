@@ -85,10 +85,14 @@
 
 /*Use a custom tick source that tells the elapsed time in milliseconds.
  *It removes the need to manually update the tick with `lv_tick_inc()`)*/
-#define LV_TICK_CUSTOM 0
+#ifndef LV_TICK_CUSTOM
+    #define LV_TICK_CUSTOM 0
+#endif
 #if LV_TICK_CUSTOM
-    #define LV_TICK_CUSTOM_INCLUDE <rs_timer.h>             /*Header for the system time function*/
-    #define LV_TICK_CUSTOM_SYS_TIME_EXPR (rs_lv_timer())    /*Expression evaluating to current system time in ms*/
+    #ifndef LV_TICK_CUSTOM_INCLUDE
+        #define LV_TICK_CUSTOM_INCLUDE <rs_timer.h>             /*Header for the system time function*/
+        #define LV_TICK_CUSTOM_SYS_TIME_EXPR (rs_lv_timer())    /*Expression evaluating to current system time in ms*/
+    #endif
     /*If using lvgl as ESP32 component*/
     // #define LV_TICK_CUSTOM_INCLUDE "esp_timer.h"
     // #define LV_TICK_CUSTOM_SYS_TIME_EXPR ((esp_timer_get_time() / 1000LL))
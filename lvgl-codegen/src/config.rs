@@ -0,0 +1,150 @@
+use crate::CGResult;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Tuning of the generated wrappers, usually read from a `codegen.toml`:
+///
+/// ```toml
+/// [functions]
+/// # Glob patterns of functions not to wrap, and of functions to wrap even if
+/// # excluded, or left out by default as unsafe to call on a wrapped object
+/// exclude = ["lv_label_*"]
+/// include = ["lv_label_set_text", "lv_obj_clean"]
+///
+/// # C types mapped to Rust types they are cast from and to with `as`
+/// [types]
+/// lv_coord_t = "i16"
+///
+/// # Rust names of widgets, by their LVGL 8 names
+/// [widgets.rename]
+/// btnmatrix = "ButtonMatrix"
+/// img = "Image"
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub functions: FunctionFilter,
+    pub types: HashMap<String, String>,
+    pub widgets: WidgetConfig,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FunctionFilter {
+    /// Functions generated even though they match `exclude`, or are left
+    /// out by default, e.g. `lv_obj_clean()`.
+    pub include: Vec<Glob>,
+    /// Functions not to generate.
+    pub exclude: Vec<Glob>,
+}
+
+/// A pattern of function names, in which `*` stands for any text.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "String")]
+pub struct Glob(Regex);
+
+impl Glob {
+    pub fn new(pattern: &str) -> Self {
+        let regex = format!(
+            "^{}$",
+            pattern
+                .split('*')
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join(".*")
+        );
+        Self(Regex::new(&regex).expect("escaped patterns are valid regexes"))
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.0.is_match(name)
+    }
+}
+
+impl From<String> for Glob {
+    fn from(pattern: String) -> Self {
+        Self::new(&pattern)
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WidgetConfig {
+    pub rename: HashMap<String, String>,
+}
+
+impl Config {
+    pub fn parse(toml: &str) -> CGResult<Self> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> CGResult<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Whether `function` matches an `exclude` pattern, but no `include`
+    /// pattern.
+    pub fn is_excluded(&self, function: &str) -> bool {
+        let matches = |patterns: &[Glob]| patterns.iter().any(|p| p.matches(function));
+        matches(&self.functions.exclude) && !self.is_included(function)
+    }
+
+    /// Whether `function` matches an `include` pattern.
+    pub fn is_included(&self, function: &str) -> bool {
+        self.functions.include.iter().any(|p| p.matches(function))
+    }
+
+    /// The Rust type a C type is mapped to, if any. Whitespace is ignored,
+    /// so `*const lv_style_t` matches bindgen's `* const lv_style_t`.
+    pub fn rust_type(&self, c_type: &str) -> Option<&str> {
+        let strip = |s: &str| s.split_whitespace().collect::<String>();
+        let c_type = strip(c_type);
+        self.types
+            .iter()
+            .find(|(c, _)| strip(c) == c_type)
+            .map(|(_, rust)| rust.as_str())
+    }
+
+    /// The Rust name configured for a widget, if any.
+    pub fn widget_name(&self, compat_name: &str) -> Option<&str> {
+        self.widgets.rename.get(compat_name).map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = Config::parse(
+            r#"
+            [functions]
+            exclude = ["lv_label_*", "lv_arc_set_angles"]
+            include = ["lv_label_set_text"]
+
+            [types]
+            "*const lv_color_t" = "*const u16"
+
+            [widgets.rename]
+            img = "Image"
+            "#,
+        )
+        .unwrap();
+
+        assert!(config.is_excluded("lv_label_set_recolor"));
+        assert!(config.is_excluded("lv_arc_set_angles"));
+        assert!(!config.is_excluded("lv_label_set_text"));
+        assert!(!config.is_excluded("lv_arc_set_angles2"));
+        assert!(config.is_included("lv_label_set_text"));
+        assert!(!config.is_included("lv_label_set_recolor"));
+        assert!(Glob::new("lv_*.*").matches("lv_a.b"));
+        assert!(!Glob::new("lv_*.*").matches("lv_ab"));
+        assert_eq!(config.rust_type("* const lv_color_t"), Some("*const u16"));
+        assert_eq!(config.widget_name("img"), Some("Image"));
+        assert!(Config::parse("[function]").is_err());
+    }
+}
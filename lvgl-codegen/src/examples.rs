@@ -0,0 +1,146 @@
+//! Usage examples attached to the rustdoc of generated widgets.
+//!
+//! Each template is the body of a code block. `{name}` is replaced with the
+//! Rust name of the widget and `{var}` with a variable name for it. Widgets
+//! without a template of their own get `GENERIC`, which only creates them.
+//!
+//! The examples are compiled as doctests, within a function returning
+//! `LvResult` that is given the `display`.
+
+const GENERIC: &str = "let mut {var} = {name}::create(&mut screen)?;";
+
+const TEMPLATES: &[(&str, &str)] = &[
+    (
+        "arc",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_value_f32(0.75);",
+    ),
+    (
+        "bar",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_value(40, AnimationState::ON);",
+    ),
+    (
+        "btn",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.on_event(|_, event| {
+    if let Event::Clicked = event {
+        // ...
+    }
+})?;",
+    ),
+    (
+        "canvas",
+        "static mut BUFFER: CanvasBuffer<{ 64 * 64 }> = CanvasBuffer::new();
+
+let mut {var} = {name}::create(&mut screen)?;
+{var}.set_buffer(unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) }, 64, 64)?;
+{var}.fill_bg(Color::from_rgb((0, 0, 0)), Opacity::OPA_COVER);",
+    ),
+    (
+        "chart",
+        "let mut {var} = {name}::create(&mut screen)?;
+let series = {var}.add_series(Color::from_rgb((255, 0, 0)), ChartAxis::PrimaryY)?;
+{var}.set_next_value_f32(&series, 0.5)?;",
+    ),
+    (
+        "dropdown",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_options_iter([\"Low\", \"Medium\", \"High\"])?;",
+    ),
+    (
+        "label",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_text(\"Hello, world!\");",
+    ),
+    (
+        "menu",
+        "let mut {var} = {name}::create(&mut screen)?;
+let mut root = {var}.create_page(None)?;
+let wifi = {var}.create_page(Some(cstr_core::cstr!(\"Wi-Fi\")))?;
+let item = {name}::create_item(&mut root)?;
+{var}.set_load_page_event(&item, &wifi);
+{var}.set_page(Some(&root));",
+    ),
+    (
+        "meter",
+        "let mut {var} = {name}::create(&mut screen)?;
+let scale = {var}.add_scale()?;
+{var}.set_scale_range(&scale, 0, 100, 270, 135)?;
+let needle = {var}.add_needle_line(&scale, 4, Color::from_rgb((0, 0, 0)), -10)?;
+{var}.set_needle_value(&needle, 42)?;",
+    ),
+    (
+        "roller",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_options(cstr_core::cstr!(\"00\\n15\\n30\\n45\"), RollerMode::Infinite);
+{var}.set_selected(2, AnimationState::OFF);",
+    ),
+    (
+        "slider",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_value_f32(0.5, AnimationState::OFF);",
+    ),
+    (
+        "table",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_rows([[\"Time\", \"Temperature\"], [\"12:00\", \"21.5 °C\"]])?;",
+    ),
+    (
+        "tileview",
+        "let mut {var} = {name}::create(&mut screen)?;
+let home = {var}.add_tile(0, 0, Direction::RIGHT)?;
+let details = {var}.add_tile(1, 0, Direction::LEFT)?;
+{var}.set_tile(&details, AnimationState::ON);",
+    ),
+    (
+        "textarea",
+        "let mut {var} = {name}::create(&mut screen)?;
+{var}.set_one_line(true);
+{var}.set_placeholder_text(\"Name\");
+{var}.on_event(|{var}, event| {
+    if let Event::Ready = event {
+        let name = {var}.get_text();
+        // ...
+    }
+})?;",
+    ),
+];
+
+/// Hidden lines setting up the function an example is compiled in.
+const PROLOGUE: &str = "# use lvgl::style::Opacity;
+# use lvgl::widgets::*;
+# use lvgl::*;
+# fn example(display: &Display) -> LvResult<()> {
+";
+
+const EPILOGUE: &str = "# Ok(())
+# }
+";
+
+/// Returns the rustdoc example section for a widget.
+pub fn example_for(widget: &str, rust_name: &str) -> String {
+    let template = TEMPLATES
+        .iter()
+        .find(|(name, _)| *name == widget)
+        .map_or(GENERIC, |(_, template)| template);
+    let body = template
+        .replace("{name}", rust_name)
+        .replace("{var}", widget);
+    format!("# Example\n\n```no_run\n{PROLOGUE}let mut screen = display.get_scr_act()?;\n{body}\n{EPILOGUE}```")
+}
+
+#[cfg(test)]
+mod test {
+    use super::example_for;
+
+    #[test]
+    fn fills_in_template() {
+        let doc = example_for("slider", "Slider");
+        assert!(doc.starts_with("# Example\n\n```no_run\n# use lvgl::style::Opacity;\n"));
+        assert!(doc.ends_with("# Ok(())\n# }\n```"));
+        assert!(doc.contains("let mut slider = Slider::create(&mut screen)?;"));
+        assert!(doc.contains("slider.set_value_f32("));
+        assert!(example_for("led", "Led").contains("let mut led = Led::create"));
+    }
+}
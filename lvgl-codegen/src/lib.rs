@@ -1,4 +1,8 @@
 mod analysis;
+mod config;
+mod examples;
+
+pub use config::{Config, FunctionFilter, Glob, WidgetConfig};
 
 use inflector::cases::pascalcase::to_pascal_case;
 use lazy_static::lazy_static;
@@ -14,6 +18,73 @@ type CGResult<T> = Result<T, Box<dyn Error>>;
 
 const LIB_PREFIX: &str = "lv_";
 
+/// Widgets renamed in LVGL 9, mapped to their LVGL 8 names so the generated
+/// wrappers keep the same Rust names across versions.
+const V9_WIDGET_RENAMES: &[(&str, &str)] = &[
+    ("animimage", "animimg"),
+    ("button", "btn"),
+    ("buttonmatrix", "btnmatrix"),
+    ("image", "img"),
+    ("imagebutton", "imgbtn"),
+];
+
+/// Widgets whose LVGL class derives from another widget's class than
+/// `lv_obj_class`, by their LVGL 8 names, e.g. `lv_keyboard_class` is based
+/// on `lv_btnmatrix_class`.
+const BASE_CLASSES: &[(&str, &str)] = &[
+    ("animimg", "img"),
+    ("canvas", "img"),
+    ("gif", "img"),
+    ("keyboard", "btnmatrix"),
+    ("qrcode", "canvas"),
+    ("slider", "bar"),
+    ("spinbox", "textarea"),
+    ("spinner", "arc"),
+];
+
+/// `lv_obj_*` functions not generated as `Widget` methods unless included in
+/// the `Config`, as they delete
+/// objects wrappers may still point to, remove the event callbacks the
+/// wrappers free their data in, or are internals of LVGL's object classes.
+const UNSAFE_OBJ_FUNCTIONS: &[&str] = &[
+    "lv_obj_allocate_spec_attr",
+    "lv_obj_class_init_obj",
+    "lv_obj_clean",
+    "lv_obj_del",
+    "lv_obj_del_async",
+    "lv_obj_del_delayed",
+    "lv_obj_enable_style_refresh",
+    "lv_obj_remove_event_cb",
+    "lv_obj_remove_event_cb_with_user_data",
+    "lv_obj_remove_event_dsc",
+];
+
+/// Pointer arguments LVGL accepts NULL for, by function. Their documentation
+/// doesn't say so reliably, e.g. for the icon of `lv_list_add_btn()`.
+const NULLABLE_ARGS: &[(&str, &str)] = &[
+    ("lv_dropdown_set_symbol", "symbol"),
+    ("lv_dropdown_set_text", "txt"),
+    ("lv_label_set_text", "text"),
+    ("lv_list_add_btn", "icon"),
+    ("lv_list_add_btn", "txt"),
+    ("lv_menu_page_create", "title"),
+    ("lv_msgbox_create", "title"),
+    ("lv_msgbox_create", "btn_txts"),
+];
+
+/// Functions taking a pointer and a length, see `LvFunc::slices()`, that
+/// only read the elements during the call. LVGL may keep referencing the
+/// slices passed to any other function, so those have to be `'static`.
+const COPIED_SLICES: &[&str] = &["lv_canvas_draw_line", "lv_canvas_draw_polygon"];
+
+/// String arguments LVGL keeps referencing after the call, by function,
+/// besides those of the `*_static` variants of setters. Their documentation
+/// can't be relied on to say so.
+const KEPT_STRINGS: &[(&str, &str)] = &[
+    ("lv_dropdown_set_text", "txt"),
+    ("lv_textarea_set_accepted_chars", "list"),
+];
+
 lazy_static! {
     static ref TYPE_MAPPINGS: HashMap<&'static str, &'static str> = [
         ("u16", "u16"),
@@ -25,11 +96,89 @@ lazy_static! {
     .iter()
     .cloned()
     .collect();
+
+    /// Integer typedefs taken by `lv_obj_*` functions, the Rust types the
+    /// generated `Widget` methods take for them, and how those convert back.
+    /// Widget functions taking these mostly have hand-written wrappers, so
+    /// they are only mapped for `lv_obj_*`.
+    static ref OBJ_TYPE_MAPPINGS: HashMap<&'static str, (&'static str, Conversion)> = [
+        ("lv_coord_t", ("i16", Conversion::Cast)),
+        ("lv_obj_flag_t", ("u32", Conversion::Cast)),
+        ("lv_state_t", ("u16", Conversion::Cast)),
+        ("lv_align_t", ("crate::Align", Conversion::Into)),
+        ("lv_anim_enable_t", ("crate::AnimationState", Conversion::Into)),
+        ("lv_dir_t", ("crate::Direction", Conversion::Into)),
+        ("u32", ("u32", Conversion::None)),
+    ]
+    .iter()
+    .cloned()
+    .collect();
 }
 
+/// How a Rust argument is turned into the C type of an `lv_obj_*` function.
 #[derive(Debug, Copy, Clone)]
+enum Conversion {
+    None,
+    /// `as` the C typedef
+    Cast,
+    /// `into()` the C typedef
+    Into,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WrapperError {
-    Skip,
+    Skip(SkipReason),
+}
+
+/// Why no wrapper was generated for a function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// Not a method of a widget that is created with just a parent.
+    NotAWidgetMethod,
+    /// Wrapped by hand in the `lvgl` crate.
+    HandWritten,
+    /// Takes an argument of a type with no Rust mapping.
+    UnknownArgType(String),
+    /// Returns a type with no Rust mapping.
+    UnknownReturnType(String),
+    /// Takes a callback.
+    Callback(String),
+    /// Excluded in the `Config`.
+    Excluded,
+    /// Deletes objects or is internal to LVGL, so has no safe wrapper.
+    Unsafe,
+}
+
+impl SkipReason {
+    /// A short identifier of the reason, as used in the skip report.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SkipReason::NotAWidgetMethod => "not_a_widget_method",
+            SkipReason::HandWritten => "hand_written",
+            SkipReason::UnknownArgType(_) => "unknown_arg_type",
+            SkipReason::UnknownReturnType(_) => "unknown_return_type",
+            SkipReason::Callback(_) => "callback",
+            SkipReason::Excluded => "excluded",
+            SkipReason::Unsafe => "unsafe",
+        }
+    }
+
+    /// The C type that caused the function to be skipped, if any.
+    pub fn c_type(&self) -> Option<&str> {
+        match self {
+            SkipReason::UnknownArgType(t)
+            | SkipReason::UnknownReturnType(t)
+            | SkipReason::Callback(t) => Some(t),
+            _ => None,
+        }
+    }
+}
+
+/// A function no wrapper was generated for, see `CodeGen::skipped()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Skipped {
+    pub function: String,
+    pub reason: SkipReason,
 }
 
 pub type WrapperResult<T> = Result<T, WrapperError>;
@@ -44,11 +193,37 @@ pub trait Rusty {
 pub struct LvWidget {
     name: String,
     methods: Vec<LvFunc>,
+    /// The name of the Rust wrapper, if renamed in the `Config`.
+    rust_name: Option<String>,
 }
 
 impl LvWidget {
+    /// The LVGL name of the widget, e.g. `arc` for `lv_arc_*`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The LVGL 8 name of the widget, which the Rust wrapper is named after.
+    /// Differs from `name()` only for widgets renamed in LVGL 9, e.g. `img`
+    /// for `lv_image_*`.
+    pub fn compat_name(&self) -> &str {
+        V9_WIDGET_RENAMES
+            .iter()
+            .find(|(v9, _)| *v9 == self.name)
+            .map_or(&self.name, |(_, v8)| v8)
+    }
+
+    /// The cargo feature the generated wrapper is gated on, e.g.
+    /// `widget_arc`.
+    pub fn feature(&self) -> String {
+        format!("widget_{}", self.compat_name())
+    }
+
     fn pascal_name(&self) -> String {
-        to_pascal_case(&self.name)
+        match &self.rust_name {
+            Some(name) => name.clone(),
+            None => to_pascal_case(self.compat_name()),
+        }
     }
 }
 
@@ -58,17 +233,45 @@ impl Rusty for LvWidget {
     fn code(&self, _parent: &Self::Parent) -> WrapperResult<TokenStream> {
         // We don't generate for the generic Obj
         if self.name.as_str().eq("obj") {
-            return Err(WrapperError::Skip);
+            return Err(WrapperError::Skip(SkipReason::HandWritten));
         }
 
         let widget_name = format_ident!("{}", self.pascal_name());
+        let feature = self.feature();
+        let doc = format!(
+            "The LVGL `lv_{}` widget.\n\n{}",
+            self.name,
+            examples::example_for(self.compat_name(), &self.pascal_name())
+        );
         let methods: Vec<TokenStream> = self.methods.iter().flat_map(|m| m.code(self)).collect();
+        // Renamed widgets keep their default name, which the hand-written
+        // wrappers use, as an alias
+        let default_name = to_pascal_case(self.compat_name());
+        let alias = (widget_name != default_name).then(|| {
+            let alias = format_ident!("{}", default_name);
+            let doc = format!("The default name of `{widget_name}`.");
+            quote! {
+                #[cfg(feature = #feature)]
+                #[cfg_attr(docsrs, doc(cfg(feature = #feature)))]
+                #[doc = #doc]
+                pub type #alias<'a> = #widget_name<'a>;
+            }
+        });
         Ok(quote! {
-            define_object!(#widget_name);
-
+            #[cfg(feature = #feature)]
+            define_object!(
+                #[doc = #doc]
+                #[cfg_attr(docsrs, doc(cfg(feature = #feature)))]
+                #widget_name
+            );
+
+            #[cfg(feature = #feature)]
+            #[cfg_attr(docsrs, doc(cfg(feature = #feature)))]
             impl<'a> #widget_name<'a> {
                 #(#methods)*
             }
+
+            #alias
         })
     }
 }
@@ -78,11 +281,109 @@ pub struct LvFunc {
     name: String,
     args: Vec<LvArg>,
     ret: Option<LvType>,
+    docs: Vec<String>,
 }
 
 impl LvFunc {
-    pub fn new(name: String, args: Vec<LvArg>, ret: Option<LvType>) -> Self {
-        Self { name, args, ret }
+    pub fn new(name: String, mut args: Vec<LvArg>, ret: Option<LvType>) -> Self {
+        for arg in &mut args {
+            arg.nullable = NULLABLE_ARGS
+                .iter()
+                .any(|(f, a)| *f == name && arg.is_named(a));
+        }
+        Self {
+            name,
+            args,
+            ret,
+            docs: Vec::new(),
+        }
+    }
+
+    /// Sets the lines of the Doxygen comment of the C function, as found in
+    /// the `#[doc]` attributes bindgen generates.
+    pub fn with_docs(mut self, docs: Vec<String>) -> Self {
+        self.docs = docs;
+        self
+    }
+
+    /// The argument a `@param` documents.
+    fn arg_named(&self, doc_name: &str) -> Option<&LvArg> {
+        self.args.iter().find(|arg| arg.is_named(doc_name))
+    }
+
+    /// The indices of pointer arguments directly followed by the number of
+    /// elements they point to, like `points` and `point_num` of
+    /// `lv_line_set_points()`, and of those lengths.
+    fn slices(&self) -> Vec<(usize, usize)> {
+        self.args
+            .windows(2)
+            .enumerate()
+            .skip(1)
+            .filter(|(_, pair)| pair[0].typ.slice_element().is_some() && pair[1].is_length())
+            .map(|(i, _)| (i, i + 1))
+            .collect()
+    }
+
+    /// Whether LVGL keeps referencing `arg` after the call, i.e. it is listed
+    /// in `KEPT_STRINGS`, or is a string passed to a `*_static` variant of a
+    /// setter, e.g. `lv_label_set_text_static()`.
+    fn keeps_reference(&self, arg: &LvArg) -> bool {
+        if !arg.typ.is_str() {
+            return false;
+        }
+        self.name.ends_with("_static")
+            || KEPT_STRINGS
+                .iter()
+                .any(|(f, a)| *f == self.name && arg.is_named(a))
+    }
+
+    /// Converts the Doxygen comment of the C function to rustdoc. `@param`s
+    /// are listed under the Rust names of the arguments (bindgen appends `_`
+    /// to keywords), leaving out the first one, which is the object the
+    /// method is called on.
+    fn rust_doc(&self) -> Option<String> {
+        let DocComment {
+            mut description,
+            params,
+            returns,
+        } = DocComment::parse(&self.docs);
+        while description.last().is_some_and(|l| l.is_empty()) {
+            description.pop();
+        }
+        let mut doc = description.join("\n");
+        let params: Vec<String> = params
+            .iter()
+            .filter(|(name, _)| self.args.first().map(|obj| &obj.name) != Some(name))
+            .map(|(name, text)| {
+                let name = self
+                    .arg_named(name)
+                    .map_or(name.clone(), |arg| arg.get_name_ident().to_string());
+                format!("* `{name}` - {text}")
+            })
+            .collect();
+        if !params.is_empty() {
+            doc.push_str("\n\n# Arguments\n\n");
+            doc.push_str(&params.join("\n"));
+        }
+        if let Some(returns) = returns.filter(|r| !r.is_empty()) {
+            doc.push_str("\n\n# Returns\n\n");
+            doc.push_str(&returns);
+        }
+        let doc = doc.trim_start_matches('\n');
+        (!doc.is_empty()).then(|| doc.to_string())
+    }
+
+    /// The name of the method generated for `parent`, e.g. `set_value` for
+    /// `lv_bar_set_value()`.
+    fn method_name(&self, parent: &LvWidget) -> String {
+        let templ = format!("{}{}_", LIB_PREFIX, parent.name.as_str());
+        self.name.replace(templ.as_str(), "")
+    }
+
+    /// Whether this is an `lv_obj_*` function, generated as a method of the
+    /// `Widget` trait.
+    pub fn is_obj_func(&self) -> bool {
+        self.name.starts_with("lv_obj_")
     }
 
     pub fn is_method(&self) -> bool {
@@ -94,14 +395,82 @@ impl LvFunc {
     }
 }
 
+/// The parts of a Doxygen comment the wrappers are documented with.
+struct DocComment {
+    description: Vec<String>,
+    /// `@param`s by name
+    params: Vec<(String, String)>,
+    returns: Option<String>,
+}
+
+impl DocComment {
+    fn parse(lines: &[String]) -> Self {
+        enum Section {
+            Description,
+            Param,
+            Return,
+        }
+
+        let mut description: Vec<String> = Vec::new();
+        let mut params: Vec<(String, String)> = Vec::new();
+        let mut returns: Option<String> = None;
+        let mut section = Section::Description;
+        // bindgen emits a comment as one attribute, spanning several lines
+        for line in lines.iter().flat_map(|l| l.lines()).map(str::trim) {
+            if let Some(param) = line.strip_prefix("@param") {
+                let param = param.trim();
+                let (name, text) = param.split_once(char::is_whitespace).unwrap_or((param, ""));
+                params.push((name.to_string(), text.trim().to_string()));
+                section = Section::Param;
+            } else if let Some(ret) = line.strip_prefix("@return") {
+                returns = Some(ret.trim_start_matches('s').trim().to_string());
+                section = Section::Return;
+            } else if let Some(note) = line.strip_prefix("@note") {
+                description.push(String::new());
+                description.push(format!("Note: {}", note.trim()));
+                section = Section::Description;
+            } else if line.is_empty() {
+                section = Section::Description;
+                if description.last().is_some_and(|l| !l.is_empty()) {
+                    description.push(String::new());
+                }
+            } else {
+                let continued = match section {
+                    Section::Description => {
+                        description.push(line.to_string());
+                        continue;
+                    }
+                    Section::Param => params.last_mut().map(|(_, text)| text),
+                    Section::Return => returns.as_mut(),
+                };
+                if let Some(text) = continued {
+                    text.push(' ');
+                    text.push_str(line);
+                }
+            }
+        }
+        Self {
+            description,
+            params,
+            returns,
+        }
+    }
+}
+
 impl Rusty for LvFunc {
     type Parent = LvWidget;
 
     fn code(&self, parent: &Self::Parent) -> WrapperResult<TokenStream> {
-        let templ = format!("{}{}_", LIB_PREFIX, parent.name.as_str());
-        let new_name = self.name.replace(templ.as_str(), "");
+        let new_name = self.method_name(parent);
         let func_name = format_ident!("{}", new_name);
         let original_func_name = format_ident!("{}", self.name.as_str());
+        // Methods of the generic Obj are default methods of the `Widget` trait
+        let is_obj = parent.name.as_str().eq("obj");
+
+        // `Obj` has a hand-written constructor
+        if is_obj && new_name.as_str().eq("create") {
+            return Err(WrapperError::Skip(SkipReason::HandWritten));
+        }
 
         // generate constructor
         if new_name.as_str().eq("create") {
@@ -126,6 +495,28 @@ impl Rusty for LvFunc {
                     Self::create(&mut parent)
                 }
 
+                /// Creates the widget at a position relative to `parent`,
+                /// with a size.
+                pub fn create_at(
+                    parent: &mut impl crate::NativeObject,
+                    x: i16,
+                    y: i16,
+                    w: i16,
+                    h: i16,
+                ) -> crate::LvResult<Self> {
+                    let mut widget = Self::create(parent)?;
+                    crate::Widget::set_pos(&mut widget, x, y);
+                    crate::Widget::set_size(&mut widget, w, h);
+                    Ok(widget)
+                }
+
+                /// Creates the widget, to be set up with chained calls.
+                pub fn builder(
+                    parent: &mut impl crate::NativeObject,
+                ) -> crate::LvResult<crate::Builder<Self>> {
+                    Ok(crate::Builder::new(Self::create(parent)?))
+                }
+
             });
         }
 
@@ -134,6 +525,12 @@ impl Rusty for LvFunc {
             // function returns void
             None => quote!(()),
             // function returns something
+            Some(ref ret) if ret.mapping.is_some() => ret
+                .mapped_type()
+                .map_err(|_| {
+                    WrapperError::Skip(SkipReason::UnknownReturnType(ret.literal_name.clone()))
+                })?
+                .unwrap(),
             _ => {
                 let return_value: &LvType = self.ret.as_ref().unwrap();
                 match return_value.literal_name.as_str() {
@@ -144,14 +541,25 @@ impl Rusty for LvFunc {
                     "i16" => quote!(i16),
                     "u8" => quote!(u8),
                     "i8" => quote!(i8),
-                    _ => return Err(WrapperError::Skip)
+                    other => {
+                        return Err(WrapperError::Skip(SkipReason::UnknownReturnType(
+                            other.to_string(),
+                        )))
+                    }
                 }
             }
         };
 
+        // Pointer and length pairs are taken as one slice
+        let slices = self.slices();
+        let slice_of_len = |i: usize| slices.iter().find(|(_, len)| *len == i).map(|(p, _)| *p);
+        let is_slice = |i: usize| slices.iter().any(|(ptr, _)| *ptr == i);
+
         // Make sure all arguments can be generated, skip the first arg (self)!
-        for arg in self.args.iter().skip(1) {
-            arg.code(self)?;
+        for (i, arg) in self.args.iter().enumerate().skip(1) {
+            if !is_slice(i) && slice_of_len(i).is_none() {
+                arg.code(self)?;
+            }
         }
 
         // Generate the arguments being passed into the Rust 'wrapper'
@@ -169,6 +577,11 @@ impl Rusty for LvFunc {
                     } else {
                         quote!(&mut self)
                     }
+                } else if is_slice(arg_idx) {
+                    arg.slice_code(!COPIED_SLICES.contains(&self.name.as_str()))
+                } else if slice_of_len(arg_idx).is_some() {
+                    // Given by the length of the slice
+                    return args_accumulator;
                 } else {
                     arg.code(self).unwrap()
                 };
@@ -192,7 +605,7 @@ impl Rusty for LvFunc {
                 let next_arg = if i == 0 {
                     quote!()
                 } else {
-                    let var = arg.get_processing();
+                    let var = arg.get_processing(self.keeps_reference(arg));
                     quote!(#var)
                 };
                 if args.is_empty() {
@@ -218,9 +631,17 @@ impl Rusty for LvFunc {
             .enumerate()
             .fold(quote!(), |args_accumulator, (arg_idx, arg)| {
                 let next_arg = if arg_idx == 0 {
-                    quote!(self.core.raw().as_mut())
+                    if is_obj {
+                        quote!(self.raw().as_mut())
+                    } else {
+                        quote!(self.core.raw().as_mut())
+                    }
+                } else if let Some(slice) = slice_of_len(arg_idx) {
+                    let slice = self.args[slice].get_name_ident();
+                    let len_type = format_ident!("{}", arg.typ.literal_name);
+                    quote!(#slice.len() as #len_type)
                 } else {
-                    let var = arg.get_value_usage();
+                    let var = arg.get_value_usage(self.keeps_reference(arg));
                     quote!(#var)
                 };
 
@@ -245,11 +666,16 @@ impl Rusty for LvFunc {
         // Otherwise we should remove it
         let optional_semicolon= match self.ret {
             None => quote!(;),
+            Some(ref ret) if ret.mapping.is_some() => quote!(as #return_type),
             _ => quote!()
         };
 
+        let visibility = if is_obj { quote!() } else { quote!(pub) };
+        let doc = self.rust_doc().map(|doc| quote!(#[doc = #doc]));
+
         Ok(quote! {
-            pub fn #func_name(#args_decl) -> #return_type {
+            #doc
+            #visibility fn #func_name(#args_decl) -> #return_type {
                 #args_processing
                 unsafe {
                     lvgl_sys::#original_func_name(#ffi_args)#optional_semicolon
@@ -284,6 +710,21 @@ impl From<ForeignItemFn> for LvFunc {
                 .collect::<Vec<LvArg>>(),
             ret,
         )
+        .with_docs(
+            ffi.attrs
+                .iter()
+                .filter_map(|attr| match &attr.meta {
+                    syn::Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                        syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(line),
+                            ..
+                        }) => Some(line.value()),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect(),
+        )
     }
 }
 
@@ -291,6 +732,8 @@ impl From<ForeignItemFn> for LvFunc {
 pub struct LvArg {
     name: String,
     typ: LvType,
+    /// Listed in `NULLABLE_ARGS`.
+    nullable: bool,
 }
 
 impl From<syn::PatType> for LvArg {
@@ -301,7 +744,51 @@ impl From<syn::PatType> for LvArg {
 
 impl LvArg {
     pub fn new(name: String, typ: LvType) -> Self {
-        Self { name, typ }
+        Self {
+            name,
+            typ,
+            nullable: false,
+        }
+    }
+
+    /// Whether `doc_name` names this argument in the C documentation.
+    /// bindgen appends `_` to names that are Rust keywords.
+    fn is_named(&self, doc_name: &str) -> bool {
+        self.name == doc_name || self.name.strip_suffix('_') == Some(doc_name)
+    }
+
+    /// Whether this is the number of elements of the preceding pointer, see
+    /// `LvFunc::slices()`.
+    fn is_length(&self) -> bool {
+        lazy_static! {
+            static ref LENGTH_NAME: Regex = Regex::new("(^|_)(num|cnt|count|len|size)$").unwrap();
+        }
+        ["u8", "u16", "u32", "i32", "usize"].contains(&self.typ.literal_name.as_str())
+            && LENGTH_NAME.is_match(&self.name)
+    }
+
+    /// The slice parameter for a pointer and a length argument. Slices LVGL
+    /// may keep referencing have to be `'static`.
+    fn slice_code(&self, keeps_reference: bool) -> TokenStream {
+        let name = self.get_name_ident();
+        let element = self.typ.slice_element().unwrap();
+        let lifetime = if keeps_reference {
+            quote!('static)
+        } else {
+            quote!()
+        };
+        if self.typ.literal_name.starts_with("* const") {
+            quote!(#name: &#lifetime [#element])
+        } else {
+            quote!(#name: &#lifetime mut [#element])
+        }
+    }
+
+    /// Strings LVGL accepts NULL for are taken as
+    /// `impl IntoOptionalText`, which accepts `None` as well, or as
+    /// `impl Into<Option<&'static CStr>>` if LVGL keeps them.
+    fn is_optional_str(&self) -> bool {
+        self.nullable && self.typ.is_str() && self.typ.mapping.is_none()
     }
 
     pub fn get_name_ident(&self) -> Ident {
@@ -310,17 +797,58 @@ impl LvArg {
             .unwrap_or_else(|_| format_ident!("r#{}", self.name.as_str()))
     }
 
-    pub fn get_processing(&self) -> TokenStream {
+    /// Strings LVGL copies are taken as `impl IntoText`, so that `&str` can
+    /// be passed as well as `&CStr`, and converted before the call.
+    fn is_text(&self, keeps_reference: bool) -> bool {
+        self.typ.is_str() && self.typ.mapping.is_none() && !keeps_reference
+    }
+
+    pub fn get_processing(&self, keeps_reference: bool) -> TokenStream {
         // TODO: A better way to handle this, instead of `is_sometype()`, is using the Rust
         //       type system itself.
 
+        if self.is_text(keeps_reference) {
+            let ident = self.get_name_ident();
+            return if self.is_optional_str() {
+                quote!(let #ident = crate::IntoOptionalText::into_optional_text(#ident);)
+            } else {
+                quote!(let #ident = crate::IntoText::into_text(#ident);)
+            };
+        }
+        if self.is_optional_str() {
+            let ident = self.get_name_ident();
+            return quote! {
+                let #ident: Option<&cstr_core::CStr> = #ident.into();
+            };
+        }
+
         // No need to pre-process this type of argument
         quote! {}
     }
 
-    pub fn get_value_usage(&self) -> TokenStream {
+    pub fn get_value_usage(&self, keeps_reference: bool) -> TokenStream {
         let ident = self.get_name_ident();
-        if self.typ.is_str() {
+        if self.typ.mapping.is_some() {
+            quote!(#ident as _)
+        } else if let Some((_, conversion)) = OBJ_TYPE_MAPPINGS.get(self.typ.literal_name.as_str())
+        {
+            let typedef = format_ident!("{}", self.typ.literal_name);
+            match conversion {
+                Conversion::None => quote!(#ident),
+                Conversion::Cast => quote!(#ident as lvgl_sys::#typedef),
+                Conversion::Into => quote!(#ident.into()),
+            }
+        } else if self.is_optional_str() && keeps_reference {
+            quote! {
+                #ident.map_or(core::ptr::null(), |s| s.as_ptr())
+            }
+        } else if self.typ.slice_element().is_some() {
+            if self.typ.literal_name.starts_with("* const") {
+                quote!(#ident.as_ptr())
+            } else {
+                quote!(#ident.as_mut_ptr())
+            }
+        } else if self.typ.is_str() {
             quote! {
                 #ident.as_ptr()
             }
@@ -339,9 +867,28 @@ impl LvArg {
 impl Rusty for LvArg {
     type Parent = LvFunc;
 
-    fn code(&self, _parent: &Self::Parent) -> WrapperResult<TokenStream> {
+    fn code(&self, parent: &Self::Parent) -> WrapperResult<TokenStream> {
         let name = self.get_name_ident();
-        let typ = self.typ.code(self)?;
+        let obj_mapping = OBJ_TYPE_MAPPINGS
+            .get(self.typ.literal_name.as_str())
+            .filter(|_| parent.is_obj_func() && self.typ.mapping.is_none());
+        let typ = match obj_mapping {
+            Some((rust_type, _)) => syn::parse_str::<syn::Type>(rust_type)
+                .map_err(|_| {
+                    WrapperError::Skip(SkipReason::UnknownArgType(self.typ.literal_name.clone()))
+                })?
+                .to_token_stream(),
+            // Strings LVGL keeps referencing have to be `'static`
+            None if self.is_optional_str() && parent.keeps_reference(self) => {
+                quote!(impl Into<Option<&'static cstr_core::CStr>>)
+            }
+            None if self.is_optional_str() => quote!(impl crate::IntoOptionalText),
+            None if self.is_text(false) && parent.keeps_reference(self) => {
+                quote!(&'static cstr_core::CStr)
+            }
+            None if self.is_text(false) => quote!(impl crate::IntoText),
+            None => self.typ.code(self)?,
+        };
         Ok(quote! {
             #name: #typ
         })
@@ -352,6 +899,8 @@ impl Rusty for LvArg {
 pub struct LvType {
     literal_name: String,
     _r_type: Option<Box<syn::Type>>,
+    /// The Rust type mapped to in the `Config`, converted with `as`.
+    mapping: Option<String>,
 }
 
 impl LvType {
@@ -359,6 +908,7 @@ impl LvType {
         Self {
             literal_name,
             _r_type: None,
+            mapping: None,
         }
     }
 
@@ -366,6 +916,19 @@ impl LvType {
         Self {
             literal_name: r_type.to_token_stream().to_string(),
             _r_type: Some(r_type),
+            mapping: None,
+        }
+    }
+
+    /// The Rust type this is mapped to in the `Config`, if any.
+    fn mapped_type(&self) -> WrapperResult<Option<TokenStream>> {
+        match &self.mapping {
+            Some(rust_type) => syn::parse_str::<syn::Type>(rust_type)
+                .map(|t| Some(t.to_token_stream()))
+                .map_err(|_| {
+                    WrapperError::Skip(SkipReason::UnknownArgType(self.literal_name.clone()))
+                }),
+            None => Ok(None),
         }
     }
 
@@ -376,12 +939,39 @@ impl LvType {
     pub fn is_str(&self) -> bool {
         self.literal_name.ends_with("* const cty :: c_char")
     }
+
+    /// The element type, if this is a pointer to primitives or LVGL types
+    /// that can be passed as a slice.
+    fn slice_element(&self) -> Option<TokenStream> {
+        let element = self
+            .literal_name
+            .strip_prefix("* const ")
+            .or_else(|| self.literal_name.strip_prefix("* mut "))?;
+        if ["u8", "i8", "u16", "i16", "u32", "i32"].contains(&element) {
+            let element = format_ident!("{}", element);
+            Some(quote!(#element))
+        } else if element.starts_with("lv_") && !element.contains(' ') && element != "lv_obj_t" {
+            let element = format_ident!("{}", element);
+            Some(quote!(lvgl_sys::#element))
+        } else {
+            None
+        }
+    }
+
+    /// Whether this is a function pointer, or a typedef of one like
+    /// `lv_event_cb_t`.
+    pub fn is_callback(&self) -> bool {
+        self.literal_name.ends_with("_cb_t") || self.literal_name.contains("extern")
+    }
 }
 
 impl Rusty for LvType {
     type Parent = LvArg;
 
     fn code(&self, _parent: &Self::Parent) -> WrapperResult<TokenStream> {
+        if let Some(mapped) = self.mapped_type()? {
+            return Ok(mapped);
+        }
         match TYPE_MAPPINGS.get(self.literal_name.as_str()) {
             Some(name) => {
                 let val = if self.is_str() {
@@ -397,7 +987,12 @@ impl Rusty for LvType {
                     #val
                 })
             }
-            None => Err(WrapperError::Skip),
+            None if self.is_callback() => Err(WrapperError::Skip(SkipReason::Callback(
+                self.literal_name.clone(),
+            ))),
+            None => Err(WrapperError::Skip(SkipReason::UnknownArgType(
+                self.literal_name.clone(),
+            ))),
         }
     }
 }
@@ -408,22 +1003,212 @@ impl From<Box<syn::Type>> for LvType {
     }
 }
 
+/// Version of the LVGL library the bindings were generated from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LvglVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
 pub struct CodeGen {
     functions: Vec<LvFunc>,
     widgets: Vec<LvWidget>,
+    version: Option<LvglVersion>,
+    config: Config,
 }
 
 impl CodeGen {
     pub fn from(code: &str) -> CGResult<Self> {
         let functions = Self::load_func_defs(code)?;
         let widgets = Self::extract_widgets(&functions)?;
-        Ok(Self { functions, widgets })
+        let version = Self::detect_version(code)?;
+        Ok(Self {
+            functions,
+            widgets,
+            version,
+            config: Config::default(),
+        })
+    }
+
+    /// Applies the function filters, type mappings and widget renames of
+    /// `config`.
+    pub fn with_config(mut self, config: Config) -> Self {
+        let map_types = |f: &mut LvFunc| {
+            for typ in f.args.iter_mut().map(|a| &mut a.typ).chain(f.ret.as_mut()) {
+                typ.mapping = config.rust_type(&typ.literal_name).map(String::from);
+            }
+        };
+        self.functions.iter_mut().for_each(map_types);
+        for widget in &mut self.widgets {
+            widget.rust_name = config.widget_name(widget.compat_name()).map(String::from);
+            widget.methods.retain(|m| !config.is_excluded(&m.name));
+            widget.methods.iter_mut().for_each(map_types);
+        }
+        self.config = config;
+        self
     }
 
     pub fn get_widgets(&self) -> &Vec<LvWidget> {
         &self.widgets
     }
 
+    /// Generates the `lv_obj_*` functions as default methods of the `Widget`
+    /// trait, so every widget gets them. Methods named in `hand_written` are
+    /// left out, as the trait already implements them, and so are functions
+    /// that can't be called safely on a wrapped object, e.g. `lv_obj_clean()`.
+    pub fn widget_trait_methods(&self, hand_written: &[&str]) -> TokenStream {
+        let Some(obj) = self.widgets.iter().find(|w| w.name == "obj") else {
+            return quote!();
+        };
+        let methods = obj
+            .methods
+            .iter()
+            .filter(|m| !hand_written.contains(&m.name.trim_start_matches("lv_obj_")))
+            .filter(|m| {
+                !UNSAFE_OBJ_FUNCTIONS.contains(&m.name.as_str()) || self.config.is_included(&m.name)
+            })
+            .flat_map(|m| m.code(obj));
+        quote! {
+            #(#methods)*
+        }
+    }
+
+    /// Generates `Deref` and `AsRef` implementations from each widget to
+    /// the widget its LVGL class is based on, so that e.g. a `Keyboard` can
+    /// be passed where a `&Btnmatrix` is expected. Only widgets generated on
+    /// both ends are related.
+    ///
+    /// The generated methods of the base widgets, e.g. `Btnmatrix`, are
+    /// forwarded to the derived ones, unless they define a method of the
+    /// same name. `hand_written` are the names of the methods implemented by
+    /// hand, by the LVGL 8 name of the widget. A `&mut` to the base widget
+    /// is never handed out, as it could be swapped with one of another class.
+    pub fn inheritance_code(&self, hand_written: &HashMap<String, Vec<String>>) -> TokenStream {
+        let widget = |name: &str| self.widgets.iter().find(|w| w.compat_name() == name);
+        let base_of = |w: &LvWidget| {
+            BASE_CLASSES
+                .iter()
+                .find(|(derived, _)| *derived == w.compat_name())
+                .and_then(|(_, base)| widget(base))
+        };
+        let impls = self.widgets.iter().filter_map(|derived| {
+            let base = base_of(derived)?;
+            let derived_name = format_ident!("{}", derived.pascal_name());
+            let base_name = format_ident!("{}", base.pascal_name());
+            let derived_feature = derived.feature();
+            let base_feature = base.feature();
+
+            let mut taken: Vec<String> = derived
+                .methods
+                .iter()
+                .map(|m| m.method_name(derived))
+                .chain(hand_written.get(derived.compat_name()).into_iter().flatten().cloned())
+                .collect();
+            // Every base up the chain, e.g. `Canvas` and `Img` for `Qrcode`,
+            // with the features all the widgets in between are gated on
+            let mut forwarded = Vec::new();
+            let mut features = vec![derived_feature.clone()];
+            let mut next = Some(base);
+            while let Some(base) = next {
+                features.push(base.feature());
+                let methods: Vec<TokenStream> = base
+                    .methods
+                    .iter()
+                    .filter(|m| {
+                        let name = m.method_name(base);
+                        name != "create" && !taken.contains(&name)
+                    })
+                    .flat_map(|m| m.code(base))
+                    .collect();
+                taken.extend(base.methods.iter().map(|m| m.method_name(base)));
+                forwarded.push(quote! {
+                    #[cfg(all(#(feature = #features),*))]
+                    impl<'a> #derived_name<'a> {
+                        #(#methods)*
+                    }
+                });
+                next = base_of(base);
+            }
+
+            Some(quote! {
+                #[cfg(all(feature = #derived_feature, feature = #base_feature))]
+                impl<'a> core::ops::Deref for #derived_name<'a> {
+                    type Target = #base_name<'a>;
+
+                    fn deref(&self) -> &Self::Target {
+                        // Widgets are `#[repr(transparent)]` wrappers of an `Obj`
+                        unsafe { &*(self as *const Self as *const Self::Target) }
+                    }
+                }
+
+                #[cfg(all(feature = #derived_feature, feature = #base_feature))]
+                impl<'a> AsRef<#base_name<'a>> for #derived_name<'a> {
+                    fn as_ref(&self) -> &#base_name<'a> {
+                        self
+                    }
+                }
+
+                #(#forwarded)*
+            })
+        });
+        quote! {
+            #(#impls)*
+        }
+    }
+
+    /// Returns a Markdown table of the generated widgets and the cargo
+    /// features enabling them, sorted by name.
+    pub fn feature_matrix(&self) -> String {
+        let mut rows: Vec<(String, String)> = self
+            .widgets
+            .iter()
+            .filter(|w| w.name != "obj")
+            .map(|w| (w.pascal_name(), w.feature()))
+            .collect();
+        rows.sort();
+        let mut table = String::from("| Widget | Feature |\n|---|---|\n");
+        for (name, feature) in rows {
+            table.push_str(&format!("| [`{name}`]({name}) | `{feature}` |\n"));
+        }
+        table
+    }
+
+    /// The LVGL version found in the bindings, if they include `lvgl.h`'s
+    /// version defines.
+    pub fn version(&self) -> Option<LvglVersion> {
+        self.version
+    }
+
+    fn detect_version(bindgen_code: &str) -> CGResult<Option<LvglVersion>> {
+        let ast: syn::File = syn::parse_str(bindgen_code)?;
+        let mut version = [None; 3];
+        for item in ast.items {
+            let Item::Const(c) = item else { continue };
+            let idx = match c.ident.to_string().as_str() {
+                "LVGL_VERSION_MAJOR" => 0,
+                "LVGL_VERSION_MINOR" => 1,
+                "LVGL_VERSION_PATCH" => 2,
+                _ => continue,
+            };
+            if let syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(n),
+                ..
+            }) = *c.expr
+            {
+                version[idx] = Some(n.base10_parse()?);
+            }
+        }
+        Ok(match version {
+            [Some(major), Some(minor), Some(patch)] => Some(LvglVersion {
+                major,
+                minor,
+                patch,
+            }),
+            _ => None,
+        })
+    }
+
     fn extract_widgets(functions: &[LvFunc]) -> CGResult<Vec<LvWidget>> {
         let widget_names = Self::get_widget_names(functions);
 
@@ -437,6 +1222,7 @@ impl CodeGen {
                         .or_insert_with(|| LvWidget {
                             name: widget_name.clone(),
                             methods: Vec::new(),
+                            rust_name: None,
                         })
                         .methods
                         .push(f.clone())
@@ -498,12 +1284,144 @@ impl CodeGen {
     pub fn get_function_names(&self) -> CGResult<Vec<String>> {
         Ok(self.functions.iter().map(|f| f.name.clone()).collect())
     }
+
+    /// Returns every `lv_` function no wrapper is generated for, and why,
+    /// sorted by name. `hand_written` are the `Widget` methods left out of
+    /// `widget_trait_methods()`.
+    pub fn skipped(&self, hand_written: &[&str]) -> Vec<Skipped> {
+        let mut skipped: Vec<Skipped> = self
+            .functions
+            .iter()
+            .filter_map(|f| {
+                // Functions may be picked up by several widgets sharing a
+                // prefix, e.g. `lv_btn_` and `lv_btnmatrix_`
+                let owners: Vec<&LvWidget> = self
+                    .widgets
+                    .iter()
+                    .filter(|w| w.methods.iter().any(|m| m.name == f.name))
+                    .collect();
+                let reason = if self.config.is_excluded(&f.name) {
+                    SkipReason::Excluded
+                } else if owners.is_empty() {
+                    SkipReason::NotAWidgetMethod
+                } else if owners.iter().any(|w| w.name == "obj")
+                    && hand_written.contains(&f.name.trim_start_matches("lv_obj_"))
+                {
+                    SkipReason::HandWritten
+                } else if UNSAFE_OBJ_FUNCTIONS.contains(&f.name.as_str())
+                    && !self.config.is_included(&f.name)
+                {
+                    SkipReason::Unsafe
+                } else if owners.iter().any(|w| f.code(w).is_ok()) {
+                    return None;
+                } else {
+                    match f.code(owners[0]) {
+                        Err(WrapperError::Skip(reason)) => reason,
+                        Ok(_) => return None,
+                    }
+                };
+                Some(Skipped {
+                    function: f.name.clone(),
+                    reason,
+                })
+            })
+            .collect();
+        skipped.sort_by(|a, b| a.function.cmp(&b.function));
+        skipped
+    }
+
+    /// Returns `skipped()` as a JSON report, along with the number of
+    /// functions found and the LVGL version, to track the coverage of the
+    /// wrappers across LVGL releases.
+    pub fn skip_report(&self, hand_written: &[&str]) -> String {
+        let skipped = self.skipped(hand_written);
+        let version = match self.version {
+            Some(v) => format!("\"{}.{}.{}\"", v.major, v.minor, v.patch),
+            None => "null".to_string(),
+        };
+        let entries: Vec<String> = skipped
+            .iter()
+            .map(|s| {
+                let c_type = match s.reason.c_type() {
+                    Some(t) => format!(", \"type\": \"{}\"", json_escape(t)),
+                    None => String::new(),
+                };
+                format!(
+                    "    {{ \"function\": \"{}\", \"reason\": \"{}\"{} }}",
+                    json_escape(&s.function),
+                    s.reason.kind(),
+                    c_type
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"lvgl_version\": {},\n  \"functions\": {},\n  \"generated\": {},\n  \"skipped\": [\n{}\n  ]\n}}\n",
+            version,
+            self.functions.len(),
+            self.functions.len() - skipped.len(),
+            entries.join(",\n")
+        )
+    }
+}
+
+/// Returns the names of the methods of the trait `trait_name` in the Rust
+/// `source`, e.g. the `Widget` methods to pass as `hand_written` to
+/// `CodeGen::widget_trait_methods()`.
+pub fn trait_method_names(source: &str, trait_name: &str) -> CGResult<Vec<String>> {
+    let file = syn::parse_file(source)?;
+    Ok(file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Trait(t) if t.ident == trait_name => Some(t),
+            _ => None,
+        })
+        .flat_map(|t| &t.items)
+        .filter_map(|item| match item {
+            syn::TraitItem::Fn(f) => Some(f.sig.ident.to_string()),
+            _ => None,
+        })
+        .collect())
+}
+
+/// Returns the names of the methods in the inherent `impl` blocks of Rust
+/// `source`, e.g. of a hand-written widget module, see
+/// `CodeGen::inheritance_code()`.
+pub fn inherent_method_names(source: &str) -> CGResult<Vec<String>> {
+    let file = syn::parse_file(source)?;
+    Ok(file
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            Item::Impl(i) if i.trait_.is_none() => Some(i),
+            _ => None,
+        })
+        .flat_map(|i| &i.items)
+        .filter_map(|item| match item {
+            syn::ImplItem::Fn(f) => Some(f.sig.ident.to_string()),
+            _ => None,
+        })
+        .collect())
+}
+
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 #[cfg(test)]
 mod test {
-    use crate::{CodeGen, LvArg, LvFunc, LvType, LvWidget, Rusty};
+    use crate::{examples, CodeGen, Config, LvArg, LvFunc, LvType, LvWidget, Rusty, SkipReason};
     use quote::quote;
+    use std::collections::HashMap;
 
     #[test]
     fn can_load_bindgen_fns() {
@@ -573,6 +1491,24 @@ mod test {
         assert_eq!(widget_names.len(), 3);
     }
 
+    #[test]
+    fn only_compiled_in_widgets_are_extracted() {
+        // Bindings of an lv_conf.h with LV_USE_BAR disabled lack lv_bar_*
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_arc_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_arc_set_bg_end_angle(arc: *mut lv_obj_t, end: u16);
+                pub fn lv_label_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+            }
+        };
+
+        let cg = CodeGen::from(bindgen_code.to_string().as_str()).unwrap();
+        let mut names: Vec<&str> = cg.get_widgets().iter().map(|w| w.name()).collect();
+        names.sort();
+
+        assert_eq!(names, vec!["arc", "label"]);
+    }
+
     #[test]
     fn generate_method_wrapper() {
         // pub fn lv_arc_set_bg_end_angle(arc: *mut lv_obj_t, end: u16);
@@ -587,6 +1523,7 @@ mod test {
         let arc_widget = LvWidget {
             name: "arc".to_string(),
             methods: vec![],
+            rust_name: None,
         };
 
         let code = arc_set_bg_end_angle.code(&arc_widget).unwrap();
@@ -617,22 +1554,202 @@ mod test {
         let parent_widget = LvWidget {
             name: "label".to_string(),
             methods: vec![],
+            rust_name: None,
         };
 
         let code = label_set_text.code(&parent_widget).unwrap();
+        let doc = "Set a new text for a label. Memory will be allocated to store the text by the label.\n\n\
+                   # Arguments\n\n\
+                   * `text` - '\\0' terminated character string. NULL to refresh with the current text.";
+        let expected_code = quote! {
+            #[doc = #doc]
+
+            pub fn set_text(&mut self, text: impl crate::IntoOptionalText) -> () {
+                let text = crate::IntoOptionalText::into_optional_text(text);
+                unsafe {
+                    lvgl_sys::lv_label_set_text(self.core.raw().as_mut(), text.as_ptr());
+                }
+            }
+
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn non_nullable_str_arguments_stay_required() {
+        let bindgen_code = quote! {
+            extern "C" {
+                #[doc = " Insert a text to a label."]
+                #[doc = " @param obj pointer to a label object"]
+                #[doc = " @param txt pointer to the text to insert, must not be NULL"]
+                pub fn lv_label_ins_text(obj: *mut lv_obj_t, txt: *const cty::c_char);
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let label = LvWidget {
+            name: "label".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        let code = cg[0].code(&label).unwrap();
+        let doc = "Insert a text to a label.\n\n\
+                   # Arguments\n\n\
+                   * `txt` - pointer to the text to insert, must not be NULL";
+        let expected_code = quote! {
+            #[doc = #doc]
+            pub fn ins_text(&mut self, txt: impl crate::IntoText) -> () {
+                let txt = crate::IntoText::into_text(txt);
+                unsafe {
+                    lvgl_sys::lv_label_ins_text(self.core.raw().as_mut(), txt.as_ptr());
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn listed_str_arguments_are_optional() {
+        let bindgen_code = quote! {
+            extern "C" {
+                #[doc = " Set a new text for a label. Memory will be allocated to store the text by the label."]
+                #[doc = " @param obj pointer to a label object"]
+                #[doc = " @param text the text, NULL to refresh with the current text"]
+                pub fn lv_label_set_text(obj: *mut lv_obj_t, text: *const cty::c_char);
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let label = LvWidget {
+            name: "label".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        let code = cg[0].code(&label).unwrap();
+        let doc = "Set a new text for a label. Memory will be allocated to store the text by the label.\n\n\
+                   # Arguments\n\n\
+                   * `text` - the text, NULL to refresh with the current text";
         let expected_code = quote! {
+            #[doc = #doc]
+            pub fn set_text(&mut self, text: impl crate::IntoOptionalText) -> () {
+                let text = crate::IntoOptionalText::into_optional_text(text);
+                unsafe {
+                    lvgl_sys::lv_label_set_text(self.core.raw().as_mut(), text.as_ptr());
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
 
-            pub fn set_text(&mut self, text: &cstr_core::CStr) -> () {
+    #[test]
+    fn strings_kept_by_lvgl_are_static() {
+        let bindgen_code = quote! {
+            extern "C" {
+                #[doc = " Set a new text for a label. Memory will be allocated to store the text by the label."]
+                #[doc = " @param obj pointer to a label object"]
+                #[doc = " @param text pointer to a text. NOT copied by the label so the text must be alive while the label is alive."]
+                pub fn lv_label_set_text_static(obj: *mut lv_obj_t, text: *const cty::c_char);
+                #[doc = " Set the options in a drop-down list from a string."]
+                #[doc = " @param obj pointer to drop-down list object"]
+                #[doc = " @param options options separated with new lines"]
+                pub fn lv_dropdown_set_options_static(obj: *mut lv_obj_t, options: *const cty::c_char);
+                #[doc = " Set text of the drop-down list's button."]
+                #[doc = " @param obj pointer to a drop-down list object"]
+                #[doc = " @param txt the text as a string"]
+                pub fn lv_dropdown_set_text(obj: *mut lv_obj_t, txt: *const cty::c_char);
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let widget = |name: &str| LvWidget {
+            name: name.to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        let label = cg[0].code(&widget("label")).unwrap().to_string();
+        let expected = quote!(text: &'static cstr_core::CStr).to_string();
+        assert!(label.contains(&expected), "{label}");
+        let dropdown = cg[1].code(&widget("dropdown")).unwrap().to_string();
+        let expected = quote!(options: &'static cstr_core::CStr).to_string();
+        assert!(dropdown.contains(&expected), "{dropdown}");
+        // Listed in `KEPT_STRINGS`, whatever its documentation says
+        let dropdown = cg[2].code(&widget("dropdown")).unwrap().to_string();
+        let expected = quote!(txt: impl Into<Option<&'static cstr_core::CStr>>).to_string();
+        assert!(dropdown.contains(&expected), "{dropdown}");
+    }
+
+    #[test]
+    fn pointer_and_length_become_a_slice() {
+        let bindgen_code = quote! {
+            extern "C" {
+                #[doc = " Highlight the selected days"]
+                #[doc = " @param obj pointer to a calendar object"]
+                #[doc = " @param highlighted an array of dates. Only the pointer will be saved so this variable can't be local which will be destroyed later."]
+                #[doc = " @param date_num number of dates in the array"]
+                pub fn lv_calendar_set_highlighted_dates(obj: *mut lv_obj_t, highlighted: *mut lv_calendar_date_t, date_num: u16);
+                pub fn lv_calendar_sum(obj: *mut lv_obj_t, values: *const i32, len: u32) -> i32;
+                pub fn lv_canvas_draw_line(obj: *mut lv_obj_t, points: *const lv_point_t, point_cnt: u32);
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let calendar = LvWidget {
+            name: "calendar".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        let code = cg[0].code(&calendar).unwrap();
+        let doc = "Highlight the selected days\n\n\
+                   # Arguments\n\n\
+                   * `highlighted` - an array of dates. Only the pointer will be saved so this variable can't be local which will be destroyed later.\n\
+                   * `date_num` - number of dates in the array";
+        let expected_code = quote! {
+            #[doc = #doc]
+            pub fn set_highlighted_dates(
+                &mut self,
+                highlighted: &'static mut [lvgl_sys::lv_calendar_date_t]
+            ) -> () {
                 unsafe {
-                    lvgl_sys::lv_label_set_text(
+                    lvgl_sys::lv_calendar_set_highlighted_dates(
                         self.core.raw().as_mut(),
-                        text.as_ptr()
+                        highlighted.as_mut_ptr(),
+                        highlighted.len() as u16
                     );
                 }
             }
+        };
+        assert_eq!(code.to_string(), expected_code.to_string());
 
+        let code = cg[1].code(&calendar).unwrap();
+        let expected_code = quote! {
+            pub fn sum(&mut self, values: &'static [i32]) -> i32 {
+                unsafe {
+                    lvgl_sys::lv_calendar_sum(self.core.raw().as_mut(), values.as_ptr(), values.len() as u32)
+                }
+            }
         };
+        assert_eq!(code.to_string(), expected_code.to_string());
 
+        let canvas = LvWidget {
+            name: "canvas".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+        let code = cg[2].code(&canvas).unwrap();
+        let expected_code = quote! {
+            pub fn draw_line(&mut self, points: &[lvgl_sys::lv_point_t]) -> () {
+                unsafe {
+                    lvgl_sys::lv_canvas_draw_line(
+                        self.core.raw().as_mut(),
+                        points.as_ptr(),
+                        points.len() as u32
+                    );
+                }
+            }
+        };
         assert_eq!(code.to_string(), expected_code.to_string());
     }
 
@@ -652,16 +1769,90 @@ mod test {
         let parent_widget = LvWidget {
             name: "label".to_string(),
             methods: vec![],
+            rust_name: None,
         };
 
         let code = label_set_text.code(&parent_widget).unwrap();
+        let doc = "Set a new text for a label. Memory will be allocated to store the text by the label.\n\n\
+                   # Arguments\n\n\
+                   * `text` - '\\0' terminated character string. NULL to refresh with the current text.";
         let expected_code = quote! {
-            pub fn set_text(&mut self, text: &cstr_core::CStr) -> () {
+            #[doc = #doc]
+            pub fn set_text(&mut self, text: impl crate::IntoOptionalText) -> () {
+                let text = crate::IntoOptionalText::into_optional_text(text);
                 unsafe {
-                    lvgl_sys::lv_label_set_text(
-                        self.core.raw().as_mut(),
-                        text.as_ptr()
-                    );
+                    lvgl_sys::lv_label_set_text(self.core.raw().as_mut(), text.as_ptr());
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn method_docs_are_converted_to_rustdoc() {
+        let bindgen_code = quote! {
+            extern "C" {
+                #[doc = " Get the type of an object."]
+                #[doc = " @param obj pointer to an object"]
+                #[doc = " @param type index of the class, counted"]
+                #[doc = "             from the base class"]
+                #[doc = " @return true if the object is of"]
+                #[doc = "         that class"]
+                pub fn lv_foo_has_class(obj: *mut lv_obj_t, type_: u8) -> bool;
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let foo = LvWidget {
+            name: "foo".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        let code = cg[0].code(&foo).unwrap();
+        let doc = "Get the type of an object.\n\n\
+                   # Arguments\n\n\
+                   * `type_` - index of the class, counted from the base class\n\n\
+                   # Returns\n\n\
+                   true if the object is of that class";
+        let expected_code = quote! {
+            #[doc = #doc]
+            pub fn has_class(&mut self, type_: u8) -> bool {
+                unsafe {
+                    lvgl_sys::lv_foo_has_class(self.core.raw().as_mut(), type_)
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn multi_line_doc_attributes_are_split() {
+        let bindgen_code = quote! {
+            extern "C" {
+                #[doc = " Get the type of an object.\n @param obj pointer to an object\n @param type index of the class, counted\n             from the base class\n @return true if the object is of\n         that class"]
+                pub fn lv_foo_has_class(obj: *mut lv_obj_t, type_: u8) -> bool;
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let foo = LvWidget {
+            name: "foo".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        let code = cg[0].code(&foo).unwrap();
+        let doc = "Get the type of an object.\n\n\
+                   # Arguments\n\n\
+                   * `type_` - index of the class, counted from the base class\n\n\
+                   # Returns\n\n\
+                   true if the object is of that class";
+        let expected_code = quote! {
+            #[doc = #doc]
+            pub fn has_class(&mut self, type_: u8) -> bool {
+                unsafe {
+                    lvgl_sys::lv_foo_has_class(self.core.raw().as_mut(), type_)
                 }
             }
         };
@@ -682,6 +1873,7 @@ mod test {
         let parent_widget = LvWidget {
             name: "label".to_string(),
             methods: vec![],
+            rust_name: None,
         };
 
         let code = label_get_recolor.code(&parent_widget).unwrap();
@@ -711,6 +1903,7 @@ mod test {
         let parent_widget = LvWidget {
             name: "label".to_string(),
             methods: vec![],
+            rust_name: None,
         };
 
         let code = label_get_text_selection_start.code(&parent_widget).unwrap();
@@ -727,17 +1920,283 @@ mod test {
         assert_eq!(code.to_string(), expected_code.to_string());
     }
 
+    #[test]
+    fn generate_widget_trait_methods() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_obj_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_obj_set_x(obj: *mut lv_obj_t, x: lv_coord_t);
+                pub fn lv_obj_set_pos(obj: *mut lv_obj_t, x: lv_coord_t, y: lv_coord_t);
+                pub fn lv_obj_align(obj: *mut lv_obj_t, align: lv_align_t, x_ofs: lv_coord_t, y_ofs: lv_coord_t);
+                pub fn lv_obj_add_flag(obj: *mut lv_obj_t, f: lv_obj_flag_t);
+                pub fn lv_obj_clean(obj: *mut lv_obj_t);
+                pub fn lv_obj_del_delayed(obj: *mut lv_obj_t, delay_ms: u32);
+            }
+        };
+        let cg = CodeGen::from(bindgen_code.to_string().as_str()).unwrap();
+
+        let code = cg.widget_trait_methods(&["set_pos"]);
+        let expected_code = quote! {
+            fn set_x(&mut self, x: i16) -> () {
+                unsafe {
+                    lvgl_sys::lv_obj_set_x(self.raw().as_mut(), x as lvgl_sys::lv_coord_t);
+                }
+            }
+            fn align(&mut self, align: crate::Align, x_ofs: i16, y_ofs: i16) -> () {
+                unsafe {
+                    lvgl_sys::lv_obj_align(
+                        self.raw().as_mut(),
+                        align.into(),
+                        x_ofs as lvgl_sys::lv_coord_t,
+                        y_ofs as lvgl_sys::lv_coord_t
+                    );
+                }
+            }
+            fn add_flag(&mut self, f: u32) -> () {
+                unsafe {
+                    lvgl_sys::lv_obj_add_flag(self.raw().as_mut(), f as lvgl_sys::lv_obj_flag_t);
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn included_functions_are_generated() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_obj_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_obj_clean(obj: *mut lv_obj_t);
+            }
+        };
+        let config = Config::parse(r#"functions.include = ["lv_obj_clean"]"#).unwrap();
+        let cg = CodeGen::from(bindgen_code.to_string().as_str())
+            .unwrap()
+            .with_config(config);
+
+        let code = cg.widget_trait_methods(&[]);
+        let expected_code = quote! {
+            fn clean(&mut self) -> () {
+                unsafe {
+                    lvgl_sys::lv_obj_clean(self.raw().as_mut());
+                }
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+        assert!(cg.skipped(&[]).iter().all(|s| s.function != "lv_obj_clean"));
+    }
+
+    #[test]
+    fn trait_method_names_are_found() {
+        let source = r#"
+            pub trait NativeObject {
+                fn raw(&self) -> u32;
+            }
+
+            pub trait Widget<'a>: NativeObject {
+                fn set_pos(&mut self, x: i16, y: i16) {}
+                unsafe fn del_raw(&mut self);
+                generated_widget_methods!();
+            }
+        "#;
+
+        assert_eq!(
+            crate::trait_method_names(source, "Widget").unwrap(),
+            vec!["set_pos", "del_raw"]
+        );
+    }
+
+    #[test]
+    fn obj_typedefs_are_skipped_for_widgets() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_spangroup_set_indent(obj: *mut lv_obj_t, indent: lv_coord_t);
+            }
+        };
+        let cg = CodeGen::load_func_defs(bindgen_code.to_string().as_str()).unwrap();
+        let spangroup = LvWidget {
+            name: "spangroup".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        assert!(cg[0].code(&spangroup).is_err());
+    }
+
+    #[test]
+    fn skipped_functions_are_reported() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_obj_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_obj_set_pos(obj: *mut lv_obj_t, x: lv_coord_t, y: lv_coord_t);
+                pub fn lv_arc_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_arc_set_angles(arc: *mut lv_obj_t, start: u16, end: u16);
+                pub fn lv_arc_get_style(arc: *mut lv_obj_t) -> *mut lv_style_t;
+                pub fn lv_arc_set_style(arc: *mut lv_obj_t, style: *const lv_style_t);
+                pub fn lv_arc_set_cb(arc: *mut lv_obj_t, cb: lv_event_cb_t);
+                pub fn lv_timer_del(timer: *mut lv_timer_t);
+            }
+        };
+        let cg = CodeGen::from(bindgen_code.to_string().as_str()).unwrap();
+
+        let skipped = cg.skipped(&["set_pos"]);
+        let reasons: Vec<(&str, SkipReason)> = skipped
+            .iter()
+            .map(|s| (s.function.as_str(), s.reason.clone()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("lv_arc_get_style", SkipReason::UnknownReturnType("* mut lv_style_t".into())),
+                ("lv_arc_set_cb", SkipReason::Callback("lv_event_cb_t".into())),
+                (
+                    "lv_arc_set_style",
+                    SkipReason::UnknownArgType("* const lv_style_t".into())
+                ),
+                ("lv_obj_create", SkipReason::HandWritten),
+                ("lv_obj_set_pos", SkipReason::HandWritten),
+                ("lv_timer_del", SkipReason::NotAWidgetMethod),
+            ]
+        );
+
+        let report = cg.skip_report(&["set_pos"]);
+        assert!(report.contains("\"functions\": 8,\n  \"generated\": 2,"));
+        assert!(report.contains(
+            "{ \"function\": \"lv_arc_set_cb\", \"reason\": \"callback\", \"type\": \"lv_event_cb_t\" }"
+        ));
+    }
+
+    #[test]
+    fn config_renames_maps_and_excludes() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_btnmatrix_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_btnmatrix_set_one_checked(obj: *mut lv_obj_t, en: bool);
+                pub fn lv_btnmatrix_get_selected_btn(obj: *const lv_obj_t) -> u16;
+                pub fn lv_btnmatrix_set_pad(obj: *mut lv_obj_t, pad: lv_coord_t) -> lv_coord_t;
+            }
+        };
+        let config = Config::parse(
+            r#"
+            functions.exclude = ["lv_btnmatrix_*"]
+            functions.include = ["lv_btnmatrix_create", "lv_btnmatrix_set_pad"]
+            types.lv_coord_t = "i16"
+            widgets.rename.btnmatrix = "ButtonMatrix"
+            "#,
+        )
+        .unwrap();
+        let cg = CodeGen::from(bindgen_code.to_string().as_str())
+            .unwrap()
+            .with_config(config);
+
+        let widget = &cg.get_widgets()[0];
+        assert_eq!(widget.pascal_name(), "ButtonMatrix");
+        let alias = quote! {
+            pub type Btnmatrix<'a> = ButtonMatrix<'a>;
+        };
+        assert!(widget
+            .code(&())
+            .unwrap()
+            .to_string()
+            .contains(&alias.to_string()));
+        assert_eq!(widget.methods.len(), 2);
+        let code = widget.methods[1].code(widget).unwrap();
+        let expected_code = quote! {
+            pub fn set_pad(&mut self, pad: i16) -> i16 {
+                unsafe {
+                    lvgl_sys::lv_btnmatrix_set_pad(self.core.raw().as_mut(), pad as _) as i16
+                }
+            }
+        };
+        assert_eq!(code.to_string(), expected_code.to_string());
+
+        let excluded: Vec<String> = cg
+            .skipped(&[])
+            .into_iter()
+            .filter(|s| s.reason == SkipReason::Excluded)
+            .map(|s| s.function)
+            .collect();
+        assert_eq!(
+            excluded,
+            vec![
+                "lv_btnmatrix_get_selected_btn",
+                "lv_btnmatrix_set_one_checked"
+            ]
+        );
+    }
+
+    #[test]
+    fn detect_lvgl_version() {
+        let bindgen_code = quote! {
+            pub const LVGL_VERSION_MAJOR: u32 = 9;
+            pub const LVGL_VERSION_MINOR: u32 = 1;
+            pub const LVGL_VERSION_PATCH: u32 = 0;
+            extern "C" {
+                pub fn lv_image_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+            }
+        };
+        let cg = CodeGen::from(bindgen_code.to_string().as_str()).unwrap();
+        let version = cg.version().unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (9, 1, 0));
+    }
+
+    #[test]
+    fn v9_widgets_keep_v8_names() {
+        let image_widget = LvWidget {
+            name: "image".to_string(),
+            methods: vec![],
+            rust_name: None,
+        };
+
+        assert_eq!(image_widget.compat_name(), "img");
+        let code = image_widget.code(&()).unwrap();
+        let doc = format!(
+            "The LVGL `lv_image` widget.\n\n{}",
+            examples::example_for("img", "Img")
+        );
+        let expected_code = quote! {
+            #[cfg(feature = "widget_img")]
+            define_object!(
+                #[doc = #doc]
+                #[cfg_attr(docsrs, doc(cfg(feature = "widget_img")))]
+                Img
+            );
+
+            #[cfg(feature = "widget_img")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "widget_img")))]
+            impl<'a> Img<'a> {
+
+            }
+        };
+
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
     #[test]
     fn generate_basic_widget_code() {
         let arc_widget = LvWidget {
             name: "arc".to_string(),
             methods: vec![],
+            rust_name: None,
         };
 
         let code = arc_widget.code(&()).unwrap();
+        let doc = format!(
+            "The LVGL `lv_arc` widget.\n\n{}",
+            examples::example_for("arc", "Arc")
+        );
         let expected_code = quote! {
-            define_object!(Arc);
-
+            #[cfg(feature = "widget_arc")]
+            define_object!(
+                #[doc = #doc]
+                #[cfg_attr(docsrs, doc(cfg(feature = "widget_arc")))]
+                Arc
+            );
+
+            #[cfg(feature = "widget_arc")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "widget_arc")))]
             impl<'a> Arc<'a> {
 
             }
@@ -764,12 +2223,24 @@ mod test {
         let arc_widget = LvWidget {
             name: "arc".to_string(),
             methods: vec![arc_create],
+            rust_name: None,
         };
 
         let code = arc_widget.code(&()).unwrap();
+        let doc = format!(
+            "The LVGL `lv_arc` widget.\n\n{}",
+            examples::example_for("arc", "Arc")
+        );
         let expected_code = quote! {
-            define_object!(Arc);
-
+            #[cfg(feature = "widget_arc")]
+            define_object!(
+                #[doc = #doc]
+                #[cfg_attr(docsrs, doc(cfg(feature = "widget_arc")))]
+                Arc
+            );
+
+            #[cfg(feature = "widget_arc")]
+            #[cfg_attr(docsrs, doc(cfg(feature = "widget_arc")))]
             impl<'a> Arc<'a> {
                 pub fn create(parent: &mut impl crate::NativeObject) -> crate::LvResult<Self> {
                     unsafe {
@@ -789,9 +2260,104 @@ mod test {
                     let mut parent = crate::display::get_scr_act()?;
                     Self::create(&mut parent)
                 }
+
+                /// Creates the widget at a position relative to `parent`,
+                /// with a size.
+                pub fn create_at(
+                    parent: &mut impl crate::NativeObject,
+                    x: i16,
+                    y: i16,
+                    w: i16,
+                    h: i16,
+                ) -> crate::LvResult<Self> {
+                    let mut widget = Self::create(parent)?;
+                    crate::Widget::set_pos(&mut widget, x, y);
+                    crate::Widget::set_size(&mut widget, w, h);
+                    Ok(widget)
+                }
+
+                /// Creates the widget, to be set up with chained calls.
+                pub fn builder(
+                    parent: &mut impl crate::NativeObject,
+                ) -> crate::LvResult<crate::Builder<Self>> {
+                    Ok(crate::Builder::new(Self::create(parent)?))
+                }
             }
         };
 
         assert_eq!(code.to_string(), expected_code.to_string());
     }
+
+    #[test]
+    fn derived_widgets_deref_to_their_base() {
+        let bindgen_code = quote! {
+            extern "C" {
+                pub fn lv_btnmatrix_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_btnmatrix_set_one_checked(obj: *mut lv_obj_t, en: bool);
+                pub fn lv_btnmatrix_set_selected_btn(obj: *mut lv_obj_t, btn_id: u16);
+                pub fn lv_btnmatrix_get_popovers(obj: *const lv_obj_t) -> bool;
+                pub fn lv_keyboard_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+                pub fn lv_keyboard_get_popovers(obj: *const lv_obj_t) -> bool;
+                pub fn lv_slider_create(parent: *mut lv_obj_t) -> *mut lv_obj_t;
+            }
+        };
+        let cg = CodeGen::from(bindgen_code.to_string().as_str()).unwrap();
+        let btnmatrix = cg
+            .get_widgets()
+            .iter()
+            .find(|w| w.name() == "btnmatrix")
+            .unwrap();
+        let set_one_checked = btnmatrix.methods[1].code(btnmatrix).unwrap();
+
+        // `set_selected_btn()` is implemented by hand, `get_popovers()` is
+        // generated for the keyboard itself, and there's no `Bar` to
+        // forward to the slider
+        let hand_written = HashMap::from([(
+            "keyboard".to_string(),
+            vec!["set_selected_btn".to_string()],
+        )]);
+        let code = cg.inheritance_code(&hand_written);
+        let expected_code = quote! {
+            #[cfg(all(feature = "widget_keyboard", feature = "widget_btnmatrix"))]
+            impl<'a> core::ops::Deref for Keyboard<'a> {
+                type Target = Btnmatrix<'a>;
+
+                fn deref(&self) -> &Self::Target {
+                    unsafe { &*(self as *const Self as *const Self::Target) }
+                }
+            }
+
+            #[cfg(all(feature = "widget_keyboard", feature = "widget_btnmatrix"))]
+            impl<'a> AsRef<Btnmatrix<'a>> for Keyboard<'a> {
+                fn as_ref(&self) -> &Btnmatrix<'a> {
+                    self
+                }
+            }
+
+            #[cfg(all(feature = "widget_keyboard", feature = "widget_btnmatrix"))]
+            impl<'a> Keyboard<'a> {
+                #set_one_checked
+            }
+        };
+        assert_eq!(code.to_string(), expected_code.to_string());
+    }
+
+    #[test]
+    fn inherent_method_names_are_found() {
+        let source = r#"
+            impl<'a> Keyboard<'a> {
+                pub fn set_mode(&mut self, mode: u8) {}
+                fn unpair(&mut self) {}
+            }
+
+            impl From<u8> for KeyboardMode {
+                fn from(mode: u8) -> Self {}
+            }
+        "#;
+
+        assert_eq!(
+            crate::inherent_method_names(source).unwrap(),
+            vec!["set_mode", "unpair"]
+        );
+    }
 }
@@ -0,0 +1,204 @@
+//! Procedural macros for the `lvgl` crate, re-exported by it when its
+//! `macros` feature is enabled.
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, parenthesized, token, Expr, Ident, Path, Token};
+
+/// Creates a tree of widgets in a parent and sets them up.
+///
+/// The parent, e.g. a screen, is followed by `=>` and the widgets created in
+/// it. Each widget is its type, optionally `as` the name of the variable it's
+/// bound to, and a block of method calls and child widgets:
+///
+/// ```ignore
+/// use lvgl::widgets::{Btn, Label};
+/// use lvgl::{Align, Part, State, Widget};
+///
+/// ui! {
+///     screen =>
+///     Btn as button {
+///         set_size(120, 40);
+///         set_align(Align::Center, 0, 0);
+///         add_style(Part::Main, &mut style);
+///         on_event(|_, event| println!("{:?}", event))?;
+///         Label {
+///             set_text(cstr_core::cstr!("Click me"));
+///         }
+///     }
+/// }
+/// button.add_state(State::CHECKED);
+/// ```
+///
+/// Expands into the `create()` and method calls it stands for, in the
+/// order written. As widgets are created with `?`, the macro can only be
+/// used in functions returning a `Result` an `LvError` converts into. Named
+/// widgets stay in scope after the macro, and methods called need their
+/// traits, e.g. `Widget`, in scope.
+#[proc_macro]
+pub fn ui(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    match syn::parse(input) {
+        Ok(ui) => expand(&ui).into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct Ui {
+    parent: Expr,
+    widgets: Vec<WidgetNode>,
+}
+
+struct WidgetNode {
+    ty: Path,
+    name: Option<Ident>,
+    calls: Vec<Call>,
+    children: Vec<WidgetNode>,
+}
+
+/// A method call on a widget, e.g. `set_size(120, 40);`, which may end in
+/// `?` to propagate errors.
+struct Call {
+    method: Ident,
+    args: Punctuated<Expr, Token![,]>,
+    try_: bool,
+}
+
+impl Parse for Ui {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let parent = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let mut widgets = Vec::new();
+        while !input.is_empty() {
+            widgets.push(input.parse()?);
+        }
+        Ok(Self { parent, widgets })
+    }
+}
+
+impl Parse for WidgetNode {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        let name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        let content;
+        braced!(content in input);
+        let mut calls = Vec::new();
+        let mut children = Vec::new();
+        while !content.is_empty() {
+            if content.peek(Ident) && content.peek2(token::Paren) {
+                calls.push(content.parse()?);
+            } else {
+                children.push(content.parse()?);
+            }
+        }
+        Ok(Self {
+            ty,
+            name,
+            calls,
+            children,
+        })
+    }
+}
+
+impl Parse for Call {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let method = input.parse()?;
+        let args;
+        parenthesized!(args in input);
+        let args = args.parse_terminated(Expr::parse, Token![,])?;
+        let try_ = input.parse::<Option<Token![?]>>()?.is_some();
+        input.parse::<Token![;]>()?;
+        Ok(Self { method, args, try_ })
+    }
+}
+
+fn expand(ui: &Ui) -> TokenStream {
+    let mut count = 0;
+    // Evaluated once, however many widgets are created in it
+    let parent_expr = &ui.parent;
+    let parent = Ident::new("_ui_parent", Span::mixed_site());
+    let widgets = ui
+        .widgets
+        .iter()
+        .map(|w| expand_widget(w, &quote!(*#parent), &mut count));
+    quote! {
+        let #parent = &mut #parent_expr;
+        #(#widgets)*
+    }
+}
+
+fn expand_widget(widget: &WidgetNode, parent: &TokenStream, count: &mut usize) -> TokenStream {
+    // Unnamed widgets get variables the caller can't see
+    let var = widget.name.clone().unwrap_or_else(|| {
+        *count += 1;
+        Ident::new(&format!("_ui_widget_{count}"), Span::mixed_site())
+    });
+    let ty = &widget.ty;
+    let calls = widget.calls.iter().map(|call| {
+        let method = &call.method;
+        let args = &call.args;
+        let try_ = call.try_.then(|| quote!(?));
+        quote! {
+            #var.#method(#args)#try_;
+        }
+    });
+    let children = widget
+        .children
+        .iter()
+        .map(|child| expand_widget(child, &quote!(#var), count));
+    quote! {
+        #[allow(unused_mut)]
+        let mut #var = #ty::create(&mut #parent)?;
+        #(#calls)*
+        #(#children)*
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn widget_tree_expands_to_calls() {
+        let ui: Ui = syn::parse2(quote! {
+            screen =>
+            Btn as button {
+                set_size(120, 40);
+                on_event(|_, _| {})?;
+                Label {
+                    set_text(text);
+                }
+            }
+            widgets::Led {}
+        })
+        .unwrap();
+
+        let parent = Ident::new("_ui_parent", Span::mixed_site());
+        let label = Ident::new("_ui_widget_1", Span::mixed_site());
+        let led = Ident::new("_ui_widget_2", Span::mixed_site());
+        let expected = quote! {
+            let #parent = &mut screen;
+            #[allow(unused_mut)]
+            let mut button = Btn::create(&mut *#parent)?;
+            button.set_size(120, 40);
+            button.on_event(|_, _| {})?;
+            #[allow(unused_mut)]
+            let mut #label = Label::create(&mut button)?;
+            #label.set_text(text);
+            #[allow(unused_mut)]
+            let mut #led = widgets::Led::create(&mut *#parent)?;
+        };
+        assert_eq!(expand(&ui).to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn missing_parent_is_an_error() {
+        assert!(syn::parse2::<Ui>(quote!(Btn {})).is_err());
+    }
+}
@@ -7,7 +7,12 @@ use std::{
     path::{Path, PathBuf},
 };
 
+#[cfg(all(feature = "lvgl-v9", feature = "drivers"))]
+compile_error!("The drivers feature is not supported with LVGL 9, which includes its own drivers");
+
 static CONFIG_NAME: &str = "DEP_LV_CONFIG_PATH";
+#[cfg(feature = "lvgl-v9")]
+static LVGL_V9_NAME: &str = "LVGL_V9_PATH";
 
 // See https://github.com/rust-lang/rust-bindgen/issues/687#issuecomment-450750547
 #[cfg(feature = "drivers")]
@@ -39,6 +44,7 @@ fn main() {
     let vendor = project_dir.join("vendor");
     println!("cargo:rerun-if-env-changed={}", CONFIG_NAME);
     let lv_config_dir = get_conf_path(&vendor);
+    let lvgl_parent = get_lvgl_parent(&vendor);
     let font_extra_src: Option<PathBuf> = get_font_extra_dir();
     if let Some(p) = &font_extra_src {
         println!("cargo:rerun-if-changed={}", p.to_str().unwrap())
@@ -48,6 +54,7 @@ fn main() {
     let conf = BuildConf {
         lv_config_dir: lv_config_dir.as_path(),
         vendor: vendor.as_path(),
+        lvgl_parent: lvgl_parent.as_path(),
         shims_dir: &shims_dir,
         font_extra_src: font_extra_src.as_ref().map(PathBuf::as_path),
     };
@@ -76,6 +83,8 @@ fn get_font_extra_dir() -> Option<PathBuf> {
 struct BuildConf<'a> {
     lv_config_dir: &'a Path,
     vendor: &'a Path,
+    /// Directory containing the `lvgl` source tree
+    lvgl_parent: &'a Path,
     shims_dir: &'a Path,
     font_extra_src: Option<&'a Path>,
 }
@@ -84,9 +93,11 @@ struct BuildConf<'a> {
 fn compile_library(conf: &BuildConf) {
     let vendor = conf.vendor;
 
-    let lvgl_src = vendor.join("lvgl").join("src");
+    let lvgl_src = conf.lvgl_parent.join("lvgl").join("src");
     #[cfg(feature = "rust_timer")]
     let timer_shim = vendor.join("include").join("timer");
+    #[cfg(feature = "rust_alloc")]
+    let alloc_shim = vendor.join("include").join("alloc");
 
     // Some basic defaults; SDL2 is the only driver enabled in the provided
     // driver config by default
@@ -109,6 +120,9 @@ fn compile_library(conf: &BuildConf) {
         println!("cargo:rerun-if-env-changed=LVGL_LINK");
     }
 
+    #[cfg(feature = "freetype")]
+    println!("cargo:rerun-if-env-changed=LVGL_FREETYPE_INCLUDE");
+
     let mut cfg = Build::new();
     if let Some(p) = conf.font_extra_src {
         add_c_files(&mut cfg, p)
@@ -120,7 +134,7 @@ fn compile_library(conf: &BuildConf) {
 
     cfg.define("LV_CONF_INCLUDE_SIMPLE", Some("1"))
         .include(&lvgl_src)
-        .include(&vendor)
+        .include(conf.lvgl_parent)
         .warnings(false)
         .include(conf.lv_config_dir);
     if let Some(p) = conf.font_extra_src {
@@ -128,15 +142,22 @@ fn compile_library(conf: &BuildConf) {
     }
     #[cfg(feature = "rust_timer")]
     cfg.include(&timer_shim);
+    #[cfg(feature = "rust_alloc")]
+    cfg.include(&alloc_shim);
     #[cfg(feature = "drivers")]
     cfg.include(&drivers);
     #[cfg(feature = "drivers")]
     cfg.includes(incl_extra.split(','));
+    #[cfg(feature = "freetype")]
+    cfg.includes(freetype_include().split(','));
 
     cflags_extra.clone().for_each(|e| {
         let mut it = e.split('=');
         cfg.define(it.next().unwrap(), it.next().unwrap_or_default());
     });
+    for (name, value) in feature_defines() {
+        cfg.define(&name, Some(value.as_str()));
+    }
 
     cfg.compile("lvgl");
 
@@ -145,6 +166,18 @@ fn compile_library(conf: &BuildConf) {
         println!("cargo:rustc-link-lib={a}");
         //println!("cargo:rustc-link-search=")
     });
+
+    #[cfg(feature = "freetype")]
+    println!("cargo:rustc-link-lib=freetype");
+
+    #[cfg(feature = "rlottie")]
+    println!("cargo:rustc-link-lib=rlottie");
+}
+
+/// Directories containing the FreeType headers, comma-separated.
+#[cfg(feature = "freetype")]
+fn freetype_include() -> String {
+    env::var("LVGL_FREETYPE_INCLUDE").unwrap_or("/usr/include/freetype2".to_string())
 }
 
 fn generate_bindings(conf: &BuildConf) {
@@ -155,7 +188,7 @@ fn generate_bindings(conf: &BuildConf) {
         "-I",
         conf.lv_config_dir.to_str().unwrap(),
         "-I",
-        conf.vendor.to_str().unwrap(),
+        conf.lvgl_parent.to_str().unwrap(),
         "-I",                   // Added
         &target_c_include_path, // Added
         "-fvisibility=default",
@@ -177,6 +210,23 @@ fn generate_bindings(conf: &BuildConf) {
     let cflags_extra = cflags_extra.split(',').filter(|s| !s.is_empty());
 
     let mut additional_args = Vec::new();
+    #[cfg(feature = "rust_timer")]
+    {
+        let timer_shim = conf.vendor.join("include").join("timer");
+        additional_args.push("-I".to_string());
+        additional_args.push(timer_shim.to_string_lossy().into_owned());
+    }
+    #[cfg(feature = "rust_alloc")]
+    {
+        let alloc_shim = conf.vendor.join("include").join("alloc");
+        additional_args.push("-I".to_string());
+        additional_args.push(alloc_shim.to_string_lossy().into_owned());
+    }
+    #[cfg(feature = "freetype")]
+    for dir in freetype_include().split(',') {
+        additional_args.push("-I".to_string());
+        additional_args.push(dir.to_string());
+    }
     if target.ends_with("emscripten") {
         match env::var("EMSDK") {
             Ok(em_path) =>
@@ -226,7 +276,7 @@ fn generate_bindings(conf: &BuildConf) {
     //#[cfg(feature = "rust_timer")]
     //let bindings = bindings.header(shims_dir.join("rs_timer.h").to_str().unwrap());
     let bindings = bindings
-        .generate_comments(false)
+        .generate_comments(true)
         .derive_default(true)
         .layout_tests(false)
         .use_core()
@@ -234,14 +284,165 @@ fn generate_bindings(conf: &BuildConf) {
         .clang_args(&cc_args)
         .clang_args(&additional_args)
         .clang_args(cflags_extra.map(|f| format!("-D{f}")))
+        .clang_args(
+            feature_defines()
+                .iter()
+                .map(|(name, value)| format!("-D{name}={value}")),
+        )
         .generate()
         .expect("Unable to generate bindings");
 
+    let src = bindings.to_string();
+    check_feature_defines(&src);
+    // Exposed to the build scripts of dependents as DEP_LVGL_COLOR_DEPTH and
+    // DEP_LVGL_COLOR_16_SWAP
+    for (name, key) in [("LV_COLOR_DEPTH", "color_depth"), ("LV_COLOR_16_SWAP", "color_16_swap")] {
+        if let Some(value) = bindings_const(&src, name) {
+            println!("cargo:{key}={value}");
+        }
+    }
+
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Can't write bindings!");
 }
 
+/// Returns the value of an integer constant in the generated bindings.
+fn bindings_const(bindings: &str, name: &str) -> Option<u64> {
+    // Whitespace depends on whether bindgen could run rustfmt
+    bindings.split("pub const ").skip(1).find_map(|item| {
+        let item: String = item
+            .split(';')
+            .next()?
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        let value = item.strip_prefix(name)?.strip_prefix(':')?.split_once('=')?.1;
+        value.parse().ok()
+    })
+}
+
+/// Fails the build if `lv_conf.h` overrode a setting made through a cargo
+/// feature, which happens when it defines the setting without an `#ifndef`
+/// guard. Otherwise the Rust side could e.g. assume a different color depth
+/// than the one LVGL was compiled with.
+fn check_feature_defines(bindings: &str) {
+    for (name, value) in feature_defines() {
+        let (Ok(expected), Some(actual)) = (value.parse::<u64>(), bindings_const(bindings, &name))
+        else {
+            continue;
+        };
+        if expected != actual {
+            panic!(
+                "A cargo feature sets {name} to {expected}, but lv_conf.h sets it to {actual}. \
+                 Wrap its definition in `#ifndef {name}` for the feature to take effect."
+            );
+        }
+    }
+}
+
+/// Widgets that can be individually enabled with `use_*` features once
+/// `select_widgets` is set.
+static WIDGETS: &[&str] = &[
+    "ARC", "BAR", "BTN", "BTNMATRIX", "CANVAS", "CHECKBOX", "DROPDOWN", "IMG", "LABEL", "LINE",
+    "ROLLER", "SLIDER", "SWITCH", "TEXTAREA", "TABLE", "ANIMIMG", "CALENDAR", "CHART",
+    "COLORWHEEL", "IMGBTN", "KEYBOARD", "LED", "LIST", "MENU", "METER", "MSGBOX", "SPAN",
+    "SPINBOX", "SPINNER", "TABVIEW", "TILEVIEW", "WIN",
+];
+
+/// Returns the LVGL defines set through cargo features, as name/value pairs.
+///
+/// - `font_*` and `use_*` set the matching `LV_FONT_*`/`LV_USE_*` to 1
+/// - `select_widgets` sets `LV_USE_*` to 0 for every widget not enabled
+///   through its `use_*` feature
+/// - `color_depth_*` sets `LV_COLOR_DEPTH`, and `color_16_swap` sets
+///   `LV_COLOR_16_SWAP`
+/// - `mem_size_*k` sets `LV_MEM_SIZE`, the largest one winning
+/// - `no_theme_animation` sets `LV_THEME_DEFAULT_TRANSITION_TIME` and
+///   `LV_THEME_DEFAULT_GROW` to 0
+/// - `rust_timer` sets `LV_TICK_CUSTOM`, reading the tick from `rs_lv_timer()`
+/// - `rust_alloc` sets `LV_MEM_CUSTOM`, allocating through the functions in
+///   `rs_alloc.h`
+fn feature_defines() -> Vec<(String, String)> {
+    let features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(String::from))
+        .collect();
+    let mut defines = Vec::new();
+    let mut color_depth = None;
+    let mut mem_size = None;
+    for feature in &features {
+        if let Some(font) = feature.strip_prefix("FONT_") {
+            defines.push((format!("LV_FONT_{font}"), "1".to_string()));
+        } else if feature == "USE_VENDORED_CONFIG" {
+            continue;
+        } else if let Some(option) = feature.strip_prefix("USE_") {
+            defines.push((format!("LV_USE_{option}"), "1".to_string()));
+        } else if let Some(depth) = feature.strip_prefix("COLOR_DEPTH_") {
+            if color_depth.replace(depth).is_some() {
+                panic!("Only one color_depth_* feature of lvgl-sys may be enabled");
+            }
+        } else if let Some(kib) = feature
+            .strip_prefix("MEM_SIZE_")
+            .and_then(|s| s.strip_suffix('K'))
+            .and_then(|s| s.parse::<u32>().ok())
+        {
+            mem_size = mem_size.max(Some(kib));
+        }
+    }
+    if features.iter().any(|f| f == "FREETYPE") {
+        defines.push(("LV_USE_FREETYPE".to_string(), "1".to_string()));
+    }
+    if features.iter().any(|f| f == "RLOTTIE") {
+        defines.push(("LV_USE_RLOTTIE".to_string(), "1".to_string()));
+    }
+    if features.iter().any(|f| f == "RUST_TIMER") {
+        for (name, value) in [
+            ("LV_TICK_CUSTOM", "1"),
+            ("LV_TICK_CUSTOM_INCLUDE", "<rs_timer.h>"),
+            ("LV_TICK_CUSTOM_SYS_TIME_EXPR", "(rs_lv_timer())"),
+        ] {
+            defines.push((name.to_string(), value.to_string()));
+        }
+    }
+    if features.iter().any(|f| f == "RUST_ALLOC") {
+        for (name, value) in [
+            ("LV_MEM_CUSTOM", "1"),
+            ("LV_MEM_CUSTOM_INCLUDE", "<rs_alloc.h>"),
+            ("LV_MEM_CUSTOM_ALLOC", "rs_lv_malloc"),
+            ("LV_MEM_CUSTOM_FREE", "rs_lv_free"),
+            ("LV_MEM_CUSTOM_REALLOC", "rs_lv_realloc"),
+        ] {
+            defines.push((name.to_string(), value.to_string()));
+        }
+    }
+    if features.iter().any(|f| f == "SELECT_WIDGETS") {
+        for widget in WIDGETS {
+            if !features.iter().any(|f| f.strip_prefix("USE_") == Some(widget)) {
+                defines.push((format!("LV_USE_{widget}"), "0".to_string()));
+            }
+        }
+    }
+    if let Some(depth) = color_depth {
+        defines.push(("LV_COLOR_DEPTH".to_string(), depth.to_string()));
+    }
+    if features.iter().any(|f| f == "COLOR_16_SWAP") {
+        if color_depth.is_some_and(|depth| depth != "16") {
+            panic!("The color_16_swap feature of lvgl-sys requires 16-bit colors");
+        }
+        defines.push(("LV_COLOR_16_SWAP".to_string(), "1".to_string()));
+    }
+    if features.iter().any(|f| f == "NO_THEME_ANIMATION") {
+        defines.push(("LV_THEME_DEFAULT_GROW".to_string(), "0".to_string()));
+        defines.push(("LV_THEME_DEFAULT_TRANSITION_TIME".to_string(), "0".to_string()));
+    }
+    if let Some(kib) = mem_size {
+        defines.push(("LV_MEM_SIZE".to_string(), format!("({kib}U*1024U)")));
+    }
+    defines.sort();
+    defines.dedup();
+    defines
+}
+
 fn get_conf_path(vendor: &PathBuf) -> PathBuf {
     let conf_path = env::var(CONFIG_NAME)
         .map(PathBuf::from)
@@ -272,8 +473,17 @@ fn get_conf_path(vendor: &PathBuf) -> PathBuf {
             CONFIG_NAME
         );
     }
+    // Also accept the path of the lv_conf.h file itself
+    let conf_path = if conf_path.is_file() && conf_path.file_name() == Some(std::ffi::OsStr::new("lv_conf.h")) {
+        conf_path.parent().unwrap().to_path_buf()
+    } else {
+        conf_path
+    };
     if !conf_path.is_dir() {
-        panic!("{} needs to be a directory", CONFIG_NAME);
+        panic!(
+            "{} needs to be a directory or the path of a file called lv_conf.h",
+            CONFIG_NAME
+        );
     }
     if !conf_path.join("lv_conf.h").exists() {
         panic!(
@@ -283,6 +493,7 @@ fn get_conf_path(vendor: &PathBuf) -> PathBuf {
         );
     }
 
+    #[cfg(feature = "drivers")]
     if !conf_path.join("lv_drv_conf.h").exists() {
         panic!(
             "Directory {} referenced by {} needs to contain a file called lv_drv_conf.h",
@@ -291,6 +502,8 @@ fn get_conf_path(vendor: &PathBuf) -> PathBuf {
         );
     }
 
+    // Exposed to the build scripts of dependents as DEP_LVGL_CONFIG_PATH
+    println!("cargo:config_path={}", conf_path.to_str().unwrap());
     println!(
         "cargo:rerun-if-changed={}",
         conf_path.join("lv_conf.h").to_str().unwrap()
@@ -303,6 +516,42 @@ fn get_conf_path(vendor: &PathBuf) -> PathBuf {
     conf_path
 }
 
+/// Returns the directory containing the `lvgl` source tree: the vendored
+/// LVGL 8 one, or the LVGL 9 checkout in `LVGL_V9_PATH` with the `lvgl-v9`
+/// feature.
+fn get_lvgl_parent(vendor: &Path) -> PathBuf {
+    #[cfg(not(feature = "lvgl-v9"))]
+    {
+        vendor.to_path_buf()
+    }
+
+    #[cfg(feature = "lvgl-v9")]
+    {
+        let _ = vendor;
+        #[cfg(feature = "use-vendored-config")]
+        if env::var(CONFIG_NAME).is_err() {
+            panic!("The vendored lv_conf.h is for LVGL 8; set {} to an LVGL 9 config", CONFIG_NAME);
+        }
+
+        println!("cargo:rerun-if-env-changed={}", LVGL_V9_NAME);
+        let path = env::var(LVGL_V9_NAME)
+            .map(|p| canonicalize(PathBuf::from(p)))
+            .unwrap_or_else(|_| {
+                panic!(
+                    "The environment variable {} is required to be defined with the lvgl-v9 feature",
+                    LVGL_V9_NAME
+                )
+            });
+        if path.file_name() != Some(std::ffi::OsStr::new("lvgl")) || !path.join("lvgl.h").exists() {
+            panic!(
+                "{} needs to point at an LVGL 9 source tree in a directory called lvgl",
+                LVGL_V9_NAME
+            );
+        }
+        path.parent().unwrap().to_path_buf()
+    }
+}
+
 fn add_font_headers(bindings: bindgen::Builder, dir: Option<&Path>) -> bindgen::Builder {
     if let Some(p) = dir {
         let mut temp = bindings;
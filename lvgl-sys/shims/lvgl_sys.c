@@ -5,6 +5,25 @@ lv_color_t _LV_COLOR_MAKE(uint8_t r, uint8_t g, uint8_t b)
     return lv_color_make(r, g, b);
 }
 
+#if LVGL_VERSION_MAJOR >= 9
+
+uint16_t _LV_COLOR_GET_R(lv_color_t color)
+{
+    return color.red;
+}
+
+uint16_t _LV_COLOR_GET_G(lv_color_t color)
+{
+    return color.green;
+}
+
+uint16_t _LV_COLOR_GET_B(lv_color_t color)
+{
+    return color.blue;
+}
+
+#else
+
 uint16_t _LV_COLOR_GET_R(lv_color_t color)
 {
     return LV_COLOR_GET_R(color);
@@ -24,3 +43,5 @@ uint16_t _LV_COLOR_GET_A(lv_color_t color)
 {
     return LV_COLOR_GET_A(color);
 }
+
+#endif
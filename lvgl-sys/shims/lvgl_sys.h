@@ -11,7 +11,10 @@ lv_color_t _LV_COLOR_MAKE(uint8_t r, uint8_t g, uint8_t b);
 uint16_t _LV_COLOR_GET_R(lv_color_t color);
 uint16_t _LV_COLOR_GET_G(lv_color_t color);
 uint16_t _LV_COLOR_GET_B(lv_color_t color);
+#if LVGL_VERSION_MAJOR < 9
+/* LVGL 9 colors have no alpha channel */
 uint16_t _LV_COLOR_GET_A(lv_color_t color);
+#endif
 
 
 #ifdef __cplusplus
@@ -20,6 +20,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(not(feature = "lvgl-v9"))]
     fn basic_sanity_check() {
         unsafe {
             lv_init();
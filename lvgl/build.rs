@@ -1,28 +1,161 @@
-use lvgl_codegen::{CodeGen, Rusty};
+use lvgl_codegen::{CodeGen, Config, Glob, Rusty};
 use proc_macro2::TokenStream;
 use quote::quote;
+use std::collections::HashMap;
 use std::env;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::prelude::*;
 use std::path::PathBuf;
 
+/// Widgets hand-written wrappers may be gated on with `#[cfg(lv_widget = "...")]`.
+/// Each has a matching `widget_*` cargo feature.
+static KNOWN_WIDGETS: &[&str] = &[
+    "animimg", "arc", "bar", "btn", "btnmatrix", "calendar", "canvas", "chart", "checkbox",
+    "colorwheel", "dropdown", "gif", "img", "imgbtn", "keyboard", "label", "led", "line", "list",
+    "lottie", "menu", "meter", "msgbox", "qrcode", "roller", "slider", "spangroup", "spinbox",
+    "spinner", "switch", "table", "tabview", "textarea", "tileview", "win",
+];
+
+/// Known widgets whose LVGL functions use another name than the widget.
+static WIDGET_PREFIXES: &[(&str, &str)] = &[("lottie", "rlottie")];
+
+/// Widget functions wrapped by hand under the name the generated wrapper
+/// would have, e.g. because the generated one would borrow data LVGL keeps
+/// or take angles as `u16` rather than signed degrees.
+static HAND_WRITTEN_FUNCTIONS: &[&str] = &[
+    "lv_line_set_points",
+    "lv_arc_set_rotation",
+    "lv_arc_set_bg_angles",
+];
+
 fn main() {
     let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     let rs = out_path.join("generated.rs");
 
     let widgets_impl = lvgl_sys::_bindgen_raw_src();
 
-    let codegen = CodeGen::from(widgets_impl).unwrap();
+    // Forks may tune the generated wrappers with a `codegen.toml` next to
+    // this crate's manifest, or anywhere `LVGL_CODEGEN_CONFIG` points to
+    println!("cargo:rerun-if-env-changed=LVGL_CODEGEN_CONFIG");
+    let config_path = env::var_os("LVGL_CODEGEN_CONFIG")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("codegen.toml")
+        });
+    // Cargo reruns the script on every build if told to watch a missing file
+    let mut config = if config_path.exists() {
+        println!("cargo:rerun-if-changed={}", config_path.display());
+        Config::load(&config_path).unwrap_or_else(|e| panic!("{}: {e}", config_path.display()))
+    } else {
+        Config::default()
+    };
+    config
+        .functions
+        .exclude
+        .extend(HAND_WRITTEN_FUNCTIONS.iter().map(|f| Glob::new(f)));
+    let codegen = CodeGen::from(widgets_impl).unwrap().with_config(config);
+
+    // The methods the `Widget` trait implements by hand, which `lv_obj_*`
+    // functions of the same name aren't generated for
+    let obj_rs = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/lv_core/obj.rs");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", obj_rs.display());
+    let hand_written =
+        lvgl_codegen::trait_method_names(&fs::read_to_string(&obj_rs).unwrap(), "Widget")
+            .unwrap_or_else(|e| panic!("{}: {e}", obj_rs.display()));
+    let hand_written: Vec<&str> = hand_written.iter().map(String::as_str).collect();
+
+    // Widgets compiled out in lv_conf.h have no bindings, so neither the
+    // generated nor the hand-written wrappers may be built for them. The same
+    // goes for widgets whose `widget_*` feature is disabled.
+    let values: Vec<String> = KNOWN_WIDGETS.iter().map(|w| format!("\"{w}\"")).collect();
+    println!(
+        "cargo:rustc-check-cfg=cfg(lv_widget, values({}))",
+        values.join(", ")
+    );
+    // Widgets whose create function takes more than a parent (e.g. msgbox)
+    // aren't generated, but have hand-written wrappers as long as LVGL has
+    // the widget.
+    let functions = codegen.get_function_names().unwrap();
+    for widget in KNOWN_WIDGETS {
+        let generated = codegen
+            .get_widgets()
+            .iter()
+            .any(|w| w.compat_name() == *widget);
+        let prefix = WIDGET_PREFIXES
+            .iter()
+            .find(|(name, _)| name == widget)
+            .map_or(*widget, |(_, prefix)| prefix);
+        // Some widgets only have variants, e.g. `lv_rlottie_create_from_raw()`
+        let create = format!("lv_{prefix}_create");
+        let available = generated
+            || functions
+                .iter()
+                .any(|f| *f == create || f.starts_with(&format!("{create}_")));
+        let feature = format!("CARGO_FEATURE_WIDGET_{}", widget.to_uppercase());
+        if available && env::var_os(feature).is_some() {
+            println!("cargo:rustc-cfg=lv_widget=\"{widget}\"");
+        }
+    }
+
+    // Color settings LVGL was compiled with, see `lvgl::pixel_format::NativeFormat`
+    println!("cargo:rustc-check-cfg=cfg(lv_color_depth, values(\"1\", \"8\", \"16\", \"32\"))");
+    println!("cargo:rustc-check-cfg=cfg(lv_color_16_swap)");
+    if let Ok(depth) = env::var("DEP_LVGL_COLOR_DEPTH") {
+        println!("cargo:rustc-cfg=lv_color_depth=\"{depth}\"");
+    }
+    if env::var("DEP_LVGL_COLOR_16_SWAP").is_ok_and(|swap| swap != "0") {
+        println!("cargo:rustc-cfg=lv_color_16_swap");
+    }
+
+    // lvgl-sys may have been built against LVGL 9 through its `lvgl-v9`
+    // feature, which the hand-written wrappers don't support yet
+    println!("cargo:rustc-check-cfg=cfg(lvgl_v9)");
+    if codegen.version().is_some_and(|v| v.major >= 9) {
+        println!("cargo:rustc-cfg=lvgl_v9");
+    }
     let widgets_impl: Vec<TokenStream> = codegen
         .get_widgets()
         .iter()
         .flat_map(|w| w.code(&()))
         .collect();
+    // The methods of each widget implemented by hand, which the methods of
+    // its base widget aren't forwarded for
+    let widgets_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/widgets");
+    println!("cargo:rerun-if-changed={}", widgets_dir.display());
+    let widget_methods: HashMap<String, Vec<String>> = fs::read_dir(&widgets_dir)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let widget = path.file_stem().unwrap().to_string_lossy().into_owned();
+            let methods = lvgl_codegen::inherent_method_names(&fs::read_to_string(&path).unwrap())
+                .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+            (widget, methods)
+        })
+        .collect();
+    let inheritance = codegen.inheritance_code(&widget_methods);
 
     let code = quote! {
         #(#widgets_impl)*
+        #inheritance
     };
 
+    // Expanded inside the `Widget` trait
+    let obj_methods = codegen.widget_trait_methods(&hand_written);
+    let mut file = File::create(out_path.join("widget_methods.rs")).unwrap();
+    writeln!(
+        file,
+        "/* automatically generated by lvgl-codegen */\n{}",
+        quote! {
+            macro_rules! generated_widget_methods {
+                () => {
+                    #obj_methods
+                };
+            }
+        }
+    )
+    .unwrap();
+
     let mut file = File::create(rs).unwrap();
     writeln!(
         file,
@@ -30,4 +163,29 @@ fn main() {
         code
     )
     .unwrap();
+
+    // Included in the `widgets` module documentation
+    let mut file = File::create(out_path.join("widgets.md")).unwrap();
+    write!(file, "{}", codegen.feature_matrix()).unwrap();
+
+    // Which functions got no wrapper and why, to track the coverage
+    let report = out_path.join("codegen_report.json");
+    let mut file = File::create(&report).unwrap();
+    write!(file, "{}", codegen.skip_report(&hand_written)).unwrap();
+    let skipped = codegen.skipped(&hand_written);
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for s in &skipped {
+        match counts.iter_mut().find(|(kind, _)| *kind == s.reason.kind()) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((s.reason.kind(), 1)),
+        }
+    }
+    let counts: Vec<String> = counts.iter().map(|(k, n)| format!("{n} {k}")).collect();
+    println!(
+        "cargo:warning=lvgl-codegen wrapped {} of {} functions, skipped {}; see {}",
+        functions.len() - skipped.len(),
+        functions.len(),
+        counts.join(", "),
+        report.display()
+    );
 }
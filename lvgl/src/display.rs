@@ -1,4 +1,6 @@
 use crate::functions::CoreError;
+use crate::group::Group;
+use crate::pixel_format::{self, PixelFormat};
 use crate::Screen;
 use crate::{disp_drv_register, disp_get_default, get_str_act, NativeObject};
 use crate::{Box, Color};
@@ -12,6 +14,7 @@ use core::ptr::NonNull;
 use core::{ptr, result};
 
 #[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum ScreenLoadAnim {
     None,
     OverLeft,
@@ -30,8 +33,33 @@ pub enum ScreenLoadAnim {
     OutBottom,
 }
 
+impl ScreenLoadAnim {
+    /// Returns the animation undoing this one, e.g. for going back to the
+    /// previous screen.
+    pub const fn reversed(self) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::OverLeft => Self::OutRight,
+            Self::OverRight => Self::OutLeft,
+            Self::OverTop => Self::OutBottom,
+            Self::OverBottom => Self::OutTop,
+            Self::MoveLeft => Self::MoveRight,
+            Self::MoveRight => Self::MoveLeft,
+            Self::MoveTop => Self::MoveBottom,
+            Self::MoveBottom => Self::MoveTop,
+            Self::FadeIn => Self::FadeOut,
+            Self::FadeOut => Self::FadeIn,
+            Self::OutLeft => Self::OverRight,
+            Self::OutRight => Self::OverLeft,
+            Self::OutTop => Self::OverBottom,
+            Self::OutBottom => Self::OverTop,
+        }
+    }
+}
+
 /// Error in interacting with a `Display`.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DisplayError {
     NotAvailable,
     FailedToRegister,
@@ -57,6 +85,16 @@ impl Error for DisplayError {}
 
 type Result<T> = result::Result<T, DisplayError>;
 
+/// The default group of a display, as LVGL itself only keeps a single
+/// global one. Kept in a list with one entry per display.
+struct DefaultGroup {
+    disp: *mut lvgl_sys::lv_disp_t,
+    group: *mut lvgl_sys::lv_group_t,
+    next: Option<Box<DefaultGroup>>,
+}
+
+static mut DEFAULT_GROUPS: Option<Box<DefaultGroup>> = None;
+
 /// An LVGL-registered display. Equivalent to an `lv_disp_t`.
 pub struct Display {
     pub(crate) disp: NonNull<lvgl_sys::lv_disp_t>,
@@ -116,6 +154,124 @@ impl<'a> Display {
         }
     }
 
+    /// Makes this the default display, i.e. the one widgets are created on
+    /// when no parent is specified. Also activates the default group of the
+    /// display, if one was set with `set_default_group()`.
+    pub fn set_default(&self) {
+        unsafe {
+            lvgl_sys::lv_disp_set_default(self.disp.as_ptr());
+            lvgl_sys::lv_group_set_default(default_group_of(self.disp.as_ptr()));
+        }
+    }
+
+    /// Rotates the display, e.g. for a panel mounted at 90 degrees. The
+    /// horizontal and vertical resolution are swapped for 90 and 270 degrees,
+    /// and the screens are laid out again.
+    ///
+    /// Unless software rotation is enabled with `set_sw_rotate()`, the
+    /// flushed areas are in rotated coordinates, and the flush callback has
+    /// to configure the panel controller accordingly.
+    pub fn set_rotation(&self, rotation: Rotation) {
+        unsafe { lvgl_sys::lv_disp_set_rotation(self.disp.as_ptr(), rotation.into()) }
+    }
+
+    /// Returns the rotation of the display.
+    pub fn rotation(&self) -> Rotation {
+        let rotation = unsafe { lvgl_sys::lv_disp_get_rotation(self.disp.as_ptr()) };
+        match rotation as u32 {
+            lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_90 => Rotation::Deg90,
+            lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_180 => Rotation::Deg180,
+            lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_270 => Rotation::Deg270,
+            _ => Rotation::None,
+        }
+    }
+
+    /// Makes LVGL rotate the rendered pixels itself, so the flush callback
+    /// receives areas in the panel's native orientation. Costs CPU time and,
+    /// for 90 and 270 degrees, a second buffer of the draw buffer's size.
+    pub fn set_sw_rotate(&self, enabled: bool) {
+        unsafe {
+            let driver = (*self.disp.as_ptr()).driver;
+            (*driver).set_sw_rotate(enabled as u32);
+        }
+    }
+
+    /// Returns the horizontal resolution, taking the rotation into account.
+    pub fn hor_res(&self) -> i16 {
+        unsafe { lvgl_sys::lv_disp_get_hor_res(self.disp.as_ptr()) as i16 }
+    }
+
+    /// Returns the vertical resolution, taking the rotation into account.
+    pub fn ver_res(&self) -> i16 {
+        unsafe { lvgl_sys::lv_disp_get_ver_res(self.disp.as_ptr()) as i16 }
+    }
+
+    /// Marks the whole active screen for redrawing.
+    pub fn invalidate(&self) {
+        unsafe {
+            let scr = lvgl_sys::lv_disp_get_scr_act(self.disp.as_ptr());
+            if !scr.is_null() {
+                lvgl_sys::lv_obj_invalidate(scr);
+            }
+        }
+    }
+
+    /// Redraws the invalidated areas of the display right away, instead of
+    /// waiting for the next refresh period of `task_handler()`. Like the
+    /// latter, call it from the context running LVGL, not from interrupt
+    /// handlers: these should only flag that new data arrived.
+    pub fn refresh_now(&self) {
+        unsafe { lvgl_sys::lv_refr_now(self.disp.as_ptr()) }
+    }
+
+    /// Enables or disables the periodic refresh of the display by
+    /// `task_handler()`. While disabled, nothing is rendered until
+    /// `refresh_now()` is called, e.g. to only render when new data arrived
+    /// and stay idle otherwise.
+    pub fn set_auto_refresh(&self, enabled: bool) {
+        unsafe {
+            let timer = (*self.disp.as_ptr()).refr_timer;
+            if timer.is_null() {
+                return;
+            }
+            if enabled {
+                lvgl_sys::lv_timer_resume(timer);
+            } else {
+                lvgl_sys::lv_timer_pause(timer);
+            }
+        }
+    }
+
+    /// Sets the group newly created widgets on this display are added to.
+    /// Useful with multiple displays each driven by their own encoder or
+    /// keypad: LVGL only has one global default group, which is switched
+    /// whenever the display is made the default with `set_default()`.
+    pub fn set_default_group(&self, group: &Group) -> Result<()> {
+        let group = group.raw()?.as_ptr();
+        let disp = self.disp.as_ptr();
+        unsafe {
+            let groups = &mut *ptr::addr_of_mut!(DEFAULT_GROUPS);
+            let mut entry = groups.as_deref_mut();
+            while let Some(node) = entry {
+                if node.disp == disp {
+                    break;
+                }
+                entry = node.next.as_deref_mut();
+            }
+            match entry {
+                Some(node) => node.group = group,
+                None => {
+                    let next = groups.take();
+                    *groups = Some(Box::new(DefaultGroup { disp, group, next }));
+                }
+            }
+            if lvgl_sys::lv_disp_get_default() == disp {
+                lvgl_sys::lv_group_set_default(group);
+            }
+        }
+        Ok(())
+    }
+
     /// Registers a display from raw functions and values.
     ///
     /// # Safety
@@ -189,6 +345,46 @@ impl Drop for Display {
     }
 }
 
+/// Returns the default group registered for `disp`, or null if none was set.
+unsafe fn default_group_of(disp: *mut lvgl_sys::lv_disp_t) -> *mut lvgl_sys::lv_group_t {
+    let mut entry = (*ptr::addr_of!(DEFAULT_GROUPS)).as_deref();
+    while let Some(node) = entry {
+        if node.disp == disp {
+            return node.group;
+        }
+        entry = node.next.as_deref();
+    }
+    ptr::null_mut()
+}
+
+/// Removes a group that is about to be deleted from the per-display defaults.
+pub(crate) fn forget_default_group(group: *mut lvgl_sys::lv_group_t) {
+    unsafe { retain_default_groups(|node| node.group != group) }
+}
+
+/// Removes `disp` from LVGL, forgetting its default group so that a display
+/// later allocated at the same address doesn't inherit it.
+///
+/// # Safety
+///
+/// `disp` must be a registered display, not used after this.
+pub(crate) unsafe fn disp_remove(disp: *mut lvgl_sys::lv_disp_t) {
+    retain_default_groups(|node| node.disp != disp);
+    lvgl_sys::lv_disp_remove(disp);
+}
+
+unsafe fn retain_default_groups(keep: impl Fn(&DefaultGroup) -> bool) {
+    let groups = &mut *ptr::addr_of_mut!(DEFAULT_GROUPS);
+    let mut rest = groups.take();
+    while let Some(mut node) = rest {
+        rest = node.next.take();
+        if keep(&node) {
+            node.next = groups.take();
+            *groups = Some(node);
+        }
+    }
+}
+
 /// Gets the active screen of the default display.
 pub(crate) fn get_scr_act() -> Result<Screen<'static>> {
     Ok(get_str_act(None)?.try_into()?)
@@ -197,37 +393,96 @@ pub(crate) fn get_scr_act() -> Result<Screen<'static>> {
 /// A buffer of size `N` representing `N` pixels. `N` can be smaller than the
 /// entire number of pixels on the screen, in which case the screen will be
 /// drawn to multiple times per frame.
+///
+/// `DrawBuffer::default()` allocates the pixels on the heap. To send them to
+/// the display with DMA, or to place them in a specific RAM region, use a
+/// static `AlignedBuffer` through `from_static()` instead.
 pub struct DrawBuffer<const N: usize> {
     draw_buf: Pin<Box<lvgl_sys::lv_disp_draw_buf_t>>,
-    _refresh_buffer: Pin<Box<[MaybeUninit<lvgl_sys::lv_color_t>; N]>>,
+    /// `None` if the pixels live in a static `AlignedBuffer`.
+    _refresh_buffer: Option<Pin<Box<[MaybeUninit<lvgl_sys::lv_color_t>; N]>>>,
 }
 
 impl<const N: usize> Default for DrawBuffer<N> {
     fn default() -> Self {
         let mut buf = Box::pin([MaybeUninit::uninit(); N]);
         Self {
-            draw_buf: Box::pin(unsafe {
-                let mut inner: MaybeUninit<lvgl_sys::lv_disp_draw_buf_t> = MaybeUninit::uninit();
-                let raw_ptr = buf.as_mut_ptr() as *mut _;
-                lvgl_sys::lv_disp_draw_buf_init(
-                    inner.as_mut_ptr(),
-                    raw_ptr,
-                    ptr::null_mut(),
-                    N as u32,
-                );
-                inner.assume_init()
-            }),
-            _refresh_buffer: buf,
+            draw_buf: Self::init(buf.as_mut_ptr() as *mut _),
+            _refresh_buffer: Some(buf),
         }
     }
 }
 
 impl<const N: usize> DrawBuffer<N> {
+    /// Renders into a statically allocated buffer, typically declared with
+    /// `static_draw_buffer!`.
+    pub fn from_static(buffer: &'static mut AlignedBuffer<N>) -> Self {
+        Self {
+            draw_buf: Self::init(buffer.0.as_mut_ptr() as *mut _),
+            _refresh_buffer: None,
+        }
+    }
+
+    fn init(pixels: *mut cty::c_void) -> Pin<Box<lvgl_sys::lv_disp_draw_buf_t>> {
+        Box::pin(unsafe {
+            let mut inner: MaybeUninit<lvgl_sys::lv_disp_draw_buf_t> = MaybeUninit::uninit();
+            lvgl_sys::lv_disp_draw_buf_init(inner.as_mut_ptr(), pixels, ptr::null_mut(), N as u32);
+            inner.assume_init()
+        })
+    }
+
     fn get_ptr(&mut self) -> &mut lvgl_sys::lv_disp_draw_buf_t {
         &mut self.draw_buf
     }
 }
 
+/// Storage for `N` pixels, aligned to `AlignedBuffer::ALIGN` bytes. That
+/// satisfies DMA engines as well as the cache line size of e.g. Cortex-M7
+/// cores, so cache maintenance on the buffer doesn't touch neighboring data.
+#[repr(C, align(64))]
+pub struct AlignedBuffer<const N: usize>([MaybeUninit<lvgl_sys::lv_color_t>; N]);
+
+impl<const N: usize> AlignedBuffer<N> {
+    /// The alignment of the buffer in bytes.
+    pub const ALIGN: usize = 64;
+
+    /// Creates an uninitialized buffer, e.g. for a `static`.
+    pub const fn new() -> Self {
+        Self([MaybeUninit::uninit(); N])
+    }
+}
+
+const _: () = assert!(core::mem::align_of::<AlignedBuffer<1>>() == AlignedBuffer::<1>::ALIGN);
+
+impl<const N: usize> Default for AlignedBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Declares a static `AlignedBuffer` of `N` pixels and returns a `&'static
+/// mut` reference to it the first time the expression is evaluated, `None`
+/// afterwards. The buffer can be placed in a specific RAM region through its
+/// link section, e.g. DMA-capable internal SRAM:
+///
+/// ```ignore
+/// let buffer = lvgl::static_draw_buffer!(320 * 24, link_section = ".sram1").unwrap();
+/// let display = Display::register(DrawBuffer::from_static(buffer), 320, 240, flush)?;
+/// ```
+#[macro_export]
+macro_rules! static_draw_buffer {
+    ($n:expr $(, link_section = $section:literal)?) => {{
+        $(#[link_section = $section])?
+        static mut BUFFER: $crate::AlignedBuffer<{ $n }> = $crate::AlignedBuffer::new();
+        static TAKEN: core::sync::atomic::AtomicBool = core::sync::atomic::AtomicBool::new(false);
+        if TAKEN.swap(true, core::sync::atomic::Ordering::AcqRel) {
+            None
+        } else {
+            Some(unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) })
+        }
+    }};
+}
+
 #[repr(C)]
 pub(crate) struct DisplayDriver<const N: usize> {
     pub(crate) disp_drv: Pin<Box<lvgl_sys::lv_disp_drv_t>>,
@@ -322,7 +577,28 @@ impl<'a, const N: usize> DisplayDriver<N> {
     }
 }
 
+/// The rotation of a display, clockwise. Equivalent to `lv_disp_rot_t`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rotation {
+    None,
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+impl From<Rotation> for lvgl_sys::lv_disp_rot_t {
+    fn from(rotation: Rotation) -> Self {
+        (match rotation {
+            Rotation::None => lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_NONE,
+            Rotation::Deg90 => lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_90,
+            Rotation::Deg180 => lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_180,
+            Rotation::Deg270 => lvgl_sys::lv_disp_rot_t_LV_DISP_ROT_270,
+        }) as lvgl_sys::lv_disp_rot_t
+    }
+}
+
 /// Represents a sub-area of the display that is being updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Area {
     pub x1: i16,
     pub x2: i16,
@@ -330,12 +606,93 @@ pub struct Area {
     pub y2: i16,
 }
 
+impl Area {
+    /// Width of the area in pixels; both edges are inclusive.
+    pub fn width(&self) -> i16 {
+        self.x2 - self.x1 + 1
+    }
+
+    /// Height of the area in pixels; both edges are inclusive.
+    pub fn height(&self) -> i16 {
+        self.y2 - self.y1 + 1
+    }
+}
+
+impl From<lvgl_sys::lv_area_t> for Area {
+    fn from(area: lvgl_sys::lv_area_t) -> Self {
+        Self {
+            x1: area.x1 as i16,
+            x2: area.x2 as i16,
+            y1: area.y1 as i16,
+            y2: area.y2 as i16,
+        }
+    }
+}
+
 /// An update to the display information, contains the area that is being
 /// updated and the color of the pixels that need to be updated. The colors
-/// are represented in a contiguous array.
+/// are represented in a contiguous array the size of the draw buffer, of
+/// which the first `len()` cover `area` row by row; `area_colors()` and
+/// `rows()` return only those.
 pub struct DisplayRefresh<'a, const N: usize> {
     pub area: Area,
     pub colors: &'a [Color; N],
+    /// Whether this is the last area flushed for the current frame, see
+    /// `is_last()`.
+    pub last: bool,
+}
+
+impl<'a, const N: usize> DisplayRefresh<'a, N> {
+    /// Returns `true` if this is the last area flushed for the current
+    /// frame, i.e. the display shows a complete frame once it is drawn.
+    pub fn is_last(&self) -> bool {
+        self.last
+    }
+
+    /// Returns the pixels in the format `F`, e.g. `pixel_format::Rgb565`.
+    /// Fails to compile unless LVGL renders in that format.
+    pub fn pixels<F: PixelFormat>(&self) -> &'a [F::Pixel; N] {
+        #[allow(clippy::let_unit_value)]
+        let () = pixel_format::Check::<F>::MATCHES;
+        // `Color` is a transparent wrapper of `lv_color_t`, whose size the
+        // check above compared to `F::Pixel`
+        unsafe { &*(self.colors as *const [Color; N] as *const [F::Pixel; N]) }
+    }
+
+    /// Returns the number of pixels rendered, i.e. the size of `area`.
+    /// Areas never exceed the draw buffer, but `colors` may be longer.
+    pub fn len(&self) -> usize {
+        (self.area.width() as usize * self.area.height() as usize).min(N)
+    }
+
+    /// Returns `true` if no pixel was rendered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the rendered pixels of `area`, row by row. Unlike `colors`,
+    /// the slice ends with the last rendered pixel, so it can be written to
+    /// a display window set to `area` as is.
+    pub fn area_colors(&self) -> &'a [Color] {
+        &self.colors[..self.len()]
+    }
+
+    /// Like `area_colors()`, in the format `F`. Fails to compile unless LVGL
+    /// renders in that format.
+    pub fn area_pixels<F: PixelFormat>(&self) -> &'a [F::Pixel] {
+        &self.pixels::<F>()[..self.len()]
+    }
+
+    /// Iterates over the rows of `area` with their y coordinate, e.g. for
+    /// controllers that are written to line by line.
+    pub fn rows(&self) -> impl Iterator<Item = (i16, &'a [Color])> + 'a {
+        let y1 = self.area.y1;
+        let width = (self.area.width().max(0) as usize).max(1);
+        self.area_colors()
+            .chunks_exact(width)
+            .enumerate()
+            .map(move |(i, row)| (y1 + i as i16, row))
+    }
 }
 
 #[cfg(feature = "embedded_graphics")]
@@ -399,6 +756,7 @@ unsafe extern "C" fn disp_flush_trampoline<'a, F, const N: usize>(
                 y2: (*area).y2,
             },
             colors,
+            last: lvgl_sys::lv_disp_flush_is_last(disp_drv),
         };
         callback(&update);
     }
@@ -439,6 +797,122 @@ mod tests {
         let _screen_default = get_scr_act().expect("Return screen from the default display");
     }
 
+    #[test]
+    fn static_draw_buffer_is_aligned() {
+        tests::initialize_test(false);
+        let take = || crate::static_draw_buffer!(240 * 10);
+        let buffer = take().unwrap();
+        assert_eq!(buffer as *const _ as usize % AlignedBuffer::<1>::ALIGN, 0);
+        assert!(take().is_none());
+        let display = Display::register(DrawBuffer::from_static(buffer), 240, 240, |_| {});
+        assert!(display.is_ok());
+    }
+
+    #[test]
+    fn refresh_covers_area_only() {
+        tests::initialize_test(false);
+        let colors = [Color::from_rgb((255, 0, 0)); 8];
+        let refresh = DisplayRefresh {
+            area: Area {
+                x1: 10,
+                x2: 12,
+                y1: 4,
+                y2: 5,
+            },
+            colors: &colors,
+            last: false,
+        };
+        assert_eq!(refresh.len(), 6);
+        assert_eq!(refresh.area_colors().len(), 6);
+        let rows: Vec<_> = refresh.rows().map(|(y, row)| (y, row.len())).collect();
+        assert_eq!(rows, [(4, 3), (5, 3)]);
+    }
+
+    #[test]
+    fn rotation_swaps_resolution() {
+        tests::initialize_test(false);
+        let buffer = DrawBuffer::<{ 320 * 10 }>::default();
+        let display = Display::register(buffer, 320, 240, |_| {}).unwrap();
+        display.set_sw_rotate(true);
+        display.set_rotation(Rotation::Deg90);
+        assert_eq!(display.rotation(), Rotation::Deg90);
+        assert_eq!((display.hor_res(), display.ver_res()), (240, 320));
+        display.set_rotation(Rotation::None);
+        assert_eq!((display.hor_res(), display.ver_res()), (320, 240));
+    }
+
+    #[test]
+    fn per_display_default_group() {
+        tests::initialize_test(true);
+        let display = Display::default();
+        let group = Group::default();
+        display.set_default_group(&group).unwrap();
+        display.set_default();
+        assert_eq!(unsafe { lvgl_sys::lv_group_get_default() }, group.raw().unwrap().as_ptr());
+    }
+
+    #[test]
+    fn default_groups_of_many_displays() {
+        use crate::widgets::Btn;
+        use crate::Widget;
+
+        tests::initialize_test(false);
+        let displays: Vec<_> = (0..6)
+            .map(|_| {
+                let buffer = DrawBuffer::<{ 32 * 32 }>::default();
+                Display::register(buffer, 32, 32, |_| {}).unwrap()
+            })
+            .collect();
+        let groups: Vec<_> = displays.iter().map(|_| Group::default()).collect();
+        for (display, group) in displays.iter().zip(&groups) {
+            display.set_default_group(group).unwrap();
+        }
+        for (display, group) in displays.iter().zip(&groups) {
+            display.set_default();
+            assert_eq!(unsafe { lvgl_sys::lv_group_get_default() }, group.raw().unwrap().as_ptr());
+            let mut screen = display.get_scr_act().unwrap();
+            let btn = Btn::create(&mut screen).unwrap();
+            assert_eq!(btn.display().unwrap().disp, display.disp);
+        }
+        drop(groups);
+        displays[0].set_default();
+        assert!(unsafe { lvgl_sys::lv_group_get_default() }.is_null());
+    }
+
+    #[test]
+    fn removed_display_forgets_default_group() {
+        tests::initialize_test(false);
+        let register = || {
+            let buffer = DrawBuffer::<{ 32 * 32 }>::default();
+            Display::register(buffer, 32, 32, |_| {}).unwrap()
+        };
+        let group = Group::default();
+        let removed = register();
+        removed.set_default_group(&group).unwrap();
+        unsafe { disp_remove(removed.disp.as_ptr()) };
+
+        // Possibly at the address of the removed one
+        let display = register();
+        display.set_default();
+        assert!(unsafe { lvgl_sys::lv_group_get_default() }.is_null());
+    }
+
+    #[test]
+    fn coords_reflect_pending_layout() {
+        use crate::widgets::Btn;
+        use crate::{Align, Widget};
+
+        tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.set_size(40, 20);
+        btn.set_align(Align::Center, 0, 0);
+        let coords = btn.coords();
+        assert_eq!((coords.width(), coords.height()), (40, 20));
+        assert_eq!((coords.x1, coords.y1), (100, 110));
+    }
+
     #[test]
     fn register_display_directly() -> Result<()> {
         crate::tests::initialize_test(true);
@@ -0,0 +1,370 @@
+//! Closures called on display driver events
+//!
+//! The driver of a display calls back when a frame is started, rendered or
+//! invalidated, and when the resolution changes. `Display::on_render_start()`,
+//! `on_render_ready()`, `on_resolution_changed()` and `on_invalidate()` run
+//! closures from these callbacks until the returned `DisplayHook` is
+//! dropped:
+//!
+//! ```ignore
+//! let _backlight = display.on_render_ready(|_, _| backlight.set_high());
+//! ```
+//!
+//! Any number of hooks may be attached to the same event of a display. They
+//! are called in the order they were attached, after the driver callback
+//! set before the first of them, which is restored once the last of them is
+//! dropped. Hooks may not be attached or dropped from within a hook.
+
+use crate::display::{Area, Display};
+use crate::Box;
+use core::ptr::{self, NonNull};
+use core::time::Duration;
+
+type DrvCb = unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t);
+type MonitorCb = unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t, u32, u32);
+type RounderCb = unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t, *mut lvgl_sys::lv_area_t);
+
+/// The driver callback a hook is called from, with the callback set before
+/// the first hook of the kind, which is still called before the hooks.
+#[derive(Clone, Copy)]
+enum Chained {
+    RenderStart(Option<DrvCb>),
+    RenderReady(Option<MonitorCb>),
+    ResolutionChanged(Option<DrvCb>),
+    Invalidate(Option<RounderCb>),
+}
+
+impl Chained {
+    fn same_kind(&self, other: &Chained) -> bool {
+        core::mem::discriminant(self) == core::mem::discriminant(other)
+    }
+}
+
+/// The arguments of a driver event, as passed to the closures.
+enum Args<'a> {
+    RenderStart,
+    RenderReady(Duration, u32),
+    ResolutionChanged(i16, i16),
+    Invalidate(&'a mut Area),
+}
+
+/// A hook, in the list of all hooks of all displays.
+struct Node {
+    driver: *mut lvgl_sys::lv_disp_drv_t,
+    disp: *mut lvgl_sys::lv_disp_t,
+    chained: Chained,
+    closure: *mut cty::c_void,
+    /// Calls `closure` with the arguments of the event.
+    call: unsafe fn(*mut cty::c_void, &mut Args),
+    /// Drops `closure`.
+    drop: unsafe fn(*mut cty::c_void),
+    next: Option<Box<Node>>,
+}
+
+static mut HOOKS: Option<Box<Node>> = None;
+
+/// Returns the first hook of the kind of `kind` on `driver`.
+unsafe fn first_of(driver: *mut lvgl_sys::lv_disp_drv_t, kind: Chained) -> Option<&'static Node> {
+    let mut node = (*ptr::addr_of!(HOOKS)).as_deref();
+    while let Some(n) = node {
+        if n.driver == driver && n.chained.same_kind(&kind) {
+            return Some(n);
+        }
+        node = n.next.as_deref();
+    }
+    None
+}
+
+/// Calls `first` and the hooks of the same kind on the same driver after
+/// it.
+unsafe fn call_hooks(first: &Node, args: &mut Args) {
+    let mut node = Some(first);
+    while let Some(n) = node {
+        if n.driver == first.driver && n.chained.same_kind(&first.chained) {
+            (n.call)(n.closure, args);
+        }
+        node = n.next.as_deref();
+    }
+}
+
+/// The rounder of `driver`, or the one `batch()` chains while it collects
+/// the invalidated areas.
+unsafe fn rounder_cb(driver: *mut lvgl_sys::lv_disp_drv_t) -> &'static mut Option<RounderCb> {
+    crate::functions::batched_rounder_cb(driver).unwrap_or(&mut (*driver).rounder_cb)
+}
+
+/// A closure called on a display event, see e.g.
+/// `Display::on_render_start()`. Detached again when dropped.
+pub struct DisplayHook {
+    node: NonNull<Node>,
+}
+
+impl DisplayHook {
+    /// Adds a hook of the kind of `kind` calling `closure` with `call`. The
+    /// first hook of a kind on a display installs the trampoline calling
+    /// them as the driver callback.
+    unsafe fn attach<F: 'static>(
+        display: &Display,
+        closure: F,
+        kind: Chained,
+        call: unsafe fn(*mut cty::c_void, &mut Args),
+    ) -> Self {
+        let disp = display.disp.as_ptr();
+        let driver = (*disp).driver;
+        let chained = match first_of(driver, kind) {
+            Some(first) => first.chained,
+            None => {
+                let driver = &mut *driver;
+                match kind {
+                    Chained::RenderStart(_) => Chained::RenderStart(
+                        driver.render_start_cb.replace(render_start_trampoline),
+                    ),
+                    Chained::RenderReady(_) => {
+                        Chained::RenderReady(driver.monitor_cb.replace(render_ready_trampoline))
+                    }
+                    Chained::ResolutionChanged(_) => Chained::ResolutionChanged(
+                        driver.drv_update_cb.replace(resolution_changed_trampoline),
+                    ),
+                    Chained::Invalidate(_) => {
+                        Chained::Invalidate(rounder_cb(driver).replace(invalidate_trampoline))
+                    }
+                }
+            }
+        };
+        let mut node = Box::new(Node {
+            driver,
+            disp,
+            chained,
+            closure: Box::into_raw(Box::new(closure)) as *mut _,
+            call,
+            drop: drop_closure::<F>,
+            next: None,
+        });
+        let hook = Self {
+            node: NonNull::from(&mut *node),
+        };
+        let mut link = &mut *ptr::addr_of_mut!(HOOKS);
+        while let Some(n) = link {
+            link = &mut n.next;
+        }
+        *link = Some(node);
+        hook
+    }
+}
+
+impl Drop for DisplayHook {
+    fn drop(&mut self) {
+        unsafe {
+            let mut link = &mut *ptr::addr_of_mut!(HOOKS);
+            while link
+                .as_deref()
+                .is_some_and(|n| !ptr::eq(n, self.node.as_ptr()))
+            {
+                link = &mut link.as_mut().unwrap().next;
+            }
+            let Some(mut node) = link.take() else {
+                return;
+            };
+            *link = node.next.take();
+            (node.drop)(node.closure);
+            if first_of(node.driver, node.chained).is_some() {
+                return;
+            }
+            let driver = &mut *node.driver;
+            match node.chained {
+                Chained::RenderStart(cb) => driver.render_start_cb = cb,
+                Chained::RenderReady(cb) => driver.monitor_cb = cb,
+                Chained::ResolutionChanged(cb) => driver.drv_update_cb = cb,
+                Chained::Invalidate(cb) => *rounder_cb(driver) = cb,
+            }
+        }
+    }
+}
+
+unsafe fn drop_closure<F>(closure: *mut cty::c_void) {
+    drop(Box::from_raw(closure as *mut F));
+}
+
+impl Display {
+    /// Calls `f` when LVGL starts rendering a frame, e.g. to wait for the
+    /// tearing effect (TE) signal of the panel before the first flush.
+    pub fn on_render_start<F: FnMut() + 'static>(&self, f: F) -> DisplayHook {
+        unsafe fn call<F: FnMut()>(closure: *mut cty::c_void, _: &mut Args) {
+            (*(closure as *mut F))()
+        }
+        unsafe { DisplayHook::attach(self, f, Chained::RenderStart(None), call::<F>) }
+    }
+
+    /// Calls `f` after a frame was rendered and flushed, with the time that
+    /// took and the number of pixels redrawn, e.g. to turn on the backlight
+    /// once the first frame is shown.
+    pub fn on_render_ready<F: FnMut(Duration, u32) + 'static>(&self, f: F) -> DisplayHook {
+        unsafe fn call<F: FnMut(Duration, u32)>(closure: *mut cty::c_void, args: &mut Args) {
+            if let Args::RenderReady(time, px) = *args {
+                (*(closure as *mut F))(time, px)
+            }
+        }
+        unsafe { DisplayHook::attach(self, f, Chained::RenderReady(None), call::<F>) }
+    }
+
+    /// Calls `f` with the new horizontal and vertical resolution when the
+    /// display is resized or rotated.
+    pub fn on_resolution_changed<F: FnMut(i16, i16) + 'static>(&self, f: F) -> DisplayHook {
+        unsafe fn call<F: FnMut(i16, i16)>(closure: *mut cty::c_void, args: &mut Args) {
+            if let Args::ResolutionChanged(hor_res, ver_res) = *args {
+                (*(closure as *mut F))(hor_res, ver_res)
+            }
+        }
+        unsafe { DisplayHook::attach(self, f, Chained::ResolutionChanged(None), call::<F>) }
+    }
+
+    /// Calls `f` with every area invalidated on the display. `f` may grow
+    /// the area, e.g. to whole pages or even coordinates for controllers
+    /// that can only be written in such units.
+    ///
+    /// This is the rounder of the display driver, so `f` is also called
+    /// while rendering, on each chunk of the invalidated areas that fits in
+    /// the draw buffer.
+    pub fn on_invalidate<F: FnMut(&mut Area) + 'static>(&self, f: F) -> DisplayHook {
+        unsafe fn call<F: FnMut(&mut Area)>(closure: *mut cty::c_void, args: &mut Args) {
+            if let Args::Invalidate(area) = args {
+                (*(closure as *mut F))(&mut **area)
+            }
+        }
+        unsafe { DisplayHook::attach(self, f, Chained::Invalidate(None), call::<F>) }
+    }
+}
+
+unsafe extern "C" fn render_start_trampoline(driver: *mut lvgl_sys::lv_disp_drv_t) {
+    let Some(first) = first_of(driver, Chained::RenderStart(None)) else {
+        return;
+    };
+    if let Chained::RenderStart(Some(chained)) = first.chained {
+        chained(driver);
+    }
+    call_hooks(first, &mut Args::RenderStart);
+}
+
+unsafe extern "C" fn render_ready_trampoline(
+    driver: *mut lvgl_sys::lv_disp_drv_t,
+    time: u32,
+    px: u32,
+) {
+    let Some(first) = first_of(driver, Chained::RenderReady(None)) else {
+        return;
+    };
+    if let Chained::RenderReady(Some(chained)) = first.chained {
+        chained(driver, time, px);
+    }
+    let time = Duration::from_millis(time.into());
+    call_hooks(first, &mut Args::RenderReady(time, px));
+}
+
+unsafe extern "C" fn resolution_changed_trampoline(driver: *mut lvgl_sys::lv_disp_drv_t) {
+    let Some(first) = first_of(driver, Chained::ResolutionChanged(None)) else {
+        return;
+    };
+    if let Chained::ResolutionChanged(Some(chained)) = first.chained {
+        chained(driver);
+    }
+    let hor_res = lvgl_sys::lv_disp_get_hor_res(first.disp) as i16;
+    let ver_res = lvgl_sys::lv_disp_get_ver_res(first.disp) as i16;
+    call_hooks(first, &mut Args::ResolutionChanged(hor_res, ver_res));
+}
+
+unsafe extern "C" fn invalidate_trampoline(
+    driver: *mut lvgl_sys::lv_disp_drv_t,
+    area: *mut lvgl_sys::lv_area_t,
+) {
+    let Some(first) = first_of(driver, Chained::Invalidate(None)) else {
+        return;
+    };
+    if let Chained::Invalidate(Some(chained)) = first.chained {
+        chained(driver, area);
+    }
+    let mut rust_area = Area::from(*area);
+    call_hooks(first, &mut Args::Invalidate(&mut rust_area));
+    (*area).x1 = rust_area.x1 as lvgl_sys::lv_coord_t;
+    (*area).y1 = rust_area.y1 as lvgl_sys::lv_coord_t;
+    (*area).x2 = rust_area.x2 as lvgl_sys::lv_coord_t;
+    (*area).y2 = rust_area.y2 as lvgl_sys::lv_coord_t;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::display::{DrawBuffer, Rotation};
+    use core::sync::atomic::{AtomicI32, AtomicU32, Ordering};
+
+    #[test]
+    fn hooks_see_display_events() {
+        crate::tests::initialize_test(false);
+        let buffer = DrawBuffer::<{ 240 * 24 }>::default();
+        let display = Display::register(buffer, 240, 120, |_| {}).unwrap();
+
+        static STARTED: AtomicU32 = AtomicU32::new(0);
+        static STARTED_TOO: AtomicU32 = AtomicU32::new(0);
+        static READY: AtomicU32 = AtomicU32::new(0);
+        static RESOLUTION: AtomicI32 = AtomicI32::new(0);
+        let start = display.on_render_start(|| {
+            STARTED.fetch_add(1, Ordering::SeqCst);
+        });
+        let start_too = display.on_render_start(|| {
+            STARTED_TOO.fetch_add(1, Ordering::SeqCst);
+        });
+        let _ready = display.on_render_ready(|_, px| {
+            READY.fetch_add(px, Ordering::SeqCst);
+        });
+        let _resolution = display.on_resolution_changed(|h, v| {
+            RESOLUTION.store(i32::from(h) << 16 | i32::from(v), Ordering::SeqCst);
+        });
+        // Invalidate whole rows only
+        let _invalidate = display.on_invalidate(|area| {
+            area.x1 = 0;
+            area.x2 = 239;
+        });
+
+        unsafe {
+            let mut area = lvgl_sys::lv_area_t {
+                x1: 10,
+                y1: 10,
+                x2: 20,
+                y2: 20,
+            };
+            lvgl_sys::lv_obj_invalidate(lvgl_sys::lv_disp_get_scr_act(display.disp.as_ptr()));
+            lvgl_sys::lv_refr_now(display.disp.as_ptr());
+            let before = READY.load(Ordering::SeqCst);
+            lvgl_sys::_lv_inv_area(display.disp.as_ptr(), &mut area);
+            lvgl_sys::lv_refr_now(display.disp.as_ptr());
+            assert_eq!(READY.load(Ordering::SeqCst) - before, 240 * 11);
+        }
+        assert!(STARTED.load(Ordering::SeqCst) >= 2);
+        assert_eq!(
+            STARTED.load(Ordering::SeqCst),
+            STARTED_TOO.load(Ordering::SeqCst)
+        );
+
+        display.set_rotation(Rotation::Deg90);
+        assert_eq!(RESOLUTION.load(Ordering::SeqCst), 120 << 16 | 240);
+
+        // The driver callback is restored once the last hook is dropped
+        let driver = unsafe { &*(*display.disp.as_ptr()).driver };
+        drop(start);
+        assert!(driver.render_start_cb.is_some());
+        drop(start_too);
+        assert!(driver.render_start_cb.is_none());
+    }
+
+    #[test]
+    fn invalidate_hooks_attach_during_batch() {
+        crate::tests::initialize_test(false);
+        let buffer = DrawBuffer::<{ 240 * 24 }>::default();
+        let display = Display::register(buffer, 240, 120, |_| {}).unwrap();
+        let driver = unsafe { (*display.disp.as_ptr()).driver };
+
+        let hook = crate::batch(|| display.on_invalidate(|_| {}));
+        assert!(unsafe { (*driver).rounder_cb }.is_some());
+        crate::batch(|| drop(hook));
+        assert!(unsafe { (*driver).rounder_cb }.is_none());
+    }
+}
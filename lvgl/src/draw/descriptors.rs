@@ -0,0 +1,198 @@
+use crate::font::Font;
+use crate::style::Opacity;
+use crate::{Color, TextAlign};
+use core::mem::MaybeUninit;
+
+macro_rules! draw_dsc {
+    ($(#[$attr:meta])* $name:ident, $raw:ident, $init:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy)]
+        #[repr(transparent)]
+        pub struct $name {
+            pub(crate) raw: lvgl_sys::$raw,
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                let raw = unsafe {
+                    let mut raw = MaybeUninit::<lvgl_sys::$raw>::uninit();
+                    lvgl_sys::$init(raw.as_mut_ptr());
+                    raw.assume_init()
+                };
+                Self { raw }
+            }
+        }
+
+        impl $name {
+            /// Returns the underlying descriptor.
+            pub fn raw(&self) -> &lvgl_sys::$raw {
+                &self.raw
+            }
+        }
+    };
+}
+
+draw_dsc!(
+    /// Describes how to draw a rectangle. Equivalent to `lv_draw_rect_dsc_t`.
+    RectDsc,
+    lv_draw_rect_dsc_t,
+    lv_draw_rect_dsc_init
+);
+
+draw_dsc!(
+    /// Describes how to draw text. Equivalent to `lv_draw_label_dsc_t`.
+    LabelDsc,
+    lv_draw_label_dsc_t,
+    lv_draw_label_dsc_init
+);
+
+draw_dsc!(
+    /// Describes how to draw a line. Equivalent to `lv_draw_line_dsc_t`.
+    LineDsc,
+    lv_draw_line_dsc_t,
+    lv_draw_line_dsc_init
+);
+
+draw_dsc!(
+    /// Describes how to draw an arc. Equivalent to `lv_draw_arc_dsc_t`.
+    ArcDsc,
+    lv_draw_arc_dsc_t,
+    lv_draw_arc_dsc_init
+);
+
+draw_dsc!(
+    /// Describes how to draw an image. Equivalent to `lv_draw_img_dsc_t`.
+    ImageDrawDsc,
+    lv_draw_img_dsc_t,
+    lv_draw_img_dsc_init
+);
+
+impl RectDsc {
+    /// Sets the corner radius.
+    pub fn set_radius(&mut self, radius: i16) -> &mut Self {
+        self.raw.radius = radius;
+        self
+    }
+
+    /// Sets the background color and opacity.
+    pub fn set_bg(&mut self, color: Color, opa: Opacity) -> &mut Self {
+        self.raw.bg_color = color.into();
+        self.raw.bg_opa = opa.into();
+        self
+    }
+
+    /// Sets the border color, width and opacity.
+    pub fn set_border(&mut self, color: Color, width: i16, opa: Opacity) -> &mut Self {
+        self.raw.border_color = color.into();
+        self.raw.border_width = width;
+        self.raw.border_opa = opa.into();
+        self
+    }
+}
+
+impl LabelDsc {
+    /// Sets the text color.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.raw.color = color.into();
+        self
+    }
+
+    /// Sets the font. Like with styles, the font is leaked so it stays valid
+    /// for as long as LVGL may use it.
+    pub fn set_font(&mut self, font: Font) -> &mut Self {
+        self.raw.font = font.into();
+        self
+    }
+
+    /// Sets the text alignment.
+    pub fn set_align(&mut self, align: TextAlign) -> &mut Self {
+        self.raw.align = align.into();
+        self
+    }
+
+    /// Sets the opacity.
+    pub fn set_opa(&mut self, opa: Opacity) -> &mut Self {
+        self.raw.opa = opa.into();
+        self
+    }
+}
+
+impl LineDsc {
+    /// Sets the line color.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.raw.color = color.into();
+        self
+    }
+
+    /// Sets the line width.
+    pub fn set_width(&mut self, width: i16) -> &mut Self {
+        self.raw.width = width;
+        self
+    }
+
+    /// Rounds both ends of the line.
+    pub fn set_rounded(&mut self, rounded: bool) -> &mut Self {
+        self.raw.set_round_start(rounded as u8);
+        self.raw.set_round_end(rounded as u8);
+        self
+    }
+
+    /// Sets the opacity.
+    pub fn set_opa(&mut self, opa: Opacity) -> &mut Self {
+        self.raw.opa = opa.into();
+        self
+    }
+}
+
+impl ArcDsc {
+    /// Sets the arc color.
+    pub fn set_color(&mut self, color: Color) -> &mut Self {
+        self.raw.color = color.into();
+        self
+    }
+
+    /// Sets the arc width.
+    pub fn set_width(&mut self, width: i16) -> &mut Self {
+        self.raw.width = width;
+        self
+    }
+
+    /// Rounds the ends of the arc.
+    pub fn set_rounded(&mut self, rounded: bool) -> &mut Self {
+        self.raw.set_rounded(rounded as u8);
+        self
+    }
+
+    /// Sets the opacity.
+    pub fn set_opa(&mut self, opa: Opacity) -> &mut Self {
+        self.raw.opa = opa.into();
+        self
+    }
+}
+
+impl ImageDrawDsc {
+    /// Sets the rotation in 0.1 degree units.
+    pub fn set_angle(&mut self, angle: u16) -> &mut Self {
+        self.raw.angle = angle;
+        self
+    }
+
+    /// Sets the zoom factor, 256 being the original size.
+    pub fn set_zoom(&mut self, zoom: u16) -> &mut Self {
+        self.raw.zoom = zoom;
+        self
+    }
+
+    /// Tints the image with `color`.
+    pub fn set_recolor(&mut self, color: Color, opa: Opacity) -> &mut Self {
+        self.raw.recolor = color.into();
+        self.raw.recolor_opa = opa.into();
+        self
+    }
+
+    /// Sets the opacity.
+    pub fn set_opa(&mut self, opa: Opacity) -> &mut Self {
+        self.raw.opa = opa.into();
+        self
+    }
+}
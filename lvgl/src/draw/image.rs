@@ -0,0 +1,135 @@
+use crate::{Box, LvError, LvResult};
+use core::marker::PhantomData;
+use core::pin::Pin;
+
+/// Pixel formats of image data. Equivalent to `lv_img_cf_t`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ColorFormat {
+    /// Pixels in the native `lv_color_t` format (`LV_COLOR_DEPTH` bits each).
+    TrueColor = lvgl_sys::LV_IMG_CF_TRUE_COLOR,
+    /// Native color pixels each followed by an 8-bit alpha value.
+    TrueColorAlpha = lvgl_sys::LV_IMG_CF_TRUE_COLOR_ALPHA,
+    /// Native color pixels where `LV_COLOR_CHROMA_KEY` is transparent.
+    TrueColorChromaKeyed = lvgl_sys::LV_IMG_CF_TRUE_COLOR_CHROMA_KEYED,
+    /// 2-color palette followed by 1 bit per pixel.
+    Indexed1Bit = lvgl_sys::LV_IMG_CF_INDEXED_1BIT,
+    /// 4-color palette followed by 2 bits per pixel.
+    Indexed2Bit = lvgl_sys::LV_IMG_CF_INDEXED_2BIT,
+    /// 16-color palette followed by 4 bits per pixel.
+    Indexed4Bit = lvgl_sys::LV_IMG_CF_INDEXED_4BIT,
+    /// 256-color palette followed by 8 bits per pixel.
+    Indexed8Bit = lvgl_sys::LV_IMG_CF_INDEXED_8BIT,
+    /// 1 bit of alpha per pixel; the color comes from the style.
+    Alpha1Bit = lvgl_sys::LV_IMG_CF_ALPHA_1BIT,
+    /// 2 bits of alpha per pixel; the color comes from the style.
+    Alpha2Bit = lvgl_sys::LV_IMG_CF_ALPHA_2BIT,
+    /// 4 bits of alpha per pixel; the color comes from the style.
+    Alpha4Bit = lvgl_sys::LV_IMG_CF_ALPHA_4BIT,
+    /// 8 bits of alpha per pixel; the color comes from the style.
+    Alpha8Bit = lvgl_sys::LV_IMG_CF_ALPHA_8BIT,
+}
+
+impl From<ColorFormat> for lvgl_sys::lv_img_cf_t {
+    fn from(value: ColorFormat) -> Self {
+        value as lvgl_sys::lv_img_cf_t
+    }
+}
+
+/// An image backed by a pixel buffer owned by Rust. Equivalent to an
+/// `lv_img_dsc_t`.
+///
+/// The descriptor is pinned in LVGL memory, and borrows its pixel data for
+/// its whole lifetime, so it can be handed to widgets such as `Img` without
+/// the data being freed underneath them.
+pub struct ImageDsc<'a> {
+    raw: Pin<Box<lvgl_sys::lv_img_dsc_t>>,
+    _data: PhantomData<&'a [u8]>,
+}
+
+impl<'a> ImageDsc<'a> {
+    /// Maximum width or height of an image, as limited by `lv_img_header_t`.
+    pub const MAX_SIZE: u32 = (1 << 11) - 1;
+
+    /// Creates an image descriptor from raw pixel data in the given format.
+    ///
+    /// Returns `LvError::InvalidArgument` if either dimension is zero or
+    /// larger than `ImageDsc::MAX_SIZE`, or if `data` is too short to hold a
+    /// `width` by `height` image in `format`.
+    pub fn from_raw(
+        width: u32,
+        height: u32,
+        format: ColorFormat,
+        data: &'a [u8],
+    ) -> LvResult<Self> {
+        if width == 0 || height == 0 || width > Self::MAX_SIZE || height > Self::MAX_SIZE {
+            return Err(LvError::InvalidArgument);
+        }
+        let data_size = unsafe {
+            lvgl_sys::lv_img_buf_get_img_size(
+                width as lvgl_sys::lv_coord_t,
+                height as lvgl_sys::lv_coord_t,
+                format.into(),
+            )
+        };
+        if (data.len() as u32) < data_size {
+            return Err(LvError::InvalidArgument);
+        }
+
+        let mut raw = lvgl_sys::lv_img_dsc_t::default();
+        raw.header.set_cf(format as u32);
+        raw.header.set_always_zero(0);
+        raw.header.set_w(width);
+        raw.header.set_h(height);
+        raw.data_size = data_size;
+        raw.data = data.as_ptr();
+
+        Ok(Self {
+            raw: Box::pin(raw),
+            _data: PhantomData,
+        })
+    }
+
+    /// Returns the width of the image in pixels.
+    pub fn width(&self) -> u32 {
+        self.raw.header.w()
+    }
+
+    /// Returns the height of the image in pixels.
+    pub fn height(&self) -> u32 {
+        self.raw.header.h()
+    }
+
+    /// Returns the size of the pixel data in bytes.
+    pub fn data_size(&self) -> u32 {
+        self.raw.data_size
+    }
+
+    /// Returns a pointer to the underlying `lv_img_dsc_t`.
+    pub fn raw(&self) -> *const lvgl_sys::lv_img_dsc_t {
+        &*self.raw as *const _
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn image_from_pixel_buffer() {
+        crate::tests::initialize_test(false);
+        let data = [0u8; 4 * 4 * 4];
+        let img = ImageDsc::from_raw(4, 4, ColorFormat::TrueColor, &data).unwrap();
+        assert_eq!(img.width(), 4);
+        assert_eq!(img.height(), 4);
+        assert!(img.data_size() as usize <= data.len());
+    }
+
+    #[test]
+    fn image_rejects_short_buffer() {
+        crate::tests::initialize_test(false);
+        let data = [0u8; 3];
+        assert!(ImageDsc::from_raw(4, 4, ColorFormat::TrueColor, &data).is_err());
+        assert!(ImageDsc::from_raw(0, 4, ColorFormat::Alpha8Bit, &data).is_err());
+    }
+}
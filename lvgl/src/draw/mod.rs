@@ -0,0 +1,12 @@
+//! Drawing-related types
+//!
+//! Descriptors used by LVGL when rendering, such as images built from pixel
+//! buffers owned by Rust code, and the descriptors describing how to draw
+//! shapes and text e.g. on a `Canvas`. The `part` module customizes how
+//! widgets are drawn from their draw events.
+
+mod descriptors;
+mod image;
+pub mod part;
+pub use descriptors::*;
+pub use image::*;
@@ -0,0 +1,256 @@
+//! Custom painting from draw events
+//!
+//! While drawing a widget, LVGL sends `DRAW_PART_BEGIN` and `DRAW_PART_END`
+//! around every part it draws, e.g. each cell of a table, each tick of a
+//! meter or each bar of a chart, and `DRAW_POST` when the widget and its
+//! children are done. `Widget::on_draw_part()` hands out the part being
+//! drawn as a `DrawPart`, whose descriptors can be changed before it is
+//! drawn, or drawn over afterwards:
+//!
+//! ```ignore
+//! chart.on_draw_part(|part| {
+//!     if part.stage() == DrawStage::Begin && part.is_part(Part::Items) {
+//!         if let Some(rect) = part.rect_dsc() {
+//!             rect.set_bg(Color::from_rgb((255, 0, 0)), Opacity::OPA_COVER);
+//!         }
+//!     }
+//! });
+//! ```
+//!
+//! `Widget::on_draw_post()` draws on top of the whole widget instead.
+
+use crate::draw::{ArcDsc, ImageDrawDsc, LabelDsc, LineDsc, RectDsc};
+use crate::point::Point;
+use crate::{Area, Part};
+use core::ptr;
+use cstr_core::CStr;
+
+/// Whether a `DrawPart` is about to be drawn or was just drawn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DrawStage {
+    /// `DRAW_PART_BEGIN`: the descriptors may still be changed.
+    Begin,
+    /// `DRAW_PART_END`: the part was drawn, and may be drawn over.
+    End,
+}
+
+/// A part of a widget being drawn, see the module documentation. Which
+/// descriptors are set depends on the widget and the part.
+pub struct DrawPart<'a> {
+    dsc: &'a mut lvgl_sys::lv_obj_draw_part_dsc_t,
+    stage: DrawStage,
+}
+
+impl DrawPart<'_> {
+    /// Returns whether the part is about to be drawn or was just drawn.
+    pub fn stage(&self) -> DrawStage {
+        self.stage
+    }
+
+    /// Returns whether this is `part` of the widget.
+    pub fn is_part(&self, part: Part) -> bool {
+        self.dsc.part == part.into()
+    }
+
+    /// Returns the index of the drawn item within the part, e.g. the cell of
+    /// a table (row by row) or the point of a chart series.
+    pub fn id(&self) -> u32 {
+        self.dsc.id
+    }
+
+    /// Returns the widget-specific kind of the part, e.g.
+    /// `LV_METER_DRAW_PART_TICK` or `LV_CHART_DRAW_PART_BAR`.
+    pub fn kind(&self) -> u32 {
+        self.dsc.type_
+    }
+
+    /// Returns the area the part is drawn in, in absolute coordinates.
+    pub fn draw_area(&self) -> Option<Area> {
+        unsafe { self.dsc.draw_area.as_ref() }.map(|&area| area.into())
+    }
+
+    /// Returns the area drawing is clipped to.
+    pub fn clip_area(&self) -> Area {
+        unsafe { (*(*self.dsc.draw_ctx).clip_area).into() }
+    }
+
+    /// Returns the end points of lines, e.g. meter ticks or chart lines.
+    pub fn points(&self) -> Option<(Point, Point)> {
+        let point = |p: *const lvgl_sys::lv_point_t| {
+            unsafe { p.as_ref() }.map(|p| Point::new(p.x.into(), p.y.into()))
+        };
+        point(self.dsc.p1).zip(point(self.dsc.p2))
+    }
+
+    /// Returns the value the part shows, e.g. of a meter tick label.
+    pub fn value(&self) -> i32 {
+        self.dsc.value
+    }
+
+    /// Returns the text about to be drawn, e.g. of a meter tick label.
+    pub fn text(&self) -> Option<&CStr> {
+        if self.dsc.text.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(self.dsc.text) })
+        }
+    }
+
+    /// Returns the descriptor rectangles, e.g. cells or bars, are drawn
+    /// with.
+    pub fn rect_dsc(&mut self) -> Option<&mut RectDsc> {
+        unsafe { (self.dsc.rect_dsc as *mut RectDsc).as_mut() }
+    }
+
+    /// Returns the descriptor text is drawn with.
+    pub fn label_dsc(&mut self) -> Option<&mut LabelDsc> {
+        unsafe { (self.dsc.label_dsc as *mut LabelDsc).as_mut() }
+    }
+
+    /// Returns the descriptor lines, e.g. ticks, are drawn with.
+    pub fn line_dsc(&mut self) -> Option<&mut LineDsc> {
+        unsafe { (self.dsc.line_dsc as *mut LineDsc).as_mut() }
+    }
+
+    /// Returns the descriptor arcs are drawn with.
+    pub fn arc_dsc(&mut self) -> Option<&mut ArcDsc> {
+        unsafe { (self.dsc.arc_dsc as *mut ArcDsc).as_mut() }
+    }
+
+    /// Returns the descriptor images, e.g. meter needles, are drawn with.
+    pub fn img_dsc(&mut self) -> Option<&mut ImageDrawDsc> {
+        unsafe { (self.dsc.img_dsc as *mut ImageDrawDsc).as_mut() }
+    }
+
+    /// Returns the context to draw over the part with, in
+    /// `DrawStage::End`.
+    pub fn ctx(&mut self) -> DrawCtx<'_> {
+        DrawCtx {
+            ctx: unsafe { &mut *self.dsc.draw_ctx },
+        }
+    }
+}
+
+/// Draws into the area of the display being rendered, clipped to
+/// `clip_area()`.
+pub struct DrawCtx<'a> {
+    ctx: &'a mut lvgl_sys::lv_draw_ctx_t,
+}
+
+impl DrawCtx<'_> {
+    /// Returns the area drawing is clipped to.
+    pub fn clip_area(&self) -> Area {
+        unsafe { (*self.ctx.clip_area).into() }
+    }
+
+    /// Draws a rectangle covering `area`.
+    pub fn draw_rect(&mut self, dsc: &RectDsc, area: Area) {
+        unsafe { lvgl_sys::lv_draw_rect(self.ctx, dsc.raw(), &raw_area(area)) }
+    }
+
+    /// Draws `text` within `area`.
+    pub fn draw_label(&mut self, dsc: &LabelDsc, area: Area, text: &CStr) {
+        unsafe {
+            lvgl_sys::lv_draw_label(
+                self.ctx,
+                dsc.raw(),
+                &raw_area(area),
+                text.as_ptr(),
+                ptr::null_mut(),
+            )
+        }
+    }
+
+    /// Draws a line from `p1` to `p2`.
+    pub fn draw_line(&mut self, dsc: &LineDsc, p1: Point, p2: Point) {
+        let point = |p: Point| lvgl_sys::lv_point_t {
+            x: p.x as lvgl_sys::lv_coord_t,
+            y: p.y as lvgl_sys::lv_coord_t,
+        };
+        unsafe { lvgl_sys::lv_draw_line(self.ctx, dsc.raw(), &point(p1), &point(p2)) }
+    }
+}
+
+fn raw_area(area: Area) -> lvgl_sys::lv_area_t {
+    lvgl_sys::lv_area_t {
+        x1: area.x1 as lvgl_sys::lv_coord_t,
+        y1: area.y1 as lvgl_sys::lv_coord_t,
+        x2: area.x2 as lvgl_sys::lv_coord_t,
+        y2: area.y2 as lvgl_sys::lv_coord_t,
+    }
+}
+
+pub(crate) unsafe extern "C" fn draw_part_cb<F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(&mut DrawPart),
+{
+    let event = &mut *event;
+    let Some(f) = crate::support::event_closure::<F>(event) else {
+        return;
+    };
+    let stage = match event.code {
+        lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_PART_BEGIN => DrawStage::Begin,
+        lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_PART_END => DrawStage::End,
+        _ => return,
+    };
+    let dsc = &mut *(event.param as *mut lvgl_sys::lv_obj_draw_part_dsc_t);
+    f(&mut DrawPart { dsc, stage });
+}
+
+pub(crate) unsafe extern "C" fn draw_post_cb<F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(&mut DrawCtx),
+{
+    let event = &mut *event;
+    let Some(f) = crate::support::event_closure::<F>(event) else {
+        return;
+    };
+    if event.code == lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST {
+        let ctx = &mut *(event.param as *mut lvgl_sys::lv_draw_ctx_t);
+        f(&mut DrawCtx { ctx });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Obj, Widget};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn draw_events_reach_callbacks() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut obj = Obj::create(&screen).unwrap();
+        obj.set_size(40, 40);
+        static BEGIN: AtomicU32 = AtomicU32::new(0);
+        static END: AtomicU32 = AtomicU32::new(0);
+        static POST: AtomicU32 = AtomicU32::new(0);
+        obj.on_draw_part(|part| {
+            if !part.is_part(Part::Main) {
+                return;
+            }
+            match part.stage() {
+                DrawStage::Begin => {
+                    assert!(part.rect_dsc().is_some());
+                    BEGIN.fetch_add(1, Ordering::SeqCst);
+                }
+                DrawStage::End => {
+                    END.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        });
+        obj.on_draw_post(|ctx| {
+            let area = ctx.clip_area();
+            assert!(area.width() > 0 && area.height() > 0);
+            POST.fetch_add(1, Ordering::SeqCst);
+        });
+
+        display.refresh_now();
+        assert!(BEGIN.load(Ordering::SeqCst) > 0);
+        assert_eq!(BEGIN.load(Ordering::SeqCst), END.load(Ordering::SeqCst));
+        assert!(POST.load(Ordering::SeqCst) > 0);
+        obj.del();
+    }
+}
@@ -0,0 +1,303 @@
+//! Running LVGL on ESP-IDF
+//!
+//! With the `esp-idf` feature, displays set up with IDF's `esp_lcd` driver
+//! can be registered as they are, LVGL's tick is driven by `esp_timer` and
+//! touch controllers of the `esp_lcd_touch` component serve as pointer
+//! input devices:
+//!
+//! ```ignore
+//! use lvgl::esp_idf::{self, EspLcd};
+//!
+//! // `panel` and `io` as set up with esp_lcd_new_panel_io_spi() and e.g.
+//! // esp_lcd_new_panel_st7789()
+//! let buffer = DrawBuffer::<{ 320 * 24 }>::default();
+//! let display = unsafe { EspLcd::new(panel, io) }.register(buffer, 320, 240)?;
+//! let _touch = unsafe { esp_idf::register_touch(touch, &display)? };
+//! let _tick = esp_idf::start_tick(Duration::from_millis(5))?;
+//! loop {
+//!     lvgl::task_handler();
+//!     std::thread::sleep(Duration::from_millis(5));
+//! }
+//! ```
+//!
+//! Pixels are sent as LVGL renders them, so LVGL's color depth must match
+//! the panel's. Most SPI panels also need `color_16_swap`.
+//!
+//! # Building
+//!
+//! Build as an `esp-idf-sys` binary crate. `register_touch()` additionally
+//! links against the `espressif/esp_lcd_touch` component and a driver for
+//! the touch controller, added e.g. through `extra_components` in the
+//! crate's `package.metadata.esp-idf-sys`.
+
+use crate::input_device::pointer::{Pointer, PointerInputData};
+use crate::input_device::InputDriver;
+use crate::{Display, DisplayError, DrawBuffer, LvResult, Point};
+use core::cell::Cell;
+use core::ffi::c_void;
+use core::ops::Deref;
+use core::ptr;
+use esp_idf_sys::{
+    esp, esp_lcd_panel_draw_bitmap, esp_lcd_panel_handle_t, esp_lcd_panel_io_callbacks_t,
+    esp_lcd_panel_io_event_data_t, esp_lcd_panel_io_handle_t,
+    esp_lcd_panel_io_register_event_callbacks,
+};
+
+/// Handle of a touch controller of the `esp_lcd_touch` component.
+#[allow(non_camel_case_types)]
+pub type esp_lcd_touch_handle_t = *mut c_void;
+
+extern "C" {
+    // The `espressif/esp_lcd_touch` component, which `esp-idf-sys` has no
+    // bindings for
+    fn esp_lcd_touch_read_data(tp: esp_lcd_touch_handle_t) -> esp_idf_sys::esp_err_t;
+    fn esp_lcd_touch_get_coordinates(
+        tp: esp_lcd_touch_handle_t,
+        x: *mut u16,
+        y: *mut u16,
+        strength: *mut u16,
+        point_num: *mut u8,
+        max_point_num: u8,
+    ) -> bool;
+}
+
+/// A panel set up with `esp_lcd`, and the IO it is connected through.
+pub struct EspLcd {
+    panel: esp_lcd_panel_handle_t,
+    io: esp_lcd_panel_io_handle_t,
+}
+
+impl EspLcd {
+    /// Wraps the handles of a panel that was set up and turned on.
+    ///
+    /// # Safety
+    ///
+    /// Both handles must be valid, and stay valid while the display
+    /// registered with them exists.
+    pub unsafe fn new(panel: esp_lcd_panel_handle_t, io: esp_lcd_panel_io_handle_t) -> Self {
+        Self { panel, io }
+    }
+
+    /// Registers a display drawing into the panel with
+    /// `esp_lcd_panel_draw_bitmap()`.
+    ///
+    /// Transfers are done by DMA in the background, and LVGL is told the
+    /// draw buffer is free again from the IO's `on_color_trans_done`
+    /// callback, which this takes over until the returned display is
+    /// dropped.
+    pub fn register<const N: usize>(
+        self,
+        draw_buffer: DrawBuffer<N>,
+        hor_res: u32,
+        ver_res: u32,
+    ) -> Result<EspDisplay, DisplayError> {
+        let display = unsafe {
+            Display::register_raw(
+                draw_buffer,
+                hor_res,
+                ver_res,
+                Some(flush),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )?
+        };
+        let driver = unsafe { (*display.disp.as_ptr()).driver };
+        unsafe { (*driver).user_data = self.panel as *mut c_void };
+        let callbacks = esp_lcd_panel_io_callbacks_t {
+            on_color_trans_done: Some(color_trans_done),
+        };
+        let registered = esp!(unsafe {
+            esp_lcd_panel_io_register_event_callbacks(self.io, &callbacks, driver as *mut c_void)
+        });
+        if registered.is_err() {
+            unsafe { crate::display::disp_remove(display.disp.as_ptr()) };
+            return Err(DisplayError::FailedToRegister);
+        }
+        Ok(EspDisplay {
+            display,
+            io: self.io,
+        })
+    }
+}
+
+/// A display registered by `EspLcd::register()`, used through `Deref` like
+/// any `Display`. Dropping it waits for the transfer in progress, restores
+/// the IO's callbacks and removes the display from LVGL, deleting its
+/// screens.
+pub struct EspDisplay {
+    display: Display,
+    io: esp_lcd_panel_io_handle_t,
+}
+
+impl Deref for EspDisplay {
+    type Target = Display;
+
+    fn deref(&self) -> &Display {
+        &self.display
+    }
+}
+
+impl Drop for EspDisplay {
+    fn drop(&mut self) {
+        let disp = self.display.disp.as_ptr();
+        unsafe {
+            let draw_buf = (*(*disp).driver).draw_buf;
+            while ptr::read_volatile(&(*draw_buf).flushing) != 0 {
+                core::hint::spin_loop();
+            }
+            let callbacks = esp_lcd_panel_io_callbacks_t {
+                on_color_trans_done: None,
+            };
+            esp_lcd_panel_io_register_event_callbacks(self.io, &callbacks, ptr::null_mut());
+            crate::display::disp_remove(disp);
+        }
+    }
+}
+
+unsafe extern "C" fn flush(
+    disp_drv: *mut lvgl_sys::lv_disp_drv_t,
+    area: *const lvgl_sys::lv_area_t,
+    color_p: *mut lvgl_sys::lv_color_t,
+) {
+    let panel = (*disp_drv).user_data as esp_lcd_panel_handle_t;
+    let area = &*area;
+    // The end coordinates are exclusive
+    let result = esp_lcd_panel_draw_bitmap(
+        panel,
+        area.x1.into(),
+        area.y1.into(),
+        i32::from(area.x2) + 1,
+        i32::from(area.y2) + 1,
+        color_p as *const c_void,
+    );
+    if esp!(result).is_err() {
+        // No transfer was started, so `color_trans_done()` won't be called
+        lvgl_sys::lv_disp_flush_ready(disp_drv);
+    }
+}
+
+unsafe extern "C" fn color_trans_done(
+    _io: esp_lcd_panel_io_handle_t,
+    _data: *mut esp_lcd_panel_io_event_data_t,
+    disp_drv: *mut c_void,
+) -> bool {
+    // Only clears the flags LVGL waits on, so it is safe in an interrupt
+    lvgl_sys::lv_disp_flush_ready(disp_drv as *mut lvgl_sys::lv_disp_drv_t);
+    // No higher priority task was woken
+    false
+}
+
+/// Registers a pointer input device reading the first touch point of an
+/// `esp_lcd_touch` controller. Its coordinates must already be transformed
+/// to the display's, see `esp_lcd_touch_config_t::flags`.
+///
+/// # Safety
+///
+/// `touch` must be a valid handle, and stay valid while the returned input
+/// device exists.
+pub unsafe fn register_touch(
+    touch: esp_lcd_touch_handle_t,
+    display: &Display,
+) -> LvResult<Pointer> {
+    let mut last = Point::new(0, 0);
+    Pointer::register(
+        move || {
+            let (mut x, mut y, mut count) = (0, 0, 0);
+            // Safety: `touch` is valid while the input device exists
+            let touched = unsafe {
+                esp_lcd_touch_read_data(touch);
+                esp_lcd_touch_get_coordinates(touch, &mut x, &mut y, ptr::null_mut(), &mut count, 1)
+            };
+            if touched && count > 0 {
+                last = Point::new(x.into(), y.into());
+                PointerInputData::Touch(last).pressed().once()
+            } else {
+                // Released where the touch was last seen
+                PointerInputData::Touch(last).released().once()
+            }
+        },
+        display,
+    )
+}
+
+/// An `esp_timer` increasing LVGL's tick, see `start_tick()`. Stopped when
+/// dropped.
+#[cfg(not(feature = "rust_timer"))]
+pub struct TickTimer {
+    timer: esp_idf_sys::esp_timer_handle_t,
+    // Passed to the timer callback, so freed only after the timer
+    _tick: crate::Box<Tick>,
+}
+
+#[cfg(not(feature = "rust_timer"))]
+struct Tick {
+    period_us: u64,
+    /// Microseconds not yet added to LVGL's tick.
+    carry_us: Cell<u64>,
+}
+
+/// Calls `lv_tick_inc()` every `period` from the `esp_timer` task, so the
+/// main loop only has to call `task_handler()`. Periods need not be whole
+/// milliseconds: the remainder is carried over to the next call.
+#[cfg(not(feature = "rust_timer"))]
+pub fn start_tick(period: core::time::Duration) -> Result<TickTimer, esp_idf_sys::EspError> {
+    unsafe extern "C" fn tick(tick: *mut c_void) {
+        let tick = &*(tick as *const Tick);
+        let elapsed = tick.carry_us.get() + tick.period_us;
+        tick.carry_us.set(elapsed % 1000);
+        lvgl_sys::lv_tick_inc((elapsed / 1000) as u32);
+    }
+
+    let period_us = period.as_micros() as u64;
+    let mut state = crate::Box::new(Tick {
+        period_us,
+        carry_us: Cell::new(0),
+    });
+    let args = esp_idf_sys::esp_timer_create_args_t {
+        callback: Some(tick),
+        arg: &mut *state as *mut Tick as *mut c_void,
+        dispatch_method: esp_idf_sys::esp_timer_dispatch_t_ESP_TIMER_TASK,
+        name: b"lv_tick\0".as_ptr() as *const _,
+        skip_unhandled_events: true,
+    };
+    let mut timer = ptr::null_mut();
+    unsafe {
+        esp!(esp_idf_sys::esp_timer_create(&args, &mut timer))?;
+        let tick = TickTimer {
+            timer,
+            _tick: state,
+        };
+        esp!(esp_idf_sys::esp_timer_start_periodic(timer, period_us))?;
+        Ok(tick)
+    }
+}
+
+#[cfg(not(feature = "rust_timer"))]
+impl Drop for TickTimer {
+    fn drop(&mut self) {
+        unsafe {
+            esp_idf_sys::esp_timer_stop(self.timer);
+            esp_idf_sys::esp_timer_delete(self.timer);
+        }
+    }
+}
+
+/// Milliseconds since boot, read from `esp_timer`. With `rust_timer`,
+/// register it with `timer::set_tick_source()` instead of starting a
+/// `TickTimer`.
+#[cfg(feature = "rust_timer")]
+pub struct EspTicks;
+
+#[cfg(feature = "rust_timer")]
+impl crate::timer::TickSource for EspTicks {
+    fn now_ms(&self) -> u32 {
+        (unsafe { esp_idf_sys::esp_timer_get_time() } / 1000) as u32
+    }
+}
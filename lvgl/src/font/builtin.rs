@@ -1,88 +1,52 @@
 use super::Font;
+use paste::paste;
+
+/// Defines a constructor for a built-in font. It is available if the font is
+/// enabled through its cargo feature, or on nightly if the font happens to be
+/// enabled in `lv_conf.h`.
+macro_rules! builtin_font {
+    ($name:ident, $feature:literal) => {
+        paste! {
+            #[cfg(feature = $feature)]
+            pub fn $name() -> Self {
+                unsafe { Self::new_raw(lvgl_sys::[<lv_font_ $name>]) }
+            }
+
+            #[cfg(all(feature = "nightly", not(feature = $feature)))]
+            #[cfg_accessible(lvgl_sys::[<lv_font_ $name>])]
+            pub fn $name() -> Self {
+                unsafe { Self::new_raw(lvgl_sys::[<lv_font_ $name>]) }
+            }
+        }
+    };
+}
 
 impl Font {
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_8)]
-    pub fn montserrat_8() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_8) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_10)]
-    pub fn montserrat_10() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_10) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_12)]
-    pub fn montserrat_12() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_12) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_14)]
-    pub fn montserrat_14() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_14) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_16)]
-    pub fn montserrat_16() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_16) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_18)]
-    pub fn montserrat_18() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_18) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_20)]
-    pub fn montserrat_20() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_20) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_22)]
-    pub fn montserrat_22() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_22) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_24)]
-    pub fn montserrat_24() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_24) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_26)]
-    pub fn montserrat_26() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_26) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_28)]
-    pub fn montserrat_28() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_28) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_30)]
-    pub fn montserrat_30() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_30) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_32)]
-    pub fn montserrat_32() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_32) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_34)]
-    pub fn montserrat_34() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_34) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_36)]
-    pub fn montserrat_36() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_36) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_38)]
-    pub fn montserrat_38() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_38) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_40)]
-    pub fn montserrat_40() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_40) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_42)]
-    pub fn montserrat_42() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_42) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_44)]
-    pub fn montserrat_44() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_44) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_46)]
-    pub fn montserrat_46() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_46) }
-    }
-    #[cfg_accessible(lvgl_sys::lv_font_montserrat_48)]
-    pub fn montserrat_48() -> Self {
-        unsafe { Self::new_raw(lvgl_sys::lv_font_montserrat_48) }
-    }
+    builtin_font!(montserrat_8, "font_montserrat_8");
+    builtin_font!(montserrat_10, "font_montserrat_10");
+    builtin_font!(montserrat_12, "font_montserrat_12");
+    builtin_font!(montserrat_14, "font_montserrat_14");
+    builtin_font!(montserrat_16, "font_montserrat_16");
+    builtin_font!(montserrat_18, "font_montserrat_18");
+    builtin_font!(montserrat_20, "font_montserrat_20");
+    builtin_font!(montserrat_22, "font_montserrat_22");
+    builtin_font!(montserrat_24, "font_montserrat_24");
+    builtin_font!(montserrat_26, "font_montserrat_26");
+    builtin_font!(montserrat_28, "font_montserrat_28");
+    builtin_font!(montserrat_30, "font_montserrat_30");
+    builtin_font!(montserrat_32, "font_montserrat_32");
+    builtin_font!(montserrat_34, "font_montserrat_34");
+    builtin_font!(montserrat_36, "font_montserrat_36");
+    builtin_font!(montserrat_38, "font_montserrat_38");
+    builtin_font!(montserrat_40, "font_montserrat_40");
+    builtin_font!(montserrat_42, "font_montserrat_42");
+    builtin_font!(montserrat_44, "font_montserrat_44");
+    builtin_font!(montserrat_46, "font_montserrat_46");
+    builtin_font!(montserrat_48, "font_montserrat_48");
+    builtin_font!(montserrat_12_subpx, "font_montserrat_12_subpx");
+    builtin_font!(montserrat_28_compressed, "font_montserrat_28_compressed");
+    builtin_font!(dejavu_16_persian_hebrew, "font_dejavu_16_persian_hebrew");
+    builtin_font!(simsun_16_cjk, "font_simsun_16_cjk");
+    builtin_font!(unscii_8, "font_unscii_8");
+    builtin_font!(unscii_16, "font_unscii_16");
 }
@@ -0,0 +1,123 @@
+use super::Font;
+use crate::{LvError, LvResult};
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use cstr_core::CStr;
+
+static mut FREETYPE_INIT: bool = false;
+
+bitflags! {
+    /// Synthetic styles FreeType can apply to a font face.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct FontStyle: u16 {
+        const NORMAL = lvgl_sys::LV_FT_FONT_STYLE_NORMAL as u16;
+        const ITALIC = lvgl_sys::LV_FT_FONT_STYLE_ITALIC as u16;
+        const BOLD = lvgl_sys::LV_FT_FONT_STYLE_BOLD as u16;
+    }
+}
+
+/// A font rendered at runtime from a TrueType/OpenType file by FreeType.
+/// Glyphs are rasterized on demand, so any size can be created from a single
+/// font file.
+///
+/// The font is destroyed when dropped. Use `font()` to apply it to styles.
+pub struct TtfFont<'a> {
+    raw: NonNull<lvgl_sys::lv_font_t>,
+    _data: PhantomData<&'a [u8]>,
+}
+
+impl<'a> TtfFont<'a> {
+    /// Creates a font of `size` pixels from TTF data in memory. FreeType
+    /// reads glyphs from `data` lazily, so it must outlive the font.
+    pub fn new(data: &'a [u8], size: u16) -> LvResult<Self> {
+        Self::with_style(data, size, FontStyle::NORMAL)
+    }
+
+    /// Like `new()`, applying the given synthetic style.
+    pub fn with_style(data: &'a [u8], size: u16, style: FontStyle) -> LvResult<Self> {
+        let name = b"mem\0";
+        Self::init(
+            name.as_ptr() as *const cty::c_char,
+            data.as_ptr() as *const cty::c_void,
+            data.len(),
+            size,
+            style,
+        )
+    }
+
+    fn init(
+        name: *const cty::c_char,
+        mem: *const cty::c_void,
+        mem_size: usize,
+        size: u16,
+        style: FontStyle,
+    ) -> LvResult<Self> {
+        if size == 0 {
+            return Err(LvError::InvalidArgument);
+        }
+        unsafe {
+            if !FREETYPE_INIT {
+                // Zeroes select the cache sizes configured in lv_conf.h
+                if !lvgl_sys::lv_freetype_init(0, 0, 0) {
+                    return Err(LvError::Uninitialized);
+                }
+                FREETYPE_INIT = true;
+            }
+            let mut info = lvgl_sys::lv_ft_info_t {
+                name,
+                mem,
+                mem_size: mem_size as _,
+                font: ptr::null_mut(),
+                weight: size,
+                style: style.bits(),
+            };
+            if !lvgl_sys::lv_ft_font_init(&mut info) {
+                return Err(LvError::InvalidArgument);
+            }
+            match NonNull::new(info.font) {
+                Some(raw) => Ok(Self {
+                    raw,
+                    _data: PhantomData,
+                }),
+                None => Err(LvError::LvOOMemory),
+            }
+        }
+    }
+
+    /// Returns a `Font` that can be applied to styles.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Font` refers to glyph caches owned by this `TtfFont`,
+    /// which must therefore outlive every style and object using it.
+    pub unsafe fn font(&self) -> Font {
+        Font::new_raw(*self.raw.as_ptr())
+    }
+
+    /// Returns the line height of the font in pixels.
+    pub fn line_height(&self) -> i16 {
+        unsafe { self.raw.as_ref().line_height }
+    }
+}
+
+impl TtfFont<'static> {
+    /// Creates a font of `size` pixels from a file on the host file system,
+    /// e.g. `/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf`. Note that the
+    /// path is opened by FreeType directly and not through LVGL's drivers.
+    pub fn from_file(path: &'static CStr, size: u16, style: FontStyle) -> LvResult<Self> {
+        Self::init(path.as_ptr(), ptr::null(), 0, size, style)
+    }
+
+    /// Keeps the font alive forever and returns a `Font` for it.
+    pub fn leak(self) -> Font {
+        let font = unsafe { self.font() };
+        core::mem::forget(self);
+        font
+    }
+}
+
+impl Drop for TtfFont<'_> {
+    fn drop(&mut self) {
+        unsafe { lvgl_sys::lv_ft_font_destroy(self.raw.as_ptr()) }
+    }
+}
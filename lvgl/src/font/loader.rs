@@ -0,0 +1,183 @@
+use super::Font;
+use crate::{Box, LvError, LvResult};
+use core::mem::MaybeUninit;
+use core::ptr::{self, NonNull};
+use cstr_core::CStr;
+
+/// Drive letter of the internal file system driver used to feed in-memory
+/// fonts to `lv_font_load()`.
+const MEM_DRIVE_LETTER: u8 = b'~';
+
+static mut MEM_DRV: MaybeUninit<lvgl_sys::lv_fs_drv_t> = MaybeUninit::uninit();
+static mut MEM_SOURCE: &[u8] = &[];
+
+struct MemFile {
+    data: &'static [u8],
+    pos: usize,
+}
+
+/// A font loaded at runtime from a binary font file, as produced by the
+/// [official online converter] or `lv_font_conv` with `--format bin`.
+///
+/// The font is freed when dropped. Use `font()` to apply it to styles.
+///
+/// [official online converter]: https://lvgl.io/tools/fontconverter
+pub struct LoadedFont {
+    raw: NonNull<lvgl_sys::lv_font_t>,
+}
+
+impl Font {
+    /// Loads a binary font through LVGL's file system layer, e.g.
+    /// `Font::load_from_file(cstr!("S:/fonts/roboto_24.bin"))`. The drive
+    /// letter must belong to a registered file system driver.
+    pub fn load_from_file(path: &CStr) -> LvResult<LoadedFont> {
+        let raw = unsafe { lvgl_sys::lv_font_load(path.as_ptr()) };
+        match NonNull::new(raw) {
+            Some(raw) => Ok(LoadedFont { raw }),
+            None => Err(LvError::InvalidReference),
+        }
+    }
+
+    /// Loads a binary font from memory, e.g. data embedded in the firmware
+    /// with `include_bytes!()`. The data is copied, so it only needs to live
+    /// for the duration of the call.
+    pub fn load_from_bytes(data: &[u8]) -> LvResult<LoadedFont> {
+        unsafe {
+            register_mem_drv();
+            // lv_font_load() reads the whole file before returning, so the
+            // borrow never escapes this function
+            MEM_SOURCE = core::slice::from_raw_parts(data.as_ptr(), data.len());
+            let path = [MEM_DRIVE_LETTER, b':', b'f', b'o', b'n', b't', 0];
+            let raw = lvgl_sys::lv_font_load(path.as_ptr() as *const cty::c_char);
+            MEM_SOURCE = &[];
+            match NonNull::new(raw) {
+                Some(raw) => Ok(LoadedFont { raw }),
+                None => Err(LvError::InvalidArgument),
+            }
+        }
+    }
+}
+
+impl LoadedFont {
+    /// Returns a `Font` that can be applied to styles.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Font` shares the glyph data of this `LoadedFont`, which
+    /// must therefore outlive every style and object using it. Use `leak()`
+    /// for fonts that are needed for the rest of the program.
+    pub unsafe fn font(&self) -> Font {
+        Font::new_raw(*self.raw.as_ptr())
+    }
+
+    /// Keeps the font loaded forever and returns a `Font` for it.
+    pub fn leak(self) -> Font {
+        let font = unsafe { self.font() };
+        core::mem::forget(self);
+        font
+    }
+
+    /// Returns the line height of the font in pixels.
+    pub fn line_height(&self) -> i16 {
+        unsafe { self.raw.as_ref().line_height }
+    }
+}
+
+impl Drop for LoadedFont {
+    fn drop(&mut self) {
+        unsafe { lvgl_sys::lv_font_free(self.raw.as_ptr()) }
+    }
+}
+
+/// Registers the in-memory file system driver, unless LVGL already knows it
+/// (it is forgotten when LVGL is deinitialized).
+unsafe fn register_mem_drv() {
+    if !lvgl_sys::lv_fs_get_drv(MEM_DRIVE_LETTER as cty::c_char).is_null() {
+        return;
+    }
+    let drv = ptr::addr_of_mut!(MEM_DRV) as *mut lvgl_sys::lv_fs_drv_t;
+    lvgl_sys::lv_fs_drv_init(drv);
+    let drv = &mut *drv;
+    drv.letter = MEM_DRIVE_LETTER as cty::c_char;
+    drv.open_cb = Some(mem_open);
+    drv.close_cb = Some(mem_close);
+    drv.read_cb = Some(mem_read);
+    drv.seek_cb = Some(mem_seek);
+    drv.tell_cb = Some(mem_tell);
+    lvgl_sys::lv_fs_drv_register(drv);
+}
+
+unsafe extern "C" fn mem_open(
+    _drv: *mut lvgl_sys::lv_fs_drv_t,
+    _path: *const cty::c_char,
+    _mode: lvgl_sys::lv_fs_mode_t,
+) -> *mut cty::c_void {
+    Box::into_raw(Box::new(MemFile {
+        data: MEM_SOURCE,
+        pos: 0,
+    })) as *mut _
+}
+
+unsafe extern "C" fn mem_close(
+    _drv: *mut lvgl_sys::lv_fs_drv_t,
+    file_p: *mut cty::c_void,
+) -> lvgl_sys::lv_fs_res_t {
+    drop(Box::from_raw(file_p as *mut MemFile));
+    lvgl_sys::LV_FS_RES_OK as lvgl_sys::lv_fs_res_t
+}
+
+unsafe extern "C" fn mem_read(
+    _drv: *mut lvgl_sys::lv_fs_drv_t,
+    file_p: *mut cty::c_void,
+    buf: *mut cty::c_void,
+    btr: u32,
+    br: *mut u32,
+) -> lvgl_sys::lv_fs_res_t {
+    let file = &mut *(file_p as *mut MemFile);
+    let remaining = &file.data[file.pos..];
+    let count = remaining.len().min(btr as usize);
+    ptr::copy_nonoverlapping(remaining.as_ptr(), buf as *mut u8, count);
+    file.pos += count;
+    *br = count as u32;
+    lvgl_sys::LV_FS_RES_OK as lvgl_sys::lv_fs_res_t
+}
+
+unsafe extern "C" fn mem_seek(
+    _drv: *mut lvgl_sys::lv_fs_drv_t,
+    file_p: *mut cty::c_void,
+    pos: u32,
+    whence: lvgl_sys::lv_fs_whence_t,
+) -> lvgl_sys::lv_fs_res_t {
+    let file = &mut *(file_p as *mut MemFile);
+    let target = match whence {
+        lvgl_sys::lv_fs_whence_t_LV_FS_SEEK_SET => pos as usize,
+        lvgl_sys::lv_fs_whence_t_LV_FS_SEEK_CUR => file.pos + pos as usize,
+        lvgl_sys::lv_fs_whence_t_LV_FS_SEEK_END => file.data.len() + pos as usize,
+        _ => return lvgl_sys::LV_FS_RES_INV_PARAM as lvgl_sys::lv_fs_res_t,
+    };
+    file.pos = target.min(file.data.len());
+    lvgl_sys::LV_FS_RES_OK as lvgl_sys::lv_fs_res_t
+}
+
+unsafe extern "C" fn mem_tell(
+    _drv: *mut lvgl_sys::lv_fs_drv_t,
+    file_p: *mut cty::c_void,
+    pos_p: *mut u32,
+) -> lvgl_sys::lv_fs_res_t {
+    let file = &*(file_p as *const MemFile);
+    *pos_p = file.pos as u32;
+    lvgl_sys::LV_FS_RES_OK as lvgl_sys::lv_fs_res_t
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn load_invalid_font_data() {
+        crate::tests::initialize_test(false);
+        assert!(Font::load_from_bytes(&[0u8; 16]).is_err());
+        // The driver is reused for subsequent loads
+        assert!(Font::load_from_bytes(&[]).is_err());
+    }
+}
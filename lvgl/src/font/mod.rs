@@ -18,8 +18,11 @@
 //! }
 //! ```
 //!
-//! Built-in fonts are *only* available if the `nightly` feature is enabled for
-//! the `lvgl` crate.
+//! Each built-in font has a cargo feature on the `lvgl` crate, e.g.
+//! `font_montserrat_48`, which compiles the font into LVGL and makes its
+//! constructor available. This keeps binary size under control without
+//! editing `lv_conf.h`. With the `nightly` feature, fonts enabled directly in
+//! `lv_conf.h` are detected as well.
 //!
 //! # Custom fonts
 //! Custom fonts encoded into C files can be added. At compile time, the
@@ -44,10 +47,48 @@
 //! This operation is inherently unsafe as it instantiates and uses arbitrary
 //! data structures that the Rust compiler can't check.
 //!
+//! # Runtime fonts
+//! Fonts converted to LVGL's binary format can be shipped as assets and
+//! loaded at runtime, either through a file system driver or from memory:
+//! ```ignore
+//! use lvgl::font::Font;
+//! use lvgl::style::Style;
+//!
+//! static ROBOTO_24: &[u8] = include_bytes!("roboto_24.bin");
+//!
+//! let roboto = Font::load_from_bytes(ROBOTO_24).unwrap();
+//! let mut my_style = Style::default();
+//! my_style.set_text_font(unsafe { roboto.font() });
+//! // `roboto` must outlive the style; it is unloaded when dropped
+//! ```
+//!
+//! # Rust font engines
+//! Glyphs can also be supplied by Rust code implementing `FontProvider`,
+//! which `CustomFont` registers with LVGL as a regular font.
+//!
+//! # Complex scripts
+//! `ShapedFont` wraps a font with a `Shaper` picking the glyph drawn for each
+//! letter based on the letter following it, and shifting marks into place.
+//! Only text using the wrapped font is affected.
+//!
+//! # FreeType fonts
+//! With the `freetype` feature, `TtfFont` renders TrueType fonts of any size
+//! at runtime. This needs the FreeType library installed and is best suited
+//! to devices with plenty of RAM for the glyph cache.
+//!
 //! [official online converter]: https://lvgl.io/tools/fontconverter
 
+#[cfg(feature = "freetype")]
+mod freetype;
 mod generic;
+mod loader;
+mod provider;
+mod shaping;
+#[cfg(feature = "freetype")]
+pub use freetype::*;
 pub use generic::*;
+pub use loader::*;
+pub use provider::*;
+pub use shaping::*;
 
-#[cfg(feature = "nightly")]
 mod builtin;
@@ -0,0 +1,199 @@
+use super::Font;
+use crate::Box;
+use core::ptr;
+
+/// Metrics of a single glyph, as returned by `FontProvider::glyph_dsc()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GlyphDsc {
+    /// Horizontal distance to the next glyph, in pixels.
+    pub adv_w: u16,
+    /// Width of the glyph bitmap.
+    pub box_w: u16,
+    /// Height of the glyph bitmap.
+    pub box_h: u16,
+    /// Horizontal offset of the bitmap from the cursor.
+    pub ofs_x: i16,
+    /// Vertical offset of the bitmap from the baseline.
+    pub ofs_y: i16,
+    /// Bits per pixel of the bitmap: 1, 2, 4 or 8.
+    pub bpp: u8,
+}
+
+/// A font engine implemented in Rust, e.g. on top of a pure-Rust rasterizer.
+///
+/// LVGL first asks for a glyph's metrics and then, when drawing it, for its
+/// bitmap: `box_w * box_h` pixels of `bpp` bits each, packed row by row
+/// without padding.
+pub trait FontProvider {
+    /// Height of a line of text in pixels.
+    fn line_height(&self) -> i16;
+
+    /// Distance of the baseline from the bottom of the line, in pixels.
+    fn base_line(&self) -> i16;
+
+    /// Returns the metrics of `letter`, or `None` if the font has no glyph for
+    /// it. `next` is the following letter, which allows applying kerning.
+    fn glyph_dsc(&mut self, letter: char, next: Option<char>) -> Option<GlyphDsc>;
+
+    /// Returns the bitmap of `letter`. The data only has to stay valid until
+    /// the next call into the provider.
+    fn glyph_bitmap(&mut self, letter: char) -> Option<&[u8]>;
+}
+
+/// A font whose glyphs are supplied by a `FontProvider`.
+pub struct CustomFont<P: FontProvider> {
+    raw: lvgl_sys::lv_font_t,
+    provider: *mut P,
+}
+
+impl<P: FontProvider> CustomFont<P> {
+    /// Registers `provider` as an LVGL font.
+    pub fn new(provider: P) -> Self {
+        let line_height = provider.line_height();
+        let base_line = provider.base_line();
+        let provider = Box::into_raw(Box::new(provider));
+        let mut raw = lvgl_sys::lv_font_t::default();
+        raw.get_glyph_dsc = Some(get_glyph_dsc_trampoline::<P>);
+        raw.get_glyph_bitmap = Some(get_glyph_bitmap_trampoline::<P>);
+        raw.line_height = line_height as lvgl_sys::lv_coord_t;
+        raw.base_line = base_line as lvgl_sys::lv_coord_t;
+        raw.dsc = provider as *const cty::c_void;
+        Self { raw, provider }
+    }
+
+    /// Returns the provider backing this font.
+    pub fn provider(&mut self) -> &mut P {
+        unsafe { &mut *self.provider }
+    }
+
+    /// Returns a `Font` that can be applied to styles.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Font` calls into the provider owned by this
+    /// `CustomFont`, which must therefore outlive every style and object
+    /// using it.
+    pub unsafe fn font(&self) -> Font {
+        Font::new_raw(self.raw)
+    }
+
+    /// Keeps the provider alive forever and returns a `Font` for it.
+    pub fn leak(self) -> Font {
+        let font = unsafe { self.font() };
+        core::mem::forget(self);
+        font
+    }
+}
+
+impl<P: FontProvider> Drop for CustomFont<P> {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.provider)) }
+    }
+}
+
+unsafe extern "C" fn get_glyph_dsc_trampoline<P: FontProvider>(
+    font: *const lvgl_sys::lv_font_t,
+    dsc_out: *mut lvgl_sys::lv_font_glyph_dsc_t,
+    letter: u32,
+    letter_next: u32,
+) -> bool {
+    let provider = &mut *((*font).dsc as *mut P);
+    let letter = match char::from_u32(letter) {
+        Some(c) => c,
+        None => return false,
+    };
+    let next = char::from_u32(letter_next).filter(|&c| c != '\0');
+    match provider.glyph_dsc(letter, next) {
+        Some(glyph) => {
+            // LVGL doesn't clear the descriptor between lookups
+            let dsc = &mut *dsc_out;
+            dsc.resolved_font = font;
+            dsc.adv_w = glyph.adv_w;
+            dsc.box_w = glyph.box_w;
+            dsc.box_h = glyph.box_h;
+            dsc.ofs_x = glyph.ofs_x;
+            dsc.ofs_y = glyph.ofs_y;
+            dsc.set_bpp(glyph.bpp);
+            dsc.set_is_placeholder(0);
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn get_glyph_bitmap_trampoline<P: FontProvider>(
+    font: *const lvgl_sys::lv_font_t,
+    letter: u32,
+) -> *const u8 {
+    let provider = &mut *((*font).dsc as *mut P);
+    char::from_u32(letter)
+        .and_then(|c| provider.glyph_bitmap(c))
+        .map(|bitmap| bitmap.as_ptr())
+        .unwrap_or(ptr::null())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Draws every letter as a solid block.
+    struct BlockFont {
+        bitmap: [u8; 8 * 10],
+    }
+
+    impl FontProvider for BlockFont {
+        fn line_height(&self) -> i16 {
+            12
+        }
+
+        fn base_line(&self) -> i16 {
+            2
+        }
+
+        fn glyph_dsc(&mut self, letter: char, _next: Option<char>) -> Option<GlyphDsc> {
+            if letter.is_ascii_graphic() || letter == ' ' {
+                Some(GlyphDsc {
+                    adv_w: 9,
+                    box_w: 8,
+                    box_h: 10,
+                    ofs_x: 0,
+                    ofs_y: 0,
+                    bpp: 8,
+                })
+            } else {
+                None
+            }
+        }
+
+        fn glyph_bitmap(&mut self, _letter: char) -> Option<&[u8]> {
+            Some(&self.bitmap)
+        }
+    }
+
+    #[test]
+    fn custom_font_glyphs() {
+        crate::tests::initialize_test(false);
+        let font = CustomFont::new(BlockFont {
+            bitmap: [0xFF; 8 * 10],
+        });
+        let mut dsc = lvgl_sys::lv_font_glyph_dsc_t::default();
+        dsc.set_is_placeholder(1);
+        unsafe {
+            assert!(lvgl_sys::lv_font_get_glyph_dsc(
+                &font.raw,
+                &mut dsc,
+                'A' as u32,
+                0
+            ));
+            assert_eq!(dsc.adv_w, 9);
+            assert_eq!(dsc.is_placeholder(), 0);
+            assert_eq!(dsc.resolved_font, &font.raw as *const _);
+            assert!(!lvgl_sys::lv_font_get_glyph_dsc(
+                &font.raw,
+                &mut dsc,
+                '\u{1F600}' as u32,
+                0
+            ));
+        }
+    }
+}
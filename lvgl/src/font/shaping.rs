@@ -0,0 +1,250 @@
+use super::Font;
+use crate::Box;
+use core::ptr;
+
+/// The glyph a `Shaper` picks for a letter, and how to position it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShapedGlyph {
+    /// The code point of the glyph to draw, e.g. a contextual form or a
+    /// ligature from the private use area of the font.
+    pub glyph: char,
+    /// Horizontal shift of the glyph bitmap, e.g. to place a combining mark.
+    pub ofs_x: i16,
+    /// Vertical shift of the glyph bitmap.
+    pub ofs_y: i16,
+    /// Horizontal distance to the next glyph, replacing the font's own
+    /// advance if set. Zero-width marks use `Some(0)`.
+    pub adv_w: Option<u16>,
+}
+
+impl ShapedGlyph {
+    /// Draws `letter` as the font would without shaping.
+    pub fn unchanged(letter: char) -> Self {
+        Self {
+            glyph: letter,
+            ofs_x: 0,
+            ofs_y: 0,
+            adv_w: None,
+        }
+    }
+}
+
+/// Shaping for scripts LVGL's one-glyph-per-letter renderer can't lay out by
+/// itself, such as Thai marks stacked above consonants or Devanagari
+/// conjuncts.
+///
+/// LVGL iterates the text letter by letter, and looks at each letter together
+/// with the one after it, so that is the context the shaper gets. Reordering
+/// letters, e.g. Devanagari vowel signs written before their consonant, has
+/// to be done on the text before it is set on a label.
+pub trait Shaper {
+    /// Picks the glyph to draw for `letter`, followed by `next`.
+    fn shape(&mut self, letter: char, next: Option<char>) -> ShapedGlyph;
+}
+
+struct State<S> {
+    shaper: S,
+    base: *const lvgl_sys::lv_font_t,
+    /// The decision made for the letter seen last. LVGL lays out a letter
+    /// knowing the next one, but draws it without, so drawing reuses this.
+    last: Option<(u32, ShapedGlyph, *const lvgl_sys::lv_font_t)>,
+}
+
+/// A font drawing the glyphs of another font, as picked by a `Shaper`.
+///
+/// Labels and styles using this font are shaped; everything else keeps using
+/// the base font unchanged.
+pub struct ShapedFont<S: Shaper> {
+    raw: lvgl_sys::lv_font_t,
+    state: *mut State<S>,
+}
+
+impl<S: Shaper> ShapedFont<S> {
+    /// Wraps `base` so its glyphs are picked by `shaper`.
+    pub fn new(base: Font, shaper: S) -> Self {
+        let base: *const lvgl_sys::lv_font_t = base.into();
+        let state = Box::into_raw(Box::new(State {
+            shaper,
+            base,
+            last: None,
+        }));
+        // Same metrics as the base font, but glyphs come through the shaper
+        let mut raw = unsafe { *base };
+        raw.get_glyph_dsc = Some(get_glyph_dsc_trampoline::<S>);
+        raw.get_glyph_bitmap = Some(get_glyph_bitmap_trampoline::<S>);
+        raw.dsc = state as *const cty::c_void;
+        raw.fallback = ptr::null();
+        Self { raw, state }
+    }
+
+    /// Returns the shaper of this font.
+    pub fn shaper(&mut self) -> &mut S {
+        unsafe { &mut (*self.state).shaper }
+    }
+
+    /// Returns a `Font` that can be applied to styles.
+    ///
+    /// # Safety
+    ///
+    /// The returned `Font` calls into the shaper owned by this `ShapedFont`,
+    /// which must therefore outlive every style and object using it.
+    pub unsafe fn font(&self) -> Font {
+        Font::new_raw(self.raw)
+    }
+
+    /// Keeps the shaper alive forever and returns a `Font` for it.
+    pub fn leak(self) -> Font {
+        let font = unsafe { self.font() };
+        core::mem::forget(self);
+        font
+    }
+}
+
+impl<S: Shaper> Drop for ShapedFont<S> {
+    fn drop(&mut self) {
+        unsafe { drop(Box::from_raw(self.state)) }
+    }
+}
+
+unsafe extern "C" fn get_glyph_dsc_trampoline<S: Shaper>(
+    font: *const lvgl_sys::lv_font_t,
+    dsc_out: *mut lvgl_sys::lv_font_glyph_dsc_t,
+    letter: u32,
+    letter_next: u32,
+) -> bool {
+    let state = &mut *((*font).dsc as *mut State<S>);
+    let Some(c) = char::from_u32(letter) else {
+        return false;
+    };
+    let next = char::from_u32(letter_next).filter(|&c| c != '\0');
+    let shaped = match state.last {
+        Some((last, shaped, _)) if last == letter && next.is_none() => shaped,
+        _ => state.shaper.shape(c, next),
+    };
+    if !lvgl_sys::lv_font_get_glyph_dsc(state.base, dsc_out, shaped.glyph as u32, letter_next) {
+        state.last = None;
+        return false;
+    }
+    let dsc = &mut *dsc_out;
+    state.last = Some((letter, shaped, dsc.resolved_font));
+    dsc.ofs_x += shaped.ofs_x;
+    dsc.ofs_y += shaped.ofs_y;
+    if let Some(adv_w) = shaped.adv_w {
+        dsc.adv_w = adv_w;
+    }
+    true
+}
+
+unsafe extern "C" fn get_glyph_bitmap_trampoline<S: Shaper>(
+    font: *const lvgl_sys::lv_font_t,
+    letter: u32,
+) -> *const u8 {
+    let state = &mut *((*font).dsc as *mut State<S>);
+    match state.last {
+        Some((last, shaped, resolved)) if last == letter => {
+            lvgl_sys::lv_font_get_glyph_bitmap(resolved, shaped.glyph as u32)
+        }
+        _ => match char::from_u32(letter) {
+            Some(c) => {
+                let glyph = state.shaper.shape(c, None).glyph;
+                lvgl_sys::lv_font_get_glyph_bitmap(state.base, glyph as u32)
+            }
+            None => ptr::null(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::font::{CustomFont, FontProvider, GlyphDsc};
+
+    /// Remembers which glyph was asked for last.
+    struct Recorder {
+        asked: Option<char>,
+        bitmap: [u8; 4],
+    }
+
+    impl FontProvider for Recorder {
+        fn line_height(&self) -> i16 {
+            4
+        }
+
+        fn base_line(&self) -> i16 {
+            0
+        }
+
+        fn glyph_dsc(&mut self, letter: char, _next: Option<char>) -> Option<GlyphDsc> {
+            self.asked = Some(letter);
+            Some(GlyphDsc {
+                adv_w: 3,
+                box_w: 2,
+                box_h: 2,
+                ofs_x: 0,
+                ofs_y: 0,
+                bpp: 8,
+            })
+        }
+
+        fn glyph_bitmap(&mut self, letter: char) -> Option<&[u8]> {
+            self.asked = Some(letter);
+            Some(&self.bitmap)
+        }
+    }
+
+    /// Draws "f" before "i" in its ligature form, and "'" as a raised
+    /// zero-width mark.
+    struct Ligatures;
+
+    impl Shaper for Ligatures {
+        fn shape(&mut self, letter: char, next: Option<char>) -> ShapedGlyph {
+            match (letter, next) {
+                ('f', Some('i')) => ShapedGlyph::unchanged('\u{FB01}'),
+                ('\'', _) => ShapedGlyph {
+                    ofs_y: 2,
+                    adv_w: Some(0),
+                    ..ShapedGlyph::unchanged('\'')
+                },
+                _ => ShapedGlyph::unchanged(letter),
+            }
+        }
+    }
+
+    #[test]
+    fn shaper_picks_glyphs() {
+        crate::tests::initialize_test(false);
+        let mut base = CustomFont::new(Recorder {
+            asked: None,
+            bitmap: [0xFF; 4],
+        });
+        let shaped = ShapedFont::new(unsafe { base.font() }, Ligatures);
+        let mut dsc = lvgl_sys::lv_font_glyph_dsc_t::default();
+        unsafe {
+            // Laying out "fi", then drawing its first letter
+            assert!(lvgl_sys::lv_font_get_glyph_dsc(
+                &shaped.raw,
+                &mut dsc,
+                'f' as u32,
+                'i' as u32
+            ));
+            assert_eq!(base.provider().asked, Some('\u{FB01}'));
+            assert!(lvgl_sys::lv_font_get_glyph_dsc(
+                &shaped.raw,
+                &mut dsc,
+                'f' as u32,
+                0
+            ));
+            assert!(!lvgl_sys::lv_font_get_glyph_bitmap(dsc.resolved_font, 'f' as u32).is_null());
+            assert_eq!(base.provider().asked, Some('\u{FB01}'));
+
+            assert!(lvgl_sys::lv_font_get_glyph_dsc(
+                &shaped.raw,
+                &mut dsc,
+                '\'' as u32,
+                0
+            ));
+            assert_eq!(dsc.adv_w, 0);
+            assert_eq!(dsc.ofs_y, 2);
+        }
+    }
+}
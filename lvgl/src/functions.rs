@@ -1,13 +1,13 @@
 use crate::display::{Display, DisplayDriver};
 use crate::input_device::InputDriver;
-use crate::{Event, LvError, LvResult, Obj, Widget};
+use crate::{Box, Event, LvError, LvResult, Obj, Widget};
 use core::ptr::NonNull;
-#[cfg(not(feature = "rust_timer"))]
 use core::time::Duration;
 use core::{ptr, result};
 
 /// Internal LVGL error.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum CoreError {
     ResourceNotAvailable,
     OperationFailed,
@@ -64,9 +64,31 @@ pub fn tick_inc(tick_period: Duration) {
 /// Calls the LVGL timer handler. This function should be called periodically.
 #[inline]
 pub fn task_handler() {
+    #[cfg(feature = "threadsafe")]
+    crate::sync::assert_locked();
     unsafe { lvgl_sys::lv_timer_handler() };
 }
 
+/// Like `task_handler()`, but returns how long until the next LVGL timer is
+/// due, so the caller can sleep until then. Returns `None` if no timer is
+/// running.
+#[inline]
+pub fn task_handler_next() -> Option<Duration> {
+    #[cfg(feature = "threadsafe")]
+    crate::sync::assert_locked();
+    match unsafe { lvgl_sys::lv_timer_handler() } {
+        lvgl_sys::LV_NO_TIMER_READY => None,
+        ms => Some(Duration::from_millis(ms.into())),
+    }
+}
+
+/// Redraws the invalidated areas of all displays right away, see
+/// `Display::refresh_now()`.
+#[inline]
+pub fn refresh_now() {
+    unsafe { lvgl_sys::lv_refr_now(ptr::null_mut()) }
+}
+
 /// Directly send an event to a specific widget.
 #[inline]
 pub fn event_send<W: for<'a> Widget<'a>>(
@@ -78,6 +100,201 @@ pub fn event_send<W: for<'a> Widget<'a>>(
     };
 }
 
+/// Runs `f` at the start of the next `task_handler()` call, e.g. to apply a
+/// result computed elsewhere. The closure is freed after it has run, on the
+/// thread running `task_handler()`, so it must be `Send`.
+///
+/// Queuing allocates from LVGL, which is not thread-safe: from other threads
+/// (or tasks), take the lock of the `threadsafe` feature first, and don't
+/// call this from interrupt handlers.
+pub fn async_call<F: FnOnce() + Send + 'static>(f: F) -> LvResult<()> {
+    let f = Box::into_raw(Box::new(Some(f)));
+    let res = unsafe { lvgl_sys::lv_async_call(Some(async_call_trampoline::<F>), f as *mut _) };
+    if res as u32 == lvgl_sys::LV_RES_OK {
+        Ok(())
+    } else {
+        drop(unsafe { Box::from_raw(f) });
+        Err(LvError::LvOOMemory)
+    }
+}
+
+unsafe extern "C" fn async_call_trampoline<F: FnOnce()>(user_data: *mut cty::c_void) {
+    let mut f = Box::from_raw(user_data as *mut Option<F>);
+    if let Some(f) = f.take() {
+        f();
+    }
+}
+
+/// Runs `f` collecting the areas it invalidates, and invalidates them once
+/// at the end. Setting dozens of values per tick otherwise fills LVGL's
+/// list of invalidated areas, which then falls back to redrawing the whole
+/// display; batching joins overlapping areas as they come in instead.
+///
+/// Areas are collected on every display and layer. Batches may be nested,
+/// in which case only the outermost one invalidates. `f` should not refresh
+/// displays, which would redraw them entirely.
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    let _guard = BatchGuard::new();
+    f()
+}
+
+const INV_BUF_SIZE: usize = lvgl_sys::LV_INV_BUF_SIZE as usize;
+
+type RounderCb = unsafe extern "C" fn(*mut lvgl_sys::lv_disp_drv_t, *mut lvgl_sys::lv_area_t);
+
+/// Areas invalidated on a display during a batch, with the rounder of its
+/// driver that collects them.
+struct BatchedDisplay {
+    disp: *mut lvgl_sys::lv_disp_t,
+    rounder_cb: Option<RounderCb>,
+    areas: [lvgl_sys::lv_area_t; INV_BUF_SIZE],
+    len: usize,
+    next: Option<Box<BatchedDisplay>>,
+}
+
+impl BatchedDisplay {
+    /// Adds `area`, joining it into the held area growing the least once
+    /// there is no room left.
+    unsafe fn add(&mut self, area: &lvgl_sys::lv_area_t) {
+        let held = &mut self.areas[..self.len];
+        if held
+            .iter()
+            .any(|held| lvgl_sys::_lv_area_is_in(area, held, 0))
+        {
+            return;
+        }
+        if self.len < INV_BUF_SIZE {
+            self.areas[self.len] = *area;
+            self.len += 1;
+            return;
+        }
+        let joined = |held: &lvgl_sys::lv_area_t| {
+            let mut joined = lvgl_sys::lv_area_t::default();
+            lvgl_sys::_lv_area_join(&mut joined, held, area);
+            joined
+        };
+        if let Some(held) = held.iter_mut().min_by_key(|held| {
+            lvgl_sys::lv_area_get_size(&joined(held)) - lvgl_sys::lv_area_get_size(*held)
+        }) {
+            *held = joined(held);
+        }
+    }
+}
+
+static mut BATCHED: Option<Box<BatchedDisplay>> = None;
+static mut BATCH_DEPTH: usize = 0;
+
+struct BatchGuard;
+
+impl BatchGuard {
+    fn new() -> Self {
+        unsafe {
+            BATCH_DEPTH += 1;
+            if BATCH_DEPTH == 1 {
+                let mut disp = lvgl_sys::lv_disp_get_next(ptr::null_mut());
+                while !disp.is_null() {
+                    start_batch(disp);
+                    disp = lvgl_sys::lv_disp_get_next(disp);
+                }
+            }
+        }
+        Self
+    }
+}
+
+impl Drop for BatchGuard {
+    fn drop(&mut self) {
+        unsafe {
+            BATCH_DEPTH -= 1;
+            if BATCH_DEPTH > 0 {
+                return;
+            }
+            let mut batched = (*ptr::addr_of_mut!(BATCHED)).take();
+            while let Some(mut display) = batched {
+                batched = display.next.take();
+                end_batch(&display);
+            }
+        }
+    }
+}
+
+/// Starts collecting the areas invalidated on `disp`, which LVGL passes to
+/// the rounder of the driver. Makes LVGL drop them afterwards by holding
+/// the whole display as invalidated, as they are then already covered.
+unsafe fn start_batch(disp: *mut lvgl_sys::lv_disp_t) {
+    let driver = &mut *(*disp).driver;
+    // Redrawn entirely anyway
+    if driver.full_refresh() != 0 {
+        return;
+    }
+    let mut display = BatchedDisplay {
+        disp,
+        rounder_cb: driver.rounder_cb,
+        areas: [lvgl_sys::lv_area_t::default(); INV_BUF_SIZE],
+        len: 0,
+        next: (*ptr::addr_of_mut!(BATCHED)).take(),
+    };
+    for area in &(*disp).inv_areas[..(*disp).inv_p as usize] {
+        display.add(area);
+    }
+    (*disp).inv_areas[0] = lvgl_sys::lv_area_t {
+        x1: 0,
+        y1: 0,
+        x2: lvgl_sys::lv_disp_get_hor_res(disp) - 1,
+        y2: lvgl_sys::lv_disp_get_ver_res(disp) - 1,
+    };
+    (*disp).inv_p = 1;
+    driver.rounder_cb = Some(batch_rounder_cb);
+    *ptr::addr_of_mut!(BATCHED) = Some(Box::new(display));
+}
+
+/// Returns the rounder `batch()` chains for `driver` while collecting its
+/// invalidated areas, which hooks must be installed in instead of the
+/// driver during a batch.
+pub(crate) unsafe fn batched_rounder_cb(
+    driver: *mut lvgl_sys::lv_disp_drv_t,
+) -> Option<&'static mut Option<RounderCb>> {
+    let mut batched = (*ptr::addr_of_mut!(BATCHED)).as_deref_mut();
+    while let Some(display) = batched {
+        if (*display.disp).driver == driver {
+            return Some(&mut display.rounder_cb);
+        }
+        batched = display.next.as_deref_mut();
+    }
+    None
+}
+
+/// Restores the rounder of the display and invalidates the collected areas.
+unsafe fn end_batch(display: &BatchedDisplay) {
+    let disp = display.disp;
+    (*(*disp).driver).rounder_cb = display.rounder_cb;
+    // Areas were rounded when collected, and none holds another
+    (*disp).inv_areas[..display.len].copy_from_slice(&display.areas[..display.len]);
+    (*disp).inv_p = display.len as u16;
+}
+
+unsafe extern "C" fn batch_rounder_cb(
+    driver: *mut lvgl_sys::lv_disp_drv_t,
+    area: *mut lvgl_sys::lv_area_t,
+) {
+    let mut batched = (*ptr::addr_of_mut!(BATCHED)).as_deref_mut();
+    while let Some(display) = batched {
+        if (*display.disp).driver == driver {
+            if let Some(rounder_cb) = display.rounder_cb {
+                rounder_cb(driver, area);
+            }
+            // Also called on the chunks of a refresh
+            if (*display.disp).rendering_in_progress() == 0 {
+                display.add(&*area);
+                // Within the whole display, so LVGL drops it
+                *area = (*display.disp).inv_areas[0];
+            }
+            return;
+        }
+        batched = display.next.as_deref_mut();
+    }
+}
+
 /// Register an input device driver to LVGL.
 pub(crate) fn indev_drv_register<D>(input_device: &mut impl InputDriver<D>) -> LvResult<()> {
     unsafe {
@@ -89,3 +306,90 @@ pub(crate) fn indev_drv_register<D>(input_device: &mut impl InputDriver<D>) -> L
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::display::Area;
+    use crate::widgets::Btn;
+
+    #[test]
+    fn batch_invalidates_once() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        let disp = display.disp.as_ptr();
+        unsafe { lvgl_sys::lv_refr_now(disp) };
+
+        let top = NonNull::new(unsafe { lvgl_sys::lv_disp_get_layer_top(disp) }).unwrap();
+        let mut top = unsafe { Obj::from_raw(top) }.unwrap();
+        let mut popup = Btn::create(&mut top).unwrap();
+        popup.set_size(20, 20);
+        popup.set_pos(200, 200);
+        unsafe { lvgl_sys::lv_refr_now(disp) };
+
+        batch(|| {
+            batch(|| btn.set_size(50, 20));
+            // More moves than LVGL holds areas for, applied right away
+            for x in 0..2 * INV_BUF_SIZE as i16 {
+                btn.set_pos(x, 10);
+                btn.coords();
+            }
+            popup.invalidate();
+            assert_eq!(unsafe { (*disp).inv_p }, 1);
+        });
+
+        let inv = unsafe { &(*disp).inv_areas[..(*disp).inv_p as usize] };
+        let covered = |area: Area| {
+            inv.iter().any(|inv| {
+                let inv = Area::from(*inv);
+                inv.x1 <= area.x1 && inv.y1 <= area.y1 && inv.x2 >= area.x2 && inv.y2 >= area.y2
+            })
+        };
+        assert!(covered(btn.coords()));
+        assert!(covered(popup.coords()));
+        assert!(!covered(Area {
+            x1: 0,
+            y1: 0,
+            x2: 239,
+            y2: 239
+        }));
+        assert!(unsafe { (*(*disp).driver).rounder_cb.is_none() });
+    }
+
+    #[test]
+    fn manual_refresh() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        let disp = display.disp.as_ptr();
+        display.refresh_now();
+        assert_eq!(unsafe { (*disp).inv_p }, 0);
+
+        display.set_auto_refresh(false);
+        btn.invalidate();
+        assert_eq!(unsafe { (*disp).inv_p }, 1);
+        unsafe { assert!((*(*disp).refr_timer).paused() != 0) };
+        refresh_now();
+        assert_eq!(unsafe { (*disp).inv_p }, 0);
+        display.set_auto_refresh(true);
+    }
+
+    #[test]
+    fn async_call_runs_once() {
+        use core::sync::atomic::{AtomicU32, Ordering};
+        static CALLS: AtomicU32 = AtomicU32::new(0);
+
+        crate::tests::initialize_test(true);
+        async_call(|| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+        })
+        .unwrap();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 0);
+        task_handler();
+        task_handler();
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+}
@@ -31,7 +31,7 @@ pub struct Encoder {
 impl InputDriver<Encoder> for Encoder {
     fn register<F>(handler: F, _: &crate::Display) -> LvResult<Encoder>
     where
-        F: Fn() -> BufferStatus,
+        F: FnMut() -> BufferStatus,
     {
         let driver = unsafe {
             let mut indev_drv = MaybeUninit::uninit();
@@ -108,7 +108,7 @@ unsafe extern "C" fn read_input<F>(
     indev_drv: *mut lvgl_sys::lv_indev_drv_t,
     data: *mut lvgl_sys::lv_indev_data_t,
 ) where
-    F: Fn() -> BufferStatus,
+    F: FnMut() -> BufferStatus,
 {
     // convert user data to function
     let user_closure = &mut *((*indev_drv).user_data as *mut F);
@@ -1,6 +1,7 @@
 use super::encoder::*;
 use super::pointer::*;
-use crate::LvResult;
+use crate::{LvError, LvResult};
+use core::time::Duration;
 
 /// Generic data which can be associated with an input device driver. Varies
 /// based on the concrete type of the input device driver
@@ -30,6 +31,18 @@ impl InputState {
     pub fn and_continued(self) -> BufferStatus {
         BufferStatus::Buffered(self)
     }
+
+    /// Represents an input device that buffers events, e.g. a touch
+    /// controller with a FIFO. If `continue_reading` is set, LVGL calls the
+    /// read handler again right away instead of waiting for the next read
+    /// period.
+    pub fn with_continue_reading(self, continue_reading: bool) -> BufferStatus {
+        if continue_reading {
+            BufferStatus::Buffered(self)
+        } else {
+            BufferStatus::Once(self)
+        }
+    }
 }
 
 /// Boolean buffering states for an input device driver.
@@ -41,13 +54,20 @@ pub enum BufferStatus {
     Buffered(InputState),
 }
 
+impl BufferStatus {
+    /// Returns whether more entries remain to be read.
+    pub fn continue_reading(&self) -> bool {
+        matches!(self, BufferStatus::Buffered(_))
+    }
+}
+
 /// A generic input driver trait.
 pub trait InputDriver<D> {
     /// Creates an instance of a given input device, given a handler function.
     /// A `Display` must already have been created.
     fn register<F>(handler: F, display: &crate::Display) -> LvResult<D>
     where
-        F: Fn() -> BufferStatus;
+        F: FnMut() -> BufferStatus;
 
     /// Returns a pointer to the underlying raw driver.
     fn get_driver(&mut self) -> &mut lvgl_sys::lv_indev_drv_t;
@@ -76,4 +96,19 @@ pub trait InputDriver<D> {
     /// `descriptor` must point to an initialized but unregistered and unused
     /// instance of an `lv_indev_t`, and must also be aligned.
     unsafe fn set_descriptor(&mut self, descriptor: *mut lvgl_sys::lv_indev_t) -> LvResult<()>;
+
+    /// Sets how often LVGL reads the input device, `LV_INDEV_DEF_READ_PERIOD`
+    /// (30 ms in the bundled config) by default. Devices that buffer events
+    /// should rather report them all at once with
+    /// `InputState::with_continue_reading()`.
+    ///
+    /// Returns `LvError::Uninitialized` if the device isn't registered.
+    fn set_read_period(&mut self, period: Duration) -> LvResult<()> {
+        let timer = self.get_driver().read_timer;
+        if timer.is_null() {
+            return Err(LvError::Uninitialized);
+        }
+        unsafe { lvgl_sys::lv_timer_set_period(timer, period.as_millis() as u32) };
+        Ok(())
+    }
 }
@@ -0,0 +1,276 @@
+//! Synthetic input
+//!
+//! A `VirtualInput` registers a pointer and a keypad that report whatever is
+//! injected with its `inject_pointer()` and `inject_key()`, e.g. to drive the
+//! UI from integration tests or from a remote control session:
+//!
+//! ```ignore
+//! let virtual_input = VirtualInput::register(&display)?;
+//! virtual_input.set_keypad_group(&group)?;
+//! virtual_input.inject_pointer(120, 40, true)?;
+//! virtual_input.inject_pointer(120, 40, false)?;
+//! virtual_input.inject_key(lvgl_sys::LV_KEY_ENTER)?;
+//! ```
+//!
+//! Injected events are queued until LVGL reads the devices, so a press
+//! followed by a release within one read period is still seen as a click.
+//! As a `VirtualInput` can't leave the thread it was registered on, events
+//! are only injected from the context running `task_handler()`.
+
+use crate::{Box, Display, Group, LvError, LvResult};
+use core::cell::{RefCell, RefMut};
+use core::mem::MaybeUninit;
+use cty::c_void;
+
+/// How many events of each device can wait for LVGL to read them.
+const QUEUE_LEN: usize = 16;
+
+#[derive(Copy, Clone)]
+struct PointerEvent {
+    x: i16,
+    y: i16,
+    pressed: bool,
+}
+
+const RELEASED: PointerEvent = PointerEvent {
+    x: 0,
+    y: 0,
+    pressed: false,
+};
+
+struct Queue<T: Copy> {
+    items: [T; QUEUE_LEN],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy> Queue<T> {
+    const fn new(fill: T) -> Self {
+        Self {
+            items: [fill; QUEUE_LEN],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, item: T) -> LvResult<()> {
+        if self.len == QUEUE_LEN {
+            return Err(LvError::LvOOMemory);
+        }
+        self.items[(self.head + self.len) % QUEUE_LEN] = item;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let item = self.items[self.head];
+        self.head = (self.head + 1) % QUEUE_LEN;
+        self.len -= 1;
+        Some(item)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+struct State {
+    pointer: Queue<PointerEvent>,
+    /// The pointer state reported last, repeated while nothing is queued.
+    last: PointerEvent,
+    keys: Queue<u32>,
+    /// The key reported as pressed in the last read, released in the next.
+    key_down: Option<u32>,
+}
+
+/// A pointer and a keypad fed by `inject_pointer()` and `inject_key()`. The
+/// devices are removed on drop.
+pub struct VirtualInput {
+    pointer: (Box<lvgl_sys::lv_indev_drv_t>, *mut lvgl_sys::lv_indev_t),
+    keypad: (Box<lvgl_sys::lv_indev_drv_t>, *mut lvgl_sys::lv_indev_t),
+    /// Shared with the read callbacks through the drivers' `user_data`.
+    state: Box<RefCell<State>>,
+}
+
+impl VirtualInput {
+    /// Registers the virtual devices on `display`.
+    pub fn register(display: &Display) -> LvResult<Self> {
+        let mut state = Box::new(RefCell::new(State {
+            pointer: Queue::new(RELEASED),
+            last: RELEASED,
+            keys: Queue::new(0),
+            key_down: None,
+        }));
+        let user_data = &mut *state as *mut RefCell<State> as *mut c_void;
+        let pointer = register_indev(
+            display,
+            lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_POINTER,
+            read_pointer,
+            user_data,
+        );
+        let keypad = register_indev(
+            display,
+            lvgl_sys::lv_indev_type_t_LV_INDEV_TYPE_KEYPAD,
+            read_keypad,
+            user_data,
+        );
+        let input = Self {
+            pointer,
+            keypad,
+            state,
+        };
+        if input.pointer.1.is_null() || input.keypad.1.is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+        Ok(input)
+    }
+
+    /// Sends the keys injected with `inject_key()` to the widgets in `group`.
+    pub fn set_keypad_group(&self, group: &Group) -> LvResult<()> {
+        let group = group.raw()?;
+        unsafe { lvgl_sys::lv_indev_set_group(self.keypad.1, group.as_ptr()) };
+        Ok(())
+    }
+
+    /// Moves the virtual pointer to `(x, y)`, pressed or released.
+    ///
+    /// Fails with `LvError::LvOOMemory` if too many events are waiting to be
+    /// read.
+    pub fn inject_pointer(&self, x: i16, y: i16, pressed: bool) -> LvResult<()> {
+        let event = PointerEvent { x, y, pressed };
+        self.state.borrow_mut().pointer.push(event)
+    }
+
+    /// Presses and releases `key`, one of the `LV_KEY_*` constants or a
+    /// character, on the virtual keypad.
+    ///
+    /// Fails like `inject_pointer()`.
+    pub fn inject_key(&self, key: u32) -> LvResult<()> {
+        self.state.borrow_mut().keys.push(key)
+    }
+}
+
+impl Drop for VirtualInput {
+    fn drop(&mut self) {
+        unsafe {
+            for indev in [self.pointer.1, self.keypad.1] {
+                if !indev.is_null() {
+                    lvgl_sys::lv_indev_delete(indev);
+                }
+            }
+        }
+    }
+}
+
+fn register_indev(
+    display: &Display,
+    type_: lvgl_sys::lv_indev_type_t,
+    read_cb: unsafe extern "C" fn(*mut lvgl_sys::lv_indev_drv_t, *mut lvgl_sys::lv_indev_data_t),
+    user_data: *mut c_void,
+) -> (Box<lvgl_sys::lv_indev_drv_t>, *mut lvgl_sys::lv_indev_t) {
+    unsafe {
+        let mut driver = MaybeUninit::uninit();
+        lvgl_sys::lv_indev_drv_init(driver.as_mut_ptr());
+        let mut driver = Box::new(driver.assume_init());
+        driver.type_ = type_;
+        driver.read_cb = Some(read_cb);
+        driver.user_data = user_data;
+        driver.disp = display.disp.as_ptr();
+        let indev = lvgl_sys::lv_indev_drv_register(&mut *driver);
+        (driver, indev)
+    }
+}
+
+unsafe fn state<'a>(driver: *mut lvgl_sys::lv_indev_drv_t) -> RefMut<'a, State> {
+    (*((*driver).user_data as *const RefCell<State>)).borrow_mut()
+}
+
+unsafe extern "C" fn read_pointer(
+    driver: *mut lvgl_sys::lv_indev_drv_t,
+    data: *mut lvgl_sys::lv_indev_data_t,
+) {
+    let mut state = state(driver);
+    if let Some(event) = state.pointer.pop() {
+        state.last = event;
+    }
+    (*data).point.x = state.last.x as lvgl_sys::lv_coord_t;
+    (*data).point.y = state.last.y as lvgl_sys::lv_coord_t;
+    (*data).state = if state.last.pressed {
+        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED
+    } else {
+        lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED
+    };
+    (*data).continue_reading = !state.pointer.is_empty();
+}
+
+unsafe extern "C" fn read_keypad(
+    driver: *mut lvgl_sys::lv_indev_drv_t,
+    data: *mut lvgl_sys::lv_indev_data_t,
+) {
+    let mut state = state(driver);
+    // Every key is reported pressed in one read and released in the next
+    match state.key_down.take() {
+        Some(key) => {
+            (*data).key = key;
+            (*data).state = lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED;
+        }
+        None => match state.keys.pop() {
+            Some(key) => {
+                state.key_down = Some(key);
+                (*data).key = key;
+                (*data).state = lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_PRESSED;
+            }
+            None => (*data).state = lvgl_sys::lv_indev_state_t_LV_INDEV_STATE_RELEASED,
+        },
+    }
+    (*data).continue_reading = state.key_down.is_some() || !state.keys.is_empty();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Btn;
+    use crate::{Event, Widget};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    static CLICKS: AtomicU32 = AtomicU32::new(0);
+
+    #[test]
+    fn injected_click() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let input = VirtualInput::register(&display).unwrap();
+
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.set_size(40, 20);
+        btn.set_pos(0, 0);
+        btn.on_event(|_, event| {
+            if let Event::Clicked = event {
+                CLICKS.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .unwrap();
+
+        // Pressed and released before the next read, still a click
+        input.inject_pointer(10, 10, true).unwrap();
+        input.inject_pointer(10, 10, false).unwrap();
+        unsafe { lvgl_sys::lv_indev_read_timer_cb(input.pointer.0.read_timer) };
+        assert_eq!(CLICKS.load(Ordering::SeqCst), 1);
+
+        for _ in 0..QUEUE_LEN {
+            input.inject_key(lvgl_sys::LV_KEY_ENTER).unwrap();
+        }
+        assert_eq!(
+            input.inject_key(lvgl_sys::LV_KEY_ENTER),
+            Err(LvError::LvOOMemory)
+        );
+    }
+}
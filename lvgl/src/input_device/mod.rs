@@ -28,9 +28,20 @@
 //! }
 //! ```
 //! For a full example, see the `button_click` example.
+//!
+//! Devices that buffer events, such as touch controllers with a FIFO, should
+//! return every queued event from a single read: mark all but the last one
+//! with `InputState::with_continue_reading(true)` and LVGL keeps calling the
+//! handler until the buffer is drained, so fast swipes aren't undersampled
+//! at the read period.
+//!
+//! To drive the UI programmatically instead, e.g. from integration tests,
+//! register an `inject::VirtualInput` and feed it with its `inject_pointer()`
+//! and `inject_key()`.
 
 mod generic;
 pub use generic::*;
 
 pub mod encoder;
+pub mod inject;
 pub mod pointer;
@@ -30,7 +30,7 @@ pub struct Pointer {
 impl InputDriver<Pointer> for Pointer {
     fn register<F>(handler: F, _: &crate::Display) -> LvResult<Self>
     where
-        F: Fn() -> BufferStatus,
+        F: FnMut() -> BufferStatus,
     {
         let driver = unsafe {
             let mut indev_drv = MaybeUninit::uninit();
@@ -107,7 +107,7 @@ unsafe extern "C" fn read_input<F>(
     indev_drv: *mut lvgl_sys::lv_indev_drv_t,
     data: *mut lvgl_sys::lv_indev_data_t,
 ) where
-    F: Fn() -> BufferStatus,
+    F: FnMut() -> BufferStatus,
 {
     // convert user data to function
     let user_closure = &mut *((*indev_drv).user_data as *mut F);
@@ -180,6 +180,8 @@ mod test {
     use super::*;
     use crate::Display;
     use core::marker::PhantomData;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use core::time::Duration;
     use embedded_graphics::draw_target::DrawTarget;
     use embedded_graphics::geometry::Size;
     use embedded_graphics::pixelcolor::PixelColor;
@@ -230,4 +232,28 @@ mod test {
 
         let _touch_screen = Pointer::register(read_touchpad_device, &display).unwrap();
     }
+
+    #[test]
+    fn buffered_input_is_drained() {
+        static READS: AtomicUsize = AtomicUsize::new(0);
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+
+        let mut fifo = [10, 20, 30].into_iter().peekable();
+        let mut touch_screen = Pointer::register(
+            move || {
+                READS.fetch_add(1, Ordering::Relaxed);
+                let x = fifo.next().unwrap_or(30);
+                PointerInputData::Touch(Point::new(x, 10))
+                    .pressed()
+                    .with_continue_reading(fifo.peek().is_some())
+            },
+            &display,
+        )
+        .unwrap();
+        touch_screen.set_read_period(Duration::from_millis(5)).unwrap();
+
+        unsafe { lvgl_sys::lv_indev_read_timer_cb(touch_screen.get_driver().read_timer) };
+        assert_eq!(READS.load(Ordering::Relaxed), 3);
+    }
 }
@@ -15,9 +15,16 @@
 #![cfg_attr(not(test), no_std)]
 #![cfg_attr(feature = "nightly", feature(cfg_accessible))]
 #![cfg_attr(feature = "nightly", feature(error_in_core))]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
 pub use lvgl_sys as sys;
 
+#[cfg(lvgl_v9)]
+compile_error!(
+    "lvgl-sys was built against LVGL 9 (`lvgl-v9` feature), which the lvgl crate does not \
+     support yet. Use the raw bindings in lvgl-sys directly."
+);
+
 #[macro_use]
 extern crate bitflags;
 
@@ -27,6 +34,9 @@ mod lv_core;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
 // We can ONLY use `alloc::boxed::Box` if `lvgl_alloc` is enabled.
 // That is because we use `Box` to send memory references to LVGL. Since the global allocator, when
 // `lvgl_alloc` feature is enabled, is the LVGL memory manager then everything is in LVGL
@@ -37,6 +47,9 @@ use ::alloc::boxed::Box;
 #[cfg(feature = "lvgl_alloc")]
 mod allocator;
 
+#[cfg(feature = "rust_alloc")]
+mod rust_alloc;
+
 #[cfg(not(feature = "lvgl_alloc"))]
 pub(crate) mod mem;
 
@@ -57,22 +70,51 @@ use embedded_graphics::geometry::Point;
 
 pub use crate::lv_core::*;
 pub use display::*;
+pub use display_hooks::*;
 pub use functions::*;
 pub use support::*;
 
+#[cfg(feature = "macros")]
+pub use lvgl_macros::ui;
+
 mod display;
+mod display_hooks;
 mod functions;
 mod support;
 
 #[cfg(feature = "drivers")]
 pub mod drivers;
+pub mod draw;
 pub mod font;
 pub mod input_device;
 pub mod misc;
+pub mod palette;
+pub mod pixel_format;
 pub mod widgets;
 
+#[cfg(feature = "esp-idf")]
+pub mod esp_idf;
+#[cfg(feature = "ui-loader")]
+pub mod loader;
+#[cfg(any(feature = "logger", feature = "defmt"))]
+pub mod logger;
+#[cfg(feature = "mipidsi")]
+pub mod mipidsi;
+#[cfg(feature = "rtic")]
+pub mod rtic;
+#[cfg(feature = "threadsafe")]
+pub mod sync;
+#[cfg(feature = "embassy")]
+pub mod task;
 #[cfg(feature = "rust_timer")]
 pub mod timer;
+#[cfg(feature = "web")]
+pub mod web;
+#[cfg(all(
+    feature = "test_harness",
+    not(any(feature = "rust_timer", feature = "custom_timer"))
+))]
+pub mod test;
 
 #[cfg(feature = "unsafe_no_autoinit")]
 static mut IS_INIT: bool = false;
@@ -87,6 +129,7 @@ pub fn init() {
             lvgl_sys::lv_init();
             IS_INIT = true;
         }
+        crate::theme::register_theme_changed_event();
     }
 }
 
@@ -113,6 +156,7 @@ fn once_init() {
     unsafe {
         lvgl_sys::lv_init();
     }
+    crate::theme::register_theme_changed_event();
 }
 
 #[cfg(test)]
@@ -120,6 +164,9 @@ pub(crate) mod tests {
     use crate::display::{Display, DrawBuffer};
 
     pub(crate) fn initialize_test(buf: bool) {
+        // Held until the next test takes it over
+        #[cfg(feature = "threadsafe")]
+        core::mem::forget(crate::sync::steal());
         unsafe { crate::deinit() };
         crate::init();
         if buf {
@@ -0,0 +1,415 @@
+//! Widget trees loaded at runtime
+//!
+//! A `UiDesc` describes widgets, their properties and styles, and can be
+//! deserialized with `serde` from any format, e.g. JSON or RON. Layouts can
+//! so be stored outside of the firmware, e.g. in external flash, and tweaked
+//! without recompiling:
+//!
+//! ```ignore
+//! let desc: UiDesc = serde_json::from_slice(&layout)?;
+//! let ui = desc.load(&mut screen)?;
+//! let title = ui.get("title").unwrap();
+//! ```
+//!
+//! with `layout` being e.g.
+//!
+//! ```json
+//! {
+//!   "widgets": [
+//!     { "type": "label", "id": "title", "text": "Settings",
+//!       "align": ["top_mid", 0, 10], "style": { "text_color": "#ffffff" } },
+//!     { "type": "slider", "id": "volume", "width": 200, "range": [0, 11],
+//!       "value": 7, "align": ["center", 0, 0] }
+//!   ]
+//! }
+//! ```
+
+use crate::lv_core::style::Style;
+use crate::{Align, LvError, LvResult, NativeObject, Obj, Widget};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+use core::ptr::NonNull;
+use cstr_core::CString;
+use cty::c_void;
+use serde::Deserialize;
+
+/// A tree of widgets, see the module documentation.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UiDesc {
+    pub widgets: Vec<WidgetDesc>,
+}
+
+/// A widget, its properties and its children. Properties not set keep the
+/// default LVGL gives them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WidgetDesc {
+    #[serde(rename = "type")]
+    pub kind: WidgetKind,
+    /// The name the widget can be found by in the `LoadedUi`.
+    #[serde(default)]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub x: Option<i16>,
+    #[serde(default)]
+    pub y: Option<i16>,
+    #[serde(default)]
+    pub width: Option<i16>,
+    #[serde(default)]
+    pub height: Option<i16>,
+    /// Alignment to the parent, with an offset.
+    #[serde(default)]
+    pub align: Option<(Align, i16, i16)>,
+    /// Text of a label or checkbox.
+    #[serde(default)]
+    pub text: Option<String>,
+    /// Value of a bar, slider or arc.
+    #[serde(default)]
+    pub value: Option<i32>,
+    /// Minimum and maximum value of a bar, slider or arc.
+    #[serde(default)]
+    pub range: Option<(i32, i32)>,
+    #[serde(default)]
+    pub checked: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    /// Style of the main part, see `Style`'s `Deserialize` implementation.
+    #[serde(default)]
+    pub style: Option<Style>,
+    #[serde(default)]
+    pub children: Vec<WidgetDesc>,
+}
+
+/// The widgets a `WidgetDesc` can create. Widgets not enabled in this build
+/// fail to load with `LvError::InvalidArgument`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WidgetKind {
+    Obj,
+    Btn,
+    Label,
+    Bar,
+    Slider,
+    Arc,
+    Checkbox,
+    Switch,
+}
+
+/// The widgets created from a `UiDesc`. Dropping it deletes them.
+pub struct LoadedUi<'a> {
+    roots: Vec<NonNull<lvgl_sys::lv_obj_t>>,
+    ids: BTreeMap<String, Obj<'a>>,
+    // Referenced by the widgets until they are deleted
+    styles: Vec<Style>,
+}
+
+impl<'a> LoadedUi<'a> {
+    /// The widget with the `id`.
+    pub fn get(&self, id: &str) -> Option<&Obj<'a>> {
+        self.ids.get(id)
+    }
+
+    /// The widget with the `id`, as the widget type it was created as.
+    ///
+    /// # Safety
+    ///
+    /// `W` must be the type the widget was created as, e.g. `Slider` for a
+    /// `slider`.
+    pub unsafe fn get_as<W: Widget<'a>>(&self, id: &str) -> Option<W> {
+        W::from_raw(self.ids.get(id)?.raw())
+    }
+
+    /// The IDs of all widgets that have one.
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.ids.keys().map(String::as_str)
+    }
+}
+
+/// The widgets and styles of a dropped `LoadedUi`, deleted together.
+struct Remains {
+    roots: Vec<NonNull<lvgl_sys::lv_obj_t>>,
+    styles: Vec<Style>,
+}
+
+impl Drop for LoadedUi<'_> {
+    fn drop(&mut self) {
+        let remains = Remains {
+            roots: mem::take(&mut self.roots),
+            styles: mem::take(&mut self.styles),
+        };
+        // Deleting a widget dispatching an event, e.g. a button dropping its
+        // own UI, is left to the next `task_handler()`, and so are the styles
+        // the widgets are drawn with until then
+        if !remains
+            .roots
+            .iter()
+            .any(|root| crate::support::is_dispatching(root.as_ptr()))
+        {
+            remains.delete();
+            return;
+        }
+        // Out of memory otherwise, and leaking the widgets and styles beats
+        // deleting them now
+        let remains = Box::into_raw(Box::new(remains));
+        unsafe { lvgl_sys::lv_async_call(Some(delete_remains), remains as *mut c_void) };
+    }
+}
+
+impl Remains {
+    fn delete(&self) {
+        // Widgets may have been deleted with their parent already
+        for root in &self.roots {
+            unsafe {
+                if lvgl_sys::lv_obj_is_valid(root.as_ptr()) {
+                    lvgl_sys::lv_obj_del(root.as_ptr());
+                }
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn delete_remains(remains: *mut c_void) {
+    Box::from_raw(remains as *mut Remains).delete();
+}
+
+impl UiDesc {
+    /// Creates the widgets in `parent`. Fails with `LvError::InvalidArgument`
+    /// if a widget isn't enabled in this build, or a property doesn't apply
+    /// to it.
+    pub fn load<'a>(&self, parent: &mut impl NativeObject) -> LvResult<LoadedUi<'a>> {
+        let mut ui = LoadedUi {
+            roots: Vec::new(),
+            ids: BTreeMap::new(),
+            styles: Vec::new(),
+        };
+        for desc in &self.widgets {
+            let root = desc.create(parent.raw(), &mut ui)?;
+            ui.roots.push(root);
+        }
+        Ok(ui)
+    }
+}
+
+impl WidgetDesc {
+    fn create(
+        &self,
+        parent: NonNull<lvgl_sys::lv_obj_t>,
+        ui: &mut LoadedUi,
+    ) -> LvResult<NonNull<lvgl_sys::lv_obj_t>> {
+        let obj = NonNull::new(unsafe { self.kind.create(parent.as_ptr())? })
+            .ok_or(LvError::LvOOMemory)?;
+        // Deleted again with its parent if anything fails from here on
+        if let Err(e) = self.apply(obj, ui) {
+            unsafe { lvgl_sys::lv_obj_del(obj.as_ptr()) };
+            return Err(e);
+        }
+        if let Some(id) = &self.id {
+            ui.ids.insert(id.clone(), unsafe { Obj::from_raw(obj).unwrap() });
+        }
+        Ok(obj)
+    }
+
+    fn apply(&self, obj: NonNull<lvgl_sys::lv_obj_t>, ui: &mut LoadedUi) -> LvResult<()> {
+        let raw = obj.as_ptr();
+        unsafe {
+            if let Some(x) = self.x {
+                lvgl_sys::lv_obj_set_x(raw, x as lvgl_sys::lv_coord_t);
+            }
+            if let Some(y) = self.y {
+                lvgl_sys::lv_obj_set_y(raw, y as lvgl_sys::lv_coord_t);
+            }
+            if let Some(width) = self.width {
+                lvgl_sys::lv_obj_set_width(raw, width as lvgl_sys::lv_coord_t);
+            }
+            if let Some(height) = self.height {
+                lvgl_sys::lv_obj_set_height(raw, height as lvgl_sys::lv_coord_t);
+            }
+            if let Some((align, x, y)) = self.align {
+                lvgl_sys::lv_obj_align(
+                    raw,
+                    align.into(),
+                    x as lvgl_sys::lv_coord_t,
+                    y as lvgl_sys::lv_coord_t,
+                );
+            }
+            if let Some(text) = &self.text {
+                let text = CString::new(text.as_str()).map_err(|_| LvError::InvalidArgument)?;
+                self.kind.set_text(raw, &text)?;
+            }
+            if let Some((min, max)) = self.range {
+                self.kind.set_range(raw, min, max)?;
+            }
+            if let Some(value) = self.value {
+                self.kind.set_value(raw, value)?;
+            }
+            if self.checked {
+                lvgl_sys::lv_obj_add_state(raw, lvgl_sys::LV_STATE_CHECKED as lvgl_sys::lv_state_t);
+            }
+            if self.hidden {
+                lvgl_sys::lv_obj_add_flag(raw, lvgl_sys::LV_OBJ_FLAG_HIDDEN as lvgl_sys::lv_obj_flag_t);
+            }
+            if let Some(style) = &self.style {
+                let mut style = style.clone();
+                lvgl_sys::lv_obj_add_style(
+                    raw,
+                    style.raw.as_mut(),
+                    lvgl_sys::LV_PART_MAIN as lvgl_sys::lv_style_selector_t,
+                );
+                ui.styles.push(style);
+            }
+        }
+        for child in &self.children {
+            child.create(obj, ui)?;
+        }
+        Ok(())
+    }
+}
+
+impl WidgetKind {
+    unsafe fn create(self, parent: *mut lvgl_sys::lv_obj_t) -> LvResult<*mut lvgl_sys::lv_obj_t> {
+        Ok(match self {
+            WidgetKind::Obj => lvgl_sys::lv_obj_create(parent),
+            #[cfg(lv_widget = "btn")]
+            WidgetKind::Btn => lvgl_sys::lv_btn_create(parent),
+            #[cfg(lv_widget = "label")]
+            WidgetKind::Label => lvgl_sys::lv_label_create(parent),
+            #[cfg(lv_widget = "bar")]
+            WidgetKind::Bar => lvgl_sys::lv_bar_create(parent),
+            #[cfg(lv_widget = "slider")]
+            WidgetKind::Slider => lvgl_sys::lv_slider_create(parent),
+            #[cfg(lv_widget = "arc")]
+            WidgetKind::Arc => lvgl_sys::lv_arc_create(parent),
+            #[cfg(lv_widget = "checkbox")]
+            WidgetKind::Checkbox => lvgl_sys::lv_checkbox_create(parent),
+            #[cfg(lv_widget = "switch")]
+            WidgetKind::Switch => lvgl_sys::lv_switch_create(parent),
+            #[allow(unreachable_patterns)]
+            _ => return Err(LvError::InvalidArgument),
+        })
+    }
+
+    unsafe fn set_text(self, obj: *mut lvgl_sys::lv_obj_t, text: &CString) -> LvResult<()> {
+        match self {
+            #[cfg(lv_widget = "label")]
+            WidgetKind::Label => lvgl_sys::lv_label_set_text(obj, text.as_ptr()),
+            #[cfg(lv_widget = "checkbox")]
+            WidgetKind::Checkbox => lvgl_sys::lv_checkbox_set_text(obj, text.as_ptr()),
+            _ => return Err(LvError::InvalidArgument),
+        }
+        Ok(())
+    }
+
+    unsafe fn set_range(self, obj: *mut lvgl_sys::lv_obj_t, min: i32, max: i32) -> LvResult<()> {
+        match self {
+            #[cfg(any(lv_widget = "bar", lv_widget = "slider"))]
+            WidgetKind::Bar | WidgetKind::Slider => lvgl_sys::lv_bar_set_range(obj, min, max),
+            #[cfg(lv_widget = "arc")]
+            WidgetKind::Arc => lvgl_sys::lv_arc_set_range(obj, arc_value(min)?, arc_value(max)?),
+            _ => return Err(LvError::InvalidArgument),
+        }
+        Ok(())
+    }
+
+    unsafe fn set_value(self, obj: *mut lvgl_sys::lv_obj_t, value: i32) -> LvResult<()> {
+        match self {
+            #[cfg(any(lv_widget = "bar", lv_widget = "slider"))]
+            WidgetKind::Bar | WidgetKind::Slider => {
+                lvgl_sys::lv_bar_set_value(obj, value, lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF)
+            }
+            #[cfg(lv_widget = "arc")]
+            WidgetKind::Arc => lvgl_sys::lv_arc_set_value(obj, arc_value(value)?),
+            _ => return Err(LvError::InvalidArgument),
+        }
+        Ok(())
+    }
+}
+
+/// Arcs take 16-bit values only.
+#[cfg(lv_widget = "arc")]
+fn arc_value(value: i32) -> LvResult<i16> {
+    i16::try_from(value).map_err(|_| LvError::InvalidArgument)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn load_json_layout() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+
+        let desc: UiDesc = serde_json::from_str(
+            r##"{
+                "widgets": [
+                    { "type": "obj", "id": "panel", "width": 200, "height": 100,
+                      "style": { "bg_color": "#336699", "pad": 4 },
+                      "children": [
+                        { "type": "label", "id": "title", "text": "Hi",
+                          "align": ["top_mid", 0, 0] }
+                      ] }
+                ]
+            }"##,
+        )
+        .unwrap();
+        let ui = desc.load(&mut screen).unwrap();
+        assert_eq!(ui.ids().collect::<Vec<_>>(), ["panel", "title"]);
+        let panel = ui.get("panel").unwrap();
+        assert_eq!(panel.coords().x2 - panel.coords().x1, 199);
+        assert_eq!(ui.get("title").unwrap().get_parent().unwrap().raw(), panel.raw());
+        assert_eq!(screen.child_count(), 1);
+
+        drop(ui);
+        assert_eq!(screen.child_count(), 0);
+
+        let bad: UiDesc =
+            serde_json::from_str(r#"{ "widgets": [{ "type": "obj", "value": 1 }] }"#).unwrap();
+        assert!(bad.load(&mut screen).is_err());
+        assert_eq!(screen.child_count(), 0);
+    }
+
+    #[test]
+    fn drop_defers_to_dispatching_widgets() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let desc: UiDesc = serde_json::from_str(
+            r##"{ "widgets": [{ "type": "obj", "id": "panel", "style": { "bg_color": "#336699" },
+                                "children": [{ "type": "obj", "id": "button" }] }] }"##,
+        )
+        .unwrap();
+
+        let ui = desc.load(&mut screen).unwrap();
+        let panel = ui.get("panel").unwrap().raw().as_ptr();
+        let button = ui.get("button").unwrap().raw().as_ptr();
+        unsafe { crate::support::dispatching(button, || drop(ui)) };
+        assert!(unsafe { lvgl_sys::lv_obj_is_valid(panel) });
+        crate::task_handler();
+        assert!(!unsafe { lvgl_sys::lv_obj_is_valid(panel) });
+
+        // Deleted with the screen's children before the UI is dropped
+        let ui = desc.load(&mut screen).unwrap();
+        unsafe { lvgl_sys::lv_obj_clean(screen.raw().as_ptr()) };
+        drop(ui);
+        assert_eq!(screen.child_count(), 0);
+    }
+
+    #[cfg(lv_widget = "arc")]
+    #[test]
+    fn arc_range_is_16_bit() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let desc: UiDesc =
+            serde_json::from_str(r#"{ "widgets": [{ "type": "arc", "range": [0, 40000] }] }"#)
+                .unwrap();
+        assert_eq!(desc.load(&mut screen).err(), Some(LvError::InvalidArgument));
+        assert_eq!(screen.child_count(), 0);
+    }
+}
@@ -0,0 +1,103 @@
+//! LVGL's log output through the `log` crate or `defmt`
+//!
+//! With the `logger` feature, LVGL is built with `LV_USE_LOG` and its
+//! messages can be forwarded to whatever logger the application installed,
+//! under the `lvgl` target:
+//!
+//! ```ignore
+//! env_logger::init();
+//! lvgl::logger::register();
+//! ```
+//!
+//! The `defmt` feature does the same for `defmt`, e.g. for RTT on Cortex-M
+//! targets without a `log` implementation. With both features, messages go
+//! to both.
+//!
+//! Which messages LVGL produces at all is set by `LV_LOG_LEVEL` in
+//! `lv_conf.h`; the filtering of `log` or `defmt` applies on top of that.
+
+use cstr_core::CStr;
+
+/// The levels of `lv_log_add()`. `LV_LOG_LEVEL_USER` is logged as `Info`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Level {
+    Trace,
+    Info,
+    Warn,
+    Error,
+}
+
+#[cfg(feature = "logger")]
+impl From<Level> for log::Level {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Trace => log::Level::Trace,
+            Level::Info => log::Level::Info,
+            Level::Warn => log::Level::Warn,
+            Level::Error => log::Level::Error,
+        }
+    }
+}
+
+/// Forwards LVGL's log messages to the `log` crate and/or `defmt` from now
+/// on.
+pub fn register() {
+    unsafe { lvgl_sys::lv_log_register_print_cb(Some(print_cb)) }
+}
+
+unsafe extern "C" fn print_cb(buf: *const cty::c_char) {
+    if buf.is_null() {
+        return;
+    }
+    let line = CStr::from_ptr(buf).to_str().unwrap_or("<invalid UTF-8>");
+    let (level, message) = parse(line);
+    #[cfg(feature = "logger")]
+    log::log!(target: "lvgl", level.into(), "{}", message);
+    #[cfg(feature = "defmt")]
+    match level {
+        Level::Trace => defmt::trace!("lvgl: {=str}", message),
+        Level::Info => defmt::info!("lvgl: {=str}", message),
+        Level::Warn => defmt::warn!("lvgl: {=str}", message),
+        Level::Error => defmt::error!("lvgl: {=str}", message),
+    }
+}
+
+/// Splits a line formatted by `lv_log_add()`, e.g.
+/// `[Warn]\t(0.120, +120)\t lv_obj_del: message \t(in lv_obj.c line #42)\n`,
+/// into its level and the part after the timestamp.
+fn parse(line: &str) -> (Level, &str) {
+    let (level, rest) = line
+        .strip_prefix('[')
+        .and_then(|l| l.split_once(']'))
+        .unwrap_or(("", line));
+    let level = match level {
+        "Trace" => Level::Trace,
+        "Info" | "User" => Level::Info,
+        "Warn" => Level::Warn,
+        "Error" => Level::Error,
+        _ => Level::Info,
+    };
+    let rest = rest.trim_start();
+    let message = match rest.strip_prefix('(').and_then(|r| r.split_once(')')) {
+        Some((_timestamp, message)) => message,
+        None => rest,
+    };
+    (level, message.trim())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn levels_are_mapped() {
+        let (level, message) =
+            parse("[Warn]\t(0.120, +120)\t lv_obj_del: busy \t(in lv_obj.c line #42)\n");
+        assert_eq!(level, Level::Warn);
+        assert_eq!(message, "lv_obj_del: busy \t(in lv_obj.c line #42)");
+        assert_eq!(parse("[Error]\t(1.000, +0)\t x\n").0, Level::Error);
+        assert_eq!(parse("[User]\t(1.000, +0)\t x\n").0, Level::Info);
+        assert_eq!(parse("[Trace]\t(1.000, +0)\t x\n").0, Level::Trace);
+        assert_eq!(parse("no prefix"), (Level::Info, "no prefix"));
+    }
+}
@@ -0,0 +1,94 @@
+use crate::lv_core::style::Style;
+use crate::support::{add_closure_cb, event_callback, Event};
+use crate::{Align, Widget};
+
+/// Sets up a new widget with chained calls, see e.g. `Btn::builder()`.
+///
+/// ```ignore
+/// let button = Btn::builder(&mut screen)?
+///     .size(120, 40)
+///     .align(Align::Center, 0, 0)
+///     .style(Part::Main, &mut style)
+///     .on_event(|_, event| println!("{:?}", event))
+///     .build();
+/// ```
+pub struct Builder<W> {
+    widget: W,
+}
+
+impl<'a, W: Widget<'a>> Builder<W> {
+    /// Starts setting up a widget that was just created.
+    pub fn new(widget: W) -> Self {
+        Self { widget }
+    }
+
+    /// Sets the position relative to the parent, see `Widget::set_pos()`.
+    pub fn pos(mut self, x: i16, y: i16) -> Self {
+        self.widget.set_pos(x, y);
+        self
+    }
+
+    /// Sets the size, see `Widget::set_size()`.
+    pub fn size(mut self, w: i16, h: i16) -> Self {
+        self.widget.set_size(w, h);
+        self
+    }
+
+    /// Aligns the widget to its parent, see `Widget::set_align()`.
+    pub fn align(mut self, align: Align, x_mod: i32, y_mod: i32) -> Self {
+        self.widget.set_align(align, x_mod, y_mod);
+        self
+    }
+
+    /// Adds a style to a part, see `Widget::add_style()`.
+    pub fn style(mut self, part: W::Part, style: &'a mut Style) -> Self {
+        self.widget.add_style(part, style);
+        self
+    }
+
+    /// Calls `f` on every event of the widget, like the `on_event()` method
+    /// of the widget.
+    pub fn on_event<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(W, Event<W::SpecialEvent>),
+    {
+        unsafe {
+            add_closure_cb(self.widget.raw().as_ptr(), event_callback::<'a, W, F>, f);
+        }
+        self
+    }
+
+    /// Returns the widget set up.
+    pub fn build(self) -> W {
+        self.widget
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Obj, Part};
+
+    #[test]
+    fn builder_sets_up_widget() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+
+        let obj = Builder::new(Obj::create(&screen).unwrap())
+            .pos(10, 20)
+            .size(100, 40)
+            .on_event(|_, _| {})
+            .build();
+        let coords = obj.coords();
+        assert_eq!((coords.x1, coords.y1), (10, 20));
+        assert_eq!((coords.x2, coords.y2), (109, 59));
+
+        let mut style = crate::Style::default();
+        let obj = Builder::new(Obj::create(&screen).unwrap())
+            .align(Align::Center, 0, 0)
+            .style(Part::Main, &mut style)
+            .build();
+        assert!(obj.coords().x1 > 0);
+    }
+}
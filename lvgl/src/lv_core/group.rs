@@ -48,6 +48,7 @@ impl Drop for Group {
     fn drop(&mut self) {
         unsafe {
             if let Ok(mut p) = self.raw() {
+                crate::display::forget_default_group(p.as_ptr());
                 lvgl_sys::lv_group_del(p.as_mut())
             }
         }
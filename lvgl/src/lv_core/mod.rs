@@ -1,8 +1,22 @@
 #[macro_use]
 pub mod obj;
+pub mod builder;
+pub mod owned;
 pub mod group;
+#[cfg(feature = "msg")]
+pub mod msg;
 pub mod screen;
+pub mod scroll;
+pub mod scrollbar;
 pub mod style;
+#[cfg(feature = "serde")]
+mod style_serde;
+pub mod text;
+pub mod theme;
+pub(crate) mod user_data;
 
+pub use builder::*;
 pub use obj::*;
+pub use owned::*;
 pub use screen::*;
+pub use text::{IntoOptionalText, IntoText, Text};
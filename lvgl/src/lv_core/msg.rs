@@ -0,0 +1,266 @@
+//! Publish/subscribe messaging
+//!
+//! Wraps LVGL's `lv_msg` module, which delivers messages identified by a
+//! `u32` ID to every subscriber of that ID. Widgets subscribe with
+//! `Widget::observe()`; the subscription lives exactly as long as the widget
+//! and is removed by LVGL's `LV_EVENT_DELETE`, so a deleted widget is never
+//! notified.
+//!
+//! On top of that, a `Subject` holds a typed value and updates the widgets
+//! bound to it whenever the value is set, a lightweight form of data
+//! binding:
+//!
+//! ```ignore
+//! let mut temperature = Subject::new(c"--");
+//! temperature.bind(&mut label, |label, text| label.set_text(*text))?;
+//! temperature.set(c"21.5");
+//! ```
+//!
+//! Requires the `msg` feature, which sets `LV_USE_MSG` in `lvgl-sys`.
+
+use crate::{Box, LvError, LvResult, Widget};
+use core::ffi::c_void;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// IDs from here on are taken by `Subject`s, which `send()` and `notify()`
+/// refuse, as bound widgets expect the payload type of the subject.
+pub const SUBJECT_IDS: u32 = 0x8000_0000;
+
+static NEXT_SUBJECT_ID: AtomicU32 = AtomicU32::new(SUBJECT_IDS);
+
+/// A message being delivered to a subscriber.
+pub struct Msg<'m> {
+    raw: NonNull<lvgl_sys::lv_msg_t>,
+    _payload: PhantomData<&'m c_void>,
+}
+
+impl Msg<'_> {
+    /// Returns the ID the message was sent with.
+    pub fn id(&self) -> u32 {
+        unsafe { lvgl_sys::lv_msg_get_id(self.raw.as_ptr()) }
+    }
+
+    /// Returns the raw payload pointer, null if the message has none.
+    pub fn payload_ptr(&self) -> *const c_void {
+        unsafe { lvgl_sys::lv_msg_get_payload(self.raw.as_ptr()) }
+    }
+
+    /// Returns the payload as a `T`, or `None` if the message has none.
+    ///
+    /// # Safety
+    ///
+    /// The message must have been sent with a payload of type `T`, e.g. by
+    /// `send(id, &value)` with `value: T`.
+    pub unsafe fn payload<T>(&self) -> Option<&T> {
+        (self.payload_ptr() as *const T).as_ref()
+    }
+}
+
+/// Sends a message to every subscriber of `id`. Subscribers are notified
+/// synchronously, before this function returns, so the payload only needs to
+/// live for the duration of the call.
+///
+/// Fails with `LvError::InvalidArgument` if `id` is one of the `SUBJECT_IDS`.
+pub fn send<T>(id: u32, payload: &T) -> LvResult<()> {
+    check_id(id)?;
+    unsafe { send_unchecked(id, payload) };
+    Ok(())
+}
+
+/// Sends a message without a payload to every subscriber of `id`.
+///
+/// Fails like `send()`.
+pub fn notify(id: u32) -> LvResult<()> {
+    check_id(id)?;
+    unsafe { lvgl_sys::lv_msg_send(id, ptr::null()) };
+    Ok(())
+}
+
+fn check_id(id: u32) -> LvResult<()> {
+    match id {
+        SUBJECT_IDS.. => Err(LvError::InvalidArgument),
+        _ => Ok(()),
+    }
+}
+
+/// Sends `payload` with any `id`, which subscribers must expect a `T` from.
+unsafe fn send_unchecked<T>(id: u32, payload: &T) {
+    lvgl_sys::lv_msg_send(id, payload as *const T as *const c_void)
+}
+
+/// The state of one `Widget::observe()` subscription. Owned by LVGL and
+/// freed together with the widget.
+struct Observer<W, F> {
+    obj: *mut lvgl_sys::lv_obj_t,
+    sub: *mut c_void,
+    f: F,
+    _widget: PhantomData<fn(W)>,
+}
+
+pub(crate) fn observe<'a, W, F>(widget: &mut W, id: u32, f: F) -> LvResult<()>
+where
+    W: Widget<'a>,
+    F: FnMut(W, &Msg) + 'a,
+{
+    let obj = widget.raw().as_ptr();
+    let observer = Box::into_raw(Box::new(Observer {
+        obj,
+        sub: ptr::null_mut(),
+        f,
+        _widget: PhantomData::<fn(W)>,
+    }));
+    unsafe {
+        let sub =
+            lvgl_sys::lv_msg_subscribe(id, Some(notify_cb::<'a, W, F>), observer as *mut c_void);
+        if sub.is_null() {
+            drop(Box::from_raw(observer));
+            return Err(LvError::LvOOMemory);
+        }
+        (*observer).sub = sub;
+        lvgl_sys::lv_obj_add_event_cb(
+            obj,
+            Some(delete_cb::<W, F>),
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+            observer as *mut c_void,
+        );
+    }
+    Ok(())
+}
+
+unsafe extern "C" fn notify_cb<'a, W, F>(_sub: *mut c_void, msg: *mut lvgl_sys::lv_msg_t)
+where
+    W: Widget<'a>,
+    F: FnMut(W, &Msg) + 'a,
+{
+    let observer = &mut *(lvgl_sys::lv_msg_get_user_data(msg) as *mut Observer<W, F>);
+    if let (Some(obj), Some(raw)) = (NonNull::new(observer.obj), NonNull::new(msg)) {
+        let widget = W::from_raw(obj).unwrap();
+        let msg = Msg {
+            raw,
+            _payload: PhantomData,
+        };
+        crate::support::dispatching(observer.obj, || (observer.f)(widget, &msg));
+    }
+}
+
+unsafe extern "C" fn delete_cb<W, F>(e: *mut lvgl_sys::lv_event_t) {
+    let observer = Box::from_raw((*e).user_data as *mut Observer<W, F>);
+    lvgl_sys::lv_msg_unsubscribe(observer.sub);
+}
+
+/// A value widgets can be bound to, updating them whenever it changes.
+///
+/// Every subject sends its value as messages with an ID of its own, taken
+/// from `SUBJECT_IDS` onwards.
+pub struct Subject<T> {
+    id: u32,
+    value: T,
+}
+
+impl<T> Subject<T> {
+    /// Creates a subject holding `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            id: NEXT_SUBJECT_ID.fetch_add(1, Ordering::Relaxed),
+            value,
+        }
+    }
+
+    /// Returns the message ID the subject's value is sent with.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Sets the value and updates every widget bound to the subject.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        unsafe { send_unchecked(self.id, &self.value) };
+    }
+
+    /// Calls `f` with `widget` and the current value, and again with every
+    /// value set from now on, until the widget is deleted.
+    pub fn bind<'a, W, F>(&self, widget: &mut W, mut f: F) -> LvResult<()>
+    where
+        W: Widget<'a>,
+        F: FnMut(&mut W, &T) + 'a,
+        T: 'a,
+    {
+        f(widget, &self.value);
+        widget.observe(self.id, move |mut widget, msg| {
+            // Only ever sent by `set()`, with a `T`
+            if let Some(value) = unsafe { msg.payload::<T>() } {
+                f(&mut widget, value);
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Label;
+    use crate::{Display, NativeObject};
+    use core::cell::Cell;
+    use cstr_core::CStr;
+
+    #[test]
+    fn deleted_observer_is_unsubscribed() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let received = Cell::new(0);
+
+        let mut label = Label::create(&mut screen).unwrap();
+        label
+            .observe(1, |_, msg| {
+                received.set(unsafe { *msg.payload::<i32>().unwrap() });
+            })
+            .unwrap();
+
+        send(1, &42i32).unwrap();
+        assert_eq!(received.get(), 42);
+
+        label.del();
+        send(1, &7i32).unwrap();
+        assert_eq!(received.get(), 42);
+    }
+
+    #[test]
+    fn bound_widgets_follow_subject() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+
+        let mut label = Label::create(&mut screen).unwrap();
+        let mut text = Subject::new(CStr::from_bytes_with_nul(b"--\0").unwrap());
+        text.bind(&mut label, |label, text| label.set_text(*text))
+            .unwrap();
+        let label_text = |label: &Label| unsafe {
+            CStr::from_ptr(lvgl_sys::lv_label_get_text(label.raw().as_ptr())).to_bytes()
+        };
+        assert_eq!(label_text(&label), b"--");
+
+        let mut count = Subject::new(0i32);
+        assert_ne!(count.id(), text.id());
+        let seen = Cell::new(-1);
+        count.bind(&mut label, |_, value| seen.set(*value)).unwrap();
+        assert_eq!(seen.get(), 0);
+
+        text.set(CStr::from_bytes_with_nul(b"21.5\0").unwrap());
+        count.set(3);
+        assert_eq!(label_text(&label), b"21.5");
+        assert_eq!(seen.get(), 3);
+        assert_eq!(*count.get(), 3);
+
+        assert_eq!(send(count.id(), &"3"), Err(LvError::InvalidArgument));
+        assert_eq!(notify(text.id()), Err(LvError::InvalidArgument));
+        assert_eq!(seen.get(), 3);
+    }
+}
@@ -5,11 +5,15 @@
 //! are special in that they do not have a parent object but do still implement
 //! `NativeObject`.
 
-use crate::lv_core::style::Style;
-use crate::{Align, LvError, LvResult};
+use crate::draw::part::{DrawCtx, DrawPart};
+use crate::lv_core::scroll::{ScrollSnap, ScrollbarMode};
+use crate::lv_core::scrollbar::ScrollbarDraw;
+use crate::lv_core::style::{Opacity, Style};
+use crate::{Align, AnimationState, Area, Color, Display, LvError, LvResult};
 use core::fmt::{self, Debug};
 use core::marker::PhantomData;
 use core::ptr::{self, NonNull};
+use core::time::Duration;
 
 /// Represents a native LVGL object.
 pub trait NativeObject {
@@ -17,6 +21,15 @@ pub trait NativeObject {
     fn raw(&self) -> NonNull<lvgl_sys::lv_obj_t>;
 }
 
+/// A widget holding a value the user changes, e.g. a slider or a switch,
+/// for `Widget::on_value_changed()`.
+pub trait WidgetValue {
+    type Value;
+
+    /// Returns the current value.
+    fn value(&self) -> Self::Value;
+}
+
 /// Generic LVGL object.
 ///
 /// This is the parent object of all widget types. It stores the native LVGL
@@ -72,13 +85,29 @@ impl<'a> Obj<'a> {
     }
 }
 
+// SAFETY: With `threadsafe`, the object is only reached through `raw()`,
+// which panics unless the current thread holds the LVGL lock
+#[cfg(feature = "threadsafe")]
+unsafe impl Send for Obj<'_> {}
+
 impl NativeObject for Obj<'_> {
     fn raw(&self) -> ptr::NonNull<lvgl_sys::lv_obj_t> {
+        #[cfg(feature = "threadsafe")]
+        crate::sync::assert_locked();
         self.raw
     }
 }
 
+// Defines `generated_widget_methods!()`, the `lv_obj_*` functions without a
+// hand-written counterpart below
+include!(concat!(env!("OUT_DIR"), "/widget_methods.rs"));
+
 /// A wrapper for all LVGL common operations on generic objects.
+///
+/// Besides the methods below, all `lv_obj_*` functions whose arguments can be
+/// mapped to Rust types are generated as methods of this trait, e.g.
+/// `set_x()`, `align()`, `add_flag()` or `clear_state()`, so every widget has
+/// them.
 pub trait Widget<'a>: NativeObject + Sized + 'a {
     type SpecialEvent;
     type Part: Into<lvgl_sys::lv_part_t>;
@@ -139,6 +168,271 @@ pub trait Widget<'a>: NativeObject + Sized + 'a {
         }
     }
 
+    /// Extends the clickable area of a widget by `size` pixels in every
+    /// direction, making small widgets easier to hit on touch screens.
+    fn set_ext_click_area(&mut self, size: i16) {
+        unsafe {
+            lvgl_sys::lv_obj_set_ext_click_area(
+                self.raw().as_mut(),
+                size as lvgl_sys::lv_coord_t,
+            );
+        }
+    }
+
+    /// Returns the widget's final area in absolute coordinates. Pending
+    /// layout updates are applied first, so the result is valid even right
+    /// after creating or resizing widgets, before the next refresh.
+    fn coords(&self) -> Area {
+        let mut area = lvgl_sys::lv_area_t::default();
+        unsafe {
+            lvgl_sys::lv_obj_update_layout(self.raw().as_ptr());
+            lvgl_sys::lv_obj_get_coords(self.raw().as_ptr(), &mut area);
+        }
+        area.into()
+    }
+
+    /// Marks the widget's area for redrawing in the next refresh, e.g. after
+    /// changing what a custom draw callback renders. Changes made through
+    /// the widget's setters invalidate it already.
+    fn invalidate(&mut self) {
+        unsafe { lvgl_sys::lv_obj_invalidate(self.raw().as_ptr()) }
+    }
+
+    /// Returns the display the widget is shown on.
+    fn display(&self) -> LvResult<Display> {
+        match NonNull::new(unsafe { lvgl_sys::lv_obj_get_disp(self.raw().as_ptr()) }) {
+            Some(disp) => Ok(Display::from_raw(disp, None)),
+            None => Err(LvError::InvalidReference),
+        }
+    }
+
+    /// Returns the parent of the widget, or `None` for a screen.
+    fn get_parent(&self) -> Option<Obj<'a>> {
+        let parent = unsafe { lvgl_sys::lv_obj_get_parent(self.raw().as_ptr()) };
+        NonNull::new(parent).and_then(|parent| unsafe { Obj::from_raw(parent) })
+    }
+
+    /// Returns the number of children of the widget.
+    fn child_count(&self) -> u32 {
+        unsafe { lvgl_sys::lv_obj_get_child_cnt(self.raw().as_ptr()) }
+    }
+
+    /// Returns the child at `index`, in the order they were created. Negative
+    /// indices count from the end, so -1 is the child created last.
+    fn get_child(&self, index: i32) -> Option<Obj<'a>> {
+        let child = unsafe { lvgl_sys::lv_obj_get_child(self.raw().as_ptr(), index) };
+        NonNull::new(child).and_then(|child| unsafe { Obj::from_raw(child) })
+    }
+
+    /// Returns an iterator over the children of the widget, in the order they
+    /// were created.
+    fn children(&self) -> Children<'a> {
+        Children {
+            parent: self.raw(),
+            index: 0,
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// Returns the first of the widget's descendants, depth first, whose
+    /// `set_user_data()` value is a `T` equal to `value`. The widget itself
+    /// isn't included.
+    fn find_by_user_data<T: PartialEq + 'static>(&self, value: &T) -> Option<Obj<'a>> {
+        unsafe fn find<T: PartialEq + 'static>(
+            obj: *mut lvgl_sys::lv_obj_t,
+            value: &T,
+        ) -> Option<NonNull<lvgl_sys::lv_obj_t>> {
+            for i in 0..lvgl_sys::lv_obj_get_child_cnt(obj) {
+                let child = lvgl_sys::lv_obj_get_child(obj, i as i32);
+                if crate::lv_core::user_data::get::<T>(child).map_or(false, |v| *v == *value) {
+                    return NonNull::new(child);
+                }
+                if let Some(found) = find(child, value) {
+                    return Some(found);
+                }
+            }
+            None
+        }
+        unsafe { find(self.raw().as_ptr(), value).and_then(|obj| Obj::from_raw(obj)) }
+    }
+
+    /// Attaches `value` to the widget, e.g. application state to be used in
+    /// its event callbacks. A value attached before is dropped; this one is
+    /// dropped when the widget is deleted.
+    fn set_user_data<T: 'static>(&mut self, value: T) {
+        crate::lv_core::user_data::set(self.raw().as_ptr(), value)
+    }
+
+    /// Returns the value attached to the widget, or `None` if there is none
+    /// or it isn't a `T`.
+    fn get_user_data<T: 'static>(&self) -> Option<&T> {
+        unsafe { crate::lv_core::user_data::get::<T>(self.raw().as_ptr()).map(|v| &*v) }
+    }
+
+    /// Returns the value attached to the widget, or `None` if there is none
+    /// or it isn't a `T`.
+    fn get_user_data_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        unsafe { crate::lv_core::user_data::get::<T>(self.raw().as_ptr()) }
+    }
+
+    /// Detaches the value attached to the widget and returns it, if it is a
+    /// `T`.
+    fn take_user_data<T: 'static>(&mut self) -> Option<T> {
+        crate::lv_core::user_data::take(self.raw().as_ptr())
+    }
+
+    /// Deletes the widget and all of its children, right away if possible.
+    ///
+    /// Deleting an object while one of its event callbacks (or one of its
+    /// children's) is running would crash LVGL, so deletion is deferred to
+    /// the next `task_handler()` in that case. Handles to the children are
+    /// left dangling either way; hold them in `Owned` to find out.
+    fn del(self) {
+        unsafe { crate::support::del(self.raw().as_ptr()) }
+    }
+
+    /// Deletes the widget and all of its children on the next call to
+    /// `task_handler()`.
+    fn del_async(self) {
+        unsafe { lvgl_sys::lv_obj_del_async(self.raw().as_ptr()) };
+    }
+
+    /// Sets the thickness of the widget's scrollbars.
+    fn set_scrollbar_width(&mut self, width: i16) {
+        unsafe {
+            lvgl_sys::lv_obj_set_style_width(
+                self.raw().as_ptr(),
+                width as lvgl_sys::lv_coord_t,
+                lvgl_sys::LV_PART_SCROLLBAR,
+            );
+        }
+    }
+
+    /// Sets the color of the widget's scrollbars.
+    fn set_scrollbar_color(&mut self, color: Color) {
+        unsafe {
+            lvgl_sys::lv_obj_set_style_bg_color(
+                self.raw().as_ptr(),
+                color.into(),
+                lvgl_sys::LV_PART_SCROLLBAR,
+            );
+        }
+    }
+
+    /// Sets the opacity of the scrollbars while the widget is idle and while
+    /// it is being scrolled. An idle opacity of `OPA_TRANSP` hides them until
+    /// the user scrolls; see `set_scrollbar_fade()` to animate the change.
+    fn set_scrollbar_opa(&mut self, idle: Opacity, scrolling: Opacity) {
+        let obj = self.raw().as_ptr();
+        unsafe {
+            lvgl_sys::lv_obj_set_style_bg_opa(obj, idle.into(), lvgl_sys::LV_PART_SCROLLBAR);
+            lvgl_sys::lv_obj_set_style_bg_opa(
+                obj,
+                scrolling.into(),
+                lvgl_sys::LV_PART_SCROLLBAR | lvgl_sys::LV_STATE_SCROLLED,
+            );
+        }
+    }
+
+    /// Fades the scrollbars between their idle and scrolling opacity over
+    /// `duration` instead of switching instantly.
+    fn set_scrollbar_fade(&mut self, duration: Duration) {
+        crate::scrollbar::set_fade(self.raw().as_ptr(), duration);
+    }
+
+    /// Sets the corner radius of the widget's scrollbars.
+    fn set_scrollbar_radius(&mut self, radius: i16) {
+        unsafe {
+            lvgl_sys::lv_obj_set_style_radius(
+                self.raw().as_ptr(),
+                radius as lvgl_sys::lv_coord_t,
+                lvgl_sys::LV_PART_SCROLLBAR,
+            );
+        }
+    }
+
+    /// Sets the distance of the scrollbars from the edges of the widget.
+    fn set_scrollbar_pad(&mut self, pad: i16) {
+        let obj = self.raw().as_ptr();
+        let pad = pad as lvgl_sys::lv_coord_t;
+        let part = lvgl_sys::LV_PART_SCROLLBAR;
+        unsafe {
+            lvgl_sys::lv_obj_set_style_pad_top(obj, pad, part);
+            lvgl_sys::lv_obj_set_style_pad_bottom(obj, pad, part);
+            lvgl_sys::lv_obj_set_style_pad_left(obj, pad, part);
+            lvgl_sys::lv_obj_set_style_pad_right(obj, pad, part);
+        }
+    }
+
+    /// Calls `f` just before each of the widget's scrollbars is drawn, to
+    /// adjust how it looks.
+    fn on_scrollbar_draw<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut ScrollbarDraw) + 'a,
+    {
+        unsafe {
+            crate::support::add_closure_cb(self.raw().as_ptr(), crate::scrollbar::draw_cb::<F>, f)
+        }
+    }
+
+    /// Calls `f` before and after each part of the widget is drawn, to
+    /// change how it looks or to paint over it, see `draw::part`.
+    fn on_draw_part<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut DrawPart) + 'a,
+    {
+        unsafe {
+            crate::support::add_closure_cb(
+                self.raw().as_ptr(),
+                crate::draw::part::draw_part_cb::<F>,
+                f,
+            )
+        }
+    }
+
+    /// Calls `f` after the widget and its children are drawn, to paint on
+    /// top of them.
+    fn on_draw_post<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut DrawCtx) + 'a,
+    {
+        unsafe {
+            crate::support::add_closure_cb(
+                self.raw().as_ptr(),
+                crate::draw::part::draw_post_cb::<F>,
+                f,
+            )
+        }
+    }
+
+    /// Calls `f` with the widget and its new value whenever the user changes
+    /// it, i.e. on every `ValueChanged` event. Values set by the program
+    /// aren't reported.
+    fn on_value_changed<F>(&mut self, f: F) -> LvResult<()>
+    where
+        Self: WidgetValue,
+        F: FnMut(Self, <Self as WidgetValue>::Value) + 'a,
+    {
+        unsafe {
+            crate::support::add_closure_cb(
+                self.raw().as_ptr(),
+                crate::support::value_changed_cb::<'a, Self, F>,
+                f,
+            )
+        }
+        Ok(())
+    }
+
+    /// Calls `f` with the widget for every message sent with `id`, see the
+    /// `msg` module. The subscription is removed when the widget is deleted.
+    #[cfg(feature = "msg")]
+    fn observe<F>(&mut self, id: u32, f: F) -> LvResult<()>
+    where
+        F: FnMut(Self, &crate::msg::Msg) + 'a,
+    {
+        crate::msg::observe(self, id, f)
+    }
+
     /// Sets a widget's align relative to its parent along with an offset.
     fn set_align(&mut self, align: Align, x_mod: i32, y_mod: i32) {
         unsafe {
@@ -150,6 +444,174 @@ pub trait Widget<'a>: NativeObject + Sized + 'a {
             );
         }
     }
+
+    /// Sets `flags` on the widget, leaving its other flags unchanged.
+    fn add_flag(&mut self, flags: ObjFlag) {
+        unsafe { lvgl_sys::lv_obj_add_flag(self.raw().as_mut(), flags.into()) }
+    }
+
+    /// Clears `flags` on the widget, leaving its other flags unchanged.
+    fn clear_flag(&mut self, flags: ObjFlag) {
+        unsafe { lvgl_sys::lv_obj_clear_flag(self.raw().as_mut(), flags.into()) }
+    }
+
+    /// Returns `true` if all of `flags` are set on the widget.
+    fn has_flag(&self, flags: ObjFlag) -> bool {
+        unsafe { lvgl_sys::lv_obj_has_flag(self.raw().as_ptr(), flags.into()) }
+    }
+
+    /// Returns `true` if any of `flags` is set on the widget.
+    fn has_flag_any(&self, flags: ObjFlag) -> bool {
+        unsafe { lvgl_sys::lv_obj_has_flag_any(self.raw().as_ptr(), flags.into()) }
+    }
+
+    /// Adds `states` to the widget, e.g. `State::CHECKED`. Styles added for
+    /// those states apply from now on.
+    fn add_state(&mut self, states: State) {
+        unsafe { lvgl_sys::lv_obj_add_state(self.raw().as_mut(), states.into()) }
+    }
+
+    /// Removes `states` from the widget.
+    fn clear_state(&mut self, states: State) {
+        unsafe { lvgl_sys::lv_obj_clear_state(self.raw().as_mut(), states.into()) }
+    }
+
+    /// Returns `true` if the widget is in all of `states`.
+    fn has_state(&self, states: State) -> bool {
+        unsafe { lvgl_sys::lv_obj_has_state(self.raw().as_ptr(), states.into()) }
+    }
+
+    /// Returns the states the widget is in.
+    fn get_state(&self) -> State {
+        State::from_bits_retain(unsafe { lvgl_sys::lv_obj_get_state(self.raw().as_ptr()) })
+    }
+
+    /// Scrolls the content of the widget so that `(x, y)` of the content is
+    /// at its top left corner.
+    fn scroll_to(&mut self, x: i16, y: i16, anim: AnimationState) {
+        unsafe {
+            lvgl_sys::lv_obj_scroll_to(
+                self.raw().as_mut(),
+                x as lvgl_sys::lv_coord_t,
+                y as lvgl_sys::lv_coord_t,
+                anim.into(),
+            )
+        }
+    }
+
+    /// Scrolls the content of the widget by `dx` and `dy`. Positive values
+    /// move the content right and down, towards its start.
+    fn scroll_by(&mut self, dx: i16, dy: i16, anim: AnimationState) {
+        unsafe {
+            lvgl_sys::lv_obj_scroll_by(
+                self.raw().as_mut(),
+                dx as lvgl_sys::lv_coord_t,
+                dy as lvgl_sys::lv_coord_t,
+                anim.into(),
+            )
+        }
+    }
+
+    /// Scrolls the parent of the widget until the widget is visible in it.
+    fn scroll_to_view(&mut self, anim: AnimationState) {
+        unsafe { lvgl_sys::lv_obj_scroll_to_view(self.raw().as_mut(), anim.into()) }
+    }
+
+    /// Scrolls all ancestors of the widget until the widget is visible on
+    /// the screen, e.g. for an item in a nested scrolling list.
+    fn scroll_to_view_recursive(&mut self, anim: AnimationState) {
+        unsafe { lvgl_sys::lv_obj_scroll_to_view_recursive(self.raw().as_mut(), anim.into()) }
+    }
+
+    /// Returns how far the content of the widget is scrolled horizontally
+    /// and vertically.
+    fn scroll_position(&self) -> (i16, i16) {
+        let obj = self.raw().as_ptr();
+        unsafe {
+            (
+                lvgl_sys::lv_obj_get_scroll_x(obj) as i16,
+                lvgl_sys::lv_obj_get_scroll_y(obj) as i16,
+            )
+        }
+    }
+
+    /// Returns how far the content of the widget can still be scrolled up,
+    /// i.e. how much of it is hidden above the widget.
+    fn get_scroll_top(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_scroll_top(self.raw().as_ptr()) as i16 }
+    }
+
+    /// Returns how much of the content is hidden below the widget.
+    fn get_scroll_bottom(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_scroll_bottom(self.raw().as_ptr()) as i16 }
+    }
+
+    /// Returns how much of the content is hidden left of the widget.
+    fn get_scroll_left(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_scroll_left(self.raw().as_ptr()) as i16 }
+    }
+
+    /// Returns how much of the content is hidden right of the widget.
+    fn get_scroll_right(&self) -> i16 {
+        unsafe { lvgl_sys::lv_obj_get_scroll_right(self.raw().as_ptr()) as i16 }
+    }
+
+    /// Sets when the scrollbars of the widget are shown.
+    fn set_scrollbar_mode(&mut self, mode: ScrollbarMode) {
+        unsafe { lvgl_sys::lv_obj_set_scrollbar_mode(self.raw().as_mut(), mode.into()) }
+    }
+
+    /// Returns when the scrollbars of the widget are shown.
+    fn get_scrollbar_mode(&self) -> ScrollbarMode {
+        unsafe { lvgl_sys::lv_obj_get_scrollbar_mode(self.raw().as_ptr()) }.into()
+    }
+
+    /// Sets where snappable children come to rest horizontally and
+    /// vertically once scrolling ends.
+    fn set_scroll_snap(&mut self, x: ScrollSnap, y: ScrollSnap) {
+        let obj = self.raw().as_ptr();
+        unsafe {
+            lvgl_sys::lv_obj_set_scroll_snap_x(obj, x.into());
+            lvgl_sys::lv_obj_set_scroll_snap_y(obj, y.into());
+        }
+    }
+
+    /// Returns the horizontal and vertical snapping of the widget.
+    fn get_scroll_snap(&self) -> (ScrollSnap, ScrollSnap) {
+        let obj = self.raw().as_ptr();
+        unsafe {
+            (
+                lvgl_sys::lv_obj_get_scroll_snap_x(obj).into(),
+                lvgl_sys::lv_obj_get_scroll_snap_y(obj).into(),
+            )
+        }
+    }
+
+    generated_widget_methods!();
+}
+
+/// Iterator over the children of a widget, see `Widget::children()`.
+pub struct Children<'a> {
+    parent: NonNull<lvgl_sys::lv_obj_t>,
+    index: u32,
+    _lifetime: PhantomData<Obj<'a>>,
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Obj<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let child = unsafe { lvgl_sys::lv_obj_get_child(self.parent.as_ptr(), self.index as i32) };
+        let child = NonNull::new(child)?;
+        self.index += 1;
+        unsafe { Obj::from_raw(child) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = unsafe { lvgl_sys::lv_obj_get_child_cnt(self.parent.as_ptr()) };
+        let left = count.saturating_sub(self.index) as usize;
+        (left, Some(left))
+    }
 }
 
 impl<'a> Widget<'a> for Obj<'a> {
@@ -165,20 +627,22 @@ impl<'a> Widget<'a> for Obj<'a> {
 }
 
 macro_rules! define_object {
-    ($item:ident) => {
-        define_object!($item, event = (), part = $crate::Part);
+    ($(#[$attr:meta])* $item:ident) => {
+        define_object!($(#[$attr])* $item, event = (), part = $crate::Part);
     };
-    ($item:ident, event = $event_type:ty) => {
-        define_object!($item, event = $event_type, part = $crate::Part);
+    ($(#[$attr:meta])* $item:ident, event = $event_type:ty) => {
+        define_object!($(#[$attr])* $item, event = $event_type, part = $crate::Part);
     };
-    ($item:ident, part = $part_type:ty) => {
-        define_object!($item, event = (), part = $part_type);
+    ($(#[$attr:meta])* $item:ident, part = $part_type:ty) => {
+        define_object!($(#[$attr])* $item, event = (), part = $part_type);
     };
-    ($item:ident, part = $part_type:ty, event = $event_type:ty) => {
-        define_object!($item, event = $event_type, part = $part_type);
+    ($(#[$attr:meta])* $item:ident, part = $part_type:ty, event = $event_type:ty) => {
+        define_object!($(#[$attr])* $item, event = $event_type, part = $part_type);
     };
-    ($item:ident, event = $event_type:ty, part = $part_type:ty) => {
+    ($(#[$attr:meta])* $item:ident, event = $event_type:ty, part = $part_type:ty) => {
+        $(#[$attr])*
         #[derive(Debug)]
+        #[repr(transparent)]
         pub struct $item<'a> {
             core: $crate::Obj<'a>,
         }
@@ -190,15 +654,10 @@ macro_rules! define_object {
             {
                 use $crate::NativeObject;
                 unsafe {
-                    let obj = self.raw().as_mut();
-                    obj.user_data = $crate::Box::into_raw($crate::Box::new(f)) as *mut _;
-                    lvgl_sys::lv_obj_add_event_cb(
-                        obj,
-                        lvgl_sys::lv_event_cb_t::Some(
-                            $crate::support::event_callback::<'a, Self, F>,
-                        ),
-                        lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
-                        obj.user_data,
+                    $crate::support::add_closure_cb(
+                        self.raw().as_ptr(),
+                        $crate::support::event_callback::<'a, Self, F>,
+                        f,
                     );
                 }
                 Ok(())
@@ -293,3 +752,121 @@ impl From<Part> for lvgl_sys::lv_part_t {
         }
     }
 }
+
+bitflags! {
+    /// Flags controlling how a widget behaves, e.g. whether it is shown,
+    /// clickable or scrollable. Equivalent to `lv_obj_flag_t`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ObjFlag: u32 {
+        const HIDDEN = lvgl_sys::LV_OBJ_FLAG_HIDDEN as u32;
+        const CLICKABLE = lvgl_sys::LV_OBJ_FLAG_CLICKABLE as u32;
+        const CLICK_FOCUSABLE = lvgl_sys::LV_OBJ_FLAG_CLICK_FOCUSABLE as u32;
+        const CHECKABLE = lvgl_sys::LV_OBJ_FLAG_CHECKABLE as u32;
+        const SCROLLABLE = lvgl_sys::LV_OBJ_FLAG_SCROLLABLE as u32;
+        const SCROLL_ELASTIC = lvgl_sys::LV_OBJ_FLAG_SCROLL_ELASTIC as u32;
+        const SCROLL_MOMENTUM = lvgl_sys::LV_OBJ_FLAG_SCROLL_MOMENTUM as u32;
+        const SCROLL_ONE = lvgl_sys::LV_OBJ_FLAG_SCROLL_ONE as u32;
+        const SCROLL_CHAIN_HOR = lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN_HOR as u32;
+        const SCROLL_CHAIN_VER = lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN_VER as u32;
+        const SCROLL_CHAIN = lvgl_sys::LV_OBJ_FLAG_SCROLL_CHAIN as u32;
+        const SCROLL_ON_FOCUS = lvgl_sys::LV_OBJ_FLAG_SCROLL_ON_FOCUS as u32;
+        const SCROLL_WITH_ARROW = lvgl_sys::LV_OBJ_FLAG_SCROLL_WITH_ARROW as u32;
+        const SNAPPABLE = lvgl_sys::LV_OBJ_FLAG_SNAPPABLE as u32;
+        const PRESS_LOCK = lvgl_sys::LV_OBJ_FLAG_PRESS_LOCK as u32;
+        const EVENT_BUBBLE = lvgl_sys::LV_OBJ_FLAG_EVENT_BUBBLE as u32;
+        const GESTURE_BUBBLE = lvgl_sys::LV_OBJ_FLAG_GESTURE_BUBBLE as u32;
+        const ADV_HITTEST = lvgl_sys::LV_OBJ_FLAG_ADV_HITTEST as u32;
+        const IGNORE_LAYOUT = lvgl_sys::LV_OBJ_FLAG_IGNORE_LAYOUT as u32;
+        const FLOATING = lvgl_sys::LV_OBJ_FLAG_FLOATING as u32;
+        const OVERFLOW_VISIBLE = lvgl_sys::LV_OBJ_FLAG_OVERFLOW_VISIBLE as u32;
+        const LAYOUT_1 = lvgl_sys::LV_OBJ_FLAG_LAYOUT_1 as u32;
+        const LAYOUT_2 = lvgl_sys::LV_OBJ_FLAG_LAYOUT_2 as u32;
+        const WIDGET_1 = lvgl_sys::LV_OBJ_FLAG_WIDGET_1 as u32;
+        const WIDGET_2 = lvgl_sys::LV_OBJ_FLAG_WIDGET_2 as u32;
+        const USER_1 = lvgl_sys::LV_OBJ_FLAG_USER_1 as u32;
+        const USER_2 = lvgl_sys::LV_OBJ_FLAG_USER_2 as u32;
+        const USER_3 = lvgl_sys::LV_OBJ_FLAG_USER_3 as u32;
+        const USER_4 = lvgl_sys::LV_OBJ_FLAG_USER_4 as u32;
+    }
+}
+
+impl From<ObjFlag> for lvgl_sys::lv_obj_flag_t {
+    fn from(flags: ObjFlag) -> Self {
+        flags.bits() as lvgl_sys::lv_obj_flag_t
+    }
+}
+
+bitflags! {
+    /// States a widget can be in, e.g. pressed or checked. Styles can be
+    /// added for specific states. Equivalent to `lv_state_t`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct State: u16 {
+        const DEFAULT = lvgl_sys::LV_STATE_DEFAULT as u16;
+        const CHECKED = lvgl_sys::LV_STATE_CHECKED as u16;
+        const FOCUSED = lvgl_sys::LV_STATE_FOCUSED as u16;
+        const FOCUS_KEY = lvgl_sys::LV_STATE_FOCUS_KEY as u16;
+        const EDITED = lvgl_sys::LV_STATE_EDITED as u16;
+        const HOVERED = lvgl_sys::LV_STATE_HOVERED as u16;
+        const PRESSED = lvgl_sys::LV_STATE_PRESSED as u16;
+        const SCROLLED = lvgl_sys::LV_STATE_SCROLLED as u16;
+        const DISABLED = lvgl_sys::LV_STATE_DISABLED as u16;
+        const USER_1 = lvgl_sys::LV_STATE_USER_1 as u16;
+        const USER_2 = lvgl_sys::LV_STATE_USER_2 as u16;
+        const USER_3 = lvgl_sys::LV_STATE_USER_3 as u16;
+        const USER_4 = lvgl_sys::LV_STATE_USER_4 as u16;
+        const ANY = lvgl_sys::LV_STATE_ANY as u16;
+    }
+}
+
+impl From<State> for lvgl_sys::lv_state_t {
+    fn from(states: State) -> Self {
+        states.bits() as lvgl_sys::lv_state_t
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn flags_and_states() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut obj = Obj::create(&screen).unwrap();
+
+        obj.add_flag(ObjFlag::HIDDEN | ObjFlag::CHECKABLE);
+        assert!(obj.has_flag(ObjFlag::HIDDEN | ObjFlag::CHECKABLE));
+        obj.clear_flag(ObjFlag::HIDDEN);
+        assert!(!obj.has_flag(ObjFlag::HIDDEN | ObjFlag::CHECKABLE));
+        assert!(obj.has_flag_any(ObjFlag::HIDDEN | ObjFlag::CHECKABLE));
+
+        obj.add_state(State::CHECKED | State::DISABLED);
+        assert!(obj.has_state(State::CHECKED));
+        obj.clear_state(State::DISABLED);
+        assert_eq!(obj.get_state(), State::CHECKED);
+    }
+
+    #[test]
+    fn walk_tree() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let parent = Obj::create(&screen).unwrap();
+        let first = Obj::create(&parent).unwrap();
+        let second = Obj::create(&parent).unwrap();
+        let mut nested = Obj::create(&second).unwrap();
+        nested.set_user_data(7u8);
+
+        assert_eq!(parent.child_count(), 2);
+        let children: Vec<_> = parent.children().map(|c| c.raw()).collect();
+        assert_eq!(children, [first.raw(), second.raw()]);
+        assert_eq!(parent.get_child(-1).unwrap().raw(), second.raw());
+        assert!(parent.get_child(2).is_none());
+        assert_eq!(nested.get_parent().unwrap().raw(), second.raw());
+        assert_eq!(parent.find_by_user_data(&7u8).unwrap().raw(), nested.raw());
+        assert!(parent.find_by_user_data(&8u8).is_none());
+        // Only values of the same type match
+        assert!(parent.find_by_user_data(&7u32).is_none());
+    }
+}
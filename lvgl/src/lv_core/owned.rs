@@ -0,0 +1,131 @@
+//! Widgets deleted when their handle is dropped
+//!
+//! Widget handles are plain pointers: dropping one leaves the LVGL object in
+//! place, as it is owned by its parent. That suits widgets living as long as
+//! their screen. For widgets with a shorter life, e.g. a popup, `Owned` ties
+//! the object to a Rust value instead, deleting it when the value is dropped.
+
+use crate::{Box, NativeObject, Widget};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+/// A widget deleted, along with its children, when this is dropped.
+///
+/// If the object is deleted first, e.g. together with its parent, the handle
+/// learns about it from the object's delete event: `get()` then returns
+/// `None` and dropping does nothing.
+pub struct Owned<'a, W: Widget<'a>> {
+    widget: W,
+    alive: NonNull<Cell<bool>>,
+    _lifetime: core::marker::PhantomData<&'a ()>,
+}
+
+impl<'a, W: Widget<'a>> Owned<'a, W> {
+    /// Takes ownership of `widget`.
+    pub fn new(widget: W) -> Self {
+        let alive = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Cell::new(true)))) };
+        unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                widget.raw().as_ptr(),
+                Some(deleted_cb),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                alive.as_ptr() as *mut _,
+            );
+        }
+        Self {
+            widget,
+            alive,
+            _lifetime: core::marker::PhantomData,
+        }
+    }
+
+    /// Returns `true` until the object has been deleted.
+    pub fn is_alive(&self) -> bool {
+        unsafe { self.alive.as_ref().get() }
+    }
+
+    /// Returns the widget, unless it has been deleted.
+    pub fn get(&self) -> Option<&W> {
+        self.is_alive().then_some(&self.widget)
+    }
+
+    /// Returns the widget, unless it has been deleted.
+    pub fn get_mut(&mut self) -> Option<&mut W> {
+        if self.is_alive() {
+            Some(&mut self.widget)
+        } else {
+            None
+        }
+    }
+
+    /// Gives up ownership, returning the widget without deleting it. Returns
+    /// `None` if it has been deleted already.
+    pub fn release(self) -> Option<W> {
+        let this = core::mem::ManuallyDrop::new(self);
+        let alive = this.is_alive();
+        let widget = unsafe { core::ptr::read(&this.widget) };
+        unsafe {
+            if alive {
+                lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                    widget.raw().as_ptr(),
+                    Some(deleted_cb),
+                    this.alive.as_ptr() as *mut _,
+                );
+            }
+            drop(Box::from_raw(this.alive.as_ptr()));
+        }
+        alive.then_some(widget)
+    }
+}
+
+impl<'a, W: Widget<'a>> Drop for Owned<'a, W> {
+    fn drop(&mut self) {
+        let obj = self.widget.raw().as_ptr();
+        unsafe {
+            if self.is_alive() {
+                lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                    obj,
+                    Some(deleted_cb),
+                    self.alive.as_ptr() as *mut _,
+                );
+                crate::support::del(obj);
+            }
+            drop(Box::from_raw(self.alive.as_ptr()));
+        }
+    }
+}
+
+unsafe extern "C" fn deleted_cb(event: *mut lvgl_sys::lv_event_t) {
+    let alive = &*((*event).user_data as *const Cell<bool>);
+    alive.set(false);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Obj};
+
+    #[test]
+    fn deleted_on_drop() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let parent = Obj::create(&screen).unwrap();
+
+        let popup = Owned::new(Obj::create(&parent).unwrap());
+        assert!(popup.get().is_some());
+        assert_eq!(parent.child_count(), 1);
+        drop(popup);
+        assert_eq!(parent.child_count(), 0);
+
+        let kept = Owned::new(Obj::create(&parent).unwrap());
+        let kept = kept.release().unwrap();
+        assert_eq!(parent.child_count(), 1);
+
+        // Deleted along with its parent before the handle is dropped
+        let orphan = Owned::new(Obj::create(&kept).unwrap());
+        unsafe { lvgl_sys::lv_obj_del(kept.raw().as_ptr()) };
+        assert!(!orphan.is_alive());
+        assert!(orphan.get().is_none());
+    }
+}
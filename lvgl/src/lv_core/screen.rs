@@ -1,4 +1,5 @@
-use crate::{LvError, LvResult, NativeObject, Obj, Part, Widget};
+use crate::{LvError, LvResult, NativeObject, Obj, Part, ScreenLoadAnim, Widget};
+use core::ptr::NonNull;
 
 /// An LVGL screen.
 #[derive(Debug)]
@@ -10,6 +11,113 @@ impl Screen<'_> {
     pub fn blank() -> LvResult<Self> {
         Ok(Self { raw: Obj::blank()? })
     }
+
+    /// Makes this the active screen of its display.
+    pub fn load(&self) {
+        unsafe { lvgl_sys::lv_disp_load_scr(self.raw().as_ptr()) }
+    }
+
+    /// Makes this the active screen of its display with an animation taking
+    /// `time` milliseconds, starting after `delay` milliseconds. With
+    /// `auto_del`, the previously active screen is deleted once the
+    /// animation is done.
+    pub fn load_with_anim(&self, anim: ScreenLoadAnim, time: u32, delay: u32, auto_del: bool) {
+        unsafe {
+            lvgl_sys::lv_scr_load_anim(self.raw().as_ptr(), anim as u32, time, delay, auto_del)
+        }
+    }
+}
+
+/// A stack of up to `N` screens for drill-down navigation, e.g. from a menu
+/// into a settings page and back.
+///
+/// Pushing a screen loads it and remembers the screen it replaced; popping
+/// goes back to that screen and deletes the one popped. Screens are
+/// therefore moved into the stack when pushed.
+pub struct ScreenStack<const N: usize> {
+    screens: [Option<NonNull<lvgl_sys::lv_obj_t>>; N],
+    depth: usize,
+    anim: ScreenLoadAnim,
+    time: u32,
+}
+
+impl<const N: usize> ScreenStack<N> {
+    /// Creates an empty stack switching screens without animation.
+    pub const fn new() -> Self {
+        Self::with_anim(ScreenLoadAnim::None, 0)
+    }
+
+    /// Creates an empty stack animating pushes with `anim` for `time`
+    /// milliseconds. Pops play the reverse animation, e.g.
+    /// `ScreenLoadAnim::MoveRight` for `ScreenLoadAnim::MoveLeft`.
+    pub const fn with_anim(anim: ScreenLoadAnim, time: u32) -> Self {
+        Self {
+            screens: [None; N],
+            depth: 0,
+            anim,
+            time,
+        }
+    }
+
+    /// Loads `screen`, remembering the active screen to go back to.
+    ///
+    /// Fails with `LvError::LvOOMemory` if `N` screens are stacked already,
+    /// and with `LvError::InvalidArgument` if `screen` is the active screen,
+    /// which popping would delete.
+    pub fn push_screen(&mut self, screen: Screen) -> LvResult<()> {
+        if self.depth == N {
+            return Err(LvError::LvOOMemory);
+        }
+        let active = unsafe {
+            lvgl_sys::lv_disp_get_scr_act(lvgl_sys::lv_obj_get_disp(screen.raw().as_ptr()))
+        };
+        if active == screen.raw().as_ptr() {
+            return Err(LvError::InvalidArgument);
+        }
+        self.screens[self.depth] = Some(NonNull::new(active).ok_or(LvError::InvalidReference)?);
+        self.depth += 1;
+        screen.load_with_anim(self.anim, self.time, 0, false);
+        Ok(())
+    }
+
+    /// Goes back to the screen active before the last push, deleting the
+    /// active screen.
+    ///
+    /// Fails with `LvError::InvalidReference` if nothing was pushed, or if
+    /// the screen to go back to has been deleted in the meantime.
+    pub fn pop_screen(&mut self) -> LvResult<()> {
+        if self.depth == 0 {
+            return Err(LvError::InvalidReference);
+        }
+        self.depth -= 1;
+        let previous = self.screens[self.depth]
+            .take()
+            .ok_or(LvError::InvalidReference)?;
+        unsafe {
+            if !lvgl_sys::lv_obj_is_valid(previous.as_ptr()) {
+                return Err(LvError::InvalidReference);
+            }
+            lvgl_sys::lv_scr_load_anim(
+                previous.as_ptr(),
+                self.anim.reversed() as u32,
+                self.time,
+                0,
+                true,
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the number of screens that can be popped.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<const N: usize> Default for ScreenStack<N> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl NativeObject for Screen<'_> {
@@ -77,4 +185,31 @@ mod test {
         display.set_scr_act(&mut screen_new);
         display.set_scr_act(&mut screen_old);
     }
+
+    #[test]
+    fn navigation_stack() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let home = display.get_scr_act().unwrap();
+        let settings = Screen::blank().unwrap();
+        let settings_raw = settings.raw().as_ptr();
+        let mut stack = ScreenStack::<2>::new();
+        let active = || unsafe { lvgl_sys::lv_scr_act() };
+
+        assert_eq!(
+            stack.push_screen(display.get_scr_act().unwrap()),
+            Err(LvError::InvalidArgument)
+        );
+        assert_eq!(stack.depth(), 0);
+        stack.push_screen(settings).unwrap();
+        assert_eq!(active(), settings_raw);
+        assert_eq!(stack.depth(), 1);
+
+        stack.pop_screen().unwrap();
+        assert_eq!(active(), home.raw().as_ptr());
+        assert!(stack.pop_screen().is_err());
+
+        home.load();
+        assert_eq!(active(), home.raw().as_ptr());
+    }
 }
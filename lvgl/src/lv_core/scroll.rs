@@ -0,0 +1,110 @@
+//! Programmatic scrolling
+//!
+//! Any widget with the `ObjFlag::SCROLLABLE` flag scrolls when its children
+//! overflow it. Besides by the user, it can be scrolled with
+//! `Widget::scroll_to()`, `scroll_by()` and `scroll_to_view()`, and made to
+//! snap children into place with `Widget::set_scroll_snap()`.
+
+/// When the scrollbars of a widget are shown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScrollbarMode {
+    /// Never.
+    Off,
+    /// Always.
+    On,
+    /// While the widget is being scrolled.
+    Active,
+    /// When the content is large enough to be scrolled.
+    Auto,
+}
+
+impl From<ScrollbarMode> for lvgl_sys::lv_scrollbar_mode_t {
+    fn from(mode: ScrollbarMode) -> Self {
+        (match mode {
+            ScrollbarMode::Off => lvgl_sys::LV_SCROLLBAR_MODE_OFF,
+            ScrollbarMode::On => lvgl_sys::LV_SCROLLBAR_MODE_ON,
+            ScrollbarMode::Active => lvgl_sys::LV_SCROLLBAR_MODE_ACTIVE,
+            ScrollbarMode::Auto => lvgl_sys::LV_SCROLLBAR_MODE_AUTO,
+        }) as lvgl_sys::lv_scrollbar_mode_t
+    }
+}
+
+impl From<lvgl_sys::lv_scrollbar_mode_t> for ScrollbarMode {
+    fn from(mode: lvgl_sys::lv_scrollbar_mode_t) -> Self {
+        match mode as u32 {
+            lvgl_sys::LV_SCROLLBAR_MODE_OFF => ScrollbarMode::Off,
+            lvgl_sys::LV_SCROLLBAR_MODE_ON => ScrollbarMode::On,
+            lvgl_sys::LV_SCROLLBAR_MODE_ACTIVE => ScrollbarMode::Active,
+            _ => ScrollbarMode::Auto,
+        }
+    }
+}
+
+/// Where the children of a scrolled widget come to rest once scrolling ends.
+/// Only children with the `ObjFlag::SNAPPABLE` flag are snapped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ScrollSnap {
+    /// Anywhere.
+    None,
+    /// With their start aligned to the start of the widget.
+    Start,
+    /// With their end aligned to the end of the widget.
+    End,
+    /// Centered in the widget.
+    Center,
+}
+
+impl From<ScrollSnap> for lvgl_sys::lv_scroll_snap_t {
+    fn from(snap: ScrollSnap) -> Self {
+        (match snap {
+            ScrollSnap::None => lvgl_sys::LV_SCROLL_SNAP_NONE,
+            ScrollSnap::Start => lvgl_sys::LV_SCROLL_SNAP_START,
+            ScrollSnap::End => lvgl_sys::LV_SCROLL_SNAP_END,
+            ScrollSnap::Center => lvgl_sys::LV_SCROLL_SNAP_CENTER,
+        }) as lvgl_sys::lv_scroll_snap_t
+    }
+}
+
+impl From<lvgl_sys::lv_scroll_snap_t> for ScrollSnap {
+    fn from(snap: lvgl_sys::lv_scroll_snap_t) -> Self {
+        match snap as u32 {
+            lvgl_sys::LV_SCROLL_SNAP_START => ScrollSnap::Start,
+            lvgl_sys::LV_SCROLL_SNAP_END => ScrollSnap::End,
+            lvgl_sys::LV_SCROLL_SNAP_CENTER => ScrollSnap::Center,
+            _ => ScrollSnap::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnimationState, Display, Obj, ObjFlag, Widget};
+
+    #[test]
+    fn scroll_overflowing_content() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut cont = Obj::create(&screen).unwrap();
+        cont.set_size(100, 100);
+        let mut content = Obj::create(&cont).unwrap();
+        content.set_size(80, 400);
+        content.add_flag(ObjFlag::SNAPPABLE);
+
+        cont.set_scrollbar_mode(ScrollbarMode::Active);
+        cont.set_scroll_snap(ScrollSnap::None, ScrollSnap::Start);
+        assert_eq!(cont.get_scrollbar_mode(), ScrollbarMode::Active);
+        assert_eq!(
+            cont.get_scroll_snap(),
+            (ScrollSnap::None, ScrollSnap::Start)
+        );
+
+        cont.scroll_to(0, 50, AnimationState::OFF);
+        assert_eq!(cont.scroll_position(), (0, 50));
+        cont.scroll_by(0, -20, AnimationState::OFF);
+        assert_eq!(cont.scroll_position().1, 70);
+        assert!(cont.get_scroll_bottom() > 0);
+        assert_eq!(cont.get_scroll_top(), 70);
+    }
+}
@@ -0,0 +1,154 @@
+//! Scrollbar styling and drawing
+//!
+//! Scrollbars are drawn as the `Part::Scrollbar` part of a scrollable
+//! widget. The `Widget::set_scrollbar_*()` methods style that part; for a
+//! look styles can't express, `Widget::on_scrollbar_draw()` adjusts every
+//! scrollbar just before LVGL draws it.
+
+use crate::style::Opacity;
+use crate::{Area, Box, Color};
+use core::time::Duration;
+
+/// A scrollbar about to be drawn, see `Widget::on_scrollbar_draw()`.
+pub struct ScrollbarDraw<'a> {
+    dsc: &'a mut lvgl_sys::lv_obj_draw_part_dsc_t,
+}
+
+impl ScrollbarDraw<'_> {
+    /// Returns the area the scrollbar is drawn in, in absolute coordinates.
+    pub fn area(&self) -> Area {
+        unsafe { (*self.dsc.draw_area).into() }
+    }
+
+    /// Returns `true` for the horizontal scrollbar, `false` for the vertical
+    /// one.
+    pub fn is_horizontal(&self) -> bool {
+        let area = self.area();
+        area.width() > area.height()
+    }
+
+    /// Overrides the color of the scrollbar.
+    pub fn set_color(&mut self, color: Color) {
+        unsafe { (*self.dsc.rect_dsc).bg_color = color.into() };
+    }
+
+    /// Overrides the opacity of the scrollbar.
+    pub fn set_opa(&mut self, opa: Opacity) {
+        unsafe { (*self.dsc.rect_dsc).bg_opa = opa.into() };
+    }
+
+    /// Overrides the corner radius of the scrollbar.
+    pub fn set_radius(&mut self, radius: i16) {
+        unsafe { (*self.dsc.rect_dsc).radius = radius as lvgl_sys::lv_coord_t };
+    }
+
+    /// Returns the rectangle descriptor the scrollbar is drawn with, for
+    /// properties without a setter of their own.
+    pub fn rect_dsc(&mut self) -> &mut lvgl_sys::lv_draw_rect_dsc_t {
+        unsafe { &mut *self.dsc.rect_dsc }
+    }
+}
+
+pub(crate) unsafe extern "C" fn draw_cb<F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(&mut ScrollbarDraw),
+{
+    let event = &mut *event;
+    let Some(f) = crate::support::event_closure::<F>(event) else {
+        return;
+    };
+    match event.code {
+        lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_PART_BEGIN => {
+            let dsc = &mut *(event.param as *mut lvgl_sys::lv_obj_draw_part_dsc_t);
+            if dsc.part != lvgl_sys::LV_PART_SCROLLBAR
+                || dsc.draw_area.is_null()
+                || dsc.rect_dsc.is_null()
+            {
+                return;
+            }
+            f(&mut ScrollbarDraw { dsc });
+        }
+        _ => {}
+    }
+}
+
+/// The only property the scrollbar fade transitions.
+static FADE_PROPS: [lvgl_sys::lv_style_prop_t; 2] = [
+    lvgl_sys::lv_style_prop_t_LV_STYLE_BG_OPA,
+    lvgl_sys::lv_style_prop_t_LV_STYLE_PROP_INV,
+];
+
+/// Makes the scrollbars of `obj` fade between their idle and scrolling
+/// opacity over `duration`. The transition descriptor lives as long as the
+/// object, and is reused when the fade is changed again.
+pub(crate) fn set_fade(obj: *mut lvgl_sys::lv_obj_t, duration: Duration) {
+    unsafe {
+        let mut tr = lvgl_sys::lv_obj_get_event_user_data(obj, Some(free_fade))
+            as *mut lvgl_sys::lv_style_transition_dsc_t;
+        if tr.is_null() {
+            tr = Box::into_raw(Box::new(lvgl_sys::lv_style_transition_dsc_t::default()));
+            lvgl_sys::lv_obj_add_event_cb(
+                obj,
+                Some(free_fade),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                tr as *mut _,
+            );
+        }
+        lvgl_sys::lv_style_transition_dsc_init(
+            tr,
+            FADE_PROPS.as_ptr(),
+            Some(lvgl_sys::lv_anim_path_linear),
+            duration.as_millis().try_into().unwrap_or(u32::MAX),
+            0,
+            core::ptr::null_mut(),
+        );
+        for state in [lvgl_sys::LV_STATE_DEFAULT, lvgl_sys::LV_STATE_SCROLLED] {
+            lvgl_sys::lv_obj_set_style_transition(obj, tr, lvgl_sys::LV_PART_SCROLLBAR | state);
+        }
+    }
+}
+
+unsafe extern "C" fn free_fade(event: *mut lvgl_sys::lv_event_t) {
+    drop(Box::from_raw(
+        (*event).user_data as *mut lvgl_sys::lv_style_transition_dsc_t,
+    ));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, NativeObject, Obj, Widget};
+
+    fn scrollbar_prop(obj: &Obj, prop: lvgl_sys::lv_style_prop_t) -> i32 {
+        unsafe {
+            lvgl_sys::lv_obj_get_style_prop(obj.raw().as_ptr(), lvgl_sys::LV_PART_SCROLLBAR, prop)
+                .num
+        }
+    }
+
+    #[test]
+    fn scrollbar_style() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut obj = Obj::create(&screen).unwrap();
+
+        obj.set_scrollbar_width(3);
+        obj.set_scrollbar_opa(Opacity::OPA_TRANSP, Opacity::OPA_COVER);
+        obj.set_scrollbar_fade(Duration::from_millis(200));
+        obj.set_scrollbar_fade(Duration::from_millis(400));
+        assert_eq!(
+            scrollbar_prop(&obj, lvgl_sys::lv_style_prop_t_LV_STYLE_WIDTH),
+            3
+        );
+        assert_eq!(
+            scrollbar_prop(&obj, lvgl_sys::lv_style_prop_t_LV_STYLE_BG_OPA),
+            0
+        );
+        let tr = unsafe {
+            &*(lvgl_sys::lv_obj_get_event_user_data(obj.raw().as_ptr(), Some(free_fade))
+                as *const lvgl_sys::lv_style_transition_dsc_t)
+        };
+        assert_eq!(tr.time, 400);
+    }
+}
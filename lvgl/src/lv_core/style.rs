@@ -28,7 +28,6 @@ pub enum Themes {
 
 /// An LVGL `lv_style_t`. Allows for styling objects. Once created, a `Style`
 /// should be configured and then added to an object.
-#[derive(Clone)]
 pub struct Style {
     pub(crate) raw: Box<lvgl_sys::lv_style_t>,
 }
@@ -53,6 +52,31 @@ impl Default for Style {
     }
 }
 
+impl Clone for Style {
+    /// Copies the built-in properties set in the style into a new one, which
+    /// can be changed independently of it.
+    fn clone(&self) -> Self {
+        let mut style = Self::default();
+        style.set_props_from(self);
+        style
+    }
+}
+
+impl Style {
+    /// Sets the built-in properties set in `other` in this style too.
+    pub(crate) fn set_props_from(&mut self, other: &Style) {
+        for prop in 1..=lvgl_sys::lv_style_prop_t__LV_STYLE_LAST_BUILT_IN_PROP {
+            let mut value = MaybeUninit::<lvgl_sys::lv_style_value_t>::uninit();
+            unsafe {
+                let res = lvgl_sys::lv_style_get_prop(&*other.raw, prop as _, value.as_mut_ptr());
+                if res as u32 == lvgl_sys::LV_RES_OK {
+                    lvgl_sys::lv_style_set_prop(self.raw.as_mut(), prop as _, value.assume_init());
+                }
+            }
+        }
+    }
+}
+
 bitflags! {
     /// Represents possible opacities for use on `Style` objects.
     #[derive(Debug, Clone, Copy)]
@@ -585,3 +609,34 @@ impl Style {
     gen_lv_style!(set_x, i16);
     gen_lv_style!(set_y, i16);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn num(style: &Style, prop: lvgl_sys::lv_style_prop_t) -> Option<i32> {
+        let mut value = MaybeUninit::<lvgl_sys::lv_style_value_t>::uninit();
+        unsafe {
+            let res = lvgl_sys::lv_style_get_prop(&*style.raw, prop as _, value.as_mut_ptr());
+            (res as u32 == lvgl_sys::LV_RES_OK).then(|| value.assume_init().num)
+        }
+    }
+
+    #[test]
+    fn clones_do_not_share_properties() {
+        crate::tests::initialize_test(false);
+        let mut style = Style::default();
+        style.set_radius(4);
+        let mut copy = style.clone();
+        copy.set_radius(8);
+        copy.set_pad_top(2);
+
+        let radius = lvgl_sys::lv_style_prop_t_LV_STYLE_RADIUS;
+        let pad_top = lvgl_sys::lv_style_prop_t_LV_STYLE_PAD_TOP;
+        assert_eq!((num(&style, radius), num(&style, pad_top)), (Some(4), None));
+        assert_eq!(
+            (num(&copy, radius), num(&copy, pad_top)),
+            (Some(8), Some(2))
+        );
+    }
+}
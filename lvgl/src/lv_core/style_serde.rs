@@ -0,0 +1,288 @@
+//! `serde` support for `Style`
+//!
+//! A style is (de)serialized as a map of the properties set in it, named
+//! like their setters without `set_`, e.g. `{ "bg_color": "#336699",
+//! "radius": 4 }`. Colors are `"#rrggbb"` strings, and all other properties
+//! numbers. Properties pointing to data, e.g. fonts or images, are left out.
+//! When deserializing, `"pad"` sets all four paddings at once.
+
+use crate::lv_core::style::{Style, StyleProp};
+use crate::Color;
+use core::{fmt, mem};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Num,
+    Color,
+}
+
+static PROPS: &[(&str, StyleProp, Kind)] = &[
+    ("width", StyleProp::WIDTH, Kind::Num),
+    ("min_width", StyleProp::MIN_WIDTH, Kind::Num),
+    ("max_width", StyleProp::MAX_WIDTH, Kind::Num),
+    ("height", StyleProp::HEIGHT, Kind::Num),
+    ("min_height", StyleProp::MIN_HEIGHT, Kind::Num),
+    ("max_height", StyleProp::MAX_HEIGHT, Kind::Num),
+    ("x", StyleProp::X, Kind::Num),
+    ("y", StyleProp::Y, Kind::Num),
+    ("align", StyleProp::ALIGN, Kind::Num),
+    ("transform_width", StyleProp::TRANSFORM_WIDTH, Kind::Num),
+    ("transform_height", StyleProp::TRANSFORM_HEIGHT, Kind::Num),
+    ("translate_x", StyleProp::TRANSLATE_X, Kind::Num),
+    ("translate_y", StyleProp::TRANSLATE_Y, Kind::Num),
+    ("transform_zoom", StyleProp::TRANSFORM_ZOOM, Kind::Num),
+    ("transform_angle", StyleProp::TRANSFORM_ANGLE, Kind::Num),
+    ("pad_top", StyleProp::PAD_TOP, Kind::Num),
+    ("pad_bottom", StyleProp::PAD_BOTTOM, Kind::Num),
+    ("pad_left", StyleProp::PAD_LEFT, Kind::Num),
+    ("pad_right", StyleProp::PAD_RIGHT, Kind::Num),
+    ("pad_row", StyleProp::PAD_ROW, Kind::Num),
+    ("pad_column", StyleProp::PAD_COLUMN, Kind::Num),
+    ("bg_color", StyleProp::BG_COLOR, Kind::Color),
+    ("bg_opa", StyleProp::BG_OPA, Kind::Num),
+    ("bg_grad_color", StyleProp::BG_GRAD_COLOR, Kind::Color),
+    ("bg_grad_dir", StyleProp::BG_GRAD_DIR, Kind::Num),
+    ("bg_main_stop", StyleProp::BG_MAIN_STOP, Kind::Num),
+    ("bg_grad_stop", StyleProp::BG_GRAD_STOP, Kind::Num),
+    ("bg_img_opa", StyleProp::BG_IMG_OPA, Kind::Num),
+    ("bg_img_recolor", StyleProp::BG_IMG_RECOLOR, Kind::Color),
+    (
+        "bg_img_recolor_opa",
+        StyleProp::BG_IMG_RECOLOR_OPA,
+        Kind::Num,
+    ),
+    ("bg_img_tiled", StyleProp::BG_IMG_TILED, Kind::Num),
+    ("border_color", StyleProp::BORDER_COLOR, Kind::Color),
+    ("border_opa", StyleProp::BORDER_OPA, Kind::Num),
+    ("border_width", StyleProp::BORDER_WIDTH, Kind::Num),
+    ("border_side", StyleProp::BORDER_SIDE, Kind::Num),
+    ("border_post", StyleProp::BORDER_POST, Kind::Num),
+    ("outline_width", StyleProp::OUTLINE_WIDTH, Kind::Num),
+    ("outline_color", StyleProp::OUTLINE_COLOR, Kind::Color),
+    ("outline_opa", StyleProp::OUTLINE_OPA, Kind::Num),
+    ("outline_pad", StyleProp::OUTLINE_PAD, Kind::Num),
+    ("shadow_width", StyleProp::SHADOW_WIDTH, Kind::Num),
+    ("shadow_ofs_x", StyleProp::SHADOW_OFS_X, Kind::Num),
+    ("shadow_ofs_y", StyleProp::SHADOW_OFS_Y, Kind::Num),
+    ("shadow_spread", StyleProp::SHADOW_SPREAD, Kind::Num),
+    ("shadow_color", StyleProp::SHADOW_COLOR, Kind::Color),
+    ("shadow_opa", StyleProp::SHADOW_OPA, Kind::Num),
+    ("img_opa", StyleProp::IMG_OPA, Kind::Num),
+    ("img_recolor", StyleProp::IMG_RECOLOR, Kind::Color),
+    ("img_recolor_opa", StyleProp::IMG_RECOLOR_OPA, Kind::Num),
+    ("line_width", StyleProp::LINE_WIDTH, Kind::Num),
+    ("line_dash_width", StyleProp::LINE_DASH_WIDTH, Kind::Num),
+    ("line_dash_gap", StyleProp::LINE_DASH_GAP, Kind::Num),
+    ("line_rounded", StyleProp::LINE_ROUNDED, Kind::Num),
+    ("line_color", StyleProp::LINE_COLOR, Kind::Color),
+    ("line_opa", StyleProp::LINE_OPA, Kind::Num),
+    ("arc_width", StyleProp::ARC_WIDTH, Kind::Num),
+    ("arc_rounded", StyleProp::ARC_ROUNDED, Kind::Num),
+    ("arc_color", StyleProp::ARC_COLOR, Kind::Color),
+    ("arc_opa", StyleProp::ARC_OPA, Kind::Num),
+    ("text_color", StyleProp::TEXT_COLOR, Kind::Color),
+    ("text_opa", StyleProp::TEXT_OPA, Kind::Num),
+    ("text_letter_space", StyleProp::TEXT_LETTER_SPACE, Kind::Num),
+    ("text_line_space", StyleProp::TEXT_LINE_SPACE, Kind::Num),
+    ("text_decor", StyleProp::TEXT_DECOR, Kind::Num),
+    ("text_align", StyleProp::TEXT_ALIGN, Kind::Num),
+    ("radius", StyleProp::RADIUS, Kind::Num),
+    ("clip_corner", StyleProp::CLIP_CORNER, Kind::Num),
+    ("opa", StyleProp::OPA, Kind::Num),
+    ("color_filter_opa", StyleProp::COLOR_FILTER_OPA, Kind::Num),
+    ("anim_time", StyleProp::ANIM_TIME, Kind::Num),
+    ("anim_speed", StyleProp::ANIM_SPEED, Kind::Num),
+    ("blend_mode", StyleProp::BLEND_MODE, Kind::Num),
+    ("layout", StyleProp::LAYOUT, Kind::Num),
+    ("base_dir", StyleProp::BASE_DIR, Kind::Num),
+];
+
+static PAD: &[StyleProp] = &[
+    StyleProp::PAD_TOP,
+    StyleProp::PAD_BOTTOM,
+    StyleProp::PAD_LEFT,
+    StyleProp::PAD_RIGHT,
+];
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (r, g, b) = self.to_rgb();
+        serializer.collect_str(&format_args!("#{r:02x}{g:02x}{b:02x}"))
+    }
+}
+
+impl<'de> Deserialize<'de> for Color {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_str(ValueVisitor)
+            .and_then(|value| match value {
+                Value::Color(color) => Ok(color),
+                Value::Num(n) => Err(de::Error::invalid_type(
+                    de::Unexpected::Signed(n.into()),
+                    &"a color like \"#rrggbb\"",
+                )),
+            })
+    }
+}
+
+enum Value {
+    Num(i32),
+    Color(Color),
+}
+
+struct ValueVisitor;
+
+impl Visitor<'_> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a number or a color like \"#rrggbb\"")
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        v.try_into()
+            .map(Value::Num)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        v.try_into()
+            .map(Value::Num)
+            .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Num(v.into()))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        v.strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .map(|rgb| Value::Color(Color::from_hex(rgb)))
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// The style properties named in a map key.
+struct PropKey(&'static [StyleProp], Kind);
+
+impl<'de> Deserialize<'de> for PropKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct KeyVisitor;
+
+        impl Visitor<'_> for KeyVisitor {
+            type Value = PropKey;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a style property")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<PropKey, E> {
+                if v == "pad" {
+                    return Ok(PropKey(PAD, Kind::Num));
+                }
+                PROPS
+                    .iter()
+                    .find(|(name, ..)| *name == v)
+                    .map(|(_, prop, kind)| PropKey(core::slice::from_ref(prop), *kind))
+                    .ok_or_else(|| E::unknown_field(v, &[]))
+            }
+        }
+
+        deserializer.deserialize_identifier(KeyVisitor)
+    }
+}
+
+impl Serialize for Style {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        for (name, prop, kind) in PROPS {
+            let mut value: lvgl_sys::lv_style_value_t = unsafe { mem::zeroed() };
+            let res =
+                unsafe { lvgl_sys::lv_style_get_prop(&*self.raw, prop.bits() as _, &mut value) };
+            if res as u32 != lvgl_sys::LV_RES_OK {
+                continue;
+            }
+            match kind {
+                Kind::Num => map.serialize_entry(name, &unsafe { value.num })?,
+                Kind::Color => {
+                    map.serialize_entry(name, &Color::from_raw(unsafe { value.color }))?
+                }
+            }
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Style {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StyleVisitor;
+
+        impl<'de> Visitor<'de> for StyleVisitor {
+            type Value = Style;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of style properties")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Style, A::Error> {
+                let mut style = Style::default();
+                while let Some(PropKey(props, kind)) = map.next_key()? {
+                    let value = match (map.next_value()?, kind) {
+                        (Value::Num(num), Kind::Num) => lvgl_sys::lv_style_value_t { num },
+                        (Value::Color(color), Kind::Color) => {
+                            lvgl_sys::lv_style_value_t { color: color.raw }
+                        }
+                        _ => return Err(de::Error::custom("wrong type of style property")),
+                    };
+                    for prop in props {
+                        unsafe {
+                            lvgl_sys::lv_style_set_prop(style.raw.as_mut(), prop.bits() as _, value)
+                        };
+                    }
+                }
+                Ok(style)
+            }
+        }
+
+        deserializer.deserialize_map(StyleVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn style_round_trips_through_json() {
+        crate::tests::initialize_test(false);
+        let style: Style =
+            serde_json::from_str(r##"{ "bg_color": "#ff0000", "radius": 4, "bg_opa": 255 }"##)
+                .unwrap();
+        let json = serde_json::to_string(&style).unwrap();
+        assert_eq!(json, r##"{"bg_color":"#ff0000","bg_opa":255,"radius":4}"##);
+
+        assert!(serde_json::from_str::<Style>(r#"{ "bg_color": 3 }"#).is_err());
+        assert!(serde_json::from_str::<Style>(r#"{ "colour": "#000000" }"#).is_err());
+    }
+
+    #[test]
+    fn pad_sets_all_paddings() {
+        crate::tests::initialize_test(false);
+        let style: Style = serde_json::from_str(r#"{ "pad": 4, "pad_left": 2 }"#).unwrap();
+        let json = serde_json::to_string(&style).unwrap();
+        assert_eq!(
+            json,
+            r#"{"pad_top":4,"pad_bottom":4,"pad_left":2,"pad_right":4}"#
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Text arguments of generated methods
+//!
+//! Methods taking text accept a `&CStr` (or `&CString`) as it is, or a
+//! `&str`, which is copied into a C string for the duration of the call:
+//!
+//! ```ignore
+//! label.set_text("Hello");
+//! label.set_text(cstr!("Hello"));
+//! label.set_text(None); // Refreshes the current text
+//! ```
+//!
+//! Strings shorter than `INLINE_TEXT` bytes are copied to the stack. Longer
+//! ones go to the heap with the `alloc` feature, and to LVGL's heap without
+//! it. Should LVGL's heap be exhausted, they are cut to `INLINE_TEXT` bytes
+//! instead. As in C, a `&str` containing NUL ends at the first one.
+
+use core::ptr;
+use cstr_core::CStr;
+use cty::c_char;
+
+/// The longest `&str`, in bytes, that is converted without allocating.
+pub const INLINE_TEXT: usize = 63;
+
+/// Text converted into a C string for a call into LVGL, see the module
+/// documentation.
+pub struct Text {
+    storage: Storage,
+}
+
+enum Storage {
+    /// Borrowed from the caller, who keeps it alive during the call.
+    Borrowed(*const c_char),
+    Inline([u8; INLINE_TEXT + 1]),
+    #[cfg(feature = "alloc")]
+    Heap(alloc::vec::Vec<u8>),
+    #[cfg(not(feature = "alloc"))]
+    LvHeap(ptr::NonNull<u8>),
+}
+
+impl Text {
+    /// Returns the NUL-terminated text, or NULL for `None`.
+    pub(crate) fn as_ptr(&self) -> *const c_char {
+        match &self.storage {
+            Storage::Borrowed(text) => *text,
+            Storage::Inline(buf) => buf.as_ptr() as *const c_char,
+            #[cfg(feature = "alloc")]
+            Storage::Heap(buf) => buf.as_ptr() as *const c_char,
+            #[cfg(not(feature = "alloc"))]
+            Storage::LvHeap(buf) => buf.as_ptr() as *const c_char,
+        }
+    }
+
+    fn borrowed(text: *const c_char) -> Self {
+        Self {
+            storage: Storage::Borrowed(text),
+        }
+    }
+
+    fn copied(text: &str) -> Self {
+        let storage = if text.len() <= INLINE_TEXT {
+            Self::inline(text)
+        } else {
+            Self::heap(text)
+        };
+        Self { storage }
+    }
+
+    fn inline(text: &str) -> Storage {
+        let mut buf = [0; INLINE_TEXT + 1];
+        buf[..text.len()].copy_from_slice(text.as_bytes());
+        Storage::Inline(buf)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn heap(text: &str) -> Storage {
+        let mut buf = alloc::vec::Vec::with_capacity(text.len() + 1);
+        buf.extend_from_slice(text.as_bytes());
+        buf.push(0);
+        Storage::Heap(buf)
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn heap(text: &str) -> Storage {
+        let bytes = text.as_bytes();
+        let buf = unsafe { lvgl_sys::lv_mem_alloc((bytes.len() + 1) as cty::size_t) as *mut u8 };
+        let Some(buf) = ptr::NonNull::new(buf) else {
+            // Out of memory, so the text is cut at the last whole character
+            let end = (0..=INLINE_TEXT)
+                .rev()
+                .find(|&end| text.is_char_boundary(end))
+                .unwrap_or(0);
+            return Self::inline(&text[..end]);
+        };
+        unsafe {
+            ptr::copy_nonoverlapping(bytes.as_ptr(), buf.as_ptr(), bytes.len());
+            buf.as_ptr().add(bytes.len()).write(0);
+        }
+        Storage::LvHeap(buf)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl Drop for Text {
+    fn drop(&mut self) {
+        if let Storage::LvHeap(buf) = self.storage {
+            unsafe { lvgl_sys::lv_mem_free(buf.as_ptr() as *mut _) }
+        }
+    }
+}
+
+/// Text a generated method accepts, i.e. `&CStr`, `&CString`, `&str` or
+/// `&String`.
+pub trait IntoText {
+    fn into_text(self) -> Text;
+}
+
+/// Text, or `None` for methods LVGL accepts NULL for.
+pub trait IntoOptionalText {
+    fn into_optional_text(self) -> Text;
+}
+
+impl IntoText for &CStr {
+    fn into_text(self) -> Text {
+        Text::borrowed(self.as_ptr())
+    }
+}
+
+impl IntoText for &str {
+    fn into_text(self) -> Text {
+        Text::copied(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl IntoText for &cstr_core::CString {
+    fn into_text(self) -> Text {
+        Text::borrowed(self.as_ptr())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl IntoText for &alloc::string::String {
+    fn into_text(self) -> Text {
+        Text::copied(self)
+    }
+}
+
+impl<T: IntoText> IntoOptionalText for T {
+    fn into_optional_text(self) -> Text {
+        self.into_text()
+    }
+}
+
+// A single `Option` implementation, so that a bare `None` needs no type
+impl IntoOptionalText for Option<&CStr> {
+    fn into_optional_text(self) -> Text {
+        Text::borrowed(self.map_or(ptr::null(), CStr::as_ptr))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn read(text: &Text) -> &str {
+        unsafe { CStr::from_ptr(text.as_ptr()) }.to_str().unwrap()
+    }
+
+    #[test]
+    fn strings_are_nul_terminated() {
+        crate::tests::initialize_test(false);
+        let c = CStr::from_bytes_with_nul(b"borrowed\0").unwrap();
+        assert_eq!(c.into_text().as_ptr(), c.as_ptr());
+        assert_eq!(read(&"short".into_text()), "short");
+        let long = "long".repeat(INLINE_TEXT);
+        assert_eq!(read(&long.as_str().into_text()), long);
+        assert_eq!(read(&"cut\0off".into_text()), "cut");
+        assert!(None.into_optional_text().as_ptr().is_null());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn c_strings_are_borrowed() {
+        crate::tests::initialize_test(false);
+        let c = cstr_core::CString::new("owned").unwrap();
+        assert_eq!((&c).into_text().as_ptr(), c.as_ptr());
+    }
+}
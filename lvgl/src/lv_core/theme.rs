@@ -0,0 +1,211 @@
+//! Themes for LVGL displays
+//!
+//! A theme holds the default styles LVGL applies to newly created objects on
+//! a display. Switching themes at runtime with `Theme::apply_to_display()`
+//! also restyles every object already living on that display, so e.g. a
+//! dark/light mode toggle takes effect without rebuilding any screen.
+//!
+//! Restyling an object removes all styles previously added to it, including
+//! those added with `add_style()`. Every restyled object receives an
+//! `Event::ThemeChanged` event afterwards, which is the place to re-add any
+//! application-specific styles.
+//!
+//! `Theme::default_init()` configures LVGL's default theme with the colors and
+//! font of a product, and `Theme::custom()` extends a theme with styling
+//! written in Rust through the `CustomTheme` trait.
+//!
+//! With the `theme_scheduler` feature enabled, `ThemeScheduler` switches
+//! between a light and a dark theme automatically.
+//!
+//! `Metrics` reports the paddings and widget sizes a theme results in, for
+//! custom widgets that have to line up with the built-in ones.
+
+mod custom;
+mod metrics;
+#[cfg(feature = "theme_scheduler")]
+mod scheduler;
+
+pub use custom::*;
+pub use metrics::*;
+#[cfg(feature = "theme_scheduler")]
+pub use scheduler::*;
+
+use crate::font::Font;
+use crate::{Color, Display, LvError, LvResult};
+use core::ptr::{self, NonNull};
+
+static mut THEME_CHANGED_EVENT: u32 = 0;
+
+/// Registers the event code sent to objects after their theme changed.
+/// Called by `init()`; LVGL hands out ids from a counter that survives
+/// `deinit()`, so the code is only registered once.
+pub(crate) fn register_theme_changed_event() {
+    unsafe {
+        if THEME_CHANGED_EVENT == 0 {
+            THEME_CHANGED_EVENT = lvgl_sys::lv_event_register_id();
+        }
+    }
+}
+
+/// Returns the event code sent to objects after their theme changed, or
+/// `None` before `init()` registered it.
+pub(crate) fn theme_changed_event_code() -> Option<lvgl_sys::lv_event_code_t> {
+    match unsafe { THEME_CHANGED_EVENT } {
+        0 => None,
+        code => Some(code as lvgl_sys::lv_event_code_t),
+    }
+}
+
+/// An LVGL theme. Equivalent to an `lv_theme_t`.
+pub struct Theme {
+    pub(crate) raw: NonNull<lvgl_sys::lv_theme_t>,
+}
+
+impl Theme {
+    /// Creates a `Theme` from a raw `lv_theme_t` pointer.
+    ///
+    /// # Safety
+    ///
+    /// The pointed-to theme must stay valid for as long as any display uses
+    /// it.
+    pub unsafe fn from_raw(raw: NonNull<lvgl_sys::lv_theme_t>) -> Self {
+        Self { raw }
+    }
+
+    /// Initializes LVGL's default theme for `display` with the given palette
+    /// and font, in light or `dark` mode. Apply it with `apply_to_display()`.
+    ///
+    /// There is only one default theme, so initializing it again, e.g. for
+    /// another display, reconfigures the theme returned before.
+    pub fn default_init(
+        display: &Display,
+        primary: Color,
+        secondary: Color,
+        dark: bool,
+        font: Font,
+    ) -> LvResult<Self> {
+        let raw = unsafe {
+            lvgl_sys::lv_theme_default_init(
+                display.disp.as_ptr(),
+                primary.into(),
+                secondary.into(),
+                dark,
+                font.into(),
+            )
+        };
+        NonNull::new(raw)
+            .map(|raw| Self { raw })
+            .ok_or(LvError::Uninitialized)
+    }
+
+    /// Returns the theme currently active on a display.
+    pub fn from_display(display: &Display) -> LvResult<Self> {
+        match NonNull::new(unsafe { lvgl_sys::lv_disp_get_theme(display.disp.as_ptr()) }) {
+            Some(raw) => Ok(Self { raw }),
+            None => Err(LvError::Uninitialized),
+        }
+    }
+
+    /// Returns a pointer to the underlying `lv_theme_t`.
+    pub fn raw(&self) -> NonNull<lvgl_sys::lv_theme_t> {
+        self.raw
+    }
+
+    /// Makes this the active theme of `display` and restyles every object on
+    /// it, including the top and system layers. Each restyled object is sent
+    /// an `Event::ThemeChanged` event.
+    pub fn apply_to_display(&self, display: &Display) {
+        unsafe {
+            let disp = display.disp.as_ptr();
+            lvgl_sys::lv_disp_set_theme(disp, self.raw.as_ptr());
+
+            let disp = &*disp;
+            for i in 0..disp.screen_cnt as usize {
+                restyle_tree(*disp.screens.add(i));
+            }
+            restyle_tree(disp.top_layer);
+            restyle_tree(disp.sys_layer);
+
+            // Let LVGL recompute the cached style properties of every object
+            lvgl_sys::lv_obj_report_style_change(ptr::null_mut());
+        }
+    }
+}
+
+/// The settings of LVGL's default theme, which can be kept in e.g. a RON or
+/// JSON asset with the `serde` feature:
+///
+/// ```json
+/// { "primary": "#2196f3", "secondary": "#f44336", "dark": true }
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThemeDesc {
+    pub primary: Color,
+    pub secondary: Color,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dark: bool,
+}
+
+impl ThemeDesc {
+    /// Initializes LVGL's default theme for `display` with these settings,
+    /// see `Theme::default_init()`.
+    pub fn init(&self, display: &Display, font: Font) -> LvResult<Theme> {
+        Theme::default_init(display, self.primary, self.secondary, self.dark, font)
+    }
+}
+
+/// Reapplies the theme to `obj` and all of its descendants.
+unsafe fn restyle_tree(obj: *mut lvgl_sys::lv_obj_t) {
+    if obj.is_null() {
+        return;
+    }
+    lvgl_sys::lv_theme_apply(obj);
+    for i in 0..lvgl_sys::lv_obj_get_child_cnt(obj) {
+        restyle_tree(lvgl_sys::lv_obj_get_child(obj, i as i32));
+    }
+    if let Some(code) = theme_changed_event_code() {
+        lvgl_sys::lv_event_send(obj, code, ptr::null_mut());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Btn;
+
+    #[test]
+    fn theme_changed_code_is_registered_by_init() {
+        crate::tests::initialize_test(false);
+        let code = theme_changed_event_code().unwrap();
+        let event = crate::Event::<()>::try_from(code);
+        assert_eq!(event, Ok(crate::Event::ThemeChanged));
+        crate::init();
+        assert_eq!(theme_changed_event_code(), Some(code));
+    }
+
+    #[test]
+    fn theme_hot_swap() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let _btn = Btn::create(&mut screen).unwrap();
+        let theme = Theme::from_display(&display).unwrap();
+        theme.apply_to_display(&display);
+    }
+
+    #[test]
+    fn default_theme_colors() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let current = Theme::from_display(&display).unwrap();
+        let font = unsafe { Font::new_raw(*(*current.raw().as_ptr()).font_normal) };
+        let primary = Color::from_rgb((0, 0, 255));
+        let theme =
+            Theme::default_init(&display, primary, Color::from_rgb((255, 0, 0)), true, font)
+                .unwrap();
+        theme.apply_to_display(&display);
+        let color = Color::from_raw(unsafe { (*theme.raw().as_ptr()).color_primary });
+        assert_eq!((color.r(), color.b()), (primary.r(), primary.b()));
+    }
+}
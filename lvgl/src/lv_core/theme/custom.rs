@@ -0,0 +1,104 @@
+use super::Theme;
+use crate::{Box, Obj, Widget};
+use core::ptr::NonNull;
+
+/// Styling written in Rust, applied to every object created on a display
+/// using the theme.
+///
+/// The styles added in `apply()` are typically kept in the theme itself,
+/// which lives as long as the displays using it:
+///
+/// ```ignore
+/// struct Brand {
+///     button: Style,
+/// }
+///
+/// impl CustomTheme for Brand {
+///     fn apply<'a>(&'a mut self, obj: &mut Obj<'a>) {
+///         let is_btn = unsafe {
+///             lvgl_sys::lv_obj_check_type(obj.raw().as_ptr(), &lvgl_sys::lv_btn_class)
+///         };
+///         if is_btn {
+///             obj.add_style(Part::Main, &mut self.button);
+///         }
+///     }
+/// }
+/// ```
+pub trait CustomTheme {
+    /// Styles a newly created object. Runs after the parent theme styled it,
+    /// so styles added here take precedence.
+    fn apply<'a>(&'a mut self, obj: &mut Obj<'a>);
+}
+
+impl Theme {
+    /// Creates a theme applying `custom` on top of `parent`, from which it
+    /// also inherits the palette and fonts.
+    ///
+    /// Displays keep using their theme for as long as they exist, so the
+    /// theme and `custom` are never freed.
+    pub fn custom<T: CustomTheme + 'static>(parent: &Theme, custom: T) -> Self {
+        unsafe {
+            let raw = Box::into_raw(Box::new(*parent.raw.as_ptr()));
+            lvgl_sys::lv_theme_set_parent(raw, parent.raw.as_ptr());
+            lvgl_sys::lv_theme_set_apply_cb(raw, Some(apply_trampoline::<T>));
+            (*raw).user_data = Box::into_raw(Box::new(custom)) as *mut _;
+            Self {
+                raw: NonNull::new_unchecked(raw),
+            }
+        }
+    }
+}
+
+unsafe extern "C" fn apply_trampoline<T: CustomTheme>(
+    theme: *mut lvgl_sys::lv_theme_t,
+    obj: *mut lvgl_sys::lv_obj_t,
+) {
+    let custom = &mut *((*theme).user_data as *mut T);
+    if let Some(mut obj) = NonNull::new(obj).and_then(|obj| Obj::from_raw(obj)) {
+        custom.apply(&mut obj);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::style::Style;
+    use crate::widgets::Btn;
+    use crate::{Display, Part};
+
+    struct Counting {
+        style: Style,
+        applied: u32,
+    }
+
+    impl CustomTheme for Counting {
+        fn apply<'a>(&'a mut self, obj: &mut Obj<'a>) {
+            self.applied += 1;
+            obj.add_style(Part::Main, &mut self.style);
+        }
+    }
+
+    #[test]
+    fn custom_theme_styles_new_objects() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let parent = Theme::from_display(&display).unwrap();
+        let mut style = Style::default();
+        style.set_radius(3);
+        let theme = Theme::custom(&parent, Counting { style, applied: 0 });
+        theme.apply_to_display(&display);
+
+        let mut screen = display.get_scr_act().unwrap();
+        let btn = Btn::create(&mut screen).unwrap();
+        unsafe {
+            let custom = &*((*theme.raw().as_ptr()).user_data as *const Counting);
+            assert!(custom.applied >= 2);
+            let radius = lvgl_sys::lv_obj_get_style_prop(
+                btn.raw().as_ptr(),
+                lvgl_sys::LV_PART_MAIN,
+                lvgl_sys::lv_style_prop_t_LV_STYLE_RADIUS,
+            );
+            assert_eq!(radius.num, 3);
+        }
+    }
+}
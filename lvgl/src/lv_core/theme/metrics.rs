@@ -0,0 +1,162 @@
+use crate::{Display, LvError, LvResult};
+use core::ptr::NonNull;
+
+/// Sizes the active theme of a display gives to containers and built-in
+/// widgets, for laying out custom widgets so they line up with the built-in
+/// ones at any DPI.
+///
+/// The sizes are measured on widgets created off-screen, so they reflect the
+/// theme as it is actually applied, including its adjustments to the display
+/// size and DPI. Widget sizes are `None` for widgets not compiled in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Metrics {
+    /// Dots per inch of the display.
+    pub dpi: u32,
+    /// Padding between the edge of a container and its children.
+    pub pad: i16,
+    /// Gap between the children of a flex or grid container.
+    pub gap: i16,
+    /// Corner radius of a container.
+    pub radius: i16,
+    /// Border width of a container.
+    pub border_width: i16,
+    /// Height of a button holding one line of text.
+    pub btn_height: Option<i16>,
+    /// Height of a one-line text area.
+    pub textarea_height: Option<i16>,
+    /// Height of a closed dropdown.
+    pub dropdown_height: Option<i16>,
+    /// Height of a checkbox with a one-line label.
+    pub checkbox_height: Option<i16>,
+    /// Height of the track of a horizontal slider.
+    pub slider_height: Option<i16>,
+    /// Width and height of a switch.
+    pub switch_size: Option<(i16, i16)>,
+}
+
+impl Metrics {
+    /// Measures the metrics of the theme active on `display`.
+    pub fn from_display(display: &Display) -> LvResult<Self> {
+        let disp = display.disp.as_ptr();
+        unsafe {
+            // Objects without a parent are created on the default display
+            let default = lvgl_sys::lv_disp_get_default();
+            lvgl_sys::lv_disp_set_default(disp);
+            let probe = NonNull::new(lvgl_sys::lv_obj_create(core::ptr::null_mut()));
+            lvgl_sys::lv_disp_set_default(default);
+            let probe = probe.ok_or(LvError::LvOOMemory)?.as_ptr();
+
+            let metrics = measure(disp, probe);
+            lvgl_sys::lv_obj_del(probe);
+            Ok(metrics)
+        }
+    }
+
+    /// Scales `px`, a size in pixels at 160 DPI, to the DPI of the display
+    /// the metrics were measured on. Equivalent to `lv_disp_dpx()`.
+    pub fn dpx(&self, px: i16) -> i16 {
+        if px == 0 {
+            return 0;
+        }
+        ((self.dpi as i32 * px as i32 + 80) / 160).max(1) as i16
+    }
+}
+
+unsafe fn measure(disp: *mut lvgl_sys::lv_disp_t, probe: *mut lvgl_sys::lv_obj_t) -> Metrics {
+    let cont = lvgl_sys::lv_obj_create(probe);
+    // Only assigned to for the widgets compiled in
+    #[allow(unused_mut)]
+    let mut metrics = Metrics {
+        dpi: lvgl_sys::lv_disp_get_dpi(disp) as u32,
+        pad: style_num(cont, lvgl_sys::lv_style_prop_t_LV_STYLE_PAD_TOP),
+        gap: style_num(cont, lvgl_sys::lv_style_prop_t_LV_STYLE_PAD_ROW),
+        radius: style_num(cont, lvgl_sys::lv_style_prop_t_LV_STYLE_RADIUS),
+        border_width: style_num(cont, lvgl_sys::lv_style_prop_t_LV_STYLE_BORDER_WIDTH),
+        ..Default::default()
+    };
+
+    #[cfg(all(lv_widget = "btn", lv_widget = "label"))]
+    let btn = {
+        let btn = lvgl_sys::lv_btn_create(probe);
+        let label = lvgl_sys::lv_label_create(btn);
+        lvgl_sys::lv_label_set_text(label, b"Ag\0".as_ptr() as *const _);
+        btn
+    };
+    #[cfg(lv_widget = "textarea")]
+    let textarea = {
+        let textarea = lvgl_sys::lv_textarea_create(probe);
+        lvgl_sys::lv_textarea_set_one_line(textarea, true);
+        textarea
+    };
+    #[cfg(lv_widget = "dropdown")]
+    let dropdown = lvgl_sys::lv_dropdown_create(probe);
+    #[cfg(lv_widget = "checkbox")]
+    let checkbox = lvgl_sys::lv_checkbox_create(probe);
+    #[cfg(lv_widget = "slider")]
+    let slider = lvgl_sys::lv_slider_create(probe);
+    #[cfg(lv_widget = "switch")]
+    let switch = lvgl_sys::lv_switch_create(probe);
+
+    lvgl_sys::lv_obj_update_layout(probe);
+
+    #[cfg(all(lv_widget = "btn", lv_widget = "label"))]
+    {
+        metrics.btn_height = Some(lvgl_sys::lv_obj_get_height(btn) as i16);
+    }
+    #[cfg(lv_widget = "textarea")]
+    {
+        metrics.textarea_height = Some(lvgl_sys::lv_obj_get_height(textarea) as i16);
+    }
+    #[cfg(lv_widget = "dropdown")]
+    {
+        metrics.dropdown_height = Some(lvgl_sys::lv_obj_get_height(dropdown) as i16);
+    }
+    #[cfg(lv_widget = "checkbox")]
+    {
+        metrics.checkbox_height = Some(lvgl_sys::lv_obj_get_height(checkbox) as i16);
+    }
+    #[cfg(lv_widget = "slider")]
+    {
+        metrics.slider_height = Some(lvgl_sys::lv_obj_get_height(slider) as i16);
+    }
+    #[cfg(lv_widget = "switch")]
+    {
+        metrics.switch_size = Some((
+            lvgl_sys::lv_obj_get_width(switch) as i16,
+            lvgl_sys::lv_obj_get_height(switch) as i16,
+        ));
+    }
+    metrics
+}
+
+unsafe fn style_num(obj: *mut lvgl_sys::lv_obj_t, prop: lvgl_sys::lv_style_prop_t) -> i16 {
+    lvgl_sys::lv_obj_get_style_prop(obj, lvgl_sys::LV_PART_MAIN, prop).num as i16
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn measure_default_theme() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let metrics = Metrics::from_display(&display).unwrap();
+        assert!(metrics.dpi > 0);
+        assert!(metrics.pad > 0);
+        assert_eq!(metrics.dpx(0), 0);
+        let hidpi = Metrics {
+            dpi: 320,
+            ..metrics
+        };
+        assert_eq!(hidpi.dpx(10), 20);
+
+        #[cfg(all(lv_widget = "btn", lv_widget = "label"))]
+        assert!(metrics.btn_height.unwrap() > 0);
+        #[cfg(lv_widget = "switch")]
+        {
+            let (w, h) = metrics.switch_size.unwrap();
+            assert!(w > h);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use super::Theme;
+use crate::Display;
+use core::time::Duration;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The appearance a `ThemeScheduler` can switch between.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+}
+
+/// Decides which `ThemeMode` should currently be active.
+///
+/// Implemented for any `FnMut() -> ThemeMode` closure, as well as for the
+/// provided `Schedule` and `AmbientLight` sources.
+pub trait ModeSource {
+    /// Returns the mode that should be active right now.
+    fn mode(&mut self) -> ThemeMode;
+}
+
+impl<F> ModeSource for F
+where
+    F: FnMut() -> ThemeMode,
+{
+    fn mode(&mut self) -> ThemeMode {
+        self()
+    }
+}
+
+/// Selects the mode based on the time of day.
+///
+/// `clock` returns the current time, of which only the time of day is
+/// considered (i.e. it is taken modulo 24 hours).
+pub struct Schedule<C>
+where
+    C: FnMut() -> Duration,
+{
+    clock: C,
+    light_from: Duration,
+    dark_from: Duration,
+}
+
+impl<C> Schedule<C>
+where
+    C: FnMut() -> Duration,
+{
+    /// Creates a schedule switching to light mode at `light_from` and to dark
+    /// mode at `dark_from`, both given as time since midnight.
+    pub fn new(clock: C, light_from: Duration, dark_from: Duration) -> Self {
+        Self {
+            clock,
+            light_from: time_of_day(light_from),
+            dark_from: time_of_day(dark_from),
+        }
+    }
+}
+
+impl<C> ModeSource for Schedule<C>
+where
+    C: FnMut() -> Duration,
+{
+    fn mode(&mut self) -> ThemeMode {
+        let now = time_of_day((self.clock)());
+        let is_light = if self.light_from <= self.dark_from {
+            self.light_from <= now && now < self.dark_from
+        } else {
+            !(self.dark_from <= now && now < self.light_from)
+        };
+        if is_light {
+            ThemeMode::Light
+        } else {
+            ThemeMode::Dark
+        }
+    }
+}
+
+/// Selects the mode based on an ambient light sensor reading.
+///
+/// To avoid flickering between modes around the threshold, the mode only
+/// changes once the reading is more than `hysteresis` away from it.
+pub struct AmbientLight<S>
+where
+    S: FnMut() -> u32,
+{
+    sensor: S,
+    threshold: u32,
+    hysteresis: u32,
+    current: ThemeMode,
+}
+
+impl<S> AmbientLight<S>
+where
+    S: FnMut() -> u32,
+{
+    /// Creates a sensor-driven source. `sensor` returns the current light
+    /// level in an arbitrary unit (e.g. lux), readings below `threshold`
+    /// select dark mode.
+    pub fn new(sensor: S, threshold: u32, hysteresis: u32) -> Self {
+        Self {
+            sensor,
+            threshold,
+            hysteresis,
+            current: ThemeMode::Light,
+        }
+    }
+}
+
+impl<S> ModeSource for AmbientLight<S>
+where
+    S: FnMut() -> u32,
+{
+    fn mode(&mut self) -> ThemeMode {
+        let level = (self.sensor)();
+        if level < self.threshold.saturating_sub(self.hysteresis) {
+            self.current = ThemeMode::Dark;
+        } else if level > self.threshold.saturating_add(self.hysteresis) {
+            self.current = ThemeMode::Light;
+        }
+        self.current
+    }
+}
+
+/// Automatically switches a display between a light and a dark theme.
+///
+/// Call `update()` periodically, e.g. next to `lvgl::task_handler()`. When
+/// the `ModeSource` reports a different mode, the matching theme is applied
+/// with `Theme::apply_to_display()` and the active screen fades in with the
+/// new styles.
+pub struct ThemeScheduler<M: ModeSource> {
+    light: Theme,
+    dark: Theme,
+    source: M,
+    current: Option<ThemeMode>,
+    fade_time: Duration,
+}
+
+impl<M: ModeSource> ThemeScheduler<M> {
+    /// Creates a scheduler switching between `light` and `dark`. No theme is
+    /// applied until the first call to `update()`.
+    pub fn new(light: Theme, dark: Theme, source: M) -> Self {
+        Self {
+            light,
+            dark,
+            source,
+            current: None,
+            fade_time: Duration::from_millis(300),
+        }
+    }
+
+    /// Sets the duration of the fade transition. A zero duration switches
+    /// instantly.
+    pub fn set_fade_time(&mut self, fade_time: Duration) {
+        self.fade_time = fade_time;
+    }
+
+    /// Returns the currently applied mode, if any.
+    pub fn mode(&self) -> Option<ThemeMode> {
+        self.current
+    }
+
+    /// Polls the mode source and switches themes if needed. Returns `true`
+    /// if a new theme was applied.
+    pub fn update(&mut self, display: &Display) -> bool {
+        let mode = self.source.mode();
+        if self.current == Some(mode) {
+            return false;
+        }
+        self.apply(display, mode);
+        true
+    }
+
+    /// Applies the theme for `mode` immediately, regardless of the source.
+    pub fn force(&mut self, display: &Display, mode: ThemeMode) {
+        self.apply(display, mode);
+    }
+
+    fn apply(&mut self, display: &Display, mode: ThemeMode) {
+        match mode {
+            ThemeMode::Light => self.light.apply_to_display(display),
+            ThemeMode::Dark => self.dark.apply_to_display(display),
+        }
+        // Only fade when switching, not when applying the initial theme
+        if self.current.is_some() && !self.fade_time.is_zero() {
+            unsafe {
+                let screen = lvgl_sys::lv_disp_get_scr_act(display.disp.as_ptr());
+                if !screen.is_null() {
+                    lvgl_sys::lv_obj_fade_in(
+                        screen,
+                        self.fade_time.as_millis().try_into().unwrap_or(u32::MAX),
+                        0,
+                    );
+                }
+            }
+        }
+        self.current = Some(mode);
+    }
+}
+
+fn time_of_day(time: Duration) -> Duration {
+    Duration::from_millis((time.as_millis() % DAY.as_millis()) as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hours(h: u64) -> Duration {
+        Duration::from_secs(h * 60 * 60)
+    }
+
+    #[test]
+    fn schedule_selects_mode_by_time_of_day() {
+        let mut now = hours(12);
+        let mut schedule = Schedule::new(|| now, hours(7), hours(19));
+        assert_eq!(schedule.mode(), ThemeMode::Light);
+        now = hours(22);
+        let mut schedule = Schedule::new(|| now, hours(7), hours(19));
+        assert_eq!(schedule.mode(), ThemeMode::Dark);
+        // Times past a full day wrap around
+        now = hours(24 + 3);
+        let mut schedule = Schedule::new(|| now, hours(7), hours(19));
+        assert_eq!(schedule.mode(), ThemeMode::Dark);
+    }
+
+    #[test]
+    fn ambient_light_uses_hysteresis() {
+        let readings = [500, 95, 80, 105, 130];
+        let mut i = 0;
+        let mut source = AmbientLight::new(
+            || {
+                i += 1;
+                readings[i - 1]
+            },
+            100,
+            10,
+        );
+        assert_eq!(source.mode(), ThemeMode::Light);
+        assert_eq!(source.mode(), ThemeMode::Light);
+        assert_eq!(source.mode(), ThemeMode::Dark);
+        assert_eq!(source.mode(), ThemeMode::Dark);
+        assert_eq!(source.mode(), ThemeMode::Light);
+    }
+}
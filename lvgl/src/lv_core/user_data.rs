@@ -0,0 +1,163 @@
+//! Application state attached to objects
+//!
+//! The `user_data` pointer of an object holds a box tagged with the type of
+//! the value in it, so the value can only be read back as that same type.
+//! The box is dropped when the object is deleted.
+
+use crate::Box;
+use core::any::TypeId;
+use core::mem::ManuallyDrop;
+use core::ptr;
+
+/// The part of a `Tagged<T>` that can be read without knowing `T`.
+#[repr(C)]
+struct Header {
+    type_id: TypeId,
+    free: unsafe fn(*mut Header),
+}
+
+#[repr(C)]
+struct Tagged<T> {
+    header: Header,
+    value: T,
+}
+
+unsafe fn free<T>(tagged: *mut Header) {
+    drop(Box::from_raw(tagged as *mut Tagged<T>));
+}
+
+unsafe fn tagged<T: 'static>(obj: *mut lvgl_sys::lv_obj_t) -> Option<*mut Tagged<T>> {
+    if lvgl_sys::lv_obj_get_event_user_data(obj, Some(user_data_deleted)).is_null() {
+        // Not set through `set()`, so not a `Header`
+        return None;
+    }
+    let header = (*obj).user_data as *mut Header;
+    if !header.is_null() && (*header).type_id == TypeId::of::<T>() {
+        Some(header as *mut Tagged<T>)
+    } else {
+        None
+    }
+}
+
+/// Stores `value` on `obj`, dropping whatever value was stored before.
+pub(crate) fn set<T: 'static>(obj: *mut lvgl_sys::lv_obj_t, value: T) {
+    unsafe {
+        clear(obj);
+        // The delete callback is registered once per object; its user data
+        // only marks it as registered
+        if lvgl_sys::lv_obj_get_event_user_data(obj, Some(user_data_deleted)).is_null() {
+            lvgl_sys::lv_obj_add_event_cb(
+                obj,
+                Some(user_data_deleted),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                obj as *mut _,
+            );
+        }
+        let tagged = Box::new(Tagged {
+            header: Header {
+                type_id: TypeId::of::<T>(),
+                free: free::<T>,
+            },
+            value,
+        });
+        (*obj).user_data = Box::into_raw(tagged) as *mut _;
+    }
+}
+
+/// Returns the value stored on `obj`, if it is a `T`.
+///
+/// # Safety
+///
+/// The value must not be accessed through another reference while the
+/// returned one is alive.
+pub(crate) unsafe fn get<'a, T: 'static>(obj: *mut lvgl_sys::lv_obj_t) -> Option<&'a mut T> {
+    tagged::<T>(obj).map(|tagged| &mut (*tagged).value)
+}
+
+/// Removes the value stored on `obj` and returns it, if it is a `T`.
+pub(crate) fn take<T: 'static>(obj: *mut lvgl_sys::lv_obj_t) -> Option<T> {
+    unsafe {
+        let tagged = tagged::<T>(obj)?;
+        (*obj).user_data = ptr::null_mut();
+        let value = ptr::read(&(*tagged).value);
+        drop(Box::from_raw(tagged as *mut ManuallyDrop<Tagged<T>>));
+        Some(value)
+    }
+}
+
+/// Drops the value stored on `obj`, whatever its type.
+unsafe fn clear(obj: *mut lvgl_sys::lv_obj_t) {
+    if lvgl_sys::lv_obj_get_event_user_data(obj, Some(user_data_deleted)).is_null() {
+        // Not set through `set()`, so not ours to free
+        return;
+    }
+    let header = (*obj).user_data as *mut Header;
+    (*obj).user_data = ptr::null_mut();
+    if !header.is_null() {
+        ((*header).free)(header);
+    }
+}
+
+unsafe extern "C" fn user_data_deleted(event: *mut lvgl_sys::lv_event_t) {
+    clear((*event).user_data as *mut lvgl_sys::lv_obj_t);
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Display, Obj, Widget};
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    struct Counter {
+        clicks: u32,
+    }
+
+    static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+    struct Tracked;
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn typed_user_data() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut obj = Obj::create(&screen).unwrap();
+
+        assert!(obj.get_user_data::<Counter>().is_none());
+        obj.set_user_data(Counter { clicks: 0 });
+        obj.get_user_data_mut::<Counter>().unwrap().clicks += 1;
+        assert_eq!(obj.get_user_data::<Counter>().unwrap().clicks, 1);
+        // Only readable as the type it was stored as
+        assert!(obj.get_user_data::<u32>().is_none());
+        assert_eq!(obj.take_user_data::<Counter>().unwrap().clicks, 1);
+        assert!(obj.get_user_data::<Counter>().is_none());
+
+        // Dropped when replaced, and when the object is deleted
+        obj.set_user_data(Tracked);
+        obj.set_user_data(Tracked);
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+        obj.del();
+        assert_eq!(DROPPED.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn raw_user_data_is_not_read() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut obj = Obj::create(&screen).unwrap();
+
+        // As set by C code, which is not a tagged box
+        let mut raw = 0u32;
+        unsafe { obj.raw().as_mut().user_data = &mut raw as *mut u32 as *mut _ };
+        assert!(obj.get_user_data::<Counter>().is_none());
+        assert!(obj.get_user_data::<u32>().is_none());
+        unsafe { obj.raw().as_mut().user_data = core::ptr::null_mut() };
+        obj.del();
+    }
+}
@@ -0,0 +1,109 @@
+//! Displays driven by the `mipidsi` crate
+//!
+//! With the `mipidsi` feature, any RGB565 display of the [`mipidsi`] crate,
+//! e.g. ST7789, ILI9341 or GC9A01, can be registered without writing a flush
+//! callback or unsafe code. Each area LVGL redraws is written to a window of
+//! the same size, so partial updates only transfer the pixels that changed:
+//!
+//! ```ignore
+//! use lvgl::mipidsi::Mipidsi;
+//!
+//! let di = SPIInterface::new(spi, dc);
+//! let panel = mipidsi::Builder::new(ST7789, di).init(&mut delay)?;
+//! let buffer = DrawBuffer::<{ 240 * 24 }>::default();
+//! let display = Mipidsi::new(panel).register(buffer, 240, 240)?;
+//! ```
+//!
+//! [`mipidsi`]: https://docs.rs/mipidsi
+
+use crate::{Display, DisplayError, DrawBuffer};
+use ::mipidsi::models::Model;
+use display_interface::WriteOnlyDataCommand;
+use embedded_graphics::pixelcolor::raw::RawU16;
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::IntoStorage;
+use embedded_hal::digital::OutputPin;
+
+/// Adapts a `mipidsi::Display` to LVGL, see the module documentation.
+pub struct Mipidsi<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model<ColorFormat = Rgb565>,
+    RST: OutputPin,
+{
+    panel: ::mipidsi::Display<DI, M, RST>,
+    swap_bytes: bool,
+}
+
+impl<DI, M, RST> Mipidsi<DI, M, RST>
+where
+    DI: WriteOnlyDataCommand,
+    M: Model<ColorFormat = Rgb565>,
+    RST: OutputPin,
+{
+    /// Wraps an initialized display.
+    pub fn new(panel: ::mipidsi::Display<DI, M, RST>) -> Self {
+        Self {
+            panel,
+            swap_bytes: false,
+        }
+    }
+
+    /// Swaps the bytes of every pixel sent, for panels or buses expecting
+    /// RGB565 in little-endian order. Off by default; LVGL's own
+    /// `LV_COLOR_16_SWAP` is accounted for either way.
+    pub fn swap_bytes(mut self, swap: bool) -> Self {
+        self.swap_bytes = swap;
+        self
+    }
+
+    /// Registers a display drawing into the panel. Errors writing to the
+    /// panel are ignored, as LVGL can't retry flushing an area.
+    pub fn register<const N: usize>(
+        self,
+        draw_buffer: DrawBuffer<N>,
+        hor_res: u32,
+        ver_res: u32,
+    ) -> Result<Display, DisplayError> {
+        let Self {
+            mut panel,
+            swap_bytes,
+        } = self;
+        Display::register(draw_buffer, hor_res, ver_res, move |refresh| {
+            let area = refresh.area;
+            let colors = refresh.area_colors().iter().map(|&color| {
+                let color = Rgb565::from(color);
+                if swap_bytes {
+                    swapped(color)
+                } else {
+                    color
+                }
+            });
+            // Both end coordinates are inclusive
+            let _ = panel.set_pixels(
+                area.x1 as u16,
+                area.y1 as u16,
+                area.x2 as u16,
+                area.y2 as u16,
+                colors,
+            );
+        })
+    }
+}
+
+/// The color whose bytes are the ones of `color`, swapped.
+fn swapped(color: Rgb565) -> Rgb565 {
+    RawU16::new(color.into_storage().swap_bytes()).into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn swapped_colors_have_their_bytes_swapped() {
+        let red = Rgb565::new(0x1f, 0, 0);
+        assert_eq!(swapped(red).into_storage(), 0x00f8);
+        assert_eq!(swapped(swapped(red)), red);
+    }
+}
@@ -0,0 +1,388 @@
+//! Feeding UI state from external message buses
+//!
+//! HMI front-ends mostly display values that arrive from elsewhere: CAN
+//! signals, MQTT topics, readings from a sensor task. These arrive on other
+//! threads or in interrupt handlers, often much faster than the screen can
+//! show them, while LVGL may only be touched from the thread running
+//! `task_handler()`.
+//!
+//! A `BridgeInbox` is the meeting point. It is `Sync` and usually lives in a
+//! `static`; producers `post()` values into it by ID from anywhere. Only the
+//! latest value per ID is kept. On the UI thread, a `UiBridge` routes each ID
+//! to a handler updating the widgets, and `UiBridge::poll()` (called next to
+//! `task_handler()`) applies pending values, at most once per route's
+//! minimum interval.
+//!
+//! IDs are plain `u32`s, e.g. CAN identifiers; string topics are mapped to
+//! IDs with `topic_id()`.
+//!
+//! ```ignore
+//! static INBOX: BridgeInbox<4> = BridgeInbox::new();
+//! const SPEED: u32 = 0x101;
+//! const STATUS: u32 = topic_id("vehicle/status");
+//!
+//! let mut show_speed = |v: BridgeValue| {
+//!     if let BridgeValue::Int(kmh) = v {
+//!         gauge.set_value(kmh as i16);
+//!     }
+//! };
+//! let mut show_status = |v: BridgeValue| {
+//!     if let BridgeValue::Text(text) = v {
+//!         table.set_cell_str(0, 1, text).ok();
+//!     }
+//! };
+//! let mut bridge = UiBridge::new(&INBOX)?;
+//! bridge.route(SPEED, Duration::from_millis(100), &mut show_speed)?;
+//! bridge.route(STATUS, Duration::ZERO, &mut show_status)?;
+//!
+//! // On the CAN receive thread:
+//! INBOX.post(SPEED, 87)?;
+//! // In the MQTT client:
+//! INBOX.post_text(STATUS, "Charging")?;
+//!
+//! loop {
+//!     bridge.poll();
+//!     lvgl::task_handler();
+//! }
+//! ```
+
+use crate::{LvError, LvResult};
+use core::sync::atomic::{
+    fence, AtomicBool, AtomicI32, AtomicU32, AtomicU8, AtomicUsize, Ordering,
+};
+use core::time::Duration;
+
+/// Maximum length in bytes of a text value; longer texts are truncated.
+pub const BRIDGE_TEXT_CAPACITY: usize = 32;
+
+const INT: u8 = 0;
+const TEXT: u8 = 1;
+
+/// Maps a string topic, e.g. an MQTT topic, to an ID (32-bit FNV-1a hash).
+pub const fn topic_id(topic: &str) -> u32 {
+    let bytes = topic.as_bytes();
+    let mut hash: u32 = 0x811c_9dc5;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u32;
+        hash = hash.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    hash
+}
+
+/// A value delivered to a route handler.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BridgeValue<'v> {
+    Int(i32),
+    Text(&'v str),
+}
+
+/// The latest value posted for an ID. `seq` is odd while a value is being
+/// written and advances by two with every value, so the bridge can tell new
+/// values apart and detect being overwritten while reading one.
+struct Slot {
+    used: AtomicBool,
+    id: AtomicU32,
+    seq: AtomicU32,
+    kind: AtomicU8,
+    int: AtomicI32,
+    text_len: AtomicUsize,
+    text: [AtomicU8; BRIDGE_TEXT_CAPACITY],
+}
+
+impl Slot {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Slot = {
+        const ZERO: AtomicU8 = AtomicU8::new(0);
+        Slot {
+            used: AtomicBool::new(false),
+            id: AtomicU32::new(0),
+            seq: AtomicU32::new(0),
+            kind: AtomicU8::new(INT),
+            int: AtomicI32::new(0),
+            text_len: AtomicUsize::new(0),
+            text: [ZERO; BRIDGE_TEXT_CAPACITY],
+        }
+    };
+
+    /// Writes a value with `write`. Fails with `LvError::AlreadyInUse` if
+    /// another value is being written at the same time.
+    fn write(&self, write: impl FnOnce(&Slot)) -> LvResult<()> {
+        let seq = self.seq.load(Ordering::Relaxed);
+        if seq % 2 == 1
+            || self
+                .seq
+                .compare_exchange(
+                    seq,
+                    seq.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+        {
+            return Err(LvError::AlreadyInUse);
+        }
+        fence(Ordering::Release);
+        write(self);
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+        Ok(())
+    }
+
+    /// Reads the value written after `seen` into `text`, returning it with
+    /// its `seq`. Returns `None` if there is none, or it is being written.
+    fn read<'t>(&self, seen: u32, text: &'t mut [u8]) -> Option<(u32, BridgeValue<'t>)> {
+        let seq = self.seq.load(Ordering::Acquire);
+        if seq % 2 == 1 || seq == seen {
+            return None;
+        }
+        let kind = self.kind.load(Ordering::Relaxed);
+        let int = self.int.load(Ordering::Relaxed);
+        let len = self
+            .text_len
+            .load(Ordering::Relaxed)
+            .min(BRIDGE_TEXT_CAPACITY);
+        for (byte, src) in text.iter_mut().zip(&self.text[..len]) {
+            *byte = src.load(Ordering::Relaxed);
+        }
+        fence(Ordering::Acquire);
+        if self.seq.load(Ordering::Relaxed) != seq {
+            return None;
+        }
+        let value = match kind {
+            INT => BridgeValue::Int(int),
+            // Only complete UTF-8 strings are ever written
+            _ => BridgeValue::Text(unsafe { core::str::from_utf8_unchecked(&text[..len]) }),
+        };
+        Some((seq, value))
+    }
+}
+
+/// Latest values posted by producers, one slot per routed ID. See the module
+/// documentation.
+pub struct BridgeInbox<const N: usize> {
+    slots: [Slot; N],
+    bridged: AtomicBool,
+}
+
+impl<const N: usize> Default for BridgeInbox<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> BridgeInbox<N> {
+    /// Creates an inbox with room for `N` routes.
+    pub const fn new() -> Self {
+        Self {
+            slots: [Slot::EMPTY; N],
+            bridged: AtomicBool::new(false),
+        }
+    }
+
+    fn slot(&self, id: u32) -> LvResult<&Slot> {
+        self.slots
+            .iter()
+            .find(|s| s.used.load(Ordering::Acquire) && s.id.load(Ordering::Relaxed) == id)
+            .ok_or(LvError::InvalidArgument)
+    }
+
+    /// Posts a number for `id`, replacing any value not applied yet. Fails
+    /// with `LvError::InvalidArgument` if no route exists for `id`, and with
+    /// `LvError::AlreadyInUse` if another value for `id` is being posted at
+    /// the same time, e.g. by an interrupt handler that preempted a post.
+    /// The UI applying values never makes posting fail.
+    pub fn post(&self, id: u32, value: i32) -> LvResult<()> {
+        self.slot(id)?.write(|slot| {
+            slot.kind.store(INT, Ordering::Relaxed);
+            slot.int.store(value, Ordering::Relaxed);
+        })
+    }
+
+    /// Posts a text for `id`, truncated to `BRIDGE_TEXT_CAPACITY` bytes.
+    /// Fails like `post()`.
+    pub fn post_text(&self, id: u32, text: &str) -> LvResult<()> {
+        let mut len = text.len().min(BRIDGE_TEXT_CAPACITY);
+        while !text.is_char_boundary(len) {
+            len -= 1;
+        }
+        self.slot(id)?.write(|slot| {
+            for (byte, &src) in slot.text.iter().zip(&text.as_bytes()[..len]) {
+                byte.store(src, Ordering::Relaxed);
+            }
+            slot.text_len.store(len, Ordering::Relaxed);
+            slot.kind.store(TEXT, Ordering::Relaxed);
+        })
+    }
+}
+
+struct Route<'a> {
+    slot: usize,
+    min_interval: u32,
+    last_applied: Option<u32>,
+    /// `seq` of the last value applied.
+    seen: u32,
+    handler: &'a mut dyn FnMut(BridgeValue),
+}
+
+/// Applies the values of a `BridgeInbox` to the UI. See the module
+/// documentation.
+pub struct UiBridge<'a, 'i, const N: usize> {
+    inbox: &'i BridgeInbox<N>,
+    routes: [Option<Route<'a>>; N],
+}
+
+impl<'a, 'i, const N: usize> UiBridge<'a, 'i, N> {
+    /// Creates the bridge applying the values posted to `inbox`. Fails with
+    /// `LvError::AlreadyInUse` if `inbox` has a bridge already.
+    pub fn new(inbox: &'i BridgeInbox<N>) -> LvResult<Self> {
+        if inbox.bridged.swap(true, Ordering::AcqRel) {
+            return Err(LvError::AlreadyInUse);
+        }
+        Ok(Self {
+            inbox,
+            routes: core::array::from_fn(|_| None),
+        })
+    }
+
+    /// Routes the values posted for `id` to `handler`, calling it at most
+    /// once per `min_interval` with the latest value. Fails with
+    /// `LvError::AlreadyInUse` if `id` is routed already and with
+    /// `LvError::LvOOMemory` if all `N` routes are taken.
+    pub fn route(
+        &mut self,
+        id: u32,
+        min_interval: Duration,
+        handler: &'a mut dyn FnMut(BridgeValue),
+    ) -> LvResult<()> {
+        if self.inbox.slot(id).is_ok() {
+            return Err(LvError::AlreadyInUse);
+        }
+        let index = self
+            .routes
+            .iter()
+            .position(Option::is_none)
+            .ok_or(LvError::LvOOMemory)?;
+        let slot = &self.inbox.slots[index];
+        slot.id.store(id, Ordering::Relaxed);
+        slot.used.store(true, Ordering::Release);
+        self.routes[index] = Some(Route {
+            slot: index,
+            min_interval: min_interval.as_millis().try_into().unwrap_or(u32::MAX),
+            last_applied: None,
+            // Values posted for an earlier route of the slot are stale
+            seen: slot.seq.load(Ordering::Acquire) & !1,
+            handler,
+        });
+        Ok(())
+    }
+
+    /// Removes the route for `id`; values posted for it are rejected again.
+    pub fn unroute(&mut self, id: u32) {
+        for route in self.routes.iter_mut() {
+            if let Some(r) = route {
+                let slot = &self.inbox.slots[r.slot];
+                if slot.id.load(Ordering::Relaxed) == id {
+                    slot.used.store(false, Ordering::Release);
+                    *route = None;
+                }
+            }
+        }
+    }
+
+    /// Calls the handlers of all routes with a pending value whose minimum
+    /// interval has passed, returning how many were called. Must be called
+    /// from the thread running `task_handler()`. A value being posted
+    /// concurrently is applied by a later call.
+    pub fn poll(&mut self) -> usize {
+        let mut applied = 0;
+        for route in self.routes.iter_mut().flatten() {
+            if let Some(last) = route.last_applied {
+                if unsafe { lvgl_sys::lv_tick_elaps(last) } < route.min_interval {
+                    continue;
+                }
+            }
+            let mut text = [0u8; BRIDGE_TEXT_CAPACITY];
+            let Some((seq, value)) = self.inbox.slots[route.slot].read(route.seen, &mut text)
+            else {
+                continue;
+            };
+            route.seen = seq;
+            (route.handler)(value);
+            route.last_applied = Some(unsafe { lvgl_sys::lv_tick_get() });
+            applied += 1;
+        }
+        applied
+    }
+}
+
+impl<const N: usize> Drop for UiBridge<'_, '_, N> {
+    fn drop(&mut self) {
+        for route in self.routes.iter().flatten() {
+            self.inbox.slots[route.slot]
+                .used
+                .store(false, Ordering::Release);
+        }
+        self.inbox.bridged.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn latest_value_is_rate_limited() {
+        crate::tests::initialize_test(false);
+        let inbox = BridgeInbox::<2>::new();
+        let mut seen = [0i32; 4];
+        let mut count = 0;
+        let mut on_speed = |v: BridgeValue| {
+            if let BridgeValue::Int(v) = v {
+                seen[count] = v;
+                count += 1;
+            }
+        };
+        let mut bridge = UiBridge::new(&inbox).unwrap();
+        assert!(matches!(UiBridge::new(&inbox), Err(LvError::AlreadyInUse)));
+        bridge
+            .route(0x101, Duration::from_millis(100), &mut on_speed)
+            .unwrap();
+        assert_eq!(inbox.post(0x102, 1), Err(LvError::InvalidArgument));
+
+        inbox.post(0x101, 10).unwrap();
+        inbox.post(0x101, 20).unwrap();
+        assert_eq!(bridge.poll(), 1);
+        inbox.post(0x101, 30).unwrap();
+        assert_eq!(bridge.poll(), 0);
+        unsafe { lvgl_sys::lv_tick_inc(100) };
+        assert_eq!(bridge.poll(), 1);
+        drop(bridge);
+        assert_eq!(&seen[..count], &[20, 30]);
+    }
+
+    #[test]
+    fn text_is_truncated_on_char_boundary() {
+        crate::tests::initialize_test(false);
+        let inbox = BridgeInbox::<1>::new();
+        let mut last = [0u8; BRIDGE_TEXT_CAPACITY];
+        let mut len = 0;
+        let mut on_status = |v: BridgeValue| {
+            if let BridgeValue::Text(t) = v {
+                last[..t.len()].copy_from_slice(t.as_bytes());
+                len = t.len();
+            }
+        };
+        let topic = topic_id("vehicle/status");
+        let mut bridge = UiBridge::new(&inbox).unwrap();
+        bridge.route(topic, Duration::ZERO, &mut on_status).unwrap();
+
+        inbox.post_text(topic, &"é".repeat(20)).unwrap();
+        assert_eq!(bridge.poll(), 1);
+        assert_eq!(bridge.poll(), 0);
+        drop(bridge);
+        assert!(UiBridge::new(&inbox).is_ok());
+        assert_eq!(len, 32);
+        assert!(core::str::from_utf8(&last[..len]).is_ok());
+    }
+}
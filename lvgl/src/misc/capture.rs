@@ -0,0 +1,226 @@
+//! Recording the frames shown on a display
+//!
+//! A `FrameTap` assembles the areas flushed to a display into complete
+//! frames, e.g. to encode a demo of the UI as MJPEG or GIF, or to send the
+//! frames over USB to a host. Every completed frame is handed to a callback,
+//! and the last few are kept in a ring so they can be dumped after the fact,
+//! e.g. when reproducing a bug.
+//!
+//! The tap is fed from the flush callback of the display:
+//!
+//! ```ignore
+//! let mut tap = FrameTap::new(320, 240, 4, |frame| encoder.push(frame.pixels()))?;
+//! let display = Display::register(buffer, 320, 240, |refresh| {
+//!     panel.draw_iter(refresh.as_pixels()).ok();
+//!     tap.capture(refresh);
+//! })?;
+//! ```
+
+use crate::{Color, DisplayRefresh, LvError, LvResult};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// A complete frame recorded by a `FrameTap`.
+pub struct CapturedFrame {
+    sequence: u32,
+    timestamp: u32,
+    width: u16,
+    height: u16,
+    pixels: Vec<Color>,
+}
+
+impl CapturedFrame {
+    /// Returns the number of frames completed before this one.
+    pub fn sequence(&self) -> u32 {
+        self.sequence
+    }
+
+    /// Returns the LVGL tick, in milliseconds, at which the frame was
+    /// completed.
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    /// Returns the width of the frame in pixels.
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    /// Returns the height of the frame in pixels.
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Returns the pixels of the frame, row by row.
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Returns the pixels of row `y`.
+    pub fn row(&self, y: u16) -> &[Color] {
+        let start = y as usize * self.width as usize;
+        &self.pixels[start..start + self.width as usize]
+    }
+}
+
+/// Assembles flushed areas into frames, keeping the last frames in a ring.
+pub struct FrameTap<F: FnMut(&CapturedFrame)> {
+    ring: Vec<CapturedFrame>,
+    /// The slot the frame being drawn is assembled in.
+    current: usize,
+    completed: u32,
+    on_frame: F,
+}
+
+impl<F: FnMut(&CapturedFrame)> FrameTap<F> {
+    /// Creates a tap for a `width` by `height` display, calling `on_frame`
+    /// with every completed frame. Of those, the last `keep` frames stay
+    /// available through `frames()`.
+    ///
+    /// Each frame takes `width * height` pixels of memory; the tap allocates
+    /// `keep + 1` of them. Fails with `LvError::InvalidArgument` if the size
+    /// is zero.
+    pub fn new(width: u16, height: u16, keep: usize, on_frame: F) -> LvResult<Self> {
+        let pixels = width as usize * height as usize;
+        if pixels == 0 {
+            return Err(LvError::InvalidArgument);
+        }
+        let ring = (0..=keep)
+            .map(|_| CapturedFrame {
+                sequence: 0,
+                timestamp: 0,
+                width,
+                height,
+                pixels: vec![Color::default(); pixels],
+            })
+            .collect();
+        Ok(Self {
+            ring,
+            current: 0,
+            completed: 0,
+            on_frame,
+        })
+    }
+
+    /// Copies a flushed area into the frame being assembled. Once the last
+    /// area of a frame has been captured, the frame is handed to the
+    /// callback.
+    pub fn capture<const N: usize>(&mut self, refresh: &DisplayRefresh<N>) {
+        let frame = &mut self.ring[self.current];
+        let area = refresh.area;
+        let width = frame.width as i32;
+        // Parts of the area outside of the frame, e.g. with a larger display
+        // than the tap was created for, are left out
+        let x1 = (area.x1 as i32).max(0);
+        let x2 = (area.x2 as i32).min(width - 1);
+        let y1 = (area.y1 as i32).max(0);
+        let y2 = (area.y2 as i32).min(frame.height as i32 - 1);
+        if x1 <= x2 {
+            let len = (x2 - x1 + 1) as usize;
+            for y in y1..=y2 {
+                let src =
+                    ((y - area.y1 as i32) * area.width() as i32 + x1 - area.x1 as i32) as usize;
+                let Some(src) = refresh.colors.get(src..src + len) else {
+                    break;
+                };
+                let dst = (y * width + x1) as usize;
+                frame.pixels[dst..dst + len].copy_from_slice(src);
+            }
+        }
+
+        if refresh.is_last() {
+            self.complete();
+        }
+    }
+
+    /// Returns the frames kept, oldest first.
+    pub fn frames(&self) -> impl Iterator<Item = &CapturedFrame> {
+        let len = self.ring.len();
+        let kept = (self.completed as usize).min(len - 1);
+        (len - kept..len).map(move |i| &self.ring[(self.current + i) % len])
+    }
+
+    /// Returns the number of frames completed so far.
+    pub fn frame_count(&self) -> u32 {
+        self.completed
+    }
+
+    fn complete(&mut self) {
+        let frame = &mut self.ring[self.current];
+        frame.sequence = self.completed;
+        frame.timestamp = unsafe { lvgl_sys::lv_tick_get() };
+        (self.on_frame)(frame);
+        self.completed = self.completed.wrapping_add(1);
+
+        // The next refresh only redraws what changed, so it starts out as a
+        // copy of this frame
+        let next = (self.current + 1) % self.ring.len();
+        if next != self.current {
+            let (done, next_frame) = if next > self.current {
+                let (a, b) = self.ring.split_at_mut(next);
+                (&a[self.current], &mut b[0])
+            } else {
+                let (a, b) = self.ring.split_at_mut(self.current);
+                (&b[0], &mut a[next])
+            };
+            next_frame.pixels.copy_from_slice(&done.pixels);
+        }
+        self.current = next;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::display::Area;
+
+    fn refresh(area: Area, colors: &[Color; 4], last: bool) -> DisplayRefresh<'_, 4> {
+        DisplayRefresh { area, colors, last }
+    }
+
+    #[test]
+    fn frames_are_assembled() {
+        crate::tests::initialize_test(false);
+        let red = Color::from_rgb((255, 0, 0));
+        let blue = Color::from_rgb((0, 0, 255));
+        let mut sequences = Vec::new();
+        let mut tap = FrameTap::new(4, 2, 2, |frame| sequences.push(frame.sequence())).unwrap();
+
+        // Two flushes per frame, 2x2 pixels each
+        let left = Area {
+            x1: 0,
+            x2: 1,
+            y1: 0,
+            y2: 1,
+        };
+        let right = Area {
+            x1: 2,
+            x2: 3,
+            y1: 0,
+            y2: 1,
+        };
+        tap.capture(&refresh(left, &[red; 4], false));
+        tap.capture(&refresh(right, &[blue; 4], true));
+        // Only the left half changes in the second frame
+        tap.capture(&refresh(left, &[blue; 4], true));
+        // Partly off-screen areas are clipped
+        let off = Area {
+            x1: 3,
+            x2: 4,
+            y1: 1,
+            y2: 2,
+        };
+        tap.capture(&refresh(off, &[red; 4], true));
+
+        assert_eq!(tap.frame_count(), 3);
+        let kept: Vec<_> = tap.frames().collect();
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].sequence(), 1);
+        let is_red = |c: &Color| c.r() > 0 && c.b() == 0;
+        assert!(kept[0].pixels().iter().all(|c| !is_red(c)));
+        assert!(is_red(&kept[1].row(1)[3]));
+        assert!(!is_red(&kept[1].row(1)[2]));
+        drop(tap);
+        assert_eq!(sequences, [0, 1, 2]);
+    }
+}
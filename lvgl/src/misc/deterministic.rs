@@ -0,0 +1,140 @@
+//! Deterministic rendering for tests
+//!
+//! Timers, animations and screen refreshes in LVGL are all driven by the
+//! tick, which normally advances with the wall clock. `DeterministicClock`
+//! instead advances the tick in fixed steps and runs the timer handler after
+//! every step, so each timer fires at exactly the same tick and each frame is
+//! rendered from exactly the same state on every machine. Snapshot tests
+//! built on it produce byte-identical output regardless of how fast the host
+//! is.
+//!
+//! Only available when LVGL's tick is advanced through `tick_inc()`, i.e.
+//! without the `rust_timer` and `custom_timer` features. Don't call
+//! `tick_inc()` or `task_handler()` yourself while using the clock.
+
+use crate::{task_handler, tick_inc};
+use core::time::Duration;
+
+/// A tick source advanced only through explicit calls.
+#[derive(Debug)]
+pub struct DeterministicClock {
+    step: Duration,
+    elapsed: Duration,
+}
+
+impl Default for DeterministicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeterministicClock {
+    /// Creates a clock advancing in steps of one millisecond, the resolution
+    /// of LVGL's tick.
+    pub fn new() -> Self {
+        Self::with_step(Duration::from_millis(1))
+    }
+
+    /// Creates a clock advancing in steps of `step`. Larger steps run the
+    /// timer handler less often, which is faster but fires timers with a
+    /// shorter period than `step` only once per step.
+    pub fn with_step(step: Duration) -> Self {
+        Self {
+            step: step.max(Duration::from_millis(1)),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    /// Returns the time the clock has been advanced by so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Advances the tick by `by`, running the timer handler after every step.
+    /// A remainder shorter than a step is advanced as a final, shorter step.
+    pub fn advance(&mut self, by: Duration) {
+        let mut left = by;
+        while !left.is_zero() {
+            let step = self.step.min(left);
+            self.tick(step);
+            left -= step;
+        }
+    }
+
+    /// Runs the timer handler without advancing the tick, e.g. to render the
+    /// changes made since the last step.
+    pub fn run_pending(&mut self) {
+        task_handler();
+    }
+
+    /// Fast-forwards time until no animation is running anymore, so the UI
+    /// reaches the state it would settle in. Gives up after `limit`, which
+    /// protects against infinitely repeating animations, returning whether
+    /// all animations finished.
+    pub fn finish_animations(&mut self, limit: Duration) -> bool {
+        let mut left = limit;
+        while running_animations() > 0 {
+            if left.is_zero() {
+                return false;
+            }
+            let step = self.step.min(left);
+            self.tick(step);
+            left -= step;
+        }
+        true
+    }
+
+    fn tick(&mut self, step: Duration) {
+        tick_inc(step);
+        task_handler();
+        self.elapsed += step;
+    }
+}
+
+/// Returns the number of animations currently running.
+pub fn running_animations() -> u16 {
+    unsafe { lvgl_sys::lv_anim_count_running() }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Bar;
+    use crate::{AnimationState, Display};
+    use core::cell::Cell;
+    use core::ptr;
+
+    unsafe extern "C" fn count(timer: *mut lvgl_sys::lv_timer_t) {
+        let fired = &*((*timer).user_data as *const Cell<u32>);
+        fired.set(fired.get() + 1);
+    }
+
+    #[test]
+    fn timers_fire_per_tick() {
+        crate::tests::initialize_test(true);
+        let fired = Cell::new(0u32);
+        let timer =
+            unsafe { lvgl_sys::lv_timer_create(Some(count), 10, &fired as *const _ as *mut _) };
+        assert_ne!(timer, ptr::null_mut());
+
+        let mut clock = DeterministicClock::new();
+        clock.advance(Duration::from_millis(105));
+        assert_eq!(fired.get(), 10);
+        assert_eq!(clock.elapsed(), Duration::from_millis(105));
+        unsafe { lvgl_sys::lv_timer_del(timer) };
+    }
+
+    #[test]
+    fn animations_fast_forward() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut bar = Bar::create(&mut screen).unwrap();
+        bar.set_value(100, AnimationState::ON);
+        assert!(running_animations() > 0);
+
+        let mut clock = DeterministicClock::new();
+        assert!(clock.finish_animations(Duration::from_secs(5)));
+        assert_eq!(running_animations(), 0);
+    }
+}
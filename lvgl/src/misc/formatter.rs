@@ -0,0 +1,312 @@
+//! Formatting of numeric values for display
+//!
+//! Instrument UIs show the same kinds of values over and over: a reading
+//! with a fixed number of decimals, a unit, maybe an SI prefix or a locale
+//! specific decimal separator. `ValueFormatter` handles these without any
+//! heap allocation, producing a `FormattedValue` that can be passed to
+//! `Label::set_text()` directly, or writing into a C buffer such as the
+//! `text` field of a chart or meter tick label draw descriptor.
+//!
+//! ```
+//! use lvgl::misc::formatter::ValueFormatter;
+//!
+//! let power = ValueFormatter::new().decimals(2).si_prefix(true).unit("W");
+//! assert_eq!(power.format(1520.0).as_str(), "1.52 kW");
+//!
+//! let price = ValueFormatter::new().decimals(2).thousands_separator('.').decimal_comma();
+//! assert_eq!(price.format(1234567.891).as_str(), "1.234.567,89");
+//! ```
+
+use core::fmt;
+use core::ops::Deref;
+use cstr_core::CStr;
+
+/// Maximum length in bytes of a formatted value, excluding the terminator.
+pub const MAX_FORMATTED_LEN: usize = 31;
+
+const SI_PREFIXES: [(f64, &str); 6] = [
+    (1e9, "G"),
+    (1e6, "M"),
+    (1e3, "k"),
+    (1e0, ""),
+    (1e-3, "m"),
+    (1e-6, "\u{b5}"),
+];
+
+/// Formats numbers with a fixed number of decimals, optional SI prefix,
+/// separators and unit suffix.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueFormatter {
+    decimals: u8,
+    si_prefix: bool,
+    thousands_separator: Option<char>,
+    decimal_separator: char,
+    unit: &'static str,
+    unit_spacing: bool,
+}
+
+impl Default for ValueFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValueFormatter {
+    /// Creates a formatter printing integers without separators or unit.
+    pub const fn new() -> Self {
+        Self {
+            decimals: 0,
+            si_prefix: false,
+            thousands_separator: None,
+            decimal_separator: '.',
+            unit: "",
+            unit_spacing: true,
+        }
+    }
+
+    /// Sets the number of decimals (at most 9).
+    pub const fn decimals(mut self, decimals: u8) -> Self {
+        self.decimals = if decimals > 9 { 9 } else { decimals };
+        self
+    }
+
+    /// Scales values to the closest SI prefix, e.g. 1520 W becomes 1.52 kW.
+    pub const fn si_prefix(mut self, enabled: bool) -> Self {
+        self.si_prefix = enabled;
+        self
+    }
+
+    /// Groups the digits of the integer part in threes with `separator`.
+    pub const fn thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    /// Sets the character between the integer and fractional part.
+    pub const fn decimal_separator(mut self, separator: char) -> Self {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Uses a decimal comma, as common in many European locales.
+    pub const fn decimal_comma(self) -> Self {
+        self.decimal_separator(',')
+    }
+
+    /// Appends a unit, separated from the number by a space.
+    pub const fn unit(mut self, unit: &'static str) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    /// Controls whether a space is put between the number and the unit.
+    pub const fn unit_spacing(mut self, enabled: bool) -> Self {
+        self.unit_spacing = enabled;
+        self
+    }
+
+    /// Formats `value`. Output exceeding `MAX_FORMATTED_LEN` is truncated.
+    pub fn format(&self, value: f64) -> FormattedValue {
+        let mut out = FormattedValue::default();
+        self.write(value, &mut out);
+        out
+    }
+
+    /// Formats `value` into `buf` as a NUL-terminated C string, truncating
+    /// it if needed. Returns the length excluding the terminator.
+    pub fn write_to(&self, value: f64, buf: &mut [u8]) -> usize {
+        let formatted = self.format(value);
+        let len = formatted.len.min(buf.len().saturating_sub(1));
+        buf[..len].copy_from_slice(&formatted.buf[..len]);
+        if let Some(terminator) = buf.get_mut(len) {
+            *terminator = 0;
+        }
+        len
+    }
+
+    fn write(&self, value: f64, out: &mut FormattedValue) {
+        if value.is_nan() {
+            out.push_str("-");
+            return;
+        }
+
+        let (scaled, prefix) = if self.si_prefix {
+            scale_si(value)
+        } else {
+            (value, "")
+        };
+
+        let mut factor = 1u64;
+        for _ in 0..self.decimals {
+            factor *= 10;
+        }
+        // Round half away from zero; core has no f64::round()
+        let magnitude = if scaled < 0.0 { -scaled } else { scaled };
+        let fixed = (magnitude * factor as f64 + 0.5) as u64;
+        let int_part = fixed / factor;
+        let frac_part = fixed % factor;
+
+        if scaled < 0.0 && fixed != 0 {
+            out.push_char('-');
+        }
+        self.write_int(int_part, out);
+        if self.decimals > 0 {
+            out.push_char(self.decimal_separator);
+            let mut divisor = factor / 10;
+            while divisor > 0 {
+                out.push_char((b'0' + (frac_part / divisor % 10) as u8) as char);
+                divisor /= 10;
+            }
+        }
+
+        if !prefix.is_empty() || !self.unit.is_empty() {
+            if self.unit_spacing {
+                out.push_char(' ');
+            }
+            out.push_str(prefix);
+            out.push_str(self.unit);
+        }
+    }
+
+    fn write_int(&self, value: u64, out: &mut FormattedValue) {
+        let mut digits = [0u8; 20];
+        let mut count = 0;
+        let mut rest = value;
+        loop {
+            digits[count] = b'0' + (rest % 10) as u8;
+            count += 1;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        for i in (0..count).rev() {
+            out.push_char(digits[i] as char);
+            if let Some(separator) = self.thousands_separator {
+                if i > 0 && i % 3 == 0 {
+                    out.push_char(separator);
+                }
+            }
+        }
+    }
+}
+
+/// Scales `value` to the SI prefix leaving an integer part of 1 to 999.
+fn scale_si(value: f64) -> (f64, &'static str) {
+    let magnitude = if value < 0.0 { -value } else { value };
+    if magnitude == 0.0 || magnitude.is_infinite() {
+        return (value, "");
+    }
+    for (factor, prefix) in SI_PREFIXES {
+        if magnitude >= factor {
+            return (value / factor, prefix);
+        }
+    }
+    let (factor, prefix) = SI_PREFIXES[SI_PREFIXES.len() - 1];
+    (value / factor, prefix)
+}
+
+/// A formatted value stored inline, NUL-terminated so it can be passed to
+/// LVGL as a C string.
+#[derive(Clone, Copy)]
+pub struct FormattedValue {
+    buf: [u8; MAX_FORMATTED_LEN + 1],
+    len: usize,
+}
+
+impl Default for FormattedValue {
+    fn default() -> Self {
+        Self {
+            buf: [0; MAX_FORMATTED_LEN + 1],
+            len: 0,
+        }
+    }
+}
+
+impl FormattedValue {
+    /// Returns the value as a string slice.
+    pub fn as_str(&self) -> &str {
+        // Only whole UTF-8 sequences are ever pushed
+        unsafe { core::str::from_utf8_unchecked(&self.buf[..self.len]) }
+    }
+
+    /// Returns the value as a C string, e.g. for `Label::set_text()`.
+    pub fn as_cstr(&self) -> &CStr {
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.buf[..=self.len]) }
+    }
+
+    fn push_char(&mut self, c: char) {
+        let mut encoded = [0u8; 4];
+        self.push_str(c.encode_utf8(&mut encoded));
+    }
+
+    fn push_str(&mut self, s: &str) {
+        if self.len + s.len() > MAX_FORMATTED_LEN {
+            return;
+        }
+        self.buf[self.len..self.len + s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+    }
+}
+
+impl Deref for FormattedValue {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for FormattedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for FormattedValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fixed_decimals_and_rounding() {
+        let f = ValueFormatter::new().decimals(1);
+        assert_eq!(f.format(21.45).as_str(), "21.5");
+        assert_eq!(f.format(-0.04).as_str(), "0.0");
+        assert_eq!(f.format(-3.0).as_str(), "-3.0");
+        assert_eq!(ValueFormatter::new().format(2.5).as_str(), "3");
+    }
+
+    #[test]
+    fn si_prefixes_and_units() {
+        let f = ValueFormatter::new().decimals(1).si_prefix(true).unit("V");
+        assert_eq!(f.format(0.0123).as_str(), "12.3 mV");
+        assert_eq!(f.format(2_400_000.0).as_str(), "2.4 MV");
+        assert_eq!(f.format(5.0).as_str(), "5.0 V");
+        let f = ValueFormatter::new().unit("%").unit_spacing(false);
+        assert_eq!(f.format(42.0).as_str(), "42%");
+    }
+
+    #[test]
+    fn separators() {
+        let f = ValueFormatter::new().thousands_separator(',');
+        assert_eq!(f.format(1234567.0).as_str(), "1,234,567");
+        assert_eq!(f.format(999.0).as_str(), "999");
+        let f = ValueFormatter::new().decimals(2).decimal_comma();
+        assert_eq!(f.format(3.14159).as_str(), "3,14");
+    }
+
+    #[test]
+    fn write_to_c_buffer() {
+        let f = ValueFormatter::new().decimals(2);
+        let mut buf = [0xFFu8; 5];
+        assert_eq!(f.write_to(12.345, &mut buf), 4);
+        assert_eq!(&buf, b"12.3\0");
+        assert_eq!(f.format(1.5).as_cstr().to_bytes(), b"1.50");
+    }
+}
@@ -0,0 +1,348 @@
+//! Reloading assets while the UI is running
+//!
+//! In the simulator, an `AssetWatcher` watches style and layout files and
+//! re-applies them to the live widget tree when they change, so a design can
+//! be tweaked without restarting the application. Files are checked on every
+//! `poll()`, e.g. once per iteration of the main loop:
+//!
+//! ```ignore
+//! let mut assets = AssetWatcher::new(Json);
+//! assets.watch_style("assets/button.json")?;
+//! assets.watch_layout("assets/settings.json", &mut screen)?;
+//! assets.watch_images("assets/img");
+//! assets.add_style("assets/button.json", &mut button, Part::Main);
+//!
+//! loop {
+//!     for (path, error) in assets.poll() {
+//!         eprintln!("{}: {}", path.display(), error);
+//!     }
+//!     lvgl::task_handler();
+//! }
+//! ```
+//!
+//! Styles are changed in place, so widgets they were added to with
+//! `AssetWatcher::add_style()` pick up the changes. They are removed from the
+//! widgets again when the watcher is dropped. Layouts are deleted and loaded
+//! again, so widgets in them have to be looked up anew through
+//! `AssetWatcher::layout()` after a reload. Images LVGL reads from files are
+//! redrawn when a file in an image directory changes.
+
+use crate::loader::{LoadedUi, UiDesc};
+use crate::lv_core::style::Style;
+use crate::{NativeObject, Widget};
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use serde::de::DeserializeOwned;
+use std::boxed::Box;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::string::{String, ToString};
+use std::time::SystemTime;
+use std::vec::Vec;
+
+/// The file format assets are written in, e.g.
+///
+/// ```ignore
+/// struct Json;
+///
+/// impl Format for Json {
+///     fn parse<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+///         serde_json::from_slice(bytes).map_err(|e| e.to_string())
+///     }
+/// }
+/// ```
+pub trait Format {
+    fn parse<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String>;
+}
+
+enum Asset<'a> {
+    Style {
+        style: Box<Style>,
+        /// The widgets and selectors the style was added to.
+        users: Vec<(NonNull<lvgl_sys::lv_obj_t>, lvgl_sys::lv_style_selector_t)>,
+    },
+    Layout {
+        parent: NonNull<lvgl_sys::lv_obj_t>,
+        ui: Option<LoadedUi<'a>>,
+    },
+    Images,
+}
+
+struct Watched<'a> {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    asset: Asset<'a>,
+}
+
+/// Watches asset files and re-applies them when they change, see the module
+/// documentation.
+///
+/// The parents of watched layouts are borrowed for `'a`. Watched styles are
+/// removed from the widgets they were added to when the watcher is dropped.
+pub struct AssetWatcher<'a, F: Format> {
+    format: F,
+    watched: Vec<Watched<'a>>,
+    _parents: PhantomData<&'a ()>,
+}
+
+impl<'a, F: Format> AssetWatcher<'a, F> {
+    pub fn new(format: F) -> Self {
+        Self {
+            format,
+            watched: Vec::new(),
+            _parents: PhantomData,
+        }
+    }
+
+    /// Loads a style from `path`, and watches it for changes.
+    pub fn watch_style(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let style = self.read(path)?;
+        self.watched.push(Watched {
+            path: path.to_path_buf(),
+            modified: modified(path),
+            asset: Asset::Style {
+                style: Box::new(style),
+                users: Vec::new(),
+            },
+        });
+        Ok(())
+    }
+
+    /// Loads a `UiDesc` from `path` into `parent`, and watches it for
+    /// changes.
+    pub fn watch_layout(
+        &mut self,
+        path: impl AsRef<Path>,
+        parent: &'a impl NativeObject,
+    ) -> Result<(), String> {
+        let path = path.as_ref();
+        let mut watched = Watched {
+            path: path.to_path_buf(),
+            modified: modified(path),
+            asset: Asset::Layout {
+                parent: parent.raw(),
+                ui: None,
+            },
+        };
+        self.reload(&mut watched)?;
+        self.watched.push(watched);
+        Ok(())
+    }
+
+    /// Redraws the display whenever a file in `dir` changes, e.g. images
+    /// LVGL reads through a file system driver.
+    pub fn watch_images(&mut self, dir: impl AsRef<Path>) {
+        let dir = dir.as_ref();
+        self.watched.push(Watched {
+            path: dir.to_path_buf(),
+            modified: newest_in(dir),
+            asset: Asset::Images,
+        });
+    }
+
+    /// Adds the style watched at `path` to a part of `widget`. Does nothing
+    /// if `path` isn't a watched style.
+    pub fn add_style<'w, W: Widget<'w>>(
+        &mut self,
+        path: impl AsRef<Path>,
+        widget: &mut W,
+        part: W::Part,
+    ) {
+        let Some(Asset::Style { style, users }) = self.find(path.as_ref()) else {
+            return;
+        };
+        let selector = part.into();
+        unsafe {
+            lvgl_sys::lv_obj_add_style(widget.raw().as_ptr(), style.raw.as_mut(), selector);
+        }
+        users.push((widget.raw(), selector));
+    }
+
+    /// The widgets of the layout watched at `path`, if it loaded.
+    pub fn layout(&self, path: impl AsRef<Path>) -> Option<&LoadedUi<'a>> {
+        match &self.watched.iter().find(|w| w.path == path.as_ref())?.asset {
+            Asset::Layout { ui, .. } => ui.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Reloads the assets changed since the last call. Assets that fail to
+    /// load keep their previous version, and are returned with the error.
+    pub fn poll(&mut self) -> Vec<(PathBuf, String)> {
+        let mut errors = Vec::new();
+        let mut watched = core::mem::take(&mut self.watched);
+        for w in &mut watched {
+            let now = match w.asset {
+                Asset::Images => newest_in(&w.path),
+                _ => modified(&w.path),
+            };
+            if now == w.modified {
+                continue;
+            }
+            w.modified = now;
+            if let Err(e) = self.reload(w) {
+                errors.push((w.path.clone(), e));
+            }
+        }
+        self.watched = watched;
+        errors
+    }
+
+    fn reload(&self, w: &mut Watched<'a>) -> Result<(), String> {
+        match &mut w.asset {
+            Asset::Style { style, .. } => {
+                let mut new: Style = self.read(&w.path)?;
+                unsafe { lvgl_sys::lv_style_reset(style.raw.as_mut()) };
+                style.set_props_from(&new);
+                unsafe {
+                    lvgl_sys::lv_style_reset(new.raw.as_mut());
+                    lvgl_sys::lv_obj_report_style_change(style.raw.as_mut());
+                }
+            }
+            Asset::Layout { parent, ui } => {
+                let desc: UiDesc = self.read(&w.path)?;
+                // Deletes the previous widgets first
+                *ui = None;
+                let mut parent = unsafe { crate::Obj::from_raw(*parent).unwrap() };
+                *ui = Some(desc.load(&mut parent).map_err(|e| e.to_string())?);
+            }
+            Asset::Images => unsafe {
+                lvgl_sys::lv_img_cache_invalidate_src(ptr::null());
+                lvgl_sys::lv_obj_invalidate(lvgl_sys::lv_scr_act());
+            },
+        }
+        Ok(())
+    }
+
+    fn read<T: DeserializeOwned>(&self, path: &Path) -> Result<T, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        self.format.parse(&bytes)
+    }
+
+    fn find(&mut self, path: &Path) -> Option<&mut Asset<'a>> {
+        self.watched
+            .iter_mut()
+            .find(|w| w.path == path)
+            .map(|w| &mut w.asset)
+    }
+}
+
+impl<F: Format> Drop for AssetWatcher<'_, F> {
+    fn drop(&mut self) {
+        for w in &mut self.watched {
+            let Asset::Style { style, users } = &mut w.asset else {
+                continue;
+            };
+            unsafe {
+                for &(obj, selector) in users.iter() {
+                    // Widgets deleted since are skipped
+                    if lvgl_sys::lv_obj_is_valid(obj.as_ptr()) {
+                        lvgl_sys::lv_obj_remove_style(obj.as_ptr(), style.raw.as_mut(), selector);
+                    }
+                }
+                lvgl_sys::lv_style_reset(style.raw.as_mut());
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// The newest modification time of the files in `dir`.
+fn newest_in(dir: &Path) -> Option<SystemTime> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| modified(&entry.ok()?.path()))
+        .max()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+    use std::time::Duration;
+
+    struct Json;
+
+    impl Format for Json {
+        fn parse<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, String> {
+            serde_json::from_slice(bytes).map_err(|e| e.to_string())
+        }
+    }
+
+    fn write(path: &Path, contents: &str, age: u64) {
+        fs::write(path, contents).unwrap();
+        let time = SystemTime::now() - Duration::from_secs(age);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn changed_assets_are_reapplied() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let dir = std::env::temp_dir().join("lvgl_hot_reload_test");
+        fs::create_dir_all(&dir).unwrap();
+        let style_path = dir.join("style.json");
+        let layout_path = dir.join("layout.json");
+        write(&style_path, r#"{ "radius": 4 }"#, 10);
+        write(
+            &layout_path,
+            r#"{ "widgets": [{ "type": "obj", "id": "a" }] }"#,
+            10,
+        );
+
+        let mut assets = AssetWatcher::new(Json);
+        assets.watch_style(&style_path).unwrap();
+        assets.watch_layout(&layout_path, &screen).unwrap();
+        let raw = assets.layout(&layout_path).unwrap().get("a").unwrap().raw();
+        let mut panel = unsafe { crate::Obj::from_raw(raw).unwrap() };
+        assets.add_style(&style_path, &mut panel, crate::Part::Main);
+        let radius = |obj: &crate::Obj| unsafe {
+            lvgl_sys::lv_obj_get_style_prop(
+                obj.raw().as_ptr(),
+                lvgl_sys::LV_PART_MAIN as _,
+                lvgl_sys::lv_style_prop_t_LV_STYLE_RADIUS as _,
+            )
+            .num
+        };
+        assert_eq!(radius(&panel), 4);
+        assert!(assets.poll().is_empty());
+
+        write(&style_path, r#"{ "radius": 9 }"#, 5);
+        write(
+            &layout_path,
+            r#"{ "widgets": [{ "type": "obj", "id": "b" }] }"#,
+            5,
+        );
+        assert!(assets.poll().is_empty());
+        assert!(assets.layout(&layout_path).unwrap().get("a").is_none());
+        let raw = assets.layout(&layout_path).unwrap().get("b").unwrap().raw();
+        let mut panel = unsafe { crate::Obj::from_raw(raw).unwrap() };
+        assets.add_style(&style_path, &mut panel, crate::Part::Main);
+        assert_eq!(radius(&panel), 9);
+        assert_eq!(screen.child_count(), 1);
+
+        write(&style_path, "{ ", 1);
+        assert_eq!(assets.poll().len(), 1);
+        assert_eq!(radius(&panel), 9);
+
+        let mut other = crate::Obj::create(&screen).unwrap();
+        let style_cnt = |obj: &crate::Obj| unsafe { (*obj.raw().as_ptr()).style_cnt };
+        let before = style_cnt(&other);
+        assets.add_style(&style_path, &mut other, crate::Part::Main);
+        assert_eq!(style_cnt(&other), before + 1);
+        drop(assets);
+        assert_eq!(style_cnt(&other), before);
+        assert_eq!(screen.child_count(), 1);
+        other.del();
+    }
+}
@@ -0,0 +1,131 @@
+//! Heap statistics of LVGL's memory pool
+//!
+//! `MemoryInfo::get()` takes a snapshot of the pool `lv_mem_alloc()` hands
+//! out memory from. To keep an eye on it while the UI runs, e.g. to log a
+//! warning when fragmentation grows, a `MemoryMonitor` takes a snapshot
+//! periodically:
+//!
+//! ```ignore
+//! let _monitor = MemoryMonitor::new(Duration::from_secs(5), |info| {
+//!     if info.used_pct > 80 {
+//!         log::warn!("LVGL heap {}% used", info.used_pct);
+//!     }
+//! })?;
+//! ```
+//!
+//! With `LV_MEM_CUSTOM`, e.g. the `rust_alloc` feature, LVGL doesn't manage a
+//! pool and all statistics are zero.
+
+use crate::{Box, LvError, LvResult};
+use core::ptr::NonNull;
+use core::time::Duration;
+
+/// A snapshot of LVGL's memory pool. Equivalent to an `lv_mem_monitor_t`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryInfo {
+    /// Size of the pool in bytes.
+    pub total_size: u32,
+    /// Bytes not allocated.
+    pub free_size: u32,
+    /// Size of the largest free block, the largest allocation that can
+    /// currently succeed.
+    pub free_biggest_size: u32,
+    /// Number of free blocks.
+    pub free_count: u32,
+    /// Number of allocated blocks.
+    pub used_count: u32,
+    /// The most bytes that were ever allocated at once.
+    pub max_used: u32,
+    /// Percentage of the pool allocated.
+    pub used_pct: u8,
+    /// Percentage of the free memory not in the largest free block.
+    pub frag_pct: u8,
+}
+
+impl MemoryInfo {
+    /// Returns the current state of the pool.
+    pub fn get() -> Self {
+        let mut raw = lvgl_sys::lv_mem_monitor_t::default();
+        unsafe { lvgl_sys::lv_mem_monitor(&mut raw) };
+        raw.into()
+    }
+
+    /// Returns the number of bytes allocated.
+    pub fn used_size(&self) -> u32 {
+        self.total_size.saturating_sub(self.free_size)
+    }
+}
+
+impl From<lvgl_sys::lv_mem_monitor_t> for MemoryInfo {
+    fn from(raw: lvgl_sys::lv_mem_monitor_t) -> Self {
+        Self {
+            total_size: raw.total_size,
+            free_size: raw.free_size,
+            free_biggest_size: raw.free_biggest_size,
+            free_count: raw.free_cnt,
+            used_count: raw.used_cnt,
+            max_used: raw.max_used,
+            used_pct: raw.used_pct,
+            frag_pct: raw.frag_pct,
+        }
+    }
+}
+
+/// Calls a closure with a `MemoryInfo` periodically, from an LVGL timer.
+/// The timer is deleted when the monitor is dropped. As a monitor may be
+/// leaked with the timer still running, the closure can't borrow anything.
+pub struct MemoryMonitor<F: FnMut(&MemoryInfo) + 'static> {
+    timer: NonNull<lvgl_sys::lv_timer_t>,
+    callback: *mut F,
+}
+
+impl<F: FnMut(&MemoryInfo) + 'static> MemoryMonitor<F> {
+    /// Calls `callback` every `period`, from `task_handler()`.
+    pub fn new(period: Duration, callback: F) -> LvResult<Self> {
+        let period = period.as_millis().try_into().unwrap_or(u32::MAX);
+        let callback = Box::into_raw(Box::new(callback));
+        let timer =
+            unsafe { lvgl_sys::lv_timer_create(Some(monitor_cb::<F>), period, callback as *mut _) };
+        match NonNull::new(timer) {
+            Some(timer) => Ok(Self { timer, callback }),
+            None => {
+                unsafe { drop(Box::from_raw(callback)) };
+                Err(LvError::LvOOMemory)
+            }
+        }
+    }
+}
+
+impl<F: FnMut(&MemoryInfo) + 'static> Drop for MemoryMonitor<F> {
+    fn drop(&mut self) {
+        unsafe {
+            lvgl_sys::lv_timer_del(self.timer.as_ptr());
+            drop(Box::from_raw(self.callback));
+        }
+    }
+}
+
+unsafe extern "C" fn monitor_cb<F: FnMut(&MemoryInfo)>(timer: *mut lvgl_sys::lv_timer_t) {
+    let callback = &mut *((*timer).user_data as *mut F);
+    callback(&MemoryInfo::get());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Obj};
+
+    #[test]
+    fn pool_usage() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+
+        let before = MemoryInfo::get();
+        assert!(before.total_size > 0);
+        let _obj = Obj::create(&screen).unwrap();
+        let after = MemoryInfo::get();
+        assert!(after.used_size() > before.used_size());
+        assert!(after.max_used >= after.used_size());
+    }
+}
@@ -1,2 +1,23 @@
 pub mod anim;
 pub mod area;
+pub mod bridge;
+#[cfg(feature = "alloc")]
+pub mod capture;
+#[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+pub mod deterministic;
+pub mod formatter;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+pub mod memory;
+pub mod perf;
+pub mod persist;
+pub mod scale;
+#[cfg(feature = "screenshot")]
+pub mod screenshot;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+#[cfg(feature = "snapshot")]
+pub mod thumbnail;
+pub mod touch_audit;
+#[cfg(feature = "alloc")]
+pub mod undo;
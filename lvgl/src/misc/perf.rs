@@ -0,0 +1,145 @@
+//! Frame rate and render time of a display
+//!
+//! LVGL's `LV_USE_PERF_MONITOR` only draws its numbers on the screen. A
+//! `PerfMonitor` measures the same from `Display::on_render_ready()`, which
+//! is called after every refresh, so they can be logged or sent elsewhere:
+//!
+//! ```ignore
+//! let perf = PerfMonitor::attach(&display);
+//! loop {
+//!     lvgl::task_handler();
+//!     if perf.frame_count() % 100 == 0 {
+//!         log::info!("{} FPS, {:?} per frame", perf.fps(), perf.avg_render_time());
+//!     }
+//! }
+//! ```
+
+use crate::{Box, Display, DisplayHook};
+use core::cell::Cell;
+use core::ptr::NonNull;
+use core::time::Duration;
+
+/// Length of the window `fps()` and `avg_render_time()` are averaged over.
+const WINDOW_MS: u32 = 1000;
+
+#[derive(Clone, Copy, Default)]
+struct Stats {
+    window_start: u32,
+    window_frames: u32,
+    window_render_ms: u32,
+    fps: u32,
+    avg_render_ms: u32,
+    last_render_ms: u32,
+    last_px: u32,
+    frames: u32,
+}
+
+impl Stats {
+    fn record(&mut self, time: u32, px: u32) {
+        self.frames = self.frames.wrapping_add(1);
+        self.last_render_ms = time;
+        self.last_px = px;
+        self.window_frames += 1;
+        self.window_render_ms = self.window_render_ms.saturating_add(time);
+        let elapsed = unsafe { lvgl_sys::lv_tick_elaps(self.window_start) };
+        if elapsed >= WINDOW_MS {
+            self.fps = self.window_frames * 1000 / elapsed;
+            self.avg_render_ms = self.window_render_ms / self.window_frames;
+            self.window_start = unsafe { lvgl_sys::lv_tick_get() };
+            self.window_frames = 0;
+            self.window_render_ms = 0;
+        }
+    }
+}
+
+/// Measures the refreshes of a display. Detached again when dropped.
+pub struct PerfMonitor {
+    /// Owned by the closure of `_hook`.
+    stats: NonNull<Cell<Stats>>,
+    _hook: DisplayHook,
+}
+
+impl PerfMonitor {
+    /// Starts measuring the refreshes of `display`.
+    pub fn attach(display: &Display) -> Self {
+        let stats = Box::new(Cell::new(Stats {
+            window_start: unsafe { lvgl_sys::lv_tick_get() },
+            ..Stats::default()
+        }));
+        let ptr = NonNull::from(&*stats);
+        let hook = display.on_render_ready(move |time, px| {
+            let mut current = stats.get();
+            current.record(time.as_millis() as u32, px);
+            stats.set(current);
+        });
+        Self {
+            stats: ptr,
+            _hook: hook,
+        }
+    }
+
+    fn stats(&self) -> Stats {
+        unsafe { self.stats.as_ref() }.get()
+    }
+
+    /// Returns the frames per second, over the last completed second.
+    pub fn fps(&self) -> u32 {
+        self.stats().fps
+    }
+
+    /// Returns the average time a refresh took, over the last completed
+    /// second.
+    pub fn avg_render_time(&self) -> Duration {
+        Duration::from_millis(self.stats().avg_render_ms.into())
+    }
+
+    /// Returns the time the last refresh took.
+    pub fn last_render_time(&self) -> Duration {
+        Duration::from_millis(self.stats().last_render_ms.into())
+    }
+
+    /// Returns the number of pixels the last refresh redrew.
+    pub fn last_refreshed_px(&self) -> u32 {
+        self.stats().last_px
+    }
+
+    /// Returns the number of refreshes since the monitor was attached.
+    pub fn frame_count(&self) -> u32 {
+        self.stats().frames
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(any(feature = "rust_timer", feature = "custom_timer")))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fps_over_a_second() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let perf = PerfMonitor::attach(&display);
+        let other = PerfMonitor::attach(&display);
+
+        let driver = unsafe { (*display.disp.as_ptr()).driver };
+        let monitor_cb = unsafe { (*driver).monitor_cb }.unwrap();
+        for _ in 0..29 {
+            unsafe { monitor_cb(driver, 4, 100) };
+        }
+        assert_eq!(perf.fps(), 0);
+        unsafe {
+            lvgl_sys::lv_tick_inc(1000);
+            monitor_cb(driver, 10, 50);
+        }
+        assert_eq!(perf.fps(), 30);
+        assert_eq!(perf.avg_render_time(), Duration::from_millis(4));
+        assert_eq!(perf.last_render_time(), Duration::from_millis(10));
+        assert_eq!(perf.last_refreshed_px(), 50);
+        assert_eq!(perf.frame_count(), 30);
+        assert_eq!(other.frame_count(), 30);
+
+        drop(perf);
+        drop(other);
+        assert!(unsafe { (*driver).monitor_cb }.is_none());
+    }
+}
@@ -0,0 +1,472 @@
+//! Saving and restoring widget state across reboots
+//!
+//! Devices are expected to reopen exactly where the user left them: on the
+//! same tab, with the same option selected and the list scrolled to the same
+//! row. Widgets implementing `Persistent` capture that state as a
+//! `WidgetState`, which encodes to a small fixed-size token. A
+//! `StateRegistry` collects the widgets of an application under stable keys
+//! and hands their tokens to, or takes them from, whatever storage the
+//! application uses (flash, EEPROM, a file).
+//!
+//! ```ignore
+//! let mut registry = StateRegistry::<8>::new();
+//! registry.register(1, &tabview)?;
+//! registry.register(2, &brightness_slider)?;
+//!
+//! // Before powering down:
+//! registry.save(|key, token| storage.write(key, token));
+//!
+//! // After building the UI on the next boot:
+//! registry.restore(|key, token| storage.read(key, token).is_ok());
+//! ```
+
+use crate::{Box, LvError, LvResult, NativeObject, Obj, Widget};
+use core::cell::Cell;
+use core::ptr::NonNull;
+
+/// Length in bytes of an encoded `WidgetState`.
+pub const STATE_TOKEN_LEN: usize = 5;
+
+/// The user-visible state of a widget.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WidgetState {
+    /// The selected option or tab.
+    Selected(u16),
+    /// The value of a slider, bar or arc.
+    Value(i32),
+    /// Whether a checkbox or switch is checked.
+    Checked(bool),
+    /// The scroll position of a container.
+    Scroll { x: i16, y: i16 },
+}
+
+impl WidgetState {
+    /// Encodes the state as a token for storage.
+    pub fn to_token(&self) -> [u8; STATE_TOKEN_LEN] {
+        let (tag, payload) = match *self {
+            Self::Selected(index) => (1, (index as u32).to_le_bytes()),
+            Self::Value(value) => (2, value.to_le_bytes()),
+            Self::Checked(checked) => (3, (checked as u32).to_le_bytes()),
+            Self::Scroll { x, y } => {
+                let [x0, x1] = x.to_le_bytes();
+                let [y0, y1] = y.to_le_bytes();
+                (4, [x0, x1, y0, y1])
+            }
+        };
+        let [p0, p1, p2, p3] = payload;
+        [tag, p0, p1, p2, p3]
+    }
+
+    /// Decodes a token produced by `to_token()`, returning `None` if it is
+    /// malformed.
+    pub fn from_token(token: &[u8]) -> Option<Self> {
+        let &[tag, p0, p1, p2, p3] = token else {
+            return None;
+        };
+        let payload = [p0, p1, p2, p3];
+        Some(match tag {
+            1 => Self::Selected(u16::try_from(u32::from_le_bytes(payload)).ok()?),
+            2 => Self::Value(i32::from_le_bytes(payload)),
+            3 => Self::Checked(u32::from_le_bytes(payload) != 0),
+            4 => Self::Scroll {
+                x: i16::from_le_bytes([p0, p1]),
+                y: i16::from_le_bytes([p2, p3]),
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// A widget whose state can be saved and restored.
+pub trait Persistent<'a>: Widget<'a> {
+    /// Returns the current state of the widget.
+    fn capture_state(&self) -> WidgetState;
+
+    /// Restores a state captured earlier, without animation. Fails with
+    /// `LvError::InvalidArgument` if the state is of a kind the widget
+    /// doesn't have, e.g. after the widget behind a key was changed.
+    fn restore_state(&mut self, state: WidgetState) -> LvResult<()>;
+}
+
+/// Scroll position of any object, e.g. a list or a scrollable page.
+impl<'a> Persistent<'a> for Obj<'a> {
+    fn capture_state(&self) -> WidgetState {
+        let obj = self.raw().as_ptr();
+        unsafe {
+            WidgetState::Scroll {
+                x: lvgl_sys::lv_obj_get_scroll_x(obj) as i16,
+                y: lvgl_sys::lv_obj_get_scroll_y(obj) as i16,
+            }
+        }
+    }
+
+    fn restore_state(&mut self, state: WidgetState) -> LvResult<()> {
+        let WidgetState::Scroll { x, y } = state else {
+            return Err(LvError::InvalidArgument);
+        };
+        unsafe {
+            lvgl_sys::lv_obj_scroll_to(
+                self.raw().as_ptr(),
+                x as lvgl_sys::lv_coord_t,
+                y as lvgl_sys::lv_coord_t,
+                lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF,
+            );
+        }
+        Ok(())
+    }
+}
+
+macro_rules! persistent_value {
+    ($cfg:literal, $widget:ident, $get:ident, $set:expr) => {
+        #[cfg(lv_widget = $cfg)]
+        impl<'a> Persistent<'a> for crate::widgets::$widget<'a> {
+            fn capture_state(&self) -> WidgetState {
+                WidgetState::Value(unsafe { lvgl_sys::$get(self.raw().as_ptr()) }.into())
+            }
+
+            fn restore_state(&mut self, state: WidgetState) -> LvResult<()> {
+                let WidgetState::Value(value) = state else {
+                    return Err(LvError::InvalidArgument);
+                };
+                let set: fn(*mut lvgl_sys::lv_obj_t, i32) = $set;
+                set(self.raw().as_ptr(), value);
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! persistent_checked {
+    ($cfg:literal, $widget:ident) => {
+        #[cfg(lv_widget = $cfg)]
+        impl<'a> Persistent<'a> for crate::widgets::$widget<'a> {
+            fn capture_state(&self) -> WidgetState {
+                WidgetState::Checked(unsafe {
+                    lvgl_sys::lv_obj_has_state(
+                        self.raw().as_ptr(),
+                        lvgl_sys::LV_STATE_CHECKED as lvgl_sys::lv_state_t,
+                    )
+                })
+            }
+
+            fn restore_state(&mut self, state: WidgetState) -> LvResult<()> {
+                let WidgetState::Checked(checked) = state else {
+                    return Err(LvError::InvalidArgument);
+                };
+                let obj = self.raw().as_ptr();
+                let checked_state = lvgl_sys::LV_STATE_CHECKED as lvgl_sys::lv_state_t;
+                unsafe {
+                    if checked {
+                        lvgl_sys::lv_obj_add_state(obj, checked_state);
+                    } else {
+                        lvgl_sys::lv_obj_clear_state(obj, checked_state);
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+macro_rules! persistent_selected {
+    ($cfg:literal, $widget:ident, $get:ident, $set:expr) => {
+        #[cfg(lv_widget = $cfg)]
+        impl<'a> Persistent<'a> for crate::widgets::$widget<'a> {
+            fn capture_state(&self) -> WidgetState {
+                WidgetState::Selected(unsafe { lvgl_sys::$get(self.raw().as_ptr()) })
+            }
+
+            fn restore_state(&mut self, state: WidgetState) -> LvResult<()> {
+                let WidgetState::Selected(index) = state else {
+                    return Err(LvError::InvalidArgument);
+                };
+                let set: fn(*mut lvgl_sys::lv_obj_t, u16) = $set;
+                set(self.raw().as_ptr(), index);
+                Ok(())
+            }
+        }
+    };
+}
+
+persistent_value!("bar", Bar, lv_bar_get_value, |obj, v| unsafe {
+    lvgl_sys::lv_bar_set_value(obj, v, lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF)
+});
+persistent_value!("slider", Slider, lv_bar_get_value, |obj, v| unsafe {
+    lvgl_sys::lv_bar_set_value(obj, v, lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF)
+});
+persistent_value!("arc", Arc, lv_arc_get_value, |obj, v| unsafe {
+    lvgl_sys::lv_arc_set_value(obj, v as i16)
+});
+persistent_checked!("checkbox", Checkbox);
+persistent_checked!("switch", Switch);
+persistent_selected!(
+    "dropdown",
+    Dropdown,
+    lv_dropdown_get_selected,
+    |obj, i| unsafe { lvgl_sys::lv_dropdown_set_selected(obj, i) }
+);
+persistent_selected!("roller", Roller, lv_roller_get_selected, |obj, i| unsafe {
+    lvgl_sys::lv_roller_set_selected(obj, i, lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF)
+});
+persistent_selected!(
+    "tabview",
+    Tabview,
+    lv_tabview_get_tab_act,
+    |obj, i| unsafe {
+        lvgl_sys::lv_tabview_set_act(obj, i as u32, lvgl_sys::lv_anim_enable_t_LV_ANIM_OFF)
+    }
+);
+
+type Capture = unsafe fn(NonNull<lvgl_sys::lv_obj_t>) -> WidgetState;
+type Restore = unsafe fn(NonNull<lvgl_sys::lv_obj_t>, WidgetState) -> LvResult<()>;
+
+/// A registered widget. `alive` is owned by the registry and cleared by
+/// `deleted_cb` when LVGL deletes the object.
+#[derive(Clone, Copy)]
+struct Entry {
+    key: u32,
+    obj: NonNull<lvgl_sys::lv_obj_t>,
+    alive: NonNull<Cell<bool>>,
+    capture: Capture,
+    restore: Restore,
+}
+
+impl Entry {
+    fn is_alive(&self) -> bool {
+        unsafe { self.alive.as_ref().get() }
+    }
+
+    /// Stops tracking the object and frees the `alive` flag.
+    fn release(self) {
+        unsafe {
+            if self.is_alive() {
+                lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                    self.obj.as_ptr(),
+                    Some(deleted_cb),
+                    self.alive.as_ptr() as *mut _,
+                );
+            }
+            drop(Box::from_raw(self.alive.as_ptr()));
+        }
+    }
+}
+
+unsafe extern "C" fn deleted_cb(event: *mut lvgl_sys::lv_event_t) {
+    let alive = &*((*event).user_data as *const Cell<bool>);
+    alive.set(false);
+}
+
+unsafe fn capture<'a, W: Persistent<'a>>(obj: NonNull<lvgl_sys::lv_obj_t>) -> WidgetState {
+    W::from_raw(obj).unwrap().capture_state()
+}
+
+unsafe fn restore<'a, W: Persistent<'a>>(
+    obj: NonNull<lvgl_sys::lv_obj_t>,
+    state: WidgetState,
+) -> LvResult<()> {
+    W::from_raw(obj).unwrap().restore_state(state)
+}
+
+/// Widgets whose state is saved and restored together, each under a key
+/// that stays the same across firmware versions. Holds up to `N` widgets.
+pub struct StateRegistry<const N: usize> {
+    entries: [Option<Entry>; N],
+}
+
+impl<const N: usize> Default for StateRegistry<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> StateRegistry<N> {
+    /// Creates an empty registry.
+    pub const fn new() -> Self {
+        Self { entries: [None; N] }
+    }
+
+    /// Registers `widget` under `key`, replacing any widget registered under
+    /// the same key. Fails with `LvError::LvOOMemory` if the registry is
+    /// full.
+    pub fn register<'a, W: Persistent<'a>>(&mut self, key: u32, widget: &W) -> LvResult<()> {
+        self.prune();
+        let slot = match self
+            .entries
+            .iter()
+            .position(|e| e.is_some_and(|e| e.key == key))
+        {
+            Some(i) => i,
+            None => self
+                .entries
+                .iter()
+                .position(Option::is_none)
+                .ok_or(LvError::LvOOMemory)?,
+        };
+        let obj = widget.raw();
+        let alive = unsafe { NonNull::new_unchecked(Box::into_raw(Box::new(Cell::new(true)))) };
+        unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                obj.as_ptr(),
+                Some(deleted_cb),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                alive.as_ptr() as *mut _,
+            );
+        }
+        let entry = Entry {
+            key,
+            obj,
+            alive,
+            capture: capture::<W>,
+            restore: restore::<W>,
+        };
+        if let Some(old) = self.entries[slot].replace(entry) {
+            old.release();
+        }
+        Ok(())
+    }
+
+    /// Removes the widget registered under `key`.
+    pub fn unregister(&mut self, key: u32) {
+        for entry in self.entries.iter_mut() {
+            if entry.is_some_and(|e| e.key == key) {
+                entry.take().unwrap().release();
+            }
+        }
+    }
+
+    /// Returns the current state of the widget registered under `key`.
+    pub fn capture(&mut self, key: u32) -> Option<WidgetState> {
+        self.prune();
+        let entry = self.entries.iter().flatten().find(|e| e.key == key)?;
+        Some(unsafe { (entry.capture)(entry.obj) })
+    }
+
+    /// Calls `store` with the key and state token of every registered
+    /// widget that still exists.
+    pub fn save(&mut self, mut store: impl FnMut(u32, &[u8; STATE_TOKEN_LEN])) {
+        self.prune();
+        for entry in self.entries.iter().flatten() {
+            store(entry.key, &unsafe { (entry.capture)(entry.obj) }.to_token());
+        }
+    }
+
+    /// Restores every registered widget for which `load` fills in a token
+    /// and returns `true`. Returns how many widgets were restored; tokens
+    /// that are malformed or don't fit their widget are skipped.
+    pub fn restore(
+        &mut self,
+        mut load: impl FnMut(u32, &mut [u8; STATE_TOKEN_LEN]) -> bool,
+    ) -> usize {
+        self.prune();
+        let mut restored = 0;
+        for entry in self.entries.iter().flatten() {
+            let mut token = [0; STATE_TOKEN_LEN];
+            if !load(entry.key, &mut token) {
+                continue;
+            }
+            if let Some(state) = WidgetState::from_token(&token) {
+                if unsafe { (entry.restore)(entry.obj, state) }.is_ok() {
+                    restored += 1;
+                }
+            }
+        }
+        restored
+    }
+
+    /// Forgets widgets that have been deleted since they were registered.
+    fn prune(&mut self) {
+        for entry in self.entries.iter_mut() {
+            if entry.is_some_and(|e| !e.is_alive()) {
+                entry.take().unwrap().release();
+            }
+        }
+    }
+}
+
+impl<const N: usize> Drop for StateRegistry<N> {
+    fn drop(&mut self) {
+        for entry in self.entries.iter_mut() {
+            if let Some(entry) = entry.take() {
+                entry.release();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::{Checkbox, Slider};
+    use crate::{AnimationState, Display};
+
+    #[test]
+    fn token_roundtrip() {
+        for state in [
+            WidgetState::Selected(3),
+            WidgetState::Value(-1200),
+            WidgetState::Checked(true),
+            WidgetState::Scroll { x: -4, y: 380 },
+        ] {
+            assert_eq!(WidgetState::from_token(&state.to_token()), Some(state));
+        }
+        assert_eq!(WidgetState::from_token(&[9, 0, 0, 0, 0]), None);
+        assert_eq!(WidgetState::from_token(&[1, 0]), None);
+    }
+
+    #[test]
+    fn save_and_restore() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let slider = Slider::create(&mut screen).unwrap();
+        let mut checkbox = Checkbox::create(&mut screen).unwrap();
+        slider.set_value(42, AnimationState::OFF);
+        checkbox.restore_state(WidgetState::Checked(true)).unwrap();
+
+        let mut registry = StateRegistry::<4>::new();
+        registry.register(1, &slider).unwrap();
+        registry.register(2, &checkbox).unwrap();
+        let mut storage = [(0, [0; STATE_TOKEN_LEN]); 2];
+        let mut stored = 0;
+        registry.save(|key, token| {
+            storage[stored] = (key, *token);
+            stored += 1;
+        });
+        assert_eq!(stored, 2);
+
+        slider.set_value(0, AnimationState::OFF);
+        checkbox.restore_state(WidgetState::Checked(false)).unwrap();
+        let restored =
+            registry.restore(|key, token| match storage.iter().find(|(k, _)| *k == key) {
+                Some((_, t)) => {
+                    *token = *t;
+                    true
+                }
+                None => false,
+            });
+        assert_eq!(restored, 2);
+        assert_eq!(slider.get_value(), 42);
+        assert_eq!(registry.capture(2), Some(WidgetState::Checked(true)));
+    }
+
+    #[test]
+    fn deleted_widgets_are_forgotten() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut registry = StateRegistry::<2>::new();
+        let slider = Slider::create(&mut screen).unwrap();
+        registry.register(1, &slider).unwrap();
+        slider.del();
+        assert_eq!(registry.capture(1), None);
+
+        // A new object may reuse the address of the deleted one
+        let checkbox = Checkbox::create(&mut screen).unwrap();
+        registry.register(2, &checkbox).unwrap();
+        let mut keys = 0;
+        registry.save(|key, _| keys += key);
+        assert_eq!(keys, 2);
+        drop(registry);
+        checkbox.del();
+    }
+}
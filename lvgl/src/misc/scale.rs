@@ -0,0 +1,62 @@
+//! Conversion between floating-point values and LVGL's integer ranges
+//!
+//! LVGL widgets store their values as integers within a `min..=max` range.
+//! The `*_f32` setters and getters of e.g. `Slider`, `Arc`, `Bar` and
+//! `Chart` take normalized values in `0.0..=1.0` instead and convert them
+//! with the functions in this module, so rounding and clamping behave the
+//! same everywhere.
+//!
+//! ```
+//! use lvgl::misc::scale;
+//!
+//! assert_eq!(scale::denormalize(0.5, 0, 100), 50);
+//! assert_eq!(scale::denormalize(0.333, -10, 10), -3);
+//! assert_eq!(scale::normalize(75, 50, 150), 0.25);
+//! ```
+
+/// Maps `value` from `0.0..=1.0` onto `min..=max`, rounding to the nearest
+/// integer. Values outside of `0.0..=1.0` are clamped, and NaN maps to
+/// `min`.
+pub fn denormalize(value: f32, min: i32, max: i32) -> i32 {
+    let value = if value >= 0.0 { value.min(1.0) } else { 0.0 };
+    let span = max as f64 - min as f64;
+    // The offset is never negative, so truncating after adding 0.5 rounds
+    // halfway cases away from `min` regardless of the sign of the result
+    let offset = (value as f64 * span.abs() + 0.5) as i64;
+    let offset = if span < 0.0 { -offset } else { offset };
+    (min as i64 + offset) as i32
+}
+
+/// Maps `value` from `min..=max` onto `0.0..=1.0`. Values outside of the
+/// range are clamped; an empty range maps everything to `0.0`.
+pub fn normalize(value: i32, min: i32, max: i32) -> f32 {
+    if min == max {
+        return 0.0;
+    }
+    let t = (value as f64 - min as f64) / (max as f64 - min as f64);
+    t.clamp(0.0, 1.0) as f32
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for v in -10..=10 {
+            assert_eq!(denormalize(normalize(v, -10, 10), -10, 10), v);
+        }
+        assert_eq!(denormalize(0.5, 0, 3), 2);
+        assert_eq!(denormalize(0.5, 3, 0), 1);
+    }
+
+    #[test]
+    fn out_of_range_is_clamped() {
+        assert_eq!(denormalize(-0.5, 0, 100), 0);
+        assert_eq!(denormalize(1.5, 0, 100), 100);
+        assert_eq!(denormalize(f32::NAN, 20, 100), 20);
+        assert_eq!(denormalize(1.0, i32::MIN, i32::MAX), i32::MAX);
+        assert_eq!(normalize(200, 0, 100), 1.0);
+        assert_eq!(normalize(5, 5, 5), 0.0);
+    }
+}
@@ -0,0 +1,266 @@
+//! Saving what a display shows to image files
+//!
+//! `Display::screenshot()` renders the active screen of a display with
+//! `lv_snapshot` into a `Screenshot` in 8-bit RGB, whatever LVGL's color
+//! depth, which can be saved as PNG or BMP, e.g. for documentation:
+//!
+//! ```ignore
+//! display.screenshot()?.save_png("docs/settings.png")?;
+//! ```
+//!
+//! Only the screen is captured; the top and system layers, e.g. message
+//! boxes opened on the top layer, are not.
+//!
+//! Screenshots can be loaded from PNG and compared against each other, which
+//! is what `test::Harness` checks its frames against reference images with.
+//!
+//! Requires the `screenshot` feature, which needs `std`.
+
+use crate::draw::ColorFormat;
+use crate::misc::snapshot::Snapshot;
+use crate::{Color, Display, LvError, LvResult, Obj, Widget};
+use core::mem;
+use core::ptr::NonNull;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::vec::Vec;
+
+/// A rendered image in 8-bit RGB, independent of LVGL's color depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Screenshot {
+    width: u32,
+    height: u32,
+    rgb: Vec<u8>,
+}
+
+impl Screenshot {
+    /// Wraps pixels given row by row, three bytes each. Returns `None` unless
+    /// there are `width * height` of them.
+    pub fn from_rgb(width: u32, height: u32, rgb: Vec<u8>) -> Option<Self> {
+        (rgb.len() == width as usize * height as usize * 3).then_some(Self { width, height, rgb })
+    }
+
+    /// Converts rendered colors, row by row.
+    pub(crate) fn from_colors(width: u32, height: u32, colors: &[Color]) -> Self {
+        Self {
+            width,
+            height,
+            rgb: colors
+                .iter()
+                .flat_map(|c| {
+                    let (r, g, b) = c.to_rgb();
+                    [r, g, b]
+                })
+                .collect(),
+        }
+    }
+
+    /// Converts a rendering in `ColorFormat::TrueColor`.
+    fn from_true_color(snapshot: &Snapshot) -> Self {
+        let rgb = snapshot
+            .data()
+            .chunks_exact(mem::size_of::<lvgl_sys::lv_color_t>())
+            .flat_map(|pixel| {
+                let raw =
+                    unsafe { (pixel.as_ptr() as *const lvgl_sys::lv_color_t).read_unaligned() };
+                let (r, g, b) = Color::from_raw(raw).to_rgb();
+                [r, g, b]
+            })
+            .collect();
+        Self {
+            width: snapshot.width(),
+            height: snapshot.height(),
+            rgb,
+        }
+    }
+
+    /// Returns the width in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the red, green and blue values of the pixel at `(x, y)`.
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        let i = (y * self.width + x) as usize * 3;
+        [self.rgb[i], self.rgb[i + 1], self.rgb[i + 2]]
+    }
+
+    /// Returns the pixels row by row, three bytes each.
+    pub fn rgb(&self) -> &[u8] {
+        &self.rgb
+    }
+
+    /// Unwraps the pixels, row by row, three bytes each.
+    pub fn into_rgb(self) -> Vec<u8> {
+        self.rgb
+    }
+
+    /// Writes the image to `path` as an 8-bit RGB PNG.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.rgb))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+
+    /// Reads a PNG, converting it to 8-bit RGB. Transparency is dropped.
+    pub fn load_png(path: impl AsRef<Path>) -> io::Result<Self> {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+        let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+        decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+        let mut reader = decoder.read_info().map_err(invalid)?;
+        let mut buf = std::vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(invalid)?;
+        let data = &buf[..info.buffer_size()];
+        let rgb = match info.color_type {
+            png::ColorType::Rgb => data.to_vec(),
+            png::ColorType::Rgba => data.chunks(4).flat_map(|p| [p[0], p[1], p[2]]).collect(),
+            png::ColorType::Grayscale => data.iter().flat_map(|&v| [v; 3]).collect(),
+            png::ColorType::GrayscaleAlpha => data.chunks(2).flat_map(|p| [p[0]; 3]).collect(),
+            png::ColorType::Indexed => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "unsupported PNG format",
+                ))
+            }
+        };
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            rgb,
+        })
+    }
+
+    /// Writes the image to `path` as an uncompressed 24-bit BMP.
+    pub fn save_bmp(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_bmp(&mut file)?;
+        file.flush()
+    }
+
+    fn write_bmp(&self, out: &mut impl Write) -> io::Result<()> {
+        const HEADERS: u32 = 14 + 40;
+        // Rows are padded to multiples of 4 bytes
+        let row = (self.width * 3 + 3) & !3;
+        let size = HEADERS + row * self.height;
+        out.write_all(b"BM")?;
+        for field in [size, 0, HEADERS, 40, self.width, self.height] {
+            out.write_all(&field.to_le_bytes())?;
+        }
+        // One plane, 24 bits per pixel
+        out.write_all(&1u16.to_le_bytes())?;
+        out.write_all(&24u16.to_le_bytes())?;
+        // Uncompressed, no resolution or palette
+        for field in [0u32, row * self.height, 0, 0, 0, 0] {
+            out.write_all(&field.to_le_bytes())?;
+        }
+        let padding = [0; 3];
+        // Bottom row first, in BGR order
+        for y in (0..self.height).rev() {
+            for x in 0..self.width {
+                let [r, g, b] = self.pixel(x, y);
+                out.write_all(&[b, g, r])?;
+            }
+            out.write_all(&padding[..(row - self.width * 3) as usize])?;
+        }
+        Ok(())
+    }
+
+    /// Compares against `reference`, allowing each color channel to differ
+    /// by up to `tolerance`. Returns `None` if the sizes differ.
+    pub fn diff(&self, reference: &Screenshot, tolerance: u8) -> Option<Diff> {
+        if (self.width, self.height) != (reference.width, reference.height) {
+            return None;
+        }
+        let mut diff = Diff::default();
+        let pixels = self.rgb.chunks(3).zip(reference.rgb.chunks(3));
+        for (i, (a, b)) in pixels.enumerate() {
+            if a.iter().zip(b).any(|(a, b)| a.abs_diff(*b) > tolerance) {
+                let i = i as u32;
+                diff.first.get_or_insert((i % self.width, i / self.width));
+                diff.pixels += 1;
+            }
+        }
+        Some(diff)
+    }
+}
+
+/// The outcome of comparing a screenshot against a reference image.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// The number of pixels differing by more than the tolerance.
+    pub pixels: usize,
+    /// The first differing pixel, row by row.
+    pub first: Option<(u32, u32)>,
+}
+
+impl Diff {
+    /// Returns `true` if no pixel differs.
+    pub fn is_match(&self) -> bool {
+        self.pixels == 0
+    }
+}
+
+impl Display {
+    /// Renders the active screen of the display, see the module
+    /// documentation. Fails with `LvError::InvalidArgument` if it has no
+    /// active screen.
+    pub fn screenshot(&self) -> LvResult<Screenshot> {
+        let screen = unsafe { lvgl_sys::lv_disp_get_scr_act(self.disp.as_ptr()) };
+        let screen =
+            unsafe { Obj::from_raw(NonNull::new(screen).ok_or(LvError::InvalidArgument)?) }
+                .ok_or(LvError::InvalidArgument)?;
+        let snapshot = Snapshot::take(&screen, ColorFormat::TrueColor)?;
+        Ok(Screenshot::from_true_color(&snapshot))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn screenshot_saves_bmp_and_png() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut style = crate::Style::default();
+        style.set_bg_color(Color::from_rgb((255, 0, 0)));
+        style.set_radius(0);
+        let mut obj = Obj::create(&screen).unwrap();
+        obj.set_pos(0, 0);
+        obj.set_size(10, 10);
+        obj.add_style(crate::Part::Main, &mut style);
+
+        let image = display.screenshot().unwrap();
+        assert_eq!(image.width(), display.hor_res() as u32);
+        assert_eq!(image.height(), display.ver_res() as u32);
+        assert_eq!(image.pixel(5, 5), [255, 0, 0]);
+
+        let mut bmp = Vec::new();
+        image.write_bmp(&mut bmp).unwrap();
+        assert_eq!(&bmp[..2], b"BM");
+        assert_eq!(
+            bmp.len() as u32,
+            u32::from_le_bytes(bmp[2..6].try_into().unwrap())
+        );
+
+        let path = std::env::temp_dir().join("lvgl_screenshot_test.png");
+        image.save_png(&path).unwrap();
+        let decoder = png::Decoder::new(File::open(&path).unwrap());
+        let info = decoder.read_info().unwrap().info().clone();
+        assert_eq!((info.width, info.height), (image.width(), image.height()));
+        assert_eq!(Screenshot::load_png(&path).unwrap(), image);
+        obj.del();
+    }
+}
@@ -0,0 +1,142 @@
+//! Rendering objects into buffers owned by Rust
+//!
+//! Wraps `lv_snapshot`, which renders an object and its children into an
+//! image, independently of any display. The object doesn't need to be on
+//! the active screen. Uses include thumbnails (see `misc::thumbnail`), screen
+//! transitions drawn from a still image, and comparing the rendering of a UI
+//! against reference images in tests.
+//!
+//! ```ignore
+//! let mut buf = [0u8; 100 * 40 * 4];
+//! let image = snapshot::take_into(&button, ColorFormat::TrueColor, &mut buf)?;
+//! preview.set_src(&image);
+//! ```
+//!
+//! Requires the `snapshot` feature.
+
+use crate::draw::{ColorFormat, ImageDsc};
+use crate::{LvError, LvResult, NativeObject};
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Returns the size in bytes of a snapshot of `obj` in `format`, or `None`
+/// if the object has no size yet.
+pub fn buffer_size(obj: &impl NativeObject, format: ColorFormat) -> Option<usize> {
+    let obj = obj.raw().as_ptr();
+    let size = unsafe {
+        // Objects not on the active screen aren't laid out yet
+        lvgl_sys::lv_obj_update_layout(obj);
+        lvgl_sys::lv_snapshot_buf_size_needed(obj, format.into())
+    };
+    (size > 0).then_some(size as usize)
+}
+
+/// Renders `obj` into `buf`, returning an image of the rendering borrowing
+/// the buffer.
+///
+/// Fails with `LvError::InvalidArgument` if `buf` is smaller than
+/// `buffer_size()`, or if the object has no size.
+pub fn take_into<'b>(
+    obj: &impl NativeObject,
+    format: ColorFormat,
+    buf: &'b mut [u8],
+) -> LvResult<ImageDsc<'b>> {
+    let size = buffer_size(obj, format).ok_or(LvError::InvalidArgument)?;
+    if buf.len() < size {
+        return Err(LvError::InvalidArgument);
+    }
+    let mut dsc = lvgl_sys::lv_img_dsc_t::default();
+    let res = unsafe {
+        lvgl_sys::lv_snapshot_take_to_buf(
+            obj.raw().as_ptr(),
+            format.into(),
+            &mut dsc,
+            buf.as_mut_ptr() as *mut _,
+            size as u32,
+        )
+    };
+    if res as u32 != lvgl_sys::LV_RES_OK {
+        return Err(LvError::InvalidArgument);
+    }
+    let buf: &'b [u8] = buf;
+    ImageDsc::from_raw(dsc.header.w(), dsc.header.h(), format, &buf[..size])
+}
+
+/// A rendering of an object, in a buffer of its own.
+#[cfg(feature = "alloc")]
+pub struct Snapshot {
+    width: u32,
+    height: u32,
+    format: ColorFormat,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl Snapshot {
+    /// Renders `obj` in `format`.
+    pub fn take(obj: &impl NativeObject, format: ColorFormat) -> LvResult<Self> {
+        let size = buffer_size(obj, format).ok_or(LvError::InvalidArgument)?;
+        let mut data = vec![0; size];
+        let image = take_into(obj, format, &mut data)?;
+        let (width, height) = (image.width(), image.height());
+        drop(image);
+        Ok(Self {
+            width,
+            height,
+            format,
+            data,
+        })
+    }
+
+    /// Returns the width of the rendering in pixels.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the height of the rendering in pixels.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the format of the pixel data.
+    pub fn format(&self) -> ColorFormat {
+        self.format
+    }
+
+    /// Returns the pixel data, row by row.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns an image of the rendering, e.g. to show it with an `Img`.
+    pub fn image(&self) -> LvResult<ImageDsc<'_>> {
+        ImageDsc::from_raw(self.width, self.height, self.format, &self.data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Obj, Widget};
+
+    #[test]
+    fn renders_into_buffer() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let screen = display.get_scr_act().unwrap();
+        let mut obj = Obj::create(&screen).unwrap();
+        obj.set_size(20, 10);
+
+        let size = buffer_size(&obj, ColorFormat::TrueColor).unwrap();
+        let mut small = [0u8; 4];
+        assert_eq!(
+            take_into(&obj, ColorFormat::TrueColor, &mut small).err(),
+            Some(LvError::InvalidArgument)
+        );
+        let mut buf = std::vec![0u8; size];
+        let image = take_into(&obj, ColorFormat::TrueColor, &mut buf).unwrap();
+        assert_eq!((image.width(), image.height()), (20, 10));
+    }
+}
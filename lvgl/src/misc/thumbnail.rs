@@ -0,0 +1,390 @@
+//! Scaled-down previews of screens and other objects
+//!
+//! A `Thumbnail` renders an object with `lv_snapshot` and shrinks the result
+//! into an image small enough to show several of them at once, e.g. in a
+//! "recent screens" picker or as tab previews. The object doesn't need to be
+//! on the active screen. Rendering takes a full-size snapshot first, so this
+//! is meant for devices with memory to spare for one screen-sized buffer.
+//!
+//! Thumbnails are rendered once and kept until marked stale with
+//! `invalidate()`; `ThumbnailCache` keeps the thumbnails of the most recently
+//! used screens. `Thumbnail::show()` displays one in an `Img`, which keeps
+//! its pixels alive until the `Img` is deleted, even if the thumbnail is
+//! dropped or evicted from the cache before.
+//!
+//! ```ignore
+//! let mut cache = ThumbnailCache::<4>::new(80, 60);
+//! cache.render(&settings_screen)?;
+//! cache.render(&status_screen)?;
+//!
+//! let mut preview = Img::create(&mut picker)?;
+//! cache.get(&settings_screen).unwrap().show(&mut preview);
+//! ```
+
+use crate::draw::{ColorFormat, ImageDsc};
+use crate::{Box, LvError, LvResult, NativeObject};
+use core::cell::Cell;
+use core::mem;
+use core::ptr::NonNull;
+use core::slice;
+
+/// A scaled-down rendering of an object.
+pub struct Thumbnail {
+    source: NonNull<lvgl_sys::lv_obj_t>,
+    pixels: NonNull<Pixels>,
+    stale: Cell<bool>,
+}
+
+/// The rendering of a thumbnail, shared with the `Img`s showing it and
+/// freed when the last of them is gone.
+struct Pixels {
+    refs: Cell<usize>,
+    buffer: NonNull<lvgl_sys::lv_color_t>,
+    // Borrows `buffer`, which lives as long as this
+    image: ImageDsc<'static>,
+}
+
+impl Pixels {
+    unsafe fn release(pixels: NonNull<Pixels>) {
+        let refs = pixels.as_ref().refs.get() - 1;
+        pixels.as_ref().refs.set(refs);
+        if refs == 0 {
+            let pixels = Box::from_raw(pixels.as_ptr());
+            // A new image may be allocated at the same address
+            lvgl_sys::lv_img_cache_invalidate_src(pixels.image.raw() as *const _);
+            lvgl_sys::lv_mem_free(pixels.buffer.as_ptr() as *mut _);
+        }
+    }
+}
+
+unsafe extern "C" fn img_deleted_cb(event: *mut lvgl_sys::lv_event_t) {
+    Pixels::release(NonNull::new_unchecked((*event).user_data as *mut Pixels));
+}
+
+impl Thumbnail {
+    /// Renders `source` into a `width` by `height` thumbnail.
+    ///
+    /// Fails with `LvError::InvalidArgument` if either dimension is zero or
+    /// too large for an image, and with `LvError::LvOOMemory` if LVGL is out
+    /// of memory for the thumbnail or the snapshot.
+    pub fn new(source: &impl NativeObject, width: u16, height: u16) -> LvResult<Self> {
+        let pixels = width as usize * height as usize;
+        if pixels == 0 {
+            return Err(LvError::InvalidArgument);
+        }
+        let size = pixels * mem::size_of::<lvgl_sys::lv_color_t>();
+        let buffer = unsafe { lvgl_sys::lv_mem_alloc(size as _) } as *mut lvgl_sys::lv_color_t;
+        let buffer = NonNull::new(buffer).ok_or(LvError::LvOOMemory)?;
+        let data = unsafe { slice::from_raw_parts(buffer.as_ptr() as *const u8, size) };
+        let image =
+            match ImageDsc::from_raw(width as u32, height as u32, ColorFormat::TrueColor, data) {
+                Ok(image) => image,
+                Err(e) => {
+                    unsafe { lvgl_sys::lv_mem_free(buffer.as_ptr() as *mut _) };
+                    return Err(e);
+                }
+            };
+        let pixels = Box::new(Pixels {
+            refs: Cell::new(1),
+            buffer,
+            image,
+        });
+        let thumbnail = Self {
+            source: source.raw(),
+            pixels: unsafe { NonNull::new_unchecked(Box::into_raw(pixels)) },
+            stale: Cell::new(true),
+        };
+        thumbnail.refresh()?;
+        Ok(thumbnail)
+    }
+
+    fn pixels(&self) -> &Pixels {
+        unsafe { self.pixels.as_ref() }
+    }
+
+    /// Returns the rendered image, e.g. to draw it. It is only valid while
+    /// the thumbnail is; use `show()` to display it in an `Img`.
+    pub fn image(&self) -> &ImageDsc<'static> {
+        &self.pixels().image
+    }
+
+    /// Shows the thumbnail in `img`. The rendering stays allocated until
+    /// `img` is deleted, even if this thumbnail is dropped before.
+    #[cfg(lv_widget = "img")]
+    pub fn show(&self, img: &mut crate::widgets::Img) {
+        let pixels = self.pixels();
+        pixels.refs.set(pixels.refs.get() + 1);
+        unsafe {
+            lvgl_sys::lv_img_set_src(img.raw().as_ptr(), pixels.image.raw() as *const _);
+            lvgl_sys::lv_obj_add_event_cb(
+                img.raw().as_ptr(),
+                Some(img_deleted_cb),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                self.pixels.as_ptr() as *mut _,
+            );
+        }
+    }
+
+    /// Returns the object the thumbnail shows.
+    pub fn source(&self) -> NonNull<lvgl_sys::lv_obj_t> {
+        self.source
+    }
+
+    /// Marks the thumbnail as out of date, so the next call to
+    /// `refresh_if_stale()` renders it again.
+    pub fn invalidate(&self) {
+        self.stale.set(true);
+    }
+
+    /// Returns `true` if the thumbnail was marked as out of date.
+    pub fn is_stale(&self) -> bool {
+        self.stale.get()
+    }
+
+    /// Renders the source object again. Widgets showing the thumbnail have
+    /// to be invalidated to redraw it. Widgets showing a thumbnail evicted
+    /// from a `ThumbnailCache` keep the last rendering.
+    ///
+    /// Fails with `LvError::InvalidReference` if the source object has been
+    /// deleted, leaving the previous rendering in place.
+    pub fn refresh(&self) -> LvResult<()> {
+        let source = self.source.as_ptr();
+        unsafe {
+            if !lvgl_sys::lv_obj_is_valid(source) {
+                return Err(LvError::InvalidReference);
+            }
+            // Inactive screens aren't laid out until they're loaded
+            lvgl_sys::lv_obj_update_layout(source);
+            let snapshot = lvgl_sys::lv_snapshot_take(source, ColorFormat::TrueColor.into());
+            let snapshot = NonNull::new(snapshot).ok_or(LvError::LvOOMemory)?;
+            let header = &snapshot.as_ref().header;
+            let pixels = self.pixels();
+            scale_into(
+                snapshot.as_ref().data as *const lvgl_sys::lv_color_t,
+                header.w() as usize,
+                header.h() as usize,
+                pixels.buffer.as_ptr(),
+                pixels.image.width() as usize,
+                pixels.image.height() as usize,
+            );
+            lvgl_sys::lv_snapshot_free(snapshot.as_ptr());
+            lvgl_sys::lv_img_cache_invalidate_src(pixels.image.raw() as *const _);
+        }
+        self.stale.set(false);
+        Ok(())
+    }
+
+    /// Renders the source object again if the thumbnail is stale. Returns
+    /// whether it was rendered.
+    pub fn refresh_if_stale(&self) -> LvResult<bool> {
+        if !self.is_stale() {
+            return Ok(false);
+        }
+        self.refresh().map(|()| true)
+    }
+}
+
+impl Drop for Thumbnail {
+    fn drop(&mut self) {
+        unsafe { Pixels::release(self.pixels) }
+    }
+}
+
+/// Nearest-neighbour scaling, sampling the source pixel under the center of
+/// each destination pixel. Averaging would look smoother but needs per-channel
+/// access, which depends on `LV_COLOR_DEPTH`.
+unsafe fn scale_into(
+    src: *const lvgl_sys::lv_color_t,
+    src_w: usize,
+    src_h: usize,
+    dst: *mut lvgl_sys::lv_color_t,
+    dst_w: usize,
+    dst_h: usize,
+) {
+    for y in 0..dst_h {
+        let sy = ((2 * y + 1) * src_h / (2 * dst_h)).min(src_h.saturating_sub(1));
+        for x in 0..dst_w {
+            let sx = ((2 * x + 1) * src_w / (2 * dst_w)).min(src_w.saturating_sub(1));
+            *dst.add(y * dst_w + x) = *src.add(sy * src_w + sx);
+        }
+    }
+}
+
+/// The thumbnails of up to `N` objects, all of the same size. When full,
+/// rendering a new object replaces the thumbnail used least recently.
+pub struct ThumbnailCache<const N: usize> {
+    width: u16,
+    height: u16,
+    entries: [Option<(Thumbnail, Cell<u32>)>; N],
+    clock: Cell<u32>,
+}
+
+impl<const N: usize> ThumbnailCache<N> {
+    /// Creates an empty cache of `width` by `height` thumbnails.
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            entries: core::array::from_fn(|_| None),
+            clock: Cell::new(0),
+        }
+    }
+
+    /// Makes sure the cache holds an up-to-date thumbnail of `source`,
+    /// rendering it if it is missing or stale.
+    pub fn render(&mut self, source: &impl NativeObject) -> LvResult<()> {
+        self.prune();
+        let raw = source.raw();
+        if let Some((thumbnail, _)) = self.find(raw) {
+            thumbnail.refresh_if_stale()?;
+            self.touch(raw);
+            return Ok(());
+        }
+        let thumbnail = Thumbnail::new(source, self.width, self.height)?;
+        let slot = match self.entries.iter().position(Option::is_none) {
+            Some(i) => i,
+            None => self
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, e)| e.as_ref().map_or(0, |(_, used)| used.get()))
+                .map(|(i, _)| i)
+                .ok_or(LvError::LvOOMemory)?,
+        };
+        self.entries[slot] = Some((thumbnail, Cell::new(0)));
+        self.touch(raw);
+        Ok(())
+    }
+
+    /// Returns the thumbnail of `source`, if it has been rendered.
+    pub fn get(&self, source: &impl NativeObject) -> Option<&Thumbnail> {
+        let raw = source.raw();
+        self.touch(raw);
+        self.find(raw).map(|(thumbnail, _)| thumbnail)
+    }
+
+    /// Marks the thumbnail of `source` as out of date, e.g. after its
+    /// contents changed.
+    pub fn invalidate(&self, source: &impl NativeObject) {
+        if let Some((thumbnail, _)) = self.find(source.raw()) {
+            thumbnail.invalidate();
+        }
+    }
+
+    /// Marks all thumbnails as out of date.
+    pub fn invalidate_all(&self) {
+        for (thumbnail, _) in self.entries.iter().flatten() {
+            thumbnail.invalidate();
+        }
+    }
+
+    /// Renders all stale thumbnails whose objects still exist again.
+    /// Returns how many were rendered.
+    pub fn refresh_stale(&self) -> LvResult<usize> {
+        let mut refreshed = 0;
+        for (thumbnail, _) in self.entries.iter().flatten() {
+            let valid = unsafe { lvgl_sys::lv_obj_is_valid(thumbnail.source.as_ptr()) };
+            if valid && thumbnail.refresh_if_stale()? {
+                refreshed += 1;
+            }
+        }
+        Ok(refreshed)
+    }
+
+    /// Drops the thumbnail of `source`.
+    pub fn remove(&mut self, source: &impl NativeObject) {
+        let raw = source.raw();
+        for entry in self.entries.iter_mut() {
+            if entry.as_ref().is_some_and(|(t, _)| t.source == raw) {
+                *entry = None;
+            }
+        }
+    }
+
+    fn find(&self, raw: NonNull<lvgl_sys::lv_obj_t>) -> Option<&(Thumbnail, Cell<u32>)> {
+        self.entries.iter().flatten().find(|(t, _)| t.source == raw)
+    }
+
+    fn touch(&self, raw: NonNull<lvgl_sys::lv_obj_t>) {
+        if let Some((_, used)) = self.find(raw) {
+            let now = self.clock.get().wrapping_add(1);
+            self.clock.set(now);
+            used.set(now);
+        }
+    }
+
+    /// Drops the thumbnails of objects deleted since they were rendered.
+    fn prune(&mut self) {
+        for entry in self.entries.iter_mut() {
+            let deleted = entry
+                .as_ref()
+                .is_some_and(|(t, _)| !unsafe { lvgl_sys::lv_obj_is_valid(t.source.as_ptr()) });
+            if deleted {
+                *entry = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Screen};
+
+    #[test]
+    fn thumbnail_of_inactive_screen() {
+        crate::tests::initialize_test(true);
+        let _display = Display::default();
+        let screen = Screen::blank().unwrap();
+        let thumbnail = Thumbnail::new(&screen, 32, 24).unwrap();
+        assert_eq!(thumbnail.image().width(), 32);
+        assert_eq!(thumbnail.image().height(), 24);
+        assert!(!thumbnail.is_stale());
+
+        thumbnail.invalidate();
+        assert!(thumbnail.refresh_if_stale().unwrap());
+        assert!(!thumbnail.refresh_if_stale().unwrap());
+        assert!(Thumbnail::new(&screen, 0, 24).is_err());
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        crate::tests::initialize_test(true);
+        let _display = Display::default();
+        let screens = [(); 3].map(|()| Screen::blank().unwrap());
+        let mut cache = ThumbnailCache::<2>::new(16, 12);
+        cache.render(&screens[0]).unwrap();
+        cache.render(&screens[1]).unwrap();
+        assert!(cache.get(&screens[0]).is_some());
+
+        cache.render(&screens[2]).unwrap();
+        assert!(cache.get(&screens[0]).is_some());
+        assert!(cache.get(&screens[1]).is_none());
+        assert!(cache.get(&screens[2]).is_some());
+
+        cache.invalidate_all();
+        assert_eq!(cache.refresh_stale().unwrap(), 2);
+    }
+
+    #[test]
+    fn shown_thumbnail_outlives_eviction() {
+        use crate::widgets::Img;
+
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut img = Img::create(&mut screen).unwrap();
+        let screens = [(); 2].map(|()| Screen::blank().unwrap());
+        let mut cache = ThumbnailCache::<1>::new(16, 12);
+        cache.render(&screens[0]).unwrap();
+        let thumbnail = cache.get(&screens[0]).unwrap();
+        let pixels = thumbnail.pixels;
+        thumbnail.show(&mut img);
+
+        cache.render(&screens[1]).unwrap();
+        assert!(cache.get(&screens[0]).is_none());
+        assert_eq!(unsafe { pixels.as_ref() }.refs.get(), 1);
+        display.refresh_now();
+        img.del();
+    }
+}
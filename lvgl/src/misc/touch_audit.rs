@@ -0,0 +1,200 @@
+//! Touch target auditing
+//!
+//! Small touch targets are a common usability problem on touch screens.
+//! `TouchAudit` walks the active screen of a display, measures the effective
+//! touch target of every clickable object (its size plus any extended click
+//! area, see `Widget::set_ext_click_area()`) and draws an outline around each
+//! one on the top layer: green for targets of at least the configured
+//! minimum size, red for those below it.
+//!
+//! ```ignore
+//! let audit = TouchAudit::new(8.0); // 8 mm minimum
+//! let report = audit.run(&display)?;
+//! assert_eq!(report.too_small, 0);
+//! ```
+
+use crate::{Color, Display, LvError, LvResult};
+use core::ptr::NonNull;
+
+const MM_PER_INCH: f32 = 25.4;
+
+/// Result of a `TouchAudit` run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AuditReport {
+    /// Number of clickable objects found.
+    pub checked: u32,
+    /// Number of clickable objects whose touch target is below the minimum.
+    pub too_small: u32,
+    /// The minimum size in pixels the targets were checked against.
+    pub min_size_px: i16,
+}
+
+/// Debug overlay highlighting the touch targets of clickable objects.
+pub struct TouchAudit {
+    min_size_mm: f32,
+    overlay: Option<NonNull<lvgl_sys::lv_obj_t>>,
+}
+
+impl TouchAudit {
+    /// Creates an audit flagging touch targets narrower or shorter than
+    /// `min_size_mm` millimeters at the display's DPI.
+    pub fn new(min_size_mm: f32) -> Self {
+        Self {
+            min_size_mm,
+            overlay: None,
+        }
+    }
+
+    /// Audits the active screen of `display` and (re)draws the overlay.
+    pub fn run(&mut self, display: &Display) -> LvResult<AuditReport> {
+        self.run_with(display, |_, _| {})
+    }
+
+    /// Like `run()`, additionally calling `on_too_small` with every object
+    /// whose touch target is too small and the size of that target.
+    pub fn run_with<F>(&mut self, display: &Display, mut on_too_small: F) -> LvResult<AuditReport>
+    where
+        F: FnMut(NonNull<lvgl_sys::lv_obj_t>, (i16, i16)),
+    {
+        self.clear();
+        unsafe {
+            let disp = display.disp.as_ptr();
+            let screen = lvgl_sys::lv_disp_get_scr_act(disp);
+            let top = lvgl_sys::lv_disp_get_layer_top(disp);
+            if screen.is_null() || top.is_null() {
+                return Err(LvError::Uninitialized);
+            }
+            // Make sure the coordinates of every object are up to date
+            lvgl_sys::lv_obj_update_layout(screen);
+
+            let overlay = lvgl_sys::lv_obj_create(top);
+            let overlay = NonNull::new(overlay).ok_or(LvError::LvOOMemory)?;
+            lvgl_sys::lv_obj_remove_style_all(overlay.as_ptr());
+            lvgl_sys::lv_obj_set_size(
+                overlay.as_ptr(),
+                lvgl_sys::lv_disp_get_hor_res(disp),
+                lvgl_sys::lv_disp_get_ver_res(disp),
+            );
+            clear_flag(overlay.as_ptr(), lvgl_sys::LV_OBJ_FLAG_CLICKABLE);
+            clear_flag(overlay.as_ptr(), lvgl_sys::LV_OBJ_FLAG_SCROLLABLE);
+            self.overlay = Some(overlay);
+
+            let dpi = lvgl_sys::lv_disp_get_dpi(disp) as f32;
+            let mut report = AuditReport {
+                min_size_px: (self.min_size_mm * dpi / MM_PER_INCH + 0.5) as i16,
+                ..Default::default()
+            };
+            for i in 0..lvgl_sys::lv_obj_get_child_cnt(screen) {
+                let child = lvgl_sys::lv_obj_get_child(screen, i as i32);
+                audit_tree(child, overlay.as_ptr(), &mut report, &mut on_too_small);
+            }
+            Ok(report)
+        }
+    }
+
+    /// Removes the overlay drawn by the last run, if any.
+    pub fn clear(&mut self) {
+        if let Some(overlay) = self.overlay.take() {
+            unsafe { lvgl_sys::lv_obj_del(overlay.as_ptr()) }
+        }
+    }
+}
+
+unsafe fn audit_tree<F>(
+    obj: *mut lvgl_sys::lv_obj_t,
+    overlay: *mut lvgl_sys::lv_obj_t,
+    report: &mut AuditReport,
+    on_too_small: &mut F,
+) where
+    F: FnMut(NonNull<lvgl_sys::lv_obj_t>, (i16, i16)),
+{
+    if obj.is_null() || has_flag(obj, lvgl_sys::LV_OBJ_FLAG_HIDDEN) {
+        return;
+    }
+    if has_flag(obj, lvgl_sys::LV_OBJ_FLAG_CLICKABLE) {
+        let mut area = lvgl_sys::lv_area_t::default();
+        lvgl_sys::lv_obj_get_click_area(obj, &mut area);
+        let width = area.x2 - area.x1 + 1;
+        let height = area.y2 - area.y1 + 1;
+        report.checked += 1;
+        let ok = width >= report.min_size_px && height >= report.min_size_px;
+        if !ok {
+            report.too_small += 1;
+            on_too_small(NonNull::new_unchecked(obj), (width, height));
+        }
+        draw_outline(overlay, &area, ok);
+    }
+    for i in 0..lvgl_sys::lv_obj_get_child_cnt(obj) {
+        audit_tree(
+            lvgl_sys::lv_obj_get_child(obj, i as i32),
+            overlay,
+            report,
+            on_too_small,
+        );
+    }
+}
+
+unsafe fn draw_outline(overlay: *mut lvgl_sys::lv_obj_t, area: &lvgl_sys::lv_area_t, ok: bool) {
+    let rect = lvgl_sys::lv_obj_create(overlay);
+    if rect.is_null() {
+        return;
+    }
+    let color = if ok {
+        Color::from_rgb((0, 200, 0))
+    } else {
+        Color::from_rgb((255, 0, 0))
+    };
+    lvgl_sys::lv_obj_remove_style_all(rect);
+    lvgl_sys::lv_obj_set_pos(rect, area.x1, area.y1);
+    lvgl_sys::lv_obj_set_size(rect, area.x2 - area.x1 + 1, area.y2 - area.y1 + 1);
+    lvgl_sys::lv_obj_set_style_border_width(rect, 1, lvgl_sys::LV_PART_MAIN);
+    lvgl_sys::lv_obj_set_style_border_color(rect, color.into(), lvgl_sys::LV_PART_MAIN);
+    lvgl_sys::lv_obj_set_style_border_opa(
+        rect,
+        lvgl_sys::LV_OPA_COVER as lvgl_sys::lv_opa_t,
+        lvgl_sys::LV_PART_MAIN,
+    );
+    clear_flag(rect, lvgl_sys::LV_OBJ_FLAG_CLICKABLE);
+}
+
+unsafe fn has_flag(obj: *mut lvgl_sys::lv_obj_t, flag: u32) -> bool {
+    lvgl_sys::lv_obj_has_flag(obj, flag as lvgl_sys::lv_obj_flag_t)
+}
+
+unsafe fn clear_flag(obj: *mut lvgl_sys::lv_obj_t, flag: u32) {
+    lvgl_sys::lv_obj_clear_flag(obj, flag as lvgl_sys::lv_obj_flag_t)
+}
+
+impl Drop for TouchAudit {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Btn;
+    use crate::Widget;
+
+    #[test]
+    fn flags_small_touch_targets() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut small = Btn::create(&mut screen).unwrap();
+        small.set_size(10, 10);
+        let mut large = Btn::create(&mut screen).unwrap();
+        large.set_size(100, 100);
+
+        let mut audit = TouchAudit::new(5.0);
+        let report = audit.run(&display).unwrap();
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.too_small, 1);
+
+        // Extending the click area fixes the small button
+        small.set_ext_click_area(40);
+        let report = audit.run(&display).unwrap();
+        assert_eq!(report.too_small, 0);
+    }
+}
@@ -0,0 +1,264 @@
+//! Undo/redo for user-editable widget values
+//!
+//! An `UndoStack` tracks integer properties of widgets, such as the value of
+//! a slider or the setpoints of a curve editor built from several spinboxes.
+//! Changes made by the user are recorded automatically from the widgets'
+//! `VALUE_CHANGED` events; while a widget is being dragged, the intermediate
+//! values are coalesced into a single step once it is released.
+//!
+//! ```ignore
+//! let mut undo = UndoStack::new(32);
+//! undo.track(&slider, |s| s.get_value(), |s, v| s.set_value(v, AnimationState::OFF));
+//! // ...
+//! undo.undo();
+//! ```
+
+use crate::{NativeObject, Widget};
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::pin::Pin;
+use core::ptr::NonNull;
+
+type Getter<'a> = Box<dyn FnMut(NonNull<lvgl_sys::lv_obj_t>) -> i32 + 'a>;
+type Setter<'a> = Box<dyn FnMut(NonNull<lvgl_sys::lv_obj_t>, i32) + 'a>;
+
+struct Property<'a> {
+    /// `None` once the object was deleted.
+    obj: Option<NonNull<lvgl_sys::lv_obj_t>>,
+    get: Getter<'a>,
+    set: Setter<'a>,
+    last: i32,
+}
+
+#[derive(Clone, Copy)]
+struct Change {
+    property: usize,
+    old: i32,
+    new: i32,
+}
+
+struct UndoState<'a> {
+    properties: Vec<Property<'a>>,
+    undo: VecDeque<Change>,
+    redo: Vec<Change>,
+    capacity: usize,
+}
+
+/// A bounded history of changes to tracked widget properties.
+pub struct UndoStack<'a> {
+    state: Pin<Box<UndoState<'a>>>,
+}
+
+impl<'a> UndoStack<'a> {
+    /// Creates an empty stack remembering at most `capacity` changes.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Box::pin(UndoState {
+                properties: Vec::new(),
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                capacity,
+            }),
+        }
+    }
+
+    /// Starts tracking a property of `widget`, read with `get` and restored
+    /// with `set`. Changes are recorded whenever the widget reports a value
+    /// change.
+    pub fn track<W, G, S>(&mut self, widget: &W, mut get: G, mut set: S)
+    where
+        W: Widget<'a>,
+        G: FnMut(&W) -> i32 + 'a,
+        S: FnMut(&mut W, i32) + 'a,
+    {
+        let raw = widget.raw();
+        let last = get(widget);
+        let state = self.state_mut();
+        state.properties.push(Property {
+            obj: Some(raw),
+            get: Box::new(move |raw| get(&unsafe { W::from_raw(raw) }.unwrap())),
+            set: Box::new(move |raw, v| set(&mut unsafe { W::from_raw(raw) }.unwrap(), v)),
+            last,
+        });
+        unsafe {
+            lvgl_sys::lv_obj_add_event_cb(
+                raw.as_ptr(),
+                Some(undo_event_cb),
+                lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+                state as *mut UndoState as *mut _,
+            );
+        }
+    }
+
+    /// Records the current values of all tracked properties, for changes made
+    /// programmatically rather than by the user.
+    pub fn record(&mut self) {
+        let state = self.state_mut();
+        for i in 0..state.properties.len() {
+            state.record(i);
+        }
+    }
+
+    /// Reverts the most recent change. Returns `false` if there is nothing to
+    /// undo.
+    pub fn undo(&mut self) -> bool {
+        let state = self.state_mut();
+        while let Some(change) = state.undo.pop_back() {
+            if state.apply(change.property, change.old) {
+                state.redo.push(change);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Reapplies the most recently undone change. Returns `false` if there is
+    /// nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let state = self.state_mut();
+        while let Some(change) = state.redo.pop() {
+            if state.apply(change.property, change.new) {
+                state.undo.push_back(change);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns `true` if there are changes to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.state.undo.is_empty()
+    }
+
+    /// Returns `true` if there are undone changes to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.state.redo.is_empty()
+    }
+
+    /// Forgets all recorded changes.
+    pub fn clear(&mut self) {
+        let state = self.state_mut();
+        state.undo.clear();
+        state.redo.clear();
+    }
+
+    fn state_mut(&mut self) -> &mut UndoState<'a> {
+        // The state is never moved out of its box, only mutated in place
+        unsafe { self.state.as_mut().get_unchecked_mut() }
+    }
+}
+
+impl Drop for UndoStack<'_> {
+    fn drop(&mut self) {
+        let state = self.state_mut();
+        let user_data = state as *mut UndoState as *mut cty::c_void;
+        for obj in state.properties.iter().filter_map(|p| p.obj) {
+            unsafe {
+                lvgl_sys::lv_obj_remove_event_cb_with_user_data(
+                    obj.as_ptr(),
+                    Some(undo_event_cb),
+                    user_data,
+                );
+            }
+        }
+    }
+}
+
+impl UndoState<'_> {
+    /// Pushes a change if property `index` differs from its last known value.
+    fn record(&mut self, index: usize) {
+        let property = &mut self.properties[index];
+        let obj = match property.obj {
+            Some(obj) => obj,
+            None => return,
+        };
+        let new = (property.get)(obj);
+        if new == property.last {
+            return;
+        }
+        let change = Change {
+            property: index,
+            old: property.last,
+            new,
+        };
+        property.last = new;
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        if self.capacity > 0 {
+            self.undo.push_back(change);
+        }
+        self.redo.clear();
+    }
+
+    /// Sets property `index` to `value`. Returns `false` if its object no
+    /// longer exists.
+    fn apply(&mut self, index: usize, value: i32) -> bool {
+        let property = &mut self.properties[index];
+        match property.obj {
+            Some(obj) => {
+                (property.set)(obj, value);
+                property.last = value;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+unsafe extern "C" fn undo_event_cb(event: *mut lvgl_sys::lv_event_t) {
+    let state = &mut *((*event).user_data as *mut UndoState);
+    let obj = (*event).current_target;
+    let code = (*event).code;
+    for i in 0..state.properties.len() {
+        if state.properties[i].obj.map(|o| o.as_ptr()) != Some(obj) {
+            continue;
+        }
+        match code {
+            lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED => {
+                // Wait for the release while the user is still dragging
+                if !lvgl_sys::lv_obj_has_state(obj, lvgl_sys::LV_STATE_PRESSED as _) {
+                    state.record(i);
+                }
+            }
+            lvgl_sys::lv_event_code_t_LV_EVENT_RELEASED
+            | lvgl_sys::lv_event_code_t_LV_EVENT_PRESS_LOST => state.record(i),
+            lvgl_sys::lv_event_code_t_LV_EVENT_DELETE => state.properties[i].obj = None,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::Slider;
+    use crate::{AnimationState, Display};
+
+    #[test]
+    fn undo_redo_slider_value() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut slider = Slider::create(&mut screen).unwrap();
+        let mut undo = UndoStack::new(8);
+        undo.track(
+            &slider,
+            |s| s.get_value(),
+            |s, v| s.set_value(v, AnimationState::OFF),
+        );
+
+        slider.set_value(40, AnimationState::OFF);
+        undo.record();
+        slider.set_value(70, AnimationState::OFF);
+        undo.record();
+        assert!(undo.undo());
+        assert_eq!(slider.get_value(), 40);
+        assert!(undo.undo());
+        assert_eq!(slider.get_value(), 0);
+        assert!(!undo.undo());
+        assert!(undo.redo());
+        assert_eq!(slider.get_value(), 40);
+    }
+}
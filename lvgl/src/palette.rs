@@ -0,0 +1,114 @@
+//! LVGL's color palette
+//!
+//! The palette is based on Material Design: every hue has a main color, five
+//! lighter and four darker shades. The default theme picks its primary and
+//! secondary colors from it.
+//!
+//! ```ignore
+//! let primary = palette::main(Palette::Blue);
+//! let pressed = palette::darken(Palette::Blue, 2);
+//! ```
+
+use crate::Color;
+
+/// A hue of the palette. Equivalent to `lv_palette_t`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Palette {
+    Red,
+    Pink,
+    Purple,
+    DeepPurple,
+    Indigo,
+    Blue,
+    LightBlue,
+    Cyan,
+    Teal,
+    Green,
+    LightGreen,
+    Lime,
+    Yellow,
+    Amber,
+    Orange,
+    DeepOrange,
+    Brown,
+    BlueGrey,
+    Grey,
+}
+
+impl From<Palette> for lvgl_sys::lv_palette_t {
+    fn from(palette: Palette) -> Self {
+        (match palette {
+            Palette::Red => lvgl_sys::lv_palette_t_LV_PALETTE_RED,
+            Palette::Pink => lvgl_sys::lv_palette_t_LV_PALETTE_PINK,
+            Palette::Purple => lvgl_sys::lv_palette_t_LV_PALETTE_PURPLE,
+            Palette::DeepPurple => lvgl_sys::lv_palette_t_LV_PALETTE_DEEP_PURPLE,
+            Palette::Indigo => lvgl_sys::lv_palette_t_LV_PALETTE_INDIGO,
+            Palette::Blue => lvgl_sys::lv_palette_t_LV_PALETTE_BLUE,
+            Palette::LightBlue => lvgl_sys::lv_palette_t_LV_PALETTE_LIGHT_BLUE,
+            Palette::Cyan => lvgl_sys::lv_palette_t_LV_PALETTE_CYAN,
+            Palette::Teal => lvgl_sys::lv_palette_t_LV_PALETTE_TEAL,
+            Palette::Green => lvgl_sys::lv_palette_t_LV_PALETTE_GREEN,
+            Palette::LightGreen => lvgl_sys::lv_palette_t_LV_PALETTE_LIGHT_GREEN,
+            Palette::Lime => lvgl_sys::lv_palette_t_LV_PALETTE_LIME,
+            Palette::Yellow => lvgl_sys::lv_palette_t_LV_PALETTE_YELLOW,
+            Palette::Amber => lvgl_sys::lv_palette_t_LV_PALETTE_AMBER,
+            Palette::Orange => lvgl_sys::lv_palette_t_LV_PALETTE_ORANGE,
+            Palette::DeepOrange => lvgl_sys::lv_palette_t_LV_PALETTE_DEEP_ORANGE,
+            Palette::Brown => lvgl_sys::lv_palette_t_LV_PALETTE_BROWN,
+            Palette::BlueGrey => lvgl_sys::lv_palette_t_LV_PALETTE_BLUE_GREY,
+            Palette::Grey => lvgl_sys::lv_palette_t_LV_PALETTE_GREY,
+        }) as lvgl_sys::lv_palette_t
+    }
+}
+
+/// The number of lighter shades of each hue.
+pub const LIGHTEN_LEVELS: u8 = 5;
+/// The number of darker shades of each hue.
+pub const DARKEN_LEVELS: u8 = 4;
+
+/// Returns the main color of a hue.
+pub fn main(palette: Palette) -> Color {
+    Color::from_raw(unsafe { lvgl_sys::lv_palette_main(palette.into()) })
+}
+
+/// Returns a lighter shade of a hue, from 1 (slightly lighter) to
+/// `LIGHTEN_LEVELS`. Levels out of range are clamped.
+pub fn lighten(palette: Palette, level: u8) -> Color {
+    let level = level.clamp(1, LIGHTEN_LEVELS);
+    Color::from_raw(unsafe { lvgl_sys::lv_palette_lighten(palette.into(), level) })
+}
+
+/// Returns a darker shade of a hue, from 1 (slightly darker) to
+/// `DARKEN_LEVELS`. Levels out of range are clamped.
+pub fn darken(palette: Palette, level: u8) -> Color {
+    let level = level.clamp(1, DARKEN_LEVELS);
+    Color::from_raw(unsafe { lvgl_sys::lv_palette_darken(palette.into(), level) })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn shades_get_lighter_and_darker() {
+        crate::tests::initialize_test(false);
+        let luma = |c: Color| {
+            let (r, g, b) = c.to_rgb();
+            r as u32 + g as u32 + b as u32
+        };
+        let blue = main(Palette::Blue);
+        assert_eq!(blue, Color::from_hex(0x2196F3));
+        assert!(luma(lighten(Palette::Blue, 1)) > luma(blue));
+        assert!(luma(lighten(Palette::Blue, 5)) > luma(lighten(Palette::Blue, 1)));
+        assert_eq!(lighten(Palette::Blue, 9), lighten(Palette::Blue, 5));
+        assert!(luma(darken(Palette::Blue, 4)) < luma(blue));
+        assert_eq!(darken(Palette::Blue, 0), darken(Palette::Blue, 1));
+
+        let grey = Color::from_rgb((0, 0, 0)).mix(Color::from_rgb((255, 255, 255)), 128);
+        assert_eq!(grey.lighten(255), Color::from_hex(0xFFFFFF));
+        assert_eq!(grey.darken(255), Color::from_hex(0x000000));
+        let (h, s, v) = Color::from_hsv(120, 100, 100).to_hsv();
+        assert!((115..=125).contains(&h));
+        assert_eq!((s, v), (100, 100));
+    }
+}
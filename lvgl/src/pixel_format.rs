@@ -0,0 +1,172 @@
+//! Pixel formats of display controllers
+//!
+//! The format LVGL renders in is fixed when it is compiled, through
+//! `LV_COLOR_DEPTH` and `LV_COLOR_16_SWAP` (or the `color_depth_*` and
+//! `color_16_swap` features). Flush callbacks name the format their display
+//! controller expects, and get the pixels typed accordingly:
+//!
+//! ```ignore
+//! let display = Display::register(buffer, 320, 240, |refresh| {
+//!     let pixels: &[u16; N] = refresh.pixels::<Rgb565Swapped>();
+//!     spi.write(bytemuck::cast_slice(pixels));
+//! })?;
+//! ```
+//!
+//! If LVGL was built with a different format, `pixels()` fails to compile
+//! instead of sending garbage to the display. `assert_pixel_format!` checks
+//! the format where no flush callback names it, e.g. in a board support
+//! crate:
+//!
+//! ```ignore
+//! lvgl::assert_pixel_format!(Rgb565Swapped);
+//! ```
+
+use crate::{COLOR_16_SWAP, COLOR_DEPTH};
+use core::marker::PhantomData;
+use core::mem;
+
+/// A pixel format LVGL can render in.
+pub trait PixelFormat {
+    /// The integer type holding one pixel.
+    type Pixel: Copy;
+    /// The matching `LV_COLOR_DEPTH`.
+    const DEPTH: u8;
+    /// The matching `LV_COLOR_16_SWAP`.
+    const SWAP: bool = false;
+}
+
+/// One byte per pixel, of which only the lowest bit is used.
+pub enum Mono {}
+
+/// 3 bits of red, 3 of green and 2 of blue.
+pub enum Rgb332 {}
+
+/// 5 bits of red, 6 of green and 5 of blue, in the CPU's byte order.
+pub enum Rgb565 {}
+
+/// 5 bits of red, 6 of green and 5 of blue, with the bytes swapped. Suits
+/// most SPI displays on little-endian CPUs.
+pub enum Rgb565Swapped {}
+
+/// 8 bits per channel, with alpha in the highest byte.
+pub enum Argb8888 {}
+
+impl PixelFormat for Mono {
+    type Pixel = u8;
+    const DEPTH: u8 = 1;
+}
+
+impl PixelFormat for Rgb332 {
+    type Pixel = u8;
+    const DEPTH: u8 = 8;
+}
+
+impl PixelFormat for Rgb565 {
+    type Pixel = u16;
+    const DEPTH: u8 = 16;
+}
+
+impl PixelFormat for Rgb565Swapped {
+    type Pixel = u16;
+    const DEPTH: u8 = 16;
+    const SWAP: bool = true;
+}
+
+impl PixelFormat for Argb8888 {
+    type Pixel = u32;
+    const DEPTH: u8 = 32;
+}
+
+/// The format LVGL was compiled with.
+#[cfg(lv_color_depth = "1")]
+pub type NativeFormat = Mono;
+/// The format LVGL was compiled with.
+#[cfg(lv_color_depth = "8")]
+pub type NativeFormat = Rgb332;
+/// The format LVGL was compiled with.
+#[cfg(all(lv_color_depth = "16", not(lv_color_16_swap)))]
+pub type NativeFormat = Rgb565;
+/// The format LVGL was compiled with.
+#[cfg(all(lv_color_depth = "16", lv_color_16_swap))]
+pub type NativeFormat = Rgb565Swapped;
+/// The format LVGL was compiled with.
+#[cfg(lv_color_depth = "32")]
+pub type NativeFormat = Argb8888;
+
+// LVGL and its bindings must agree on the size of a pixel
+#[cfg(any(
+    lv_color_depth = "1",
+    lv_color_depth = "8",
+    lv_color_depth = "16",
+    lv_color_depth = "32"
+))]
+const _: () = assert!(
+    mem::size_of::<<NativeFormat as PixelFormat>::Pixel>()
+        == mem::size_of::<lvgl_sys::lv_color_t>(),
+    "lv_color_t doesn't match LV_COLOR_DEPTH; LVGL and its bindings were built with different configurations"
+);
+
+/// Returns whether LVGL renders in the format `F`.
+pub const fn is_native<F: PixelFormat>() -> bool {
+    F::DEPTH == COLOR_DEPTH
+        && (F::SWAP == COLOR_16_SWAP || F::DEPTH != 16)
+        && mem::size_of::<F::Pixel>() == mem::size_of::<lvgl_sys::lv_color_t>()
+}
+
+/// Fails the build unless LVGL renders in the given `PixelFormat`, see the
+/// module documentation.
+#[macro_export]
+macro_rules! assert_pixel_format {
+    ($format:ty) => {
+        const _: () = assert!(
+            $crate::pixel_format::is_native::<$format>(),
+            "the pixel format doesn't match LV_COLOR_DEPTH and LV_COLOR_16_SWAP in lv_conf.h"
+        );
+    };
+}
+
+pub(crate) struct Check<F>(PhantomData<F>);
+
+impl<F: PixelFormat> Check<F> {
+    /// Evaluated when `pixels()` is instantiated for `F`, failing the build
+    /// if `F` isn't the format LVGL renders in.
+    pub(crate) const MATCHES: () = assert!(
+        is_native::<F>(),
+        "the pixel format doesn't match LV_COLOR_DEPTH and LV_COLOR_16_SWAP in lv_conf.h"
+    );
+}
+
+#[cfg(all(
+    test,
+    any(
+        lv_color_depth = "1",
+        lv_color_depth = "8",
+        lv_color_depth = "16",
+        lv_color_depth = "32"
+    )
+))]
+mod test {
+    use super::*;
+    use crate::display::{Area, DisplayRefresh};
+    use crate::Color;
+
+    crate::assert_pixel_format!(NativeFormat);
+
+    #[test]
+    fn native_format_matches() {
+        let colors = [Color::from_rgb((255, 0, 0)); 2];
+        let refresh = DisplayRefresh {
+            area: Area {
+                x1: 0,
+                x2: 1,
+                y1: 0,
+                y2: 0,
+            },
+            colors: &colors,
+            last: true,
+        };
+        let pixels = refresh.pixels::<NativeFormat>();
+        assert_eq!(pixels.as_ptr() as *const Color, colors.as_ptr());
+        assert_eq!(pixels[0], pixels[1]);
+    }
+}
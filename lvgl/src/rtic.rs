@@ -0,0 +1,95 @@
+//! Running LVGL under RTIC
+//!
+//! The `rtic` feature reads LVGL's tick from an RTIC monotonic, see
+//! `timer::RticTicks`, and drives LVGL's timers from an async task or from
+//! the idle loop. `run()` does so from a task, like `task::run()` does
+//! under Embassy, sleeping on the monotonic until the next timer is due:
+//!
+//! ```ignore
+//! #[task(priority = 1)]
+//! async fn lvgl_task(_: lvgl_task::Context) {
+//!     lvgl::rtic::run::<Mono, 1_000>(|| {}).await
+//! }
+//! ```
+//!
+//! Elsewhere, `timer_handler()` runs the timers that are due and returns
+//! when the next one is, so the core can sleep in between. The tick source
+//! then has to be registered first:
+//!
+//! ```ignore
+//! static TICKS: RticTicks<Mono> = RticTicks::new();
+//!
+//! #[init]
+//! fn init(cx: init::Context) -> (Shared, Local) {
+//!     lvgl::timer::set_tick_source(&TICKS);
+//!     // Register the display, build the UI...
+//! }
+//!
+//! #[task(priority = 1, shared = [ui])]
+//! async fn lvgl_task(mut cx: lvgl_task::Context) {
+//!     loop {
+//!         let deadline = cx.shared.ui.lock(|_| lvgl::rtic::timer_handler::<Mono, 1_000>());
+//!         Mono::delay_until(deadline).await;
+//!     }
+//! }
+//! ```
+//!
+//! # Sharing widgets between tasks
+//!
+//! With the `threadsafe` feature, widget handles are wrapped in
+//! `sync::Shared` and only used while holding the LVGL lock. That lock is a
+//! spin lock: a task preempting the one holding it would wait for it
+//! forever. Keeping everything touching LVGL in one RTIC shared resource
+//! avoids this, as RTIC's own lock keeps the other tasks using the resource
+//! from preempting:
+//!
+//! ```ignore
+//! struct Ui {
+//!     status: Shared<Label<'static>>,
+//! }
+//!
+//! #[shared]
+//! struct Shared {
+//!     ui: Ui,
+//! }
+//!
+//! #[task(binds = EXTI0, priority = 2, shared = [ui])]
+//! fn button(mut cx: button::Context) {
+//!     cx.shared.ui.lock(|ui| {
+//!         let mut guard = lvgl::sync::lock();
+//!         ui.status.get_mut(&mut guard).set_text(c"Pressed").ok();
+//!     });
+//! }
+//! ```
+//!
+//! `timer_handler()` then needs the lock held as well, as in
+//! `cx.shared.ui.lock(|_| lvgl::sync::lock().run(timer_handler::<Mono, 1_000>))`.
+
+use crate::timer::RticTicks;
+use core::marker::PhantomData;
+use fugit::{TimerDurationU64, TimerInstantU64};
+use rtic_time::Monotonic;
+
+/// Runs the LVGL timers that are due, like `task_handler()`, and returns the
+/// instant of monotonic `M` to call it again at.
+pub fn timer_handler<M, const FREQ: u32>() -> TimerInstantU64<FREQ>
+where
+    M: Monotonic<Instant = TimerInstantU64<FREQ>>,
+{
+    let next = crate::timer::run_due();
+    M::now() + TimerDurationU64::<FREQ>::millis(next.as_millis() as u64)
+}
+
+/// Runs LVGL forever from an async task, reading its tick from monotonic
+/// `M` and sleeping on it until the next timer is due. `after_handler` is
+/// called after every round of timers, e.g. to apply updates from other
+/// tasks.
+pub async fn run<M, const FREQ: u32>(after_handler: impl FnMut()) -> !
+where
+    M: Monotonic<Instant = TimerInstantU64<FREQ>> + 'static,
+{
+    crate::timer::run(&RticTicks::<M>(PhantomData), after_handler, |sleep| {
+        M::delay_until(M::now() + TimerDurationU64::<FREQ>::millis(sleep.as_millis() as u64))
+    })
+    .await
+}
@@ -0,0 +1,89 @@
+//! LVGL memory on the Rust heap
+//!
+//! With the `rust_alloc` feature, LVGL is built with `LV_MEM_CUSTOM` and its
+//! `lv_mem_alloc()`, `lv_mem_free()` and `lv_mem_realloc()` end up in the
+//! functions below, which allocate through Rust's global allocator. There is
+//! then a single heap for both, rather than LVGL's fixed `LV_MEM_SIZE` pool
+//! next to the Rust heap.
+//!
+//! This is the opposite of the `lvgl_alloc` feature, and the two can't be
+//! combined.
+
+use alloc::alloc::{alloc, dealloc, realloc, Layout};
+use core::ptr;
+
+#[cfg(feature = "lvgl_alloc")]
+compile_error!("The `rust_alloc` and `lvgl_alloc` features are mutually exclusive");
+
+/// Alignment of the returned memory, enough for any type LVGL allocates. The
+/// size of each allocation is stored in front of it, in a header this large.
+const ALIGN: usize = 16;
+
+fn layout(size: usize) -> Option<Layout> {
+    Layout::from_size_align(size.checked_add(ALIGN)?, ALIGN).ok()
+}
+
+/// Returns the start of the allocation `data` was returned from.
+unsafe fn header(data: *mut cty::c_void) -> *mut usize {
+    (data as *mut u8).sub(ALIGN) as *mut usize
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_malloc(size: cty::size_t) -> *mut cty::c_void {
+    let Some(layout) = layout(size as usize) else {
+        return ptr::null_mut();
+    };
+    let block = alloc(layout);
+    if block.is_null() {
+        return ptr::null_mut();
+    }
+    *(block as *mut usize) = size as usize;
+    block.add(ALIGN) as *mut _
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_free(data: *mut cty::c_void) {
+    if data.is_null() {
+        return;
+    }
+    let block = header(data);
+    dealloc(block as *mut u8, layout(*block).unwrap());
+}
+
+#[no_mangle]
+unsafe extern "C" fn rs_lv_realloc(
+    data: *mut cty::c_void,
+    new_size: cty::size_t,
+) -> *mut cty::c_void {
+    if data.is_null() {
+        return rs_lv_malloc(new_size);
+    }
+    let block = header(data);
+    let Some(new_layout) = layout(new_size as usize) else {
+        return ptr::null_mut();
+    };
+    let block = realloc(block as *mut u8, layout(*block).unwrap(), new_layout.size());
+    if block.is_null() {
+        return ptr::null_mut();
+    }
+    *(block as *mut usize) = new_size as usize;
+    block.add(ALIGN) as *mut _
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn allocations_round_trip() {
+        unsafe {
+            let data = rs_lv_malloc(3) as *mut u8;
+            assert_eq!(data as usize % ALIGN, 0);
+            data.copy_from(b"abc".as_ptr(), 3);
+            let data = rs_lv_realloc(data as *mut _, 1024) as *mut u8;
+            assert_eq!(core::slice::from_raw_parts(data, 3), b"abc");
+            rs_lv_free(data as *mut _);
+            rs_lv_free(ptr::null_mut());
+        }
+    }
+}
@@ -1,22 +1,25 @@
 use crate::display::DisplayError;
-use crate::Widget;
+use crate::{Widget, WidgetValue};
 use core::convert::{TryFrom, TryInto};
 #[cfg(feature = "nightly")]
 use core::error::Error;
 use core::fmt;
-use core::ptr::NonNull;
+use core::mem;
+use core::ptr::{self, NonNull};
 #[cfg(feature = "embedded_graphics")]
-use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888, RgbColor};
 
 pub type LvResult<T> = Result<T, LvError>;
 
 /// Generic LVGL error. All other errors can be coerced into it.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum LvError {
     InvalidReference,
     Uninitialized,
     LvOOMemory,
     AlreadyInUse,
+    InvalidArgument,
 }
 
 impl fmt::Display for LvError {
@@ -29,6 +32,7 @@ impl fmt::Display for LvError {
                 LvError::Uninitialized => "LVGL uninitialized",
                 LvError::LvOOMemory => "LVGL out of memory",
                 LvError::AlreadyInUse => "Resource already in use",
+                LvError::InvalidArgument => "Invalid argument",
             }
         )
     }
@@ -56,12 +60,25 @@ impl From<LvError> for DisplayError {
             LvError::Uninitialized => NotAvailable,
             LvError::LvOOMemory => FailedToRegister,
             LvError::AlreadyInUse => FailedToRegister,
+            LvError::InvalidArgument => FailedToRegister,
         }
     }
 }
 
+/// The color depth LVGL was compiled with (`LV_COLOR_DEPTH` in `lv_conf.h`).
+pub const COLOR_DEPTH: u8 = lvgl_sys::LV_COLOR_DEPTH as u8;
+
+/// Whether LVGL swaps the bytes of 16-bit colors (`LV_COLOR_16_SWAP` in
+/// `lv_conf.h`), as many SPI displays expect.
+pub const COLOR_16_SWAP: bool = lvgl_sys::LV_COLOR_16_SWAP != 0;
+
+// Flushed pixel buffers are reinterpreted as `Color`s, so its layout must
+// match `lv_color_t` exactly
+const _: () = assert!(mem::size_of::<Color>() == mem::size_of::<lvgl_sys::lv_color_t>());
+
 /// An LVGL color. Equivalent to `lv_color_t`.
 #[derive(Copy, Clone, Default)]
+#[repr(transparent)]
 pub struct Color {
     pub(crate) raw: lvgl_sys::lv_color_t,
 }
@@ -92,34 +109,110 @@ impl Color {
     pub fn b(&self) -> u8 {
         unsafe { lvgl_sys::_LV_COLOR_GET_B(self.raw) as u8 }
     }
+
+    /// Creates a `Color` from a 24-bit `0xRRGGBB` value, as in CSS.
+    pub fn from_hex(rgb: u32) -> Self {
+        Self::from_rgb(((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8))
+    }
+
+    /// Creates a `Color` from a hue in degrees (0 to 359), and a saturation
+    /// and value in percent (0 to 100).
+    pub fn from_hsv(h: u16, s: u8, v: u8) -> Self {
+        Self {
+            raw: unsafe { lvgl_sys::lv_color_hsv_to_rgb(h % 360, s.min(100), v.min(100)) },
+        }
+    }
+
+    /// Returns the hue in degrees, and the saturation and value in percent.
+    pub fn to_hsv(&self) -> (u16, u8, u8) {
+        let hsv = unsafe { lvgl_sys::lv_color_to_hsv(self.raw) };
+        (hsv.h, hsv.s, hsv.v)
+    }
+
+    /// Returns the red, green and blue channels scaled to 8 bits, whatever
+    /// the color depth. `r()`, `g()` and `b()` return them at the depth LVGL
+    /// was built with, e.g. 5 bits for red with 16-bit colors.
+    pub fn to_rgb(&self) -> (u8, u8, u8) {
+        let (r, g, b) = match COLOR_DEPTH {
+            1 => (1, 1, 1),
+            8 => (3, 3, 2),
+            16 => (5, 6, 5),
+            _ => return (self.r(), self.g(), self.b()),
+        };
+        let scale = |value: u8, bits: u32| (value as u32 * 255 / ((1 << bits) - 1)) as u8;
+        (scale(self.r(), r), scale(self.g(), g), scale(self.b(), b))
+    }
+
+    /// Mixes this color with `other`. A `ratio` of 255 results in this
+    /// color, 0 in `other`.
+    pub fn mix(&self, other: Color, ratio: u8) -> Self {
+        let (r1, g1, b1) = self.to_rgb();
+        let (r2, g2, b2) = other.to_rgb();
+        let mix = |a: u8, b: u8| {
+            ((a as u32 * ratio as u32 + b as u32 * (255 - ratio as u32) + 127) / 255) as u8
+        };
+        Self::from_rgb((mix(r1, r2), mix(g1, g2), mix(b1, b2)))
+    }
+
+    /// Mixes the color with white; a `level` of 255 results in white.
+    pub fn lighten(&self, level: u8) -> Self {
+        Self::from_rgb((255, 255, 255)).mix(*self, level)
+    }
+
+    /// Mixes the color with black; a `level` of 255 results in black.
+    pub fn darken(&self, level: u8) -> Self {
+        Self::from_rgb((0, 0, 0)).mix(*self, level)
+    }
+}
+
+impl PartialEq for Color {
+    fn eq(&self, other: &Self) -> bool {
+        (self.r(), self.g(), self.b()) == (other.r(), other.g(), other.b())
+    }
+}
+
+impl Eq for Color {}
+
+impl fmt::Debug for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (r, g, b) = self.to_rgb();
+        write!(f, "Color(#{:02x}{:02x}{:02x})", r, g, b)
+    }
 }
 
 #[cfg(feature = "embedded_graphics")]
 impl From<Color> for Rgb888 {
     fn from(color: Color) -> Self {
-        unsafe {
-            Rgb888::new(
-                lvgl_sys::_LV_COLOR_GET_R(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_G(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_B(color.raw) as u8,
-            )
-        }
+        let (r, g, b) = color.to_rgb();
+        Rgb888::new(r, g, b)
     }
 }
 
 #[cfg(feature = "embedded_graphics")]
 impl From<Color> for Rgb565 {
     fn from(color: Color) -> Self {
-        unsafe {
-            Rgb565::new(
-                lvgl_sys::_LV_COLOR_GET_R(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_G(color.raw) as u8,
-                lvgl_sys::_LV_COLOR_GET_B(color.raw) as u8,
-            )
+        if COLOR_DEPTH == 16 {
+            Rgb565::new(color.r(), color.g(), color.b())
+        } else {
+            Rgb888::from(color).into()
         }
     }
 }
 
+#[cfg(feature = "embedded_graphics")]
+impl From<Rgb888> for Color {
+    fn from(color: Rgb888) -> Self {
+        Color::from_rgb((color.r(), color.g(), color.b()))
+    }
+}
+
+#[cfg(feature = "embedded_graphics")]
+impl From<Rgb565> for Color {
+    fn from(color: Rgb565) -> Self {
+        Rgb888::from(color).into()
+    }
+}
+
 impl From<Color> for lvgl_sys::lv_color_t {
     fn from(val: Color) -> Self {
         val.raw
@@ -193,6 +286,23 @@ pub enum Event<T> {
     /// Called on focus
     Focused,
 
+    /// A process has finished, e.g. Enter was pressed in a one-line
+    /// `Textarea` or the OK key of a `Keyboard`
+    Ready,
+
+    /// A process has been cancelled, e.g. the close key of a `Keyboard`
+    Cancel,
+
+    /// The object's size changed, e.g. after a layout update
+    SizeChanged,
+
+    /// The layout of the object's children was recalculated
+    LayoutChanged,
+
+    /// Sent to every object on a display after a theme was applied to it with
+    /// `Theme::apply_to_display()`
+    ThemeChanged,
+
     /// Pointer-like input devices events (E.g. mouse or touchpad)
     Pointer(PointerEvent),
 
@@ -222,6 +332,14 @@ impl<S> TryFrom<lvgl_sys::lv_event_code_t> for Event<S> {
         const LV_EVENT_DRAW_POST: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST;
         const LV_EVENT_DRAW_POST_BEGIN: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST_BEGIN;
         const LV_EVENT_DRAW_POST_END: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST_END;
+        const LV_EVENT_SIZE_CHANGED: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_SIZE_CHANGED;
+        const LV_EVENT_LAYOUT_CHANGED: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_LAYOUT_CHANGED;
+        const LV_EVENT_READY: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_READY;
+        const LV_EVENT_CANCEL: u32 = lvgl_sys::lv_event_code_t_LV_EVENT_CANCEL;
+
+        if Some(value) == crate::theme::theme_changed_event_code() {
+            return Ok(Event::ThemeChanged);
+        }
 
         match value {
             LV_EVENT_PRESSED => Ok(Event::Pressed),
@@ -241,6 +359,10 @@ impl<S> TryFrom<lvgl_sys::lv_event_code_t> for Event<S> {
             LV_EVENT_DRAW_POST => Ok(Event::DrawPost),
             LV_EVENT_DRAW_POST_BEGIN => Ok(Event::DrawPostBegin),
             LV_EVENT_DRAW_POST_END => Ok(Event::DrawPostEnd),
+            LV_EVENT_SIZE_CHANGED => Ok(Event::SizeChanged),
+            LV_EVENT_LAYOUT_CHANGED => Ok(Event::LayoutChanged),
+            LV_EVENT_READY => Ok(Event::Ready),
+            LV_EVENT_CANCEL => Ok(Event::Cancel),
             _ => Err(()),
         }
     }
@@ -266,6 +388,13 @@ impl<S> From<Event<S>> for lvgl_sys::lv_event_code_t {
             Event::DrawPost => lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST,
             Event::DrawPostBegin => lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST_BEGIN,
             Event::DrawPostEnd => lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_POST_END,
+            Event::SizeChanged => lvgl_sys::lv_event_code_t_LV_EVENT_SIZE_CHANGED,
+            Event::LayoutChanged => lvgl_sys::lv_event_code_t_LV_EVENT_LAYOUT_CHANGED,
+            Event::Ready => lvgl_sys::lv_event_code_t_LV_EVENT_READY,
+            Event::Cancel => lvgl_sys::lv_event_code_t_LV_EVENT_CANCEL,
+            // Registered by `init()`, which runs before any event is sent
+            Event::ThemeChanged => crate::theme::theme_changed_event_code()
+                .unwrap_or(lvgl_sys::lv_event_code_t_LV_EVENT_ALL),
             // TODO: handle all types...
             _ => lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED,
         };
@@ -281,26 +410,158 @@ pub enum PointerEvent {
     DragThrowBegin,
 }
 
+/// An object whose event callback is currently running. Nested dispatches
+/// form a chain through the stack frames of `dispatching()`.
+struct Dispatch {
+    obj: *mut lvgl_sys::lv_obj_t,
+    prev: *const Dispatch,
+}
+
+static mut DISPATCH_TOP: *const Dispatch = ptr::null();
+
+/// Returns `true` if deleting `obj` would delete an object that is currently
+/// dispatching an event, i.e. `obj` itself or one of its ancestors is.
+pub(crate) fn is_dispatching(obj: *mut lvgl_sys::lv_obj_t) -> bool {
+    unsafe {
+        let mut dispatch = DISPATCH_TOP;
+        while let Some(d) = dispatch.as_ref() {
+            let mut current = d.obj;
+            while !current.is_null() {
+                if current == obj {
+                    return true;
+                }
+                current = lvgl_sys::lv_obj_get_parent(current);
+            }
+            dispatch = d.prev;
+        }
+    }
+    false
+}
+
+/// Deletes `obj`, or leaves it to the next `task_handler()` while it or one
+/// of its children is dispatching an event, see `Widget::del()`.
+pub(crate) unsafe fn del(obj: *mut lvgl_sys::lv_obj_t) {
+    if is_dispatching(obj) {
+        lvgl_sys::lv_obj_del_async(obj);
+    } else {
+        lvgl_sys::lv_obj_del(obj);
+    }
+}
+
+/// Runs a user callback on behalf of `obj`, remembering which object is
+/// dispatching so that it can't be deleted from under LVGL.
+pub(crate) unsafe fn dispatching<R>(obj: *mut lvgl_sys::lv_obj_t, f: impl FnOnce() -> R) -> R {
+    let dispatch = Dispatch {
+        obj,
+        prev: DISPATCH_TOP,
+    };
+    DISPATCH_TOP = &dispatch;
+    let result = f();
+    DISPATCH_TOP = dispatch.prev;
+    result
+}
+
+/// Frees the user data of the event with `lv_mem_free()`. Added for
+/// `LV_EVENT_DELETE` by `free_on_delete()`.
+unsafe extern "C" fn free_user_data_cb(event: *mut lvgl_sys::lv_event_t) {
+    lvgl_sys::lv_mem_free((*event).user_data);
+}
+
+/// Frees `buf`, allocated with `lv_mem_alloc()`, when `obj` is deleted. If
+/// `old` was passed here for `obj` before, it is freed right away instead,
+/// for buffers replaced while the object lives.
+pub(crate) unsafe fn free_on_delete(
+    obj: *mut lvgl_sys::lv_obj_t,
+    buf: *mut cty::c_void,
+    old: *mut cty::c_void,
+) {
+    if !old.is_null()
+        && lvgl_sys::lv_obj_remove_event_cb_with_user_data(obj, Some(free_user_data_cb), old)
+    {
+        lvgl_sys::lv_mem_free(old);
+    }
+    lvgl_sys::lv_obj_add_event_cb(
+        obj,
+        Some(free_user_data_cb),
+        lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+        buf,
+    );
+}
+
+/// Adds `cb` for every event of `obj`, with `f` boxed as its user data.
+/// `cb` gets the closure back with `event_closure()`, which frees it once
+/// `obj` is deleted.
+pub(crate) unsafe fn add_closure_cb<F>(
+    obj: *mut lvgl_sys::lv_obj_t,
+    cb: unsafe extern "C" fn(*mut lvgl_sys::lv_event_t),
+    f: F,
+) {
+    lvgl_sys::lv_obj_add_event_cb(
+        obj,
+        Some(cb),
+        lvgl_sys::lv_event_code_t_LV_EVENT_ALL,
+        crate::Box::into_raw(crate::Box::new(f)) as *mut _,
+    );
+}
+
+/// Returns the closure added with `add_closure_cb()`, or frees it and
+/// returns `None` on `LV_EVENT_DELETE`.
+pub(crate) unsafe fn event_closure<'e, F>(event: &lvgl_sys::lv_event_t) -> Option<&'e mut F> {
+    let f = event.user_data as *mut F;
+    if event.code == lvgl_sys::lv_event_code_t_LV_EVENT_DELETE {
+        drop(crate::Box::from_raw(f));
+        None
+    } else {
+        Some(&mut *f)
+    }
+}
+
 pub(crate) unsafe extern "C" fn event_callback<'a, T, F>(event: *mut lvgl_sys::lv_event_t)
 where
     T: Widget<'a> + Sized,
     F: FnMut(T, Event<<T as Widget<'a>>::SpecialEvent>),
 {
+    // get the Rust callback closure FnMut provided by users
+    let Some(user_closure) = event_closure::<F>(&*event) else {
+        return;
+    };
     let code = (*event).code;
     let obj = (*event).target;
     // convert the lv_event_code_t to lvgl-rs Event type
     if let Ok(code) = code.try_into() {
         if let Some(obj_ptr) = NonNull::new(obj) {
             let object = T::from_raw(obj_ptr).unwrap();
-            // get the pointer from the Rust callback closure FnMut provided by users
-            let user_closure = &mut *((*obj).user_data as *mut F);
-            // call user callback closure
-            user_closure(object, code);
+            dispatching(obj, || user_closure(object, code));
         }
     }
 }
 
+pub(crate) unsafe extern "C" fn value_changed_cb<'a, T, F>(event: *mut lvgl_sys::lv_event_t)
+where
+    T: Widget<'a> + WidgetValue,
+    F: FnMut(T, T::Value),
+{
+    let Some(f) = event_closure::<F>(&*event) else {
+        return;
+    };
+    if (*event).code != lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED {
+        return;
+    }
+    // The widget `f` was added to, rather than a child the event bubbled from
+    let obj = (*event).current_target;
+    if let Some(widget) = NonNull::new(obj).and_then(|obj| T::from_raw(obj)) {
+        let value = widget.value();
+        dispatching(obj, || f(widget, value));
+    }
+}
+
 /// Possible LVGL alignments for widgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Align {
     Center,
     TopLeft,
@@ -388,6 +649,28 @@ impl From<AnimationState> for lvgl_sys::lv_anim_enable_t {
     }
 }
 
+bitflags! {
+    /// Directions, e.g. the side a `Tabview` shows its tab buttons on or the
+    /// neighbours a `Tileview` tile can be swiped to.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Direction: u8 {
+        const NONE = lvgl_sys::LV_DIR_NONE as u8;
+        const LEFT = lvgl_sys::LV_DIR_LEFT as u8;
+        const RIGHT = lvgl_sys::LV_DIR_RIGHT as u8;
+        const TOP = lvgl_sys::LV_DIR_TOP as u8;
+        const BOTTOM = lvgl_sys::LV_DIR_BOTTOM as u8;
+        const HOR = lvgl_sys::LV_DIR_HOR as u8;
+        const VER = lvgl_sys::LV_DIR_VER as u8;
+        const ALL = lvgl_sys::LV_DIR_ALL as u8;
+    }
+}
+
+impl From<Direction> for lvgl_sys::lv_dir_t {
+    fn from(dir: Direction) -> Self {
+        dir.bits() as lvgl_sys::lv_dir_t
+    }
+}
+
 #[repr(u32)]
 pub enum LabelLongMode {
     Clip = lvgl_sys::LV_LABEL_LONG_CLIP,
@@ -421,4 +704,74 @@ mod test {
             assert_eq!(color.b(), 31);
         }
     }
+
+    #[cfg(feature = "embedded_graphics")]
+    #[test]
+    fn embedded_graphics_colors_convert() {
+        let color = Color::from_hex(0x2196F3);
+        let rgb888: Rgb888 = color.into();
+        assert_eq!(Color::from(rgb888), color);
+        let rgb565: Rgb565 = color.into();
+        if COLOR_DEPTH <= 16 {
+            assert_eq!(Color::from(rgb565), color);
+        }
+        assert_eq!(Rgb565::from(Color::from(Rgb565::new(31, 0, 0))), Rgb565::new(31, 0, 0));
+    }
+
+    #[test]
+    fn del_deferred_during_own_event() {
+        use crate::widgets::Btn;
+        use crate::{Display, NativeObject};
+
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.on_event(|btn, event| {
+            if let Event::Clicked = event {
+                btn.del();
+            }
+        })
+        .unwrap();
+        unsafe {
+            lvgl_sys::lv_event_send(
+                btn.raw().as_ptr(),
+                lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED,
+                ptr::null_mut(),
+            );
+        }
+        assert!(!is_dispatching(btn.raw().as_ptr()));
+        let raw = btn.raw().as_ptr();
+        assert!(unsafe { lvgl_sys::lv_obj_is_valid(raw) });
+        crate::task_handler();
+        assert!(!unsafe { lvgl_sys::lv_obj_is_valid(raw) });
+    }
+
+    #[test]
+    fn event_closures_freed_with_widget() {
+        use crate::widgets::Btn;
+        use crate::Display;
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        struct Guard;
+        static DROPPED: AtomicBool = AtomicBool::new(false);
+        impl Drop for Guard {
+            fn drop(&mut self) {
+                DROPPED.store(true, Ordering::SeqCst);
+            }
+        }
+
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut btn = Btn::create(&mut screen).unwrap();
+        let guard = Guard;
+        btn.on_event(move |_, _| {
+            let _ = &guard;
+        })
+        .unwrap();
+        assert!(!DROPPED.load(Ordering::SeqCst));
+        btn.del();
+        assert!(DROPPED.load(Ordering::SeqCst));
+    }
 }
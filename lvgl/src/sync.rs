@@ -0,0 +1,190 @@
+//! Sharing LVGL between threads
+//!
+//! LVGL is not thread-safe: every call into it, including `task_handler()`,
+//! must come from one thread at a time. With the `threadsafe` feature, this
+//! is enforced with a global lock. Taking it with `lock()` returns an
+//! `LvglGuard`, and every access to an object asserts that the lock is held:
+//!
+//! ```ignore
+//! // UI thread
+//! loop {
+//!     lvgl::sync::lock().run(lvgl::task_handler);
+//!     std::thread::sleep(Duration::from_millis(5));
+//! }
+//!
+//! // Worker thread
+//! let label: Shared<Label> = ...;
+//! let guard = lvgl::sync::lock();
+//! label.get_mut(&guard).set_text(c"Done")?;
+//! ```
+//!
+//! Widget handles are `Send` with this feature, as using them panics unless
+//! the current thread holds the lock. Wrapped in `Shared`, they can also be
+//! shared between threads, and only borrowed while the lock is held. Without
+//! `std`, threads can't be told apart, so only whether the lock is held at
+//! all is checked.
+//!
+//! The lock is a spin lock, so it works without an OS, e.g. between RTOS
+//! tasks; it is not reentrant. Event callbacks run inside `task_handler()`,
+//! with the lock already held, and must not take it again.
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+/// The `current_thread()` holding the lock.
+static OWNER: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns an identifier of the current thread, or 0 where threads can't be
+/// told apart.
+fn current_thread() -> usize {
+    #[cfg(feature = "std")]
+    {
+        std::thread_local!(static ID: u8 = const { 0 });
+        ID.with(|id| id as *const u8 as usize)
+    }
+    #[cfg(not(feature = "std"))]
+    0
+}
+
+/// Proof that the LVGL lock is held by the current thread. The lock is
+/// released when the guard is dropped.
+pub struct LvglGuard {
+    /// Keeps the guard on the thread that took the lock.
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Takes the LVGL lock, waiting for another thread to release it first.
+pub fn lock() -> LvglGuard {
+    loop {
+        if let Some(guard) = try_lock() {
+            return guard;
+        }
+        #[cfg(feature = "std")]
+        std::thread::yield_now();
+        #[cfg(not(feature = "std"))]
+        core::hint::spin_loop();
+    }
+}
+
+/// Takes the LVGL lock if no other thread holds it.
+pub fn try_lock() -> Option<LvglGuard> {
+    LOCKED
+        .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        .ok()
+        .map(|_| {
+            OWNER.store(current_thread(), Ordering::Relaxed);
+            LvglGuard {
+                _not_send: PhantomData,
+            }
+        })
+}
+
+/// Takes the lock for the current thread, whether it is held or not. Tests
+/// each run on a thread of their own, and leave the lock held when done.
+#[cfg(test)]
+pub(crate) fn steal() -> LvglGuard {
+    LOCKED.swap(true, Ordering::Acquire);
+    OWNER.store(current_thread(), Ordering::Relaxed);
+    LvglGuard {
+        _not_send: PhantomData,
+    }
+}
+
+impl LvglGuard {
+    /// Runs `f` with the lock held, e.g. `lock().run(lvgl::task_handler)`.
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+}
+
+impl Drop for LvglGuard {
+    fn drop(&mut self) {
+        OWNER.store(0, Ordering::Relaxed);
+        LOCKED.store(false, Ordering::Release);
+    }
+}
+
+/// Panics unless the current thread holds the LVGL lock. Called on every
+/// access to an object.
+#[inline]
+#[track_caller]
+pub(crate) fn assert_locked() {
+    assert!(
+        LOCKED.load(Ordering::Acquire) && OWNER.load(Ordering::Relaxed) == current_thread(),
+        "LVGL accessed without holding the lock from `lvgl::sync::lock()`"
+    );
+}
+
+/// A value, typically a widget handle, that may be sent to other threads but
+/// only used while holding the LVGL lock.
+pub struct Shared<T> {
+    inner: UnsafeCell<T>,
+}
+
+// SAFETY: The value is only reachable through an `LvglGuard`, so at most one
+// thread uses it at any time
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    /// Wraps `value`.
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: UnsafeCell::new(value),
+        }
+    }
+
+    /// Returns the value, for as long as `guard` is held.
+    pub fn get<'g>(&'g self, _guard: &'g LvglGuard) -> &'g T {
+        unsafe { &*self.inner.get() }
+    }
+
+    /// Returns the value mutably, for as long as `guard` is held. Borrowing
+    /// the guard mutably ensures no other reference to the value is alive.
+    pub fn get_mut<'g>(&'g self, _guard: &'g mut LvglGuard) -> &'g mut T {
+        unsafe { &mut *self.inner.get() }
+    }
+
+    /// Unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lock_is_exclusive() {
+        drop(steal());
+        let shared = Shared::new(1u32);
+        let mut guard = lock();
+        assert!(try_lock().is_none());
+        assert_locked();
+        *shared.get_mut(&mut guard) += 1;
+        assert_eq!(*shared.get(&guard), 2);
+        drop(guard);
+
+        let shared = std::sync::Arc::new(shared);
+        let worker = {
+            let shared = shared.clone();
+            std::thread::spawn(move || {
+                let mut guard = lock();
+                *shared.get_mut(&mut guard) += 1;
+            })
+        };
+        worker.join().unwrap();
+        assert_eq!(*shared.get(&lock()), 3);
+    }
+
+    #[test]
+    fn lock_belongs_to_its_thread() {
+        let _guard = steal();
+        assert_locked();
+        let other = std::thread::spawn(assert_locked);
+        assert!(other.join().is_err());
+    }
+}
@@ -0,0 +1,29 @@
+//! Running LVGL as an Embassy task
+//!
+//! Rather than polling `task_handler()` in a busy loop, `run()` sleeps on an
+//! `embassy-time` timer until LVGL's next timer is due, leaving the executor
+//! free for other tasks in between:
+//!
+//! ```ignore
+//! #[embassy_executor::task]
+//! async fn ui(display: Display) {
+//!     let mut screen = display.get_scr_act().unwrap();
+//!     // Build the UI...
+//!     lvgl::task::run(|| {}).await
+//! }
+//! ```
+//!
+//! The tick is read from `embassy-time` as well, so there is no need to call
+//! `tick_inc()`.
+
+use crate::timer::EmbassyTicks;
+use embassy_time::{Duration, Timer};
+
+/// Runs LVGL forever, calling `after_handler` after every `task_handler()`
+/// call, e.g. to feed a watchdog or apply updates from other tasks.
+pub async fn run<F: FnMut()>(after_handler: F) -> ! {
+    crate::timer::run(&EmbassyTicks, after_handler, |sleep| {
+        Timer::after(Duration::from_millis(sleep.as_millis() as u64))
+    })
+    .await
+}
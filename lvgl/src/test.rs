@@ -0,0 +1,345 @@
+//! Headless testing of user interfaces
+//!
+//! A `Harness` renders into memory instead of a panel, so widget wrappers and
+//! whole application screens can be exercised in CI. Time only moves when
+//! the test says so, input comes from a virtual pointer and keypad, and the
+//! rendered frame can be compared against a reference PNG:
+//!
+//! ```ignore
+//! let mut ui = Harness::new(240, 240)?;
+//! let mut screen = ui.display().get_scr_act()?;
+//! let mut btn = Btn::create(&mut screen)?;
+//! btn.set_size(100, 40);
+//! ui.click(50, 20);
+//! ui.advance(Duration::from_millis(500));
+//! ui.assert_golden("tests/golden/pressed.png");
+//! ```
+//!
+//! A missing reference image fails the test like a differing one. To write
+//! new references or accept changed output, run the tests with
+//! `LVGL_UPDATE_GOLDEN=1` set, which overwrites the references instead of
+//! comparing against them.
+//!
+//! The tick is driven by a `DeterministicClock`, so the harness is only
+//! available without the `rust_timer` and `custom_timer` features.
+
+use crate::input_device::inject::VirtualInput;
+use crate::misc::capture::{CapturedFrame, FrameTap};
+use crate::misc::deterministic::DeterministicClock;
+use crate::{Display, DrawBuffer, Group, LvError, LvResult};
+use core::fmt;
+use core::time::Duration;
+use std::cell::RefCell;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+pub use crate::misc::screenshot::{Diff, Screenshot};
+
+/// Size of the draw buffer in pixels, which is also the widest display a
+/// harness can render.
+const BUFFER_PIXELS: usize = 240 * 16;
+
+/// How long a pointer or key is held by `click()` and `key()`, and how long
+/// is waited after releasing it. Longer than LVGL's input read period, so
+/// each state is seen by at least one read.
+const HOLD: Duration = Duration::from_millis(50);
+
+/// Environment variable making `assert_golden()` overwrite the references.
+const UPDATE_GOLDEN: &str = "LVGL_UPDATE_GOLDEN";
+
+type Tap = FrameTap<fn(&CapturedFrame)>;
+
+/// A display rendering into memory, with virtual input devices and a
+/// deterministic clock.
+pub struct Harness {
+    display: Display,
+    frames: Rc<RefCell<Tap>>,
+    clock: DeterministicClock,
+    input: VirtualInput,
+    /// The pointer position and state injected last.
+    pointer: (i16, i16, bool),
+    tolerance: u8,
+}
+
+impl Harness {
+    /// Registers a `width` by `height` display rendering into memory and
+    /// makes it the default display. Fails with `LvError::InvalidArgument`
+    /// if either side is zero or the display is wider than the draw buffer.
+    pub fn new(width: u16, height: u16) -> LvResult<Self> {
+        if width == 0 || height == 0 || width as usize > BUFFER_PIXELS {
+            return Err(LvError::InvalidArgument);
+        }
+        let frames: Rc<RefCell<Tap>> = Rc::new(RefCell::new(FrameTap::new(
+            width,
+            height,
+            1,
+            (|_| {}) as fn(&CapturedFrame),
+        )?));
+        let tap = frames.clone();
+        let display = Display::register(
+            DrawBuffer::<BUFFER_PIXELS>::default(),
+            width as u32,
+            height as u32,
+            move |refresh| tap.borrow_mut().capture(refresh),
+        )
+        .map_err(|_| LvError::LvOOMemory)?;
+        display.set_default();
+
+        let input = match VirtualInput::register(&display) {
+            Ok(input) => input,
+            Err(e) => {
+                unsafe { crate::display::disp_remove(display.disp.as_ptr()) };
+                return Err(e);
+            }
+        };
+        Ok(Self {
+            display,
+            frames,
+            clock: DeterministicClock::new(),
+            input,
+            pointer: (0, 0, false),
+            tolerance: 0,
+        })
+    }
+
+    /// Returns the display the harness renders.
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Advances time by `by`, running timers, animations and input reads
+    /// along the way.
+    pub fn advance(&mut self, by: Duration) {
+        self.clock.advance(by);
+    }
+
+    /// Returns the time the harness has been advanced by so far.
+    pub fn elapsed(&self) -> Duration {
+        self.clock.elapsed()
+    }
+
+    /// Moves the virtual pointer to `(x, y)` and presses it. Takes effect
+    /// with the next input read, i.e. the next `advance()`.
+    pub fn press(&mut self, x: i16, y: i16) {
+        self.pointer = (x, y, true);
+        self.inject_pointer();
+    }
+
+    /// Moves the virtual pointer to `(x, y)`, keeping it pressed or
+    /// released, e.g. to drag or scroll.
+    pub fn move_to(&mut self, x: i16, y: i16) {
+        self.pointer.0 = x;
+        self.pointer.1 = y;
+        self.inject_pointer();
+    }
+
+    /// Releases the virtual pointer where it is.
+    pub fn release(&mut self) {
+        self.pointer.2 = false;
+        self.inject_pointer();
+    }
+
+    /// Clicks at `(x, y)`: presses, advances time, releases and advances
+    /// time again.
+    pub fn click(&mut self, x: i16, y: i16) {
+        self.press(x, y);
+        self.advance(HOLD);
+        self.release();
+        self.advance(HOLD);
+    }
+
+    /// Sends the widgets in `group` the keys of the virtual keypad.
+    pub fn set_keypad_group(&mut self, group: &Group) -> LvResult<()> {
+        self.input.set_keypad_group(group)
+    }
+
+    /// Presses and releases `key`, one of the `LV_KEY_*` constants or a
+    /// character, on the virtual keypad and advances time until both were
+    /// read.
+    pub fn key(&mut self, key: u32) {
+        self.input
+            .inject_key(key)
+            .expect("too many keys injected without advancing time");
+        self.advance(HOLD);
+        self.advance(HOLD);
+    }
+
+    /// Sets how far each color channel of a pixel may differ from the
+    /// reference in `compare_golden()`, e.g. to absorb rounding differences
+    /// between color depths. Defaults to 0.
+    pub fn set_tolerance(&mut self, tolerance: u8) {
+        self.tolerance = tolerance;
+    }
+
+    /// Renders everything that changed and returns the whole frame.
+    pub fn screenshot(&mut self) -> Screenshot {
+        unsafe { lvgl_sys::lv_refr_now(self.display.disp.as_ptr()) };
+        let frames = self.frames.borrow();
+        let frame = frames
+            .frames()
+            .last()
+            .expect("a display is completely drawn on its first refresh");
+        Screenshot::from_colors(frame.width() as u32, frame.height() as u32, frame.pixels())
+    }
+
+    /// Renders the display and compares it against the PNG at `path`.
+    pub fn compare_golden(&mut self, path: impl AsRef<Path>) -> Result<Diff, GoldenError> {
+        let reference = Screenshot::load_png(path)?;
+        self.diff(&self.screenshot(), &reference)
+    }
+
+    /// Renders the display and panics unless it matches the PNG at `path`.
+    /// The reference is written instead if `LVGL_UPDATE_GOLDEN` is set.
+    pub fn assert_golden(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let screenshot = self.screenshot();
+        if std::env::var_os(UPDATE_GOLDEN).is_some() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).ok();
+            }
+            if let Err(e) = screenshot.save_png(path) {
+                panic!("failed to write {}: {}", path.display(), e);
+            }
+            return;
+        }
+        let diff = Screenshot::load_png(path)
+            .map_err(GoldenError::from)
+            .and_then(|reference| self.diff(&screenshot, &reference));
+        match diff {
+            Ok(diff) if diff.is_match() => {}
+            Ok(diff) => {
+                let actual = path.with_extension("actual.png");
+                screenshot.save_png(&actual).ok();
+                panic!(
+                    "{} differs from the rendered frame in {} pixels, first at {:?}; \
+                     the frame was written to {}",
+                    path.display(),
+                    diff.pixels,
+                    diff.first.unwrap_or_default(),
+                    actual.display(),
+                );
+            }
+            Err(e) => panic!("failed to compare against {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl Harness {
+    fn diff(&self, actual: &Screenshot, reference: &Screenshot) -> Result<Diff, GoldenError> {
+        actual
+            .diff(reference, self.tolerance)
+            .ok_or(GoldenError::Size {
+                expected: (reference.width(), reference.height()),
+                actual: (actual.width(), actual.height()),
+            })
+    }
+
+    fn inject_pointer(&mut self) {
+        let (x, y, pressed) = self.pointer;
+        self.input
+            .inject_pointer(x, y, pressed)
+            .expect("too many events injected without advancing time");
+    }
+}
+
+impl Drop for Harness {
+    fn drop(&mut self) {
+        unsafe { crate::display::disp_remove(self.display.disp.as_ptr()) };
+    }
+}
+
+/// Errors reading or comparing reference images.
+#[derive(Debug)]
+pub enum GoldenError {
+    /// The reference image can't be read or decoded.
+    Io(io::Error),
+    /// The reference image has a different size than the frame.
+    Size {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoldenError::Io(e) => e.fmt(f),
+            GoldenError::Size { expected, actual } => write!(
+                f,
+                "expected a {}x{} image, rendered {}x{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+impl From<io::Error> for GoldenError {
+    fn from(e: io::Error) -> Self {
+        GoldenError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::style::{Opacity, Style};
+    use crate::widgets::Btn;
+    use crate::{Align, Color, Event, Part, Widget};
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    static CLICKS: AtomicU32 = AtomicU32::new(0);
+
+    #[test]
+    fn renders_and_clicks() {
+        crate::tests::initialize_test(false);
+        assert!(matches!(Harness::new(0, 32), Err(LvError::InvalidArgument)));
+        assert!(matches!(Harness::new(64, 0), Err(LvError::InvalidArgument)));
+        let mut ui = Harness::new(64, 32).unwrap();
+        let mut screen = ui.display().get_scr_act().unwrap();
+        let mut style = Style::default();
+        style.set_bg_color(Color::from_rgb((255, 0, 0)));
+        style.set_bg_opa(Opacity::OPA_COVER);
+        style.set_radius(0);
+        style.set_border_width(0);
+        style.set_shadow_width(0);
+        let mut btn = Btn::create(&mut screen).unwrap();
+        btn.add_style(Part::Main, &mut style);
+        btn.set_size(32, 16);
+        btn.set_align(Align::TopLeft, 0, 0);
+        btn.on_event(|_, event| {
+            if let Event::Clicked = event {
+                CLICKS.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .unwrap();
+
+        let shot = ui.screenshot();
+        assert_eq!((shot.width(), shot.height()), (64, 32));
+        assert_eq!(shot.pixel(4, 4), [255, 0, 0]);
+        assert_ne!(shot.pixel(60, 28), [255, 0, 0]);
+
+        ui.click(10, 8);
+        assert_eq!(CLICKS.load(Ordering::SeqCst), 1);
+        ui.click(50, 24);
+        assert_eq!(CLICKS.load(Ordering::SeqCst), 1);
+
+        let golden = std::env::temp_dir().join("lvgl_harness_golden.png");
+        std::fs::remove_file(&golden).ok();
+        let missing = catch_unwind(AssertUnwindSafe(|| ui.assert_golden(&golden)));
+        assert!(missing.is_err());
+        ui.screenshot().save_png(&golden).unwrap();
+        ui.assert_golden(&golden);
+        assert!(ui.compare_golden(&golden).unwrap().is_match());
+        let mut rgb = Screenshot::load_png(&golden).unwrap().into_rgb();
+        rgb[0] ^= 0xFF;
+        let other = Screenshot::from_rgb(64, 32, rgb).unwrap();
+        let diff = ui.screenshot().diff(&other, 8).unwrap();
+        assert_eq!(diff.pixels, 1);
+        assert_eq!(diff.first, Some((0, 0)));
+        std::fs::remove_file(&golden).ok();
+    }
+}
@@ -1,20 +1,38 @@
 //! Rust timer handling logic
 //!
-//! LVGL allows for an external timer function to be used. This feature enables
-//! a generic `LvClock` interface that can be used to build Rust-native timers.
+//! LVGL allows for an external timer function to be used. With the
+//! `rust_timer` feature, LVGL is built with `LV_TICK_CUSTOM` and reads its
+//! tick on demand from Rust, instead of relying on `lv_tick_inc()` being
+//! called periodically.
 //!
 //! # Building
 //!
-//! Set `LV_TICK_CUSTOM` to `1` and `LV_TICK_CUSTOM_INCLUDE` to `<rs_timer.h>`
-//! in `lv_conf.h`, and enable the `rust_timer` feature on the `lvgl` crate to
-//! enable this functionality.
+//! The `rust_timer` feature sets `LV_TICK_CUSTOM` to `1` and
+//! `LV_TICK_CUSTOM_INCLUDE` to `<rs_timer.h>`. If you use your own
+//! `lv_conf.h`, wrap its `LV_TICK_CUSTOM*` defines in `#ifndef` guards for
+//! this to take effect.
 //!
 //! # Usage
 //!
-//! Implement the `lvgl::timer::LvClock` trait on a type and initialize it on
-//! the first frame. The `since_init()` function should return a `Duration`
-//! representing time elapsed since the beginning of the first frame of the
-//! program.
+//! Register a `TickSource` once at startup. LVGL then asks it for the time
+//! whenever it needs it:
+//!
+//! ```ignore
+//! lvgl::timer::set_tick_source(&lvgl::timer::StdTicks);
+//! loop {
+//!     lvgl::task_handler();
+//! }
+//! ```
+//!
+//! Implementations are provided for `std::time::Instant` (`std` feature),
+//! `embassy-time` (`embassy` feature) and RTIC monotonics (`rtic` feature).
+//! Other clocks only need to implement `TickSource::now_ms()`.
+//!
+//! Alternatively, implement the `lvgl::timer::LvClock` trait on a type and
+//! push its time to LVGL with `update_clock()` before every
+//! `task_handler()` call. The `since_init()` function should return a
+//! `Duration` representing time elapsed since the beginning of the first
+//! frame of the program.
 //!
 //! ```no_run
 //! use lvgl::timer::LvClock;
@@ -48,14 +66,125 @@
 //! ```
 //!
 //! For a full example implementation similar to the above, see the
-//! `rust_timer` example. When running, make sure to modify the config in
-//! `examples/include/lv_conf.h` (or your own) as above first.
+//! `rust_timer` example.
 
 use core::num::TryFromIntError;
+use core::ptr;
 use core::time::Duration;
 
 static mut RET_VAL: u32 = 0;
 
+/// The source registered with `set_tick_source()`, if any.
+static mut TICK_SOURCE: Option<&'static dyn TickSource> = None;
+
+/// A clock LVGL reads its tick from, on demand.
+pub trait TickSource: Sync {
+    /// Returns the milliseconds elapsed since a fixed point in time, e.g.
+    /// boot. May wrap around.
+    fn now_ms(&self) -> u32;
+}
+
+/// Makes LVGL read its tick from `source` from now on, taking precedence
+/// over `update_clock()`.
+pub fn set_tick_source(source: &'static dyn TickSource) {
+    unsafe { TICK_SOURCE = Some(source) }
+}
+
+/// The longest the `run()` loops of `task` and `rtic` sleep when no LVGL
+/// timer is running, so that e.g. input devices are still read.
+#[cfg(any(feature = "embassy", feature = "rtic"))]
+const MAX_IDLE: Duration = Duration::from_millis(50);
+
+/// Runs the LVGL timers that are due, and returns how long until they are
+/// to be run again, at most `MAX_IDLE`.
+#[cfg(any(feature = "embassy", feature = "rtic"))]
+pub(crate) fn run_due() -> Duration {
+    crate::task_handler_next().map_or(MAX_IDLE, |next| next.min(MAX_IDLE))
+}
+
+/// Runs LVGL forever with its tick read from `ticks`, calling
+/// `after_handler` after every round of timers and awaiting `sleep` until
+/// the next one.
+#[cfg(any(feature = "embassy", feature = "rtic"))]
+pub(crate) async fn run<S, F>(
+    ticks: &'static dyn TickSource,
+    mut after_handler: impl FnMut(),
+    mut sleep: S,
+) -> !
+where
+    S: FnMut(Duration) -> F,
+    F: core::future::Future<Output = ()>,
+{
+    set_tick_source(ticks);
+    loop {
+        let next = run_due();
+        after_handler();
+        sleep(next).await;
+    }
+}
+
+/// Ticks measured with `std::time::Instant`, from the first time they are
+/// read.
+#[cfg(feature = "std")]
+pub struct StdTicks;
+
+#[cfg(feature = "std")]
+impl TickSource for StdTicks {
+    fn now_ms(&self) -> u32 {
+        use std::sync::OnceLock;
+        use std::time::Instant;
+
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed().as_millis() as u32
+    }
+}
+
+/// Ticks of the `embassy-time` driver.
+#[cfg(feature = "embassy")]
+pub struct EmbassyTicks;
+
+#[cfg(feature = "embassy")]
+impl TickSource for EmbassyTicks {
+    fn now_ms(&self) -> u32 {
+        embassy_time::Instant::now().as_millis() as u32
+    }
+}
+
+/// Ticks of an RTIC monotonic with 64-bit instants, such as the ones of
+/// `rtic-monotonics`.
+///
+/// ```ignore
+/// static TICKS: RticTicks<Systick> = RticTicks::new();
+/// lvgl::timer::set_tick_source(&TICKS);
+/// ```
+#[cfg(feature = "rtic")]
+pub struct RticTicks<M>(pub(crate) core::marker::PhantomData<fn() -> M>);
+
+#[cfg(feature = "rtic")]
+impl<M> RticTicks<M> {
+    /// Creates the tick source; usable in a `static`.
+    pub const fn new() -> Self {
+        Self(core::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "rtic")]
+impl<M> Default for RticTicks<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "rtic")]
+impl<M, const FREQ: u32> TickSource for RticTicks<M>
+where
+    M: rtic_time::Monotonic<Instant = fugit::TimerInstantU64<FREQ>>,
+{
+    fn now_ms(&self) -> u32 {
+        M::now().duration_since_epoch().to_millis() as u32
+    }
+}
+
 /// An LVGL-compatible clock
 pub trait LvClock {
     /// Returns the time since the clock was first initialized
@@ -70,5 +199,8 @@ pub fn update_clock(clock: &impl LvClock) -> Result<(), TryFromIntError> {
 
 #[no_mangle]
 unsafe extern "C" fn rs_lv_timer() -> u32 {
-    RET_VAL
+    match *ptr::addr_of!(TICK_SOURCE) {
+        Some(source) => source.now_ms(),
+        None => RET_VAL,
+    }
 }
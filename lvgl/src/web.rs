@@ -0,0 +1,167 @@
+//! Running LVGL in a web page
+//!
+//! Compiled for `wasm32-unknown-emscripten`, a `WebCanvas` draws a display
+//! into an HTML `<canvas>` and reads mouse and touch input from it, so UI
+//! demos can be shared as web pages:
+//!
+//! ```ignore
+//! use cstr_core::cstr;
+//! use lvgl::web::{self, WebCanvas};
+//!
+//! fn main() {
+//!     let buffer = DrawBuffer::<{ 320 * 24 }>::default();
+//!     let canvas = WebCanvas::new(cstr!("#lvgl"), buffer, 320, 240).unwrap();
+//!     let mut screen = canvas.display.get_scr_act().unwrap();
+//!     // Build the UI...
+//!     web::run(move || {
+//!         // Called once per animation frame
+//!     })
+//! }
+//! ```
+//!
+//! # Building
+//!
+//! Install and activate the [Emscripten SDK], which `lvgl-sys` finds through
+//! the `EMSDK` environment variable, and link the JavaScript half of the
+//! backend, `web/lvgl_canvas.js` in this crate, into the page:
+//!
+//! ```sh
+//! RUSTFLAGS="-C link-arg=--js-library=path/to/lvgl/web/lvgl_canvas.js" \
+//!     cargo build --target wasm32-unknown-emscripten --features web
+//! ```
+//!
+//! This emits a `.js` and a `.wasm` file, which are loaded by a page with a
+//! `<script>` tag once the canvas element exists.
+//!
+//! [Emscripten SDK]: https://emscripten.org/docs/getting_started/downloads.html
+
+use crate::input_device::pointer::{Pointer, PointerInputData};
+use crate::input_device::{BufferStatus, InputDriver};
+use crate::{Display, DisplayError, DrawBuffer, Point};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ffi::{c_char, c_int, c_void, CStr};
+
+extern "C" {
+    // Defined in web/lvgl_canvas.js
+    fn lvgl_canvas_init(selector: *const c_char, width: c_int, height: c_int) -> c_int;
+    fn lvgl_canvas_blit(rgba: *const u8, x: c_int, y: c_int, width: c_int, height: c_int);
+    fn lvgl_canvas_pointer(x: *mut c_int, y: *mut c_int) -> c_int;
+
+    fn emscripten_get_now() -> f64;
+    fn emscripten_set_main_loop_arg(
+        func: unsafe extern "C" fn(*mut c_void),
+        arg: *mut c_void,
+        fps: c_int,
+        simulate_infinite_loop: c_int,
+    );
+}
+
+/// A display drawn into a `<canvas>` element, and a pointer input device
+/// reading the mouse or touches on it.
+pub struct WebCanvas {
+    pub display: Display,
+    pub pointer: Pointer,
+}
+
+impl WebCanvas {
+    /// Sizes the first element matching the CSS `selector`, e.g. `#lvgl`,
+    /// to `hor_res` by `ver_res` pixels and registers a display drawing into
+    /// it. Fails with `DisplayError::NotAvailable` if there is no such canvas.
+    pub fn new<const N: usize>(
+        selector: &CStr,
+        draw_buffer: DrawBuffer<N>,
+        hor_res: u32,
+        ver_res: u32,
+    ) -> Result<Self, DisplayError> {
+        if unsafe { lvgl_canvas_init(selector.as_ptr(), hor_res as c_int, ver_res as c_int) } == 0
+        {
+            return Err(DisplayError::NotAvailable);
+        }
+        // Canvases take 8-bit RGBA, whatever depth LVGL renders in
+        let mut rgba = Vec::with_capacity(N * 4);
+        let display = Display::register(draw_buffer, hor_res, ver_res, move |refresh| {
+            rgba.clear();
+            for color in refresh.area_colors() {
+                let (r, g, b) = color.to_rgb();
+                rgba.extend_from_slice(&[r, g, b, 0xff]);
+            }
+            let area = refresh.area;
+            unsafe {
+                lvgl_canvas_blit(
+                    rgba.as_ptr(),
+                    area.x1.into(),
+                    area.y1.into(),
+                    area.width().into(),
+                    area.height().into(),
+                )
+            };
+        })?;
+        let pointer =
+            Pointer::register(read_pointer, &display).map_err(|_| DisplayError::FailedToRegister)?;
+        Ok(Self { display, pointer })
+    }
+}
+
+fn read_pointer() -> BufferStatus {
+    let (mut x, mut y) = (0, 0);
+    let pressed = unsafe { lvgl_canvas_pointer(&mut x, &mut y) } != 0;
+    let data = PointerInputData::Touch(Point::new(x, y));
+    if pressed {
+        data.pressed().once()
+    } else {
+        data.released().once()
+    }
+}
+
+/// Milliseconds since the page was loaded, as measured by the browser.
+#[cfg(feature = "rust_timer")]
+pub struct WebTicks;
+
+#[cfg(feature = "rust_timer")]
+impl crate::timer::TickSource for WebTicks {
+    fn now_ms(&self) -> u32 {
+        unsafe { emscripten_get_now() as u32 }
+    }
+}
+
+struct MainLoop<F> {
+    frame: F,
+    /// The time the tick was increased up to, less than a millisecond behind
+    /// the last step.
+    #[cfg(not(feature = "rust_timer"))]
+    last: f64,
+}
+
+/// Calls `frame`, then `task_handler()`, on every animation frame of the
+/// browser, and keeps LVGL's tick in step with the browser's clock. Never
+/// returns: a loop in `main()` would block the page, so the browser calls
+/// back into the program instead.
+pub fn run<F: FnMut() + 'static>(frame: F) -> ! {
+    #[cfg(feature = "rust_timer")]
+    crate::timer::set_tick_source(&WebTicks);
+    let state = Box::new(MainLoop {
+        frame,
+        #[cfg(not(feature = "rust_timer"))]
+        last: unsafe { emscripten_get_now() },
+    });
+    unsafe {
+        // With `simulate_infinite_loop` set, this unwinds out of `main()`
+        emscripten_set_main_loop_arg(step::<F>, Box::into_raw(state) as *mut c_void, 0, 1);
+    }
+    unreachable!("emscripten_set_main_loop_arg() returned")
+}
+
+unsafe extern "C" fn step<F: FnMut()>(arg: *mut c_void) {
+    let state = &mut *(arg as *mut MainLoop<F>);
+    #[cfg(not(feature = "rust_timer"))]
+    {
+        let now = emscripten_get_now();
+        // The tick counts whole milliseconds, so the fraction is carried over
+        let ms = (now - state.last) as u32;
+        crate::tick_inc(core::time::Duration::from_millis(ms as u64));
+        state.last += ms as f64;
+    }
+    (state.frame)();
+    crate::task_handler();
+}
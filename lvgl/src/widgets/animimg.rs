@@ -0,0 +1,61 @@
+use crate::draw::ImageDsc;
+use crate::widgets::Animimg;
+use crate::{LvError, LvResult, NativeObject};
+use core::{mem, slice};
+
+impl<'a> Animimg<'a> {
+    /// Sets the images shown one after the other. The list is copied, but
+    /// the images themselves must outlive the widget.
+    pub fn set_src(&mut self, frames: &[&'a ImageDsc<'a>]) -> LvResult<()> {
+        let count = u8::try_from(frames.len()).map_err(|_| LvError::InvalidArgument)?;
+        let obj = self.core.raw().as_ptr();
+        let size = frames.len().max(1) * mem::size_of::<*const cty::c_void>();
+        let buf = unsafe { lvgl_sys::lv_mem_alloc(size as _) } as *mut *const cty::c_void;
+        if buf.is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+        let list = unsafe { slice::from_raw_parts_mut(buf, frames.len()) };
+        for (dst, frame) in list.iter_mut().zip(frames) {
+            *dst = frame.raw() as *const cty::c_void;
+        }
+        unsafe {
+            let old = (*(obj as *mut lvgl_sys::lv_animimg_t)).dsc as *mut cty::c_void;
+            lvgl_sys::lv_animimg_set_src(obj, buf as *mut _, count);
+            // The previous list is only freed if it was allocated here
+            crate::support::free_on_delete(obj, buf as *mut cty::c_void, old);
+        }
+        Ok(())
+    }
+
+    /// Sets how long one pass through all images takes, in milliseconds.
+    pub fn set_duration(&mut self, duration: u32) {
+        unsafe { lvgl_sys::lv_animimg_set_duration(self.core.raw().as_ptr(), duration) }
+    }
+
+    /// Returns the number of images set with `set_src()`.
+    pub fn frame_count(&self) -> u8 {
+        let animimg = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_animimg_t) };
+        animimg.pic_count as u8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::draw::ColorFormat;
+    use crate::{Display, Widget};
+
+    #[test]
+    fn frames_and_duration() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let pixels = [0u8; 4 * 4 * 4];
+        let frame = ImageDsc::from_raw(4, 4, ColorFormat::TrueColor, &pixels).unwrap();
+        let mut animimg = Animimg::create(&mut screen).unwrap();
+        animimg.set_src(&[&frame, &frame, &frame]).unwrap();
+        animimg.set_duration(300);
+        assert_eq!(animimg.frame_count(), 3);
+        animimg.del();
+    }
+}
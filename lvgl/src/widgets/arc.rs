@@ -1,42 +1,144 @@
 use crate::lv_core::obj::NativeObject;
+use crate::misc::scale;
 use crate::widgets::Arc;
+use crate::WidgetValue;
+
+/// Where the indicator of an `Arc` starts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ArcMode {
+    /// From the start of the background, clockwise.
+    Normal,
+    /// From the middle of the background, for ranges spanning negative and
+    /// positive values.
+    Symmetrical,
+    /// From the end of the background, counter-clockwise.
+    Reverse,
+}
+
+impl From<ArcMode> for lvgl_sys::lv_arc_mode_t {
+    fn from(mode: ArcMode) -> Self {
+        (match mode {
+            ArcMode::Normal => lvgl_sys::LV_ARC_MODE_NORMAL,
+            ArcMode::Symmetrical => lvgl_sys::LV_ARC_MODE_SYMMETRICAL,
+            ArcMode::Reverse => lvgl_sys::LV_ARC_MODE_REVERSE,
+        }) as lvgl_sys::lv_arc_mode_t
+    }
+}
+
+/// Converts degrees clockwise from the right, possibly negative or past a
+/// full turn, to the range LVGL takes.
+fn degrees(angle: i32) -> u16 {
+    angle.rem_euclid(360) as u16
+}
 
 impl Arc<'_> {
-    // /// Set the start angle, for the given arc part.
-    // /// 0 degrees for the right, 90 degrees for the bottom, etc.
-    // pub fn set_start_angle(&mut self, angle: u16, part: ArcPart) -> LvResult<()> {
-    //     match part {
-    //         ArcPart::Background => unsafe {
-    //             lvgl_sys::lv_arc_set_bg_start_angle(self.core.raw()?.as_mut(), angle)
-    //         },
-    //         ArcPart::Indicator => unsafe {
-    //             lvgl_sys::lv_arc_set_start_angle(self.core.raw()?.as_mut(), angle)
-    //         },
-    //     }
-    //     Ok(())
-    // }
-    //
-    // /// Set the end angle, for the given arc part.
-    // /// 0 degrees for the right, 90 degrees for the bottom, etc.
-    // pub fn set_end_angle(&self, angle: u16, part: ArcPart) -> LvResult<()> {
-    //     match part {
-    //         ArcPart::Background => unsafe {
-    //             lvgl_sys::lv_arc_set_bg_end_angle(self.core.raw()?.as_mut(), angle)
-    //         },
-    //         ArcPart::Indicator => unsafe {
-    //             lvgl_sys::lv_arc_set_end_angle(self.core.raw()?.as_mut(), angle)
-    //         },
-    //     }
-    //     Ok(())
-    // }
-    //
-    // /// Rotate the arc, `angle` degrees clockwise.
-    // pub fn set_rotation(&mut self, angle: u16) -> LvResult<()> {
-    //     unsafe {
-    //         lvgl_sys::lv_arc_set_rotation(self.core.raw()?.as_mut(), angle);
-    //     }
-    //     Ok(())
-    // }
+    /// Sets the value, clamped to the arc's range. The indicator and knob
+    /// move to it right away.
+    pub fn set_value(&mut self, value: i16) {
+        unsafe { lvgl_sys::lv_arc_set_value(self.core.raw().as_mut(), value) }
+    }
+
+    /// Sets the minimum and maximum value of the arc.
+    pub fn set_range(&mut self, min: i16, max: i16) {
+        unsafe { lvgl_sys::lv_arc_set_range(self.core.raw().as_mut(), min, max) }
+    }
+
+    /// Sets where the indicator starts, see `ArcMode`.
+    pub fn set_mode(&mut self, mode: ArcMode) {
+        unsafe { lvgl_sys::lv_arc_set_mode(self.core.raw().as_mut(), mode.into()) }
+    }
+
+    /// Gets where the indicator starts.
+    pub fn get_mode(&self) -> ArcMode {
+        match unsafe { lvgl_sys::lv_arc_get_mode(self.core.raw().as_ptr()) } as u32 {
+            lvgl_sys::LV_ARC_MODE_SYMMETRICAL => ArcMode::Symmetrical,
+            lvgl_sys::LV_ARC_MODE_REVERSE => ArcMode::Reverse,
+            _ => ArcMode::Normal,
+        }
+    }
+
+    /// Rotates the whole arc `angle` degrees clockwise, e.g. `-90` to start
+    /// at the top. Angles are measured from the right.
+    pub fn set_rotation(&mut self, angle: i32) {
+        unsafe { lvgl_sys::lv_arc_set_rotation(self.core.raw().as_mut(), degrees(angle)) }
+    }
+
+    /// Sets the background to run clockwise from `start` to `end` degrees,
+    /// relative to the rotation. A circular slider with a gap at the bottom
+    /// is e.g. `set_rotation(135)` and `set_bg_angles(0, 270)`.
+    pub fn set_bg_angles(&mut self, start: i32, end: i32) {
+        unsafe {
+            lvgl_sys::lv_arc_set_bg_angles(self.core.raw().as_mut(), degrees(start), degrees(end))
+        }
+    }
+
+    /// Sets the value as a fraction of the arc's range, `0.0` being the
+    /// minimum and `1.0` the maximum.
+    pub fn set_value_f32(&mut self, value: f32) {
+        let (min, max) = self.range();
+        unsafe {
+            lvgl_sys::lv_arc_set_value(
+                self.core.raw().as_mut(),
+                scale::denormalize(value, min, max) as i16,
+            );
+        }
+    }
+
+    /// Gets the current value as a fraction of the arc's range.
+    pub fn get_value_f32(&self) -> f32 {
+        let (min, max) = self.range();
+        let value = unsafe { lvgl_sys::lv_arc_get_value(self.core.raw().as_ptr()) };
+        scale::normalize(value.into(), min, max)
+    }
+
+    fn range(&self) -> (i32, i32) {
+        unsafe {
+            let obj = self.core.raw().as_ptr();
+            (
+                lvgl_sys::lv_arc_get_min_value(obj).into(),
+                lvgl_sys::lv_arc_get_max_value(obj).into(),
+            )
+        }
+    }
+}
+
+/// The value the knob is at.
+impl WidgetValue for Arc<'_> {
+    type Value = i16;
+
+    fn value(&self) -> i16 {
+        unsafe { lvgl_sys::lv_arc_get_value(self.core.raw().as_ptr()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Event, Widget};
+    use core::sync::atomic::{AtomicI16, Ordering};
+
+    #[test]
+    fn circular_slider() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut arc = Arc::create(&mut screen).unwrap();
+        arc.set_rotation(-225);
+        arc.set_bg_angles(0, 270);
+        arc.set_mode(ArcMode::Symmetrical);
+        arc.set_range(-50, 50);
+        arc.set_value(80);
+        static VALUE: AtomicI16 = AtomicI16::new(0);
+        arc.on_value_changed(|_, value| VALUE.store(value, Ordering::SeqCst))
+            .unwrap();
+        crate::event_send(&mut arc, Event::ValueChanged);
+
+        let raw = unsafe { &*(arc.raw().as_ptr() as *const lvgl_sys::lv_arc_t) };
+        assert_eq!((raw.rotation, raw.bg_angle_start, raw.bg_angle_end), (135, 0, 270));
+        assert_eq!(arc.get_mode(), ArcMode::Symmetrical);
+        assert_eq!(arc.get_value_f32(), 1.0);
+        assert_eq!(VALUE.load(Ordering::SeqCst), 50);
+    }
 }
 /*
 /// The different parts, of an arc object.
@@ -1,3 +1,4 @@
+use crate::misc::scale;
 use crate::support::AnimationState;
 use crate::widgets::Bar;
 use crate::NativeObject;
@@ -17,6 +18,49 @@ impl Bar<'_> {
             lvgl_sys::lv_bar_set_value(self.core.raw().as_mut(), value, anim.into());
         }
     }
+
+    /// Sets a new value, with `AnimationState::ON` animating the indicator
+    /// to it over the bar's `anim_time` style, e.g.
+    /// `bar.set_value_anim(70, AnimationState::ON)`.
+    pub fn set_value_anim(&mut self, value: i32, anim: AnimationState) {
+        self.set_value(value, anim);
+    }
+
+    /// Set a new value on the bar as a fraction of its range, `0.0` being
+    /// the minimum and `1.0` the maximum
+    pub fn set_value_f32(&mut self, value: f32, anim: AnimationState) {
+        let (min, max) = unsafe {
+            let obj = self.core.raw().as_ptr();
+            (
+                lvgl_sys::lv_bar_get_min_value(obj),
+                lvgl_sys::lv_bar_get_max_value(obj),
+            )
+        };
+        self.set_value(scale::denormalize(value, min, max), anim);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn animated_value_keeps_anim_time() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut bar = Bar::create(&mut screen).unwrap();
+        let obj = bar.core.raw().as_ptr();
+        let anim_time =
+            || unsafe { lvgl_sys::lv_obj_get_style_anim_time(obj, lvgl_sys::LV_PART_MAIN) };
+        let time = anim_time();
+
+        bar.set_value_anim(70, AnimationState::ON);
+        assert_eq!(anim_time(), time);
+        // The value is set right away, and the indicator animated to it
+        assert_eq!(unsafe { lvgl_sys::lv_bar_get_value(obj) }, 70);
+    }
 }
 /*
 /// The different parts, of a bar object.
@@ -0,0 +1,247 @@
+use crate::draw::{ArcDsc, ColorFormat, ImageDrawDsc, ImageDsc, LabelDsc, LineDsc, RectDsc};
+use crate::lv_core::obj::NativeObject;
+use crate::style::Opacity;
+use crate::widgets::Canvas;
+use crate::{Color, LvError, LvResult, Point};
+use core::{mem, ptr};
+use cstr_core::CStr;
+
+/// Points of a line drawn by a single `lv_canvas_draw_line()` call.
+const LINE_CHUNK: usize = 32;
+
+/// A pixel buffer of `N` native colors, for use by a `Canvas`.
+pub struct CanvasBuffer<const N: usize> {
+    pixels: [lvgl_sys::lv_color_t; N],
+}
+
+impl<const N: usize> CanvasBuffer<N> {
+    /// Creates a buffer with all pixels zeroed. Can be used to initialize a
+    /// `static` buffer.
+    pub const fn new() -> Self {
+        Self {
+            pixels: unsafe { mem::zeroed() },
+        }
+    }
+}
+
+impl<const N: usize> Default for CanvasBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Canvas<'a> {
+    /// Draws the canvas into `buffer`, which must hold at least
+    /// `width * height` pixels. The buffer has to be `'static`, e.g. a
+    /// `static` or a leaked one, as LVGL keeps drawing from it for as long as
+    /// the canvas object exists, which can outlive this handle. Use
+    /// `alloc_buffer()` for a buffer freed together with the canvas.
+    pub fn set_buffer<const N: usize>(
+        &mut self,
+        buffer: &'static mut CanvasBuffer<N>,
+        width: u16,
+        height: u16,
+    ) -> LvResult<()> {
+        if (width as usize) * (height as usize) > N {
+            return Err(LvError::InvalidArgument);
+        }
+        unsafe {
+            self.set_raw_buffer(buffer.pixels.as_mut_ptr() as *mut _, width, height);
+        }
+        Ok(())
+    }
+
+    /// Allocates a `width * height` pixel buffer in LVGL's memory, which is
+    /// freed together with the canvas.
+    pub fn alloc_buffer(&mut self, width: u16, height: u16) -> LvResult<()> {
+        let size = unsafe {
+            lvgl_sys::lv_img_buf_get_img_size(
+                width as lvgl_sys::lv_coord_t,
+                height as lvgl_sys::lv_coord_t,
+                ColorFormat::TrueColor.into(),
+            )
+        };
+        let buf = unsafe { lvgl_sys::lv_mem_alloc(size as _) };
+        if buf.is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+        unsafe {
+            self.set_raw_buffer(buf, width, height);
+            crate::support::free_on_delete(self.core.raw().as_ptr(), buf, ptr::null_mut());
+        }
+        Ok(())
+    }
+
+    unsafe fn set_raw_buffer(&mut self, buf: *mut cty::c_void, width: u16, height: u16) {
+        lvgl_sys::lv_canvas_set_buffer(
+            self.core.raw().as_ptr(),
+            buf,
+            width as lvgl_sys::lv_coord_t,
+            height as lvgl_sys::lv_coord_t,
+            ColorFormat::TrueColor.into(),
+        );
+    }
+
+    /// Returns the size of the buffer in pixels, `(0, 0)` if none is set.
+    pub fn buffer_size(&self) -> (u16, u16) {
+        let img = unsafe { &*lvgl_sys::lv_canvas_get_img(self.core.raw().as_ptr()) };
+        if img.data.is_null() {
+            (0, 0)
+        } else {
+            (img.header.w() as u16, img.header.h() as u16)
+        }
+    }
+
+    fn check_px(&self, x: u16, y: u16) -> LvResult<()> {
+        let (width, height) = self.buffer_size();
+        if x < width && y < height {
+            Ok(())
+        } else {
+            Err(LvError::InvalidArgument)
+        }
+    }
+
+    /// Sets the color of a pixel. Returns `LvError::InvalidArgument` if it is
+    /// outside of the buffer.
+    pub fn set_px(&mut self, x: u16, y: u16, color: Color) -> LvResult<()> {
+        self.check_px(x, y)?;
+        unsafe {
+            lvgl_sys::lv_canvas_set_px_color(
+                self.core.raw().as_ptr(),
+                x as lvgl_sys::lv_coord_t,
+                y as lvgl_sys::lv_coord_t,
+                color.into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the color of a pixel. Returns `LvError::InvalidArgument` if it
+    /// is outside of the buffer.
+    pub fn get_px(&self, x: u16, y: u16) -> LvResult<Color> {
+        self.check_px(x, y)?;
+        Ok(Color::from_raw(unsafe {
+            lvgl_sys::lv_canvas_get_px(
+                self.core.raw().as_ptr(),
+                x as lvgl_sys::lv_coord_t,
+                y as lvgl_sys::lv_coord_t,
+            )
+        }))
+    }
+
+    /// Fills the whole canvas with a color.
+    pub fn fill_bg(&mut self, color: Color, opa: Opacity) {
+        unsafe { lvgl_sys::lv_canvas_fill_bg(self.core.raw().as_ptr(), color.into(), opa.into()) }
+    }
+
+    /// Draws a rectangle.
+    pub fn draw_rect(&mut self, x: i16, y: i16, width: i16, height: i16, dsc: &RectDsc) {
+        unsafe {
+            lvgl_sys::lv_canvas_draw_rect(self.core.raw().as_ptr(), x, y, width, height, dsc.raw());
+        }
+    }
+
+    /// Draws text, wrapped at `max_width`.
+    pub fn draw_text(&mut self, x: i16, y: i16, max_width: i16, dsc: &LabelDsc, text: &CStr) {
+        // LVGL takes the descriptor mutably but doesn't modify it
+        let mut dsc = *dsc;
+        unsafe {
+            lvgl_sys::lv_canvas_draw_text(
+                self.core.raw().as_ptr(),
+                x,
+                y,
+                max_width,
+                &mut dsc.raw,
+                text.as_ptr(),
+            );
+        }
+    }
+
+    /// Draws an image.
+    pub fn draw_img(&mut self, x: i16, y: i16, img: &ImageDsc, dsc: &ImageDrawDsc) {
+        unsafe {
+            lvgl_sys::lv_canvas_draw_img(
+                self.core.raw().as_ptr(),
+                x,
+                y,
+                img.raw() as *const _,
+                dsc.raw(),
+            );
+        }
+    }
+
+    /// Draws a line through `points`.
+    pub fn draw_line(&mut self, points: &[Point], dsc: &LineDsc) {
+        let mut chunk = [lvgl_sys::lv_point_t::default(); LINE_CHUNK];
+        let mut len = 0;
+        for (i, point) in points.iter().enumerate() {
+            chunk[len] = lvgl_sys::lv_point_t {
+                x: point.x as lvgl_sys::lv_coord_t,
+                y: point.y as lvgl_sys::lv_coord_t,
+            };
+            len += 1;
+            if len == LINE_CHUNK || i == points.len() - 1 {
+                unsafe {
+                    lvgl_sys::lv_canvas_draw_line(
+                        self.core.raw().as_ptr(),
+                        chunk.as_ptr(),
+                        len as u32,
+                        dsc.raw(),
+                    );
+                }
+                // Continue the next chunk from the last point
+                chunk[0] = chunk[len - 1];
+                len = 1;
+            }
+        }
+    }
+
+    /// Draws an arc around `(x, y)`. Angles are in degrees, clockwise from
+    /// the right.
+    pub fn draw_arc(
+        &mut self,
+        x: i16,
+        y: i16,
+        radius: i16,
+        start_angle: i32,
+        end_angle: i32,
+        dsc: &ArcDsc,
+    ) {
+        unsafe {
+            lvgl_sys::lv_canvas_draw_arc(
+                self.core.raw().as_ptr(),
+                x,
+                y,
+                radius,
+                start_angle,
+                end_angle,
+                dsc.raw(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn canvas_pixels() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut canvas = Canvas::create(&mut screen).unwrap();
+        let small = Box::leak(Box::new(CanvasBuffer::<{ 16 * 4 }>::new()));
+        assert!(canvas.set_buffer(small, 16, 8).is_err());
+        let buffer = Box::leak(Box::new(CanvasBuffer::<{ 16 * 8 }>::new()));
+        canvas.set_buffer(buffer, 16, 8).unwrap();
+        assert_eq!(canvas.buffer_size(), (16, 8));
+
+        canvas.fill_bg(Color::from_rgb((0, 0, 0)), Opacity::OPA_COVER);
+        canvas.set_px(15, 7, Color::from_rgb((255, 255, 255))).unwrap();
+        assert!(canvas.set_px(16, 0, Color::default()).is_err());
+        assert_eq!(canvas.get_px(15, 7).unwrap().r(), 255);
+        assert_eq!(canvas.get_px(0, 0).unwrap().r(), 0);
+    }
+}
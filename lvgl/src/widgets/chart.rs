@@ -0,0 +1,77 @@
+use crate::lv_core::obj::NativeObject;
+use crate::misc::scale;
+use crate::widgets::Chart;
+use crate::{Color, LvError, LvResult};
+use core::ptr::NonNull;
+
+/// The y axis a chart series is plotted against.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ChartAxis {
+    PrimaryY,
+    SecondaryY,
+}
+
+impl From<ChartAxis> for lvgl_sys::lv_chart_axis_t {
+    fn from(axis: ChartAxis) -> Self {
+        (match axis {
+            ChartAxis::PrimaryY => lvgl_sys::LV_CHART_AXIS_PRIMARY_Y,
+            ChartAxis::SecondaryY => lvgl_sys::LV_CHART_AXIS_SECONDARY_Y,
+        }) as lvgl_sys::lv_chart_axis_t
+    }
+}
+
+/// A data series of a `Chart`. The series is owned by the chart it was added
+/// to, and can only be used with that chart.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ChartSeries {
+    raw: NonNull<lvgl_sys::lv_chart_series_t>,
+    chart: NonNull<lvgl_sys::lv_obj_t>,
+}
+
+impl Chart<'_> {
+    /// Adds a data series drawn in `color` against the given axis.
+    pub fn add_series(&mut self, color: Color, axis: ChartAxis) -> LvResult<ChartSeries> {
+        let chart = self.core.raw();
+        let raw = unsafe { lvgl_sys::lv_chart_add_series(chart.as_ptr(), color.into(), axis.into()) };
+        Ok(ChartSeries {
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+            chart,
+        })
+    }
+
+    /// Sets the minimum and maximum value of an axis.
+    pub fn set_range(&mut self, axis: ChartAxis, min: i16, max: i16) {
+        unsafe {
+            lvgl_sys::lv_chart_set_range(self.core.raw().as_mut(), axis.into(), min, max);
+        }
+    }
+
+    /// Shifts all points of the series to the left and appends `value`.
+    pub fn set_next_value(&mut self, series: &ChartSeries, value: i16) -> LvResult<()> {
+        self.check_series(series)?;
+        unsafe {
+            lvgl_sys::lv_chart_set_next_value(self.core.raw().as_mut(), series.raw.as_ptr(), value);
+        }
+        Ok(())
+    }
+
+    /// Appends a value given as a fraction of the range of the series' axis,
+    /// `0.0` being the minimum and `1.0` the maximum.
+    pub fn set_next_value_f32(&mut self, series: &ChartSeries, value: f32) -> LvResult<()> {
+        self.check_series(series)?;
+        let (min, max) = unsafe {
+            let chart = &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_chart_t);
+            let axis = series.raw.as_ref().y_axis_sec() as usize;
+            (chart.ymin[axis].into(), chart.ymax[axis].into())
+        };
+        self.set_next_value(series, scale::denormalize(value, min, max) as i16)
+    }
+
+    fn check_series(&self, series: &ChartSeries) -> LvResult<()> {
+        if series.chart == self.core.raw() {
+            Ok(())
+        } else {
+            Err(LvError::InvalidArgument)
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::widgets::Checkbox;
+use crate::{State, Widget, WidgetValue};
+
+impl Checkbox<'_> {
+    /// Returns whether the box is checked.
+    pub fn is_checked(&self) -> bool {
+        self.has_state(State::CHECKED)
+    }
+
+    /// Checks or unchecks the box. Like other setters, this doesn't send a
+    /// `ValueChanged` event.
+    pub fn set_checked(&mut self, checked: bool) {
+        if checked {
+            self.add_state(State::CHECKED);
+        } else {
+            self.clear_state(State::CHECKED);
+        }
+    }
+}
+
+/// Whether the box is checked.
+impl WidgetValue for Checkbox<'_> {
+    type Value = bool;
+
+    fn value(&self) -> bool {
+        self.is_checked()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Event};
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    #[test]
+    fn value_changed_reports_checked() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut checkbox = Checkbox::create(&mut screen).unwrap();
+        static CHECKED: AtomicBool = AtomicBool::new(false);
+        checkbox
+            .on_value_changed(|_, checked| CHECKED.store(checked, Ordering::SeqCst))
+            .unwrap();
+
+        assert!(!checkbox.is_checked());
+        checkbox.set_checked(true);
+        assert!(checkbox.is_checked());
+        crate::event_send(&mut checkbox, Event::ValueChanged);
+        assert!(CHECKED.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::lv_core::obj::NativeObject;
+use crate::widgets::Dropdown;
+use crate::{LvError, LvResult};
+use core::ptr;
+
+impl Dropdown<'_> {
+    /// Sets the options from an iterator of strings, which must not contain
+    /// newlines or NUL characters. The newline-separated list LVGL expects is
+    /// built in LVGL's memory, so this works without `alloc`.
+    pub fn set_options_iter<'s, I>(&mut self, options: I) -> LvResult<()>
+    where
+        I: IntoIterator<Item = &'s str>,
+    {
+        let mut buf: *mut u8 = ptr::null_mut();
+        let mut len = 0;
+        for (i, option) in options.into_iter().enumerate() {
+            if option.contains(['\n', '\0']) {
+                unsafe { lvgl_sys::lv_mem_free(buf as *mut _) };
+                return Err(LvError::InvalidArgument);
+            }
+            // Room for the separator (or the terminator after the last one)
+            let new_len = len + option.len() + 1;
+            let new_buf =
+                unsafe { lvgl_sys::lv_mem_realloc(buf as *mut _, new_len as _) as *mut u8 };
+            if new_buf.is_null() {
+                unsafe { lvgl_sys::lv_mem_free(buf as *mut _) };
+                return Err(LvError::LvOOMemory);
+            }
+            buf = new_buf;
+            unsafe {
+                if i > 0 {
+                    *buf.add(len - 1) = b'\n';
+                }
+                ptr::copy_nonoverlapping(option.as_ptr(), buf.add(len), option.len());
+                *buf.add(new_len - 1) = 0;
+            }
+            len = new_len;
+        }
+        unsafe {
+            let options = if buf.is_null() { b"\0".as_ptr() } else { buf as *const u8 };
+            // LVGL copies the options
+            lvgl_sys::lv_dropdown_set_options(self.core.raw().as_ptr(), options as *const _);
+            lvgl_sys::lv_mem_free(buf as *mut _);
+        }
+        Ok(())
+    }
+
+    /// Writes the text of the selected option into `buf` and returns it. The
+    /// text is cut at a character boundary if `buf` is too small.
+    pub fn get_selected_str<'b>(&self, buf: &'b mut [u8]) -> &'b str {
+        super::selected_str(buf, |buf, len| unsafe {
+            lvgl_sys::lv_dropdown_get_selected_str(self.core.raw().as_ptr(), buf, len)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn options_from_iter() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut dropdown = Dropdown::create(&mut screen).unwrap();
+
+        dropdown.set_options_iter(["Low", "Medium", "High"]).unwrap();
+        assert_eq!(
+            unsafe { lvgl_sys::lv_dropdown_get_option_cnt(dropdown.raw().as_ptr()) },
+            3
+        );
+        unsafe { lvgl_sys::lv_dropdown_set_selected(dropdown.raw().as_ptr(), 1) };
+
+        let mut buf = [0u8; 16];
+        assert_eq!(dropdown.get_selected_str(&mut buf), "Medium");
+        let mut short = [0u8; 4];
+        assert_eq!(dropdown.get_selected_str(&mut short), "Med");
+        assert!(dropdown.set_options_iter(["a\nb"]).is_err());
+    }
+}
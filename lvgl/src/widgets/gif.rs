@@ -0,0 +1,68 @@
+use crate::widgets::Gif;
+use crate::{LvError, LvResult, NativeObject};
+use cstr_core::CStr;
+
+impl<'a> Gif<'a> {
+    /// Plays the GIF file in `data`, from the start. The data is decoded
+    /// while playing, so it must outlive the widget.
+    ///
+    /// Fails with `LvError::InvalidArgument` if `data` isn't a GIF.
+    pub fn set_src(&mut self, data: &'a [u8]) -> LvResult<()> {
+        let mut dsc = lvgl_sys::lv_img_dsc_t::default();
+        dsc.header.set_cf(lvgl_sys::LV_IMG_CF_RAW as u32);
+        dsc.data_size = data.len() as u32;
+        dsc.data = data.as_ptr();
+        // Only the data pointer is kept, so the descriptor can be temporary
+        unsafe {
+            lvgl_sys::lv_gif_set_src(
+                self.core.raw().as_ptr(),
+                &dsc as *const _ as *const cty::c_void,
+            )
+        };
+        self.check_loaded()
+    }
+
+    /// Plays the GIF file at `path` on a drive registered with LVGL, e.g.
+    /// `S:/boot.gif`.
+    ///
+    /// Fails with `LvError::InvalidArgument` if the file can't be opened or
+    /// isn't a GIF.
+    pub fn set_src_file(&mut self, path: &CStr) -> LvResult<()> {
+        unsafe {
+            lvgl_sys::lv_gif_set_src(
+                self.core.raw().as_ptr(),
+                path.as_ptr() as *const cty::c_void,
+            )
+        };
+        self.check_loaded()
+    }
+
+    fn check_loaded(&self) -> LvResult<()> {
+        let gif = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_gif_t) };
+        if gif.gif.is_null() {
+            Err(LvError::InvalidArgument)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    /// A 1x1 pixel GIF.
+    const PIXEL: &[u8] = b"GIF89a\x01\x00\x01\x00\x80\x00\x00\x00\x00\x00\xff\xff\xff\
+        !\xf9\x04\x01\x00\x00\x00\x00,\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02D\x01\x00;";
+
+    #[test]
+    fn play_from_memory() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut gif = Gif::create(&mut screen).unwrap();
+        gif.set_src(PIXEL).unwrap();
+        assert_eq!(gif.set_src(b"not a gif"), Err(LvError::InvalidArgument));
+    }
+}
@@ -0,0 +1,97 @@
+use crate::draw::ImageDsc;
+use crate::lv_core::obj::NativeObject;
+use crate::point::Point;
+use crate::style::Opacity;
+use crate::widgets::Img;
+use crate::{Color, Part};
+
+impl<'a> Img<'a> {
+    /// Sets an image descriptor as the source of the image. The descriptor
+    /// must outlive the widget.
+    pub fn set_src(&mut self, src: &'a ImageDsc<'a>) {
+        unsafe {
+            lvgl_sys::lv_img_set_src(self.core.raw().as_mut(), src.raw() as *const _);
+        }
+    }
+
+    /// Rotates the image `angle` tenths of a degree clockwise around its
+    /// pivot, e.g. `-450` for 45° counter-clockwise. Rotated images are
+    /// transformed on every redraw, which is slower than drawing them as
+    /// they are; `set_antialias(false)` trades quality for speed.
+    pub fn set_angle(&mut self, angle: i16) {
+        unsafe { lvgl_sys::lv_img_set_angle(self.core.raw().as_mut(), angle) }
+    }
+
+    /// Sets the point the image is rotated and zoomed around, relative to
+    /// its top left corner. The default is its center.
+    pub fn set_pivot(&mut self, x: i16, y: i16) {
+        unsafe { lvgl_sys::lv_img_set_pivot(self.core.raw().as_mut(), x as _, y as _) }
+    }
+
+    /// Gets the point the image is rotated and zoomed around.
+    pub fn get_pivot(&self) -> Point {
+        let mut pivot = lvgl_sys::lv_point_t::default();
+        unsafe { lvgl_sys::lv_img_get_pivot(self.core.raw().as_ptr(), &mut pivot) };
+        Point::new(pivot.x.into(), pivot.y.into())
+    }
+
+    /// Scales the image by `zoom`, `1.0` being its original size, like
+    /// `set_zoom()` with `256` for `1.0`.
+    pub fn set_zoom_f32(&mut self, zoom: f32) {
+        let zoom = (zoom * lvgl_sys::LV_IMG_ZOOM_NONE as f32) as i32;
+        let zoom = zoom.clamp(1, u16::MAX.into()) as u16;
+        unsafe { lvgl_sys::lv_img_set_zoom(self.core.raw().as_mut(), zoom) }
+    }
+
+    /// Tints the image with the given color. The opacity controls how much
+    /// of the original pixel color is replaced: `OPA_COVER` fully recolors the
+    /// image, which is what monochrome icon assets usually want.
+    pub fn set_recolor(&mut self, color: Color, opa: Opacity) {
+        unsafe {
+            lvgl_sys::lv_obj_set_style_img_recolor(
+                self.core.raw().as_mut(),
+                color.into(),
+                Part::Main.into(),
+            );
+            lvgl_sys::lv_obj_set_style_img_recolor_opa(
+                self.core.raw().as_mut(),
+                opa.into(),
+                Part::Main.into(),
+            );
+        }
+    }
+
+    /// Removes any tint previously set with `set_recolor()`, restoring the
+    /// original colors of the image.
+    pub fn clear_recolor(&mut self) {
+        unsafe {
+            lvgl_sys::lv_obj_set_style_img_recolor_opa(
+                self.core.raw().as_mut(),
+                Opacity::OPA_TRANSP.into(),
+                Part::Main.into(),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn transform() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut img = Img::create(&mut screen).unwrap();
+        img.set_angle(-450);
+        img.set_pivot(4, 60);
+        img.set_zoom_f32(1.5);
+
+        let obj = img.core.raw().as_ptr();
+        assert_eq!(unsafe { lvgl_sys::lv_img_get_angle(obj) }, 3150);
+        assert_eq!(img.get_pivot(), Point::new(4, 60));
+        assert_eq!(unsafe { lvgl_sys::lv_img_get_zoom(obj) }, 384);
+    }
+}
@@ -1,14 +1,115 @@
 use crate::widgets::{Keyboard, Textarea};
 use crate::NativeObject;
+use core::ptr;
+
+/// The set of keys a `Keyboard` shows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyboardMode {
+    TextLower,
+    TextUpper,
+    Special,
+    Number,
+    User1,
+    User2,
+    User3,
+    User4,
+}
+
+impl From<KeyboardMode> for lvgl_sys::lv_keyboard_mode_t {
+    fn from(mode: KeyboardMode) -> Self {
+        (match mode {
+            KeyboardMode::TextLower => lvgl_sys::LV_KEYBOARD_MODE_TEXT_LOWER,
+            KeyboardMode::TextUpper => lvgl_sys::LV_KEYBOARD_MODE_TEXT_UPPER,
+            KeyboardMode::Special => lvgl_sys::LV_KEYBOARD_MODE_SPECIAL,
+            KeyboardMode::Number => lvgl_sys::LV_KEYBOARD_MODE_NUMBER,
+            KeyboardMode::User1 => lvgl_sys::LV_KEYBOARD_MODE_USER_1,
+            KeyboardMode::User2 => lvgl_sys::LV_KEYBOARD_MODE_USER_2,
+            KeyboardMode::User3 => lvgl_sys::LV_KEYBOARD_MODE_USER_3,
+            KeyboardMode::User4 => lvgl_sys::LV_KEYBOARD_MODE_USER_4,
+        }) as lvgl_sys::lv_keyboard_mode_t
+    }
+}
 
 impl Keyboard<'_> {
     /// Associates a given `Textarea` to the keyboard.
+    ///
+    /// The pairing is undone when either of the two is deleted, so the
+    /// keyboard never types into a deleted text area.
     pub fn set_textarea(&mut self, textarea: &mut Textarea) {
+        let kb = self.raw().as_ptr();
+        let ta = textarea.raw().as_ptr();
         unsafe {
-            lvgl_sys::lv_keyboard_set_textarea(
-                self.raw().as_mut() as *mut lvgl_sys::lv_obj_t,
-                textarea.raw().as_mut() as *mut lvgl_sys::lv_obj_t,
-            )
+            unpair(kb);
+            lvgl_sys::lv_keyboard_set_textarea(kb, ta);
+            lvgl_sys::lv_obj_add_event_cb(
+                ta,
+                Some(textarea_deleted),
+                lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                kb as *mut _,
+            );
+            if lvgl_sys::lv_obj_get_event_user_data(kb, Some(keyboard_deleted)).is_null() {
+                lvgl_sys::lv_obj_add_event_cb(
+                    kb,
+                    Some(keyboard_deleted),
+                    lvgl_sys::lv_event_code_t_LV_EVENT_DELETE,
+                    kb as *mut _,
+                );
+            }
         }
     }
+
+    /// Detaches the keyboard from its `Textarea`, if any.
+    pub fn clear_textarea(&mut self) {
+        unsafe { unpair(self.raw().as_ptr()) }
+    }
+
+    /// Returns `true` if the keyboard is currently associated to a
+    /// `Textarea`.
+    pub fn has_textarea(&self) -> bool {
+        unsafe { !lvgl_sys::lv_keyboard_get_textarea(self.raw().as_ptr()).is_null() }
+    }
+
+    /// Switches the set of keys shown.
+    pub fn set_mode(&mut self, mode: KeyboardMode) {
+        unsafe { lvgl_sys::lv_keyboard_set_mode(self.raw().as_ptr(), mode.into()) }
+    }
+}
+
+/// Detaches `kb` from its text area, removing the text area's delete hook.
+unsafe fn unpair(kb: *mut lvgl_sys::lv_obj_t) {
+    let ta = lvgl_sys::lv_keyboard_get_textarea(kb);
+    if !ta.is_null() {
+        lvgl_sys::lv_obj_remove_event_cb_with_user_data(ta, Some(textarea_deleted), kb as *mut _);
+        lvgl_sys::lv_keyboard_set_textarea(kb, ptr::null_mut());
+    }
+}
+
+unsafe extern "C" fn textarea_deleted(event: *mut lvgl_sys::lv_event_t) {
+    lvgl_sys::lv_keyboard_set_textarea((*event).user_data as *mut _, ptr::null_mut());
+}
+
+unsafe extern "C" fn keyboard_deleted(event: *mut lvgl_sys::lv_event_t) {
+    unpair((*event).user_data as *mut _);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Widget};
+
+    #[test]
+    fn deleted_textarea_is_detached() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut keyboard = Keyboard::create(&mut screen).unwrap();
+        let mut textarea = Textarea::create(&mut screen).unwrap();
+
+        keyboard.set_textarea(&mut textarea);
+        keyboard.set_mode(KeyboardMode::Number);
+        assert!(keyboard.has_textarea());
+
+        textarea.del();
+        assert!(!keyboard.has_textarea());
+    }
 }
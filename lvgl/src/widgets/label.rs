@@ -1,5 +1,7 @@
-use crate::widgets::Label;
-use crate::{LabelLongMode, NativeObject};
+use crate::widgets::text_writer::TextTarget;
+use crate::widgets::{Label, TextWriter};
+use crate::{LabelLongMode, LvResult, NativeObject};
+use core::fmt;
 
 #[cfg(feature = "alloc")]
 mod alloc_imp {
@@ -41,4 +43,57 @@ impl Label<'_> {
     pub fn get_long_mode(&self) -> u8 {
         unsafe { lvgl_sys::lv_label_get_long_mode(self.raw().as_ref()) }
     }
+
+    /// Sets the text to formatted arguments, like `lv_label_set_text_fmt()`
+    /// but formatted by Rust rather than through C varargs. Fails, leaving
+    /// the text as it is, if the text contains a NUL character.
+    ///
+    /// ```ignore
+    /// label.set_text_fmt(format_args!("{:.1} °C", temperature))?;
+    /// ```
+    pub fn set_text_fmt(&mut self, args: fmt::Arguments) -> LvResult<()> {
+        unsafe { TextWriter::set_fmt(self.raw().as_ptr(), TextTarget::Label, args) }
+    }
+
+    /// Returns a writer replacing the text with what is written to it with
+    /// `write!()`. The text is set once the writer is dropped.
+    pub fn writer(&mut self) -> TextWriter<'_> {
+        unsafe { TextWriter::new(self.raw().as_ptr(), TextTarget::Label) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+    use core::fmt::Write;
+
+    #[test]
+    fn set_text_fmt_formats_long_text() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut label = Label::create(&mut screen).unwrap();
+
+        label.set_text_fmt(format_args!("{:.1} °C", 21.46)).unwrap();
+        fn text<'l>(label: &'l Label) -> &'l cstr_core::CStr {
+            unsafe { cstr_core::CStr::from_ptr(lvgl_sys::lv_label_get_text(label.raw().as_ptr())) }
+        }
+        assert_eq!(text(&label).to_str(), Ok("21.5 °C"));
+        // Longer than the buffer, so it would be set in pieces
+        assert!(label.set_text_fmt(format_args!("{:200}\0", "")).is_err());
+        assert_eq!(text(&label).to_str(), Ok("21.5 °C"));
+
+        {
+            let mut writer = label.writer();
+            for i in 0..40 {
+                write!(writer, "{i}é").unwrap();
+            }
+        }
+        // Longer than the buffer, with characters straddling its end
+        let text = text(&label).to_str().unwrap();
+        assert_eq!(text.len(), 150);
+        assert!(text.starts_with("0é1é2é"));
+        assert!(text.ends_with("38é39é"));
+    }
 }
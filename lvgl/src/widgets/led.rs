@@ -0,0 +1,54 @@
+use crate::widgets::Led;
+use crate::{Color, NativeObject};
+
+impl Led<'_> {
+    /// Sets the color of the LED.
+    pub fn set_color(&mut self, color: Color) {
+        unsafe { lvgl_sys::lv_led_set_color(self.core.raw().as_ptr(), color.into()) }
+    }
+
+    /// Gets the color of the LED.
+    pub fn get_color(&self) -> Color {
+        let led = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_led_t) };
+        Color::from_raw(led.color)
+    }
+
+    /// Turns the LED fully on or off.
+    pub fn set_on(&mut self, on: bool) {
+        let led = self.core.raw().as_ptr();
+        unsafe {
+            if on {
+                lvgl_sys::lv_led_on(led);
+            } else {
+                lvgl_sys::lv_led_off(led);
+            }
+        }
+    }
+
+    /// Returns `true` if the LED is brighter than when off.
+    pub fn is_on(&self) -> bool {
+        let bright = unsafe { lvgl_sys::lv_led_get_brightness(self.core.raw().as_ptr()) };
+        bright > lvgl_sys::LV_LED_BRIGHT_MIN as u8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn on_off_and_color() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut led = Led::create(&mut screen).unwrap();
+        led.set_color(Color::from_rgb((0, 255, 0)));
+        assert_eq!(led.get_color().g(), Color::from_rgb((0, 255, 0)).g());
+
+        led.set_on(false);
+        assert!(!led.is_on());
+        led.set_on(true);
+        assert!(led.is_on());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::widgets::Line;
+use crate::{LvError, LvResult, NativeObject, Point};
+use core::{mem, slice};
+
+impl Line<'_> {
+    /// Sets the points the line goes through. The points are copied into
+    /// LVGL's memory, which is freed when the points are replaced or the
+    /// line is deleted.
+    pub fn set_points(&mut self, points: &[Point]) -> LvResult<()> {
+        let count = u16::try_from(points.len()).map_err(|_| LvError::InvalidArgument)?;
+        let obj = self.core.raw().as_ptr();
+        let size = points.len().max(1) * mem::size_of::<lvgl_sys::lv_point_t>();
+        let buf = unsafe { lvgl_sys::lv_mem_alloc(size as _) } as *mut lvgl_sys::lv_point_t;
+        if buf.is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+        let copy = unsafe { slice::from_raw_parts_mut(buf, points.len()) };
+        for (dst, point) in copy.iter_mut().zip(points) {
+            *dst = lvgl_sys::lv_point_t {
+                x: point.x as lvgl_sys::lv_coord_t,
+                y: point.y as lvgl_sys::lv_coord_t,
+            };
+        }
+        unsafe {
+            let old = (*(obj as *mut lvgl_sys::lv_line_t)).point_array as *mut cty::c_void;
+            lvgl_sys::lv_line_set_points(obj, buf, count);
+            // Only free points this wrapper allocated
+            crate::support::free_on_delete(obj, buf as *mut cty::c_void, old);
+        }
+        Ok(())
+    }
+
+    /// Returns the number of points of the line.
+    pub fn point_count(&self) -> u16 {
+        let line = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_line_t) };
+        line.point_num
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Widget};
+
+    #[test]
+    fn points_are_copied() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut line = Line::create(&mut screen).unwrap();
+        {
+            let points = [Point::new(0, 0), Point::new(40, 20), Point::new(80, 0)];
+            line.set_points(&points).unwrap();
+        }
+        assert_eq!(line.point_count(), 3);
+        line.set_points(&[Point::new(5, 5), Point::new(10, 10)])
+            .unwrap();
+        assert_eq!(line.point_count(), 2);
+        line.del();
+    }
+}
@@ -0,0 +1,99 @@
+use crate::widgets::List;
+use crate::{LvError, LvResult, NativeObject, Obj, Widget};
+use core::ptr::NonNull;
+use cstr_core::CStr;
+
+/// An item of a `List`.
+pub enum ListItem<'a> {
+    /// A button added with `List::add_btn()`.
+    Btn(Obj<'a>),
+    /// A text added with `List::add_text()`.
+    Text(Obj<'a>),
+}
+
+impl<'a> ListItem<'a> {
+    /// Returns the object of the item.
+    pub fn obj(&self) -> &Obj<'a> {
+        match self {
+            ListItem::Btn(obj) | ListItem::Text(obj) => obj,
+        }
+    }
+}
+
+impl<'a> List<'a> {
+    /// Adds a button with an optional `symbol` in front of `text`, e.g.
+    /// `lvgl_sys::LV_SYMBOL_SETTINGS` or the path of an image.
+    pub fn add_btn(&mut self, symbol: Option<&CStr>, text: &CStr) -> LvResult<Obj<'a>> {
+        let symbol = symbol.map_or(core::ptr::null(), |s| s.as_ptr() as *const cty::c_void);
+        let btn =
+            unsafe { lvgl_sys::lv_list_add_btn(self.core.raw().as_ptr(), symbol, text.as_ptr()) };
+        to_obj(btn)
+    }
+
+    /// Adds a text, e.g. as the heading of a group of buttons.
+    pub fn add_text(&mut self, text: &CStr) -> LvResult<Obj<'a>> {
+        let label = unsafe { lvgl_sys::lv_list_add_text(self.core.raw().as_ptr(), text.as_ptr()) };
+        to_obj(label)
+    }
+
+    /// Returns the text of a button added with `add_btn()`.
+    pub fn get_btn_text(&self, btn: &impl NativeObject) -> Option<&CStr> {
+        let text =
+            unsafe { lvgl_sys::lv_list_get_btn_text(self.core.raw().as_ptr(), btn.raw().as_ptr()) };
+        if text.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(text) })
+        }
+    }
+
+    /// Returns the items of the list, top to bottom.
+    pub fn items(&self) -> impl Iterator<Item = ListItem<'a>> + '_ {
+        let list = self.core.raw().as_ptr();
+        let count = unsafe { lvgl_sys::lv_obj_get_child_cnt(list) };
+        (0..count).filter_map(move |i| {
+            let child = unsafe { lvgl_sys::lv_obj_get_child(list, i as i32) };
+            let obj = to_obj(child).ok()?;
+            if unsafe { lvgl_sys::lv_obj_check_type(child, &lvgl_sys::lv_list_btn_class) } {
+                Some(ListItem::Btn(obj))
+            } else {
+                Some(ListItem::Text(obj))
+            }
+        })
+    }
+}
+
+fn to_obj<'a>(raw: *mut lvgl_sys::lv_obj_t) -> LvResult<Obj<'a>> {
+    NonNull::new(raw)
+        .and_then(|raw| unsafe { Obj::from_raw(raw) })
+        .ok_or(LvError::LvOOMemory)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn items_in_order() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut list = List::create(&mut screen).unwrap();
+        let wifi = CStr::from_bytes_with_nul(b"Wi-Fi\0").unwrap();
+        let symbol = CStr::from_bytes_with_nul(lvgl_sys::LV_SYMBOL_WIFI).unwrap();
+
+        list.add_text(CStr::from_bytes_with_nul(b"Network\0").unwrap())
+            .unwrap();
+        let btn = list.add_btn(Some(symbol), wifi).unwrap();
+        list.add_btn(None, CStr::from_bytes_with_nul(b"Bluetooth\0").unwrap())
+            .unwrap();
+        assert_eq!(list.get_btn_text(&btn), Some(wifi));
+
+        let items: Vec<_> = list.items().collect();
+        assert_eq!(items.len(), 3);
+        assert!(matches!(items[0], ListItem::Text(_)));
+        assert!(matches!(items[1], ListItem::Btn(_)));
+        assert_eq!(items[1].obj().raw(), btn.raw());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{LvError, LvResult, NativeObject, Widget};
+use core::ptr::{self, NonNull};
+use cstr_core::CStr;
+
+define_object!(
+    /// A Lottie animation rendered with rlottie. Equivalent to an
+    /// `lv_rlottie` widget.
+    Lottie
+);
+
+bitflags! {
+    /// How a `Lottie` animation plays. The empty set plays forward once.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct LottiePlayback: u32 {
+        const BACKWARD = lvgl_sys::lv_rlottie_ctrl_t_LV_RLOTTIE_CTRL_BACKWARD as u32;
+        const PAUSE = lvgl_sys::lv_rlottie_ctrl_t_LV_RLOTTIE_CTRL_PAUSE as u32;
+        const LOOP = lvgl_sys::lv_rlottie_ctrl_t_LV_RLOTTIE_CTRL_LOOP as u32;
+    }
+}
+
+impl<'a> Lottie<'a> {
+    /// Creates a `width` by `height` animation from the Lottie JSON in
+    /// `json`, as a child of `parent`. The JSON is parsed right away, so it
+    /// doesn't need to outlive the widget.
+    pub fn from_json_bytes(
+        parent: &mut impl NativeObject,
+        width: i16,
+        height: i16,
+        json: &[u8],
+    ) -> LvResult<Self> {
+        // rlottie wants the JSON NUL-terminated
+        let copy = unsafe { lvgl_sys::lv_mem_alloc((json.len() + 1) as _) } as *mut u8;
+        if copy.is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+        let raw = unsafe {
+            ptr::copy_nonoverlapping(json.as_ptr(), copy, json.len());
+            *copy.add(json.len()) = 0;
+            let raw = lvgl_sys::lv_rlottie_create_from_raw(
+                parent.raw().as_ptr(),
+                width as lvgl_sys::lv_coord_t,
+                height as lvgl_sys::lv_coord_t,
+                copy as *const cty::c_char,
+            );
+            lvgl_sys::lv_mem_free(copy as *mut cty::c_void);
+            raw
+        };
+        Self::checked(raw)
+    }
+
+    /// Creates a `width` by `height` animation from the Lottie JSON file at
+    /// `path`, as a child of `parent`. The path is opened by rlottie, not
+    /// through LVGL's drives.
+    pub fn from_file(
+        parent: &mut impl NativeObject,
+        width: i16,
+        height: i16,
+        path: &CStr,
+    ) -> LvResult<Self> {
+        let raw = unsafe {
+            lvgl_sys::lv_rlottie_create_from_file(
+                parent.raw().as_ptr(),
+                width as lvgl_sys::lv_coord_t,
+                height as lvgl_sys::lv_coord_t,
+                path.as_ptr(),
+            )
+        };
+        Self::checked(raw)
+    }
+
+    /// Fails with `LvError::InvalidArgument` if rlottie couldn't load the
+    /// animation, deleting the empty widget.
+    fn checked(raw: *mut lvgl_sys::lv_obj_t) -> LvResult<Self> {
+        let raw = NonNull::new(raw).ok_or(LvError::LvOOMemory)?;
+        let lottie = unsafe { &*(raw.as_ptr() as *const lvgl_sys::lv_rlottie_t) };
+        if lottie.animation.is_null() {
+            unsafe { lvgl_sys::lv_obj_del(raw.as_ptr()) };
+            return Err(LvError::InvalidArgument);
+        }
+        Ok(unsafe { Self::from_raw(raw) }.unwrap())
+    }
+
+    /// Sets the direction of playback, and whether it is paused or loops.
+    pub fn set_play_mode(&mut self, playback: LottiePlayback) {
+        unsafe {
+            lvgl_sys::lv_rlottie_set_play_mode(
+                self.core.raw().as_ptr(),
+                playback.bits() as lvgl_sys::lv_rlottie_ctrl_t,
+            )
+        }
+    }
+
+    /// Jumps to `frame`.
+    pub fn set_current_frame(&mut self, frame: usize) {
+        unsafe { lvgl_sys::lv_rlottie_set_current_frame(self.core.raw().as_ptr(), frame as _) }
+    }
+
+    /// Returns the number of frames of the animation.
+    pub fn frame_count(&self) -> usize {
+        let lottie = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_rlottie_t) };
+        lottie.total_frames as usize
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    /// An empty animation of 10 frames.
+    const EMPTY: &[u8] = br#"{"v":"5.5.2","fr":30,"ip":0,"op":10,"w":16,"h":16,"layers":[]}"#;
+
+    #[test]
+    fn play_from_memory() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut lottie = Lottie::from_json_bytes(&mut screen, 16, 16, EMPTY).unwrap();
+        assert_eq!(lottie.frame_count(), 10);
+        lottie.set_play_mode(LottiePlayback::LOOP);
+        lottie.set_current_frame(5);
+        assert_eq!(
+            Lottie::from_json_bytes(&mut screen, 16, 16, b"not lottie").err(),
+            Some(LvError::InvalidArgument)
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use crate::widgets::Menu;
+use crate::{LvError, LvResult, NativeObject, Obj, Widget};
+use core::ptr::{self, NonNull};
+use cstr_core::CStr;
+
+/// Where the header of a `Menu` is shown.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MenuHeaderMode {
+    /// Above the page, staying in place while the page scrolls.
+    TopFixed,
+    /// Above the page, scrolling with it.
+    TopUnfixed,
+    /// Below the page, staying in place while the page scrolls.
+    BottomFixed,
+}
+
+impl From<MenuHeaderMode> for lvgl_sys::lv_menu_mode_header_t {
+    fn from(mode: MenuHeaderMode) -> Self {
+        (match mode {
+            MenuHeaderMode::TopFixed => lvgl_sys::LV_MENU_HEADER_TOP_FIXED,
+            MenuHeaderMode::TopUnfixed => lvgl_sys::LV_MENU_HEADER_TOP_UNFIXED,
+            MenuHeaderMode::BottomFixed => lvgl_sys::LV_MENU_HEADER_BOTTOM_FIXED,
+        }) as lvgl_sys::lv_menu_mode_header_t
+    }
+}
+
+impl<'a> Menu<'a> {
+    /// Creates a page of the menu, with `title` shown in the header while
+    /// the page is open. The title is copied.
+    ///
+    /// Pages stay hidden until shown with `set_page()` or
+    /// `set_load_page_event()`.
+    pub fn create_page(&mut self, title: Option<&CStr>) -> LvResult<Obj<'a>> {
+        let title = title.map_or(ptr::null(), CStr::as_ptr);
+        to_obj(unsafe { lvgl_sys::lv_menu_page_create(self.core.raw().as_ptr(), title as *mut _) })
+    }
+
+    /// Creates an item on `parent`, a page or a section. Items are
+    /// containers laid out in a row, e.g. for an icon, a label and a switch.
+    pub fn create_item(parent: &mut impl NativeObject) -> LvResult<Obj<'a>> {
+        to_obj(unsafe { lvgl_sys::lv_menu_cont_create(parent.raw().as_ptr()) })
+    }
+
+    /// Creates a section on `page`, visually grouping the items added to it.
+    pub fn create_section(page: &mut impl NativeObject) -> LvResult<Obj<'a>> {
+        to_obj(unsafe { lvgl_sys::lv_menu_section_create(page.raw().as_ptr()) })
+    }
+
+    /// Creates a separator line on `parent`, a page or a section.
+    pub fn create_separator(parent: &mut impl NativeObject) -> LvResult<Obj<'a>> {
+        to_obj(unsafe { lvgl_sys::lv_menu_separator_create(parent.raw().as_ptr()) })
+    }
+
+    /// Shows `page` in the main area, adding it to the history the back
+    /// button walks through. `None` clears the main area.
+    pub fn set_page(&mut self, page: Option<&Obj<'a>>) {
+        let page = page.map_or(ptr::null_mut(), |p| p.raw().as_ptr());
+        unsafe { lvgl_sys::lv_menu_set_page(self.core.raw().as_ptr(), page) }
+    }
+
+    /// Shows `page` in a sidebar next to the main area, as on settings
+    /// screens of wider displays. `None` removes the sidebar.
+    pub fn set_sidebar_page(&mut self, page: Option<&Obj<'a>>) {
+        let page = page.map_or(ptr::null_mut(), |p| p.raw().as_ptr());
+        unsafe { lvgl_sys::lv_menu_set_sidebar_page(self.core.raw().as_ptr(), page) }
+    }
+
+    /// Opens `page` when `item` is clicked.
+    pub fn set_load_page_event(&mut self, item: &impl NativeObject, page: &Obj<'a>) {
+        unsafe {
+            lvgl_sys::lv_menu_set_load_page_event(
+                self.core.raw().as_ptr(),
+                item.raw().as_ptr(),
+                page.raw().as_ptr(),
+            )
+        }
+    }
+
+    /// Sets where the header is shown.
+    pub fn set_header_mode(&mut self, mode: MenuHeaderMode) {
+        unsafe { lvgl_sys::lv_menu_set_mode_header(self.core.raw().as_ptr(), mode.into()) }
+    }
+
+    /// Shows or hides the back button on the root page, e.g. to leave the
+    /// settings screen through `on_back()`.
+    pub fn set_root_back_btn(&mut self, enabled: bool) {
+        let mode = if enabled {
+            lvgl_sys::LV_MENU_ROOT_BACK_BTN_ENABLED
+        } else {
+            lvgl_sys::LV_MENU_ROOT_BACK_BTN_DISABLED
+        };
+        unsafe {
+            lvgl_sys::lv_menu_set_mode_root_back_btn(
+                self.core.raw().as_ptr(),
+                mode as lvgl_sys::lv_menu_mode_root_back_btn_t,
+            )
+        }
+    }
+
+    /// Returns the page shown in the main area, if any.
+    pub fn get_cur_main_page(&self) -> Option<Obj<'a>> {
+        to_obj(unsafe { lvgl_sys::lv_menu_get_cur_main_page(self.core.raw().as_ptr()) }).ok()
+    }
+
+    /// Returns the page shown in the sidebar, if any.
+    pub fn get_cur_sidebar_page(&self) -> Option<Obj<'a>> {
+        to_obj(unsafe { lvgl_sys::lv_menu_get_cur_sidebar_page(self.core.raw().as_ptr()) }).ok()
+    }
+
+    /// Forgets the pages opened so far, so the back button doesn't return to
+    /// them.
+    pub fn clear_history(&mut self) {
+        unsafe { lvgl_sys::lv_menu_clear_history(self.core.raw().as_ptr()) }
+    }
+
+    /// Calls `f` whenever a back button of the menu is clicked. The flag
+    /// tells whether it was the back button of the root page, which LVGL
+    /// leaves to the application to handle.
+    pub fn on_back<F>(&mut self, f: F)
+    where
+        F: FnMut(Menu<'a>, bool) + 'a,
+    {
+        unsafe { crate::support::add_closure_cb(self.core.raw().as_ptr(), back_cb::<'a, F>, f) }
+    }
+}
+
+fn to_obj<'a>(raw: *mut lvgl_sys::lv_obj_t) -> LvResult<Obj<'a>> {
+    NonNull::new(raw)
+        .and_then(|raw| unsafe { Obj::from_raw(raw) })
+        .ok_or(LvError::InvalidReference)
+}
+
+unsafe extern "C" fn back_cb<'a, F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(Menu<'a>, bool) + 'a,
+{
+    let event = &mut *event;
+    let Some(f) = crate::support::event_closure::<F>(event) else {
+        return;
+    };
+    match event.code {
+        lvgl_sys::lv_event_code_t_LV_EVENT_CLICKED => {
+            let menu = event.current_target;
+            let target = event.target;
+            let is_back_btn = target == lvgl_sys::lv_menu_get_main_header_back_btn(menu)
+                || target == lvgl_sys::lv_menu_get_sidebar_header_back_btn(menu);
+            if !is_back_btn {
+                return;
+            }
+            let Some(obj) = NonNull::new(menu).and_then(|raw| Menu::from_raw(raw)) else {
+                return;
+            };
+            let is_root = lvgl_sys::lv_menu_back_btn_is_root(menu, target);
+            crate::support::dispatching(menu, || f(obj, is_root));
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn navigate_pages() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut menu = Menu::create(&mut screen).unwrap();
+
+        let mut root = menu.create_page(None).unwrap();
+        let mut display_page = menu
+            .create_page(Some(CStr::from_bytes_with_nul(b"Display\0").unwrap()))
+            .unwrap();
+        let mut section = Menu::create_section(&mut display_page).unwrap();
+        Menu::create_item(&mut section).unwrap();
+        Menu::create_separator(&mut display_page).unwrap();
+        let item = Menu::create_item(&mut root).unwrap();
+        menu.set_load_page_event(&item, &display_page);
+        menu.set_root_back_btn(true);
+
+        assert!(menu.get_cur_main_page().is_none());
+        menu.set_page(Some(&root));
+        assert_eq!(menu.get_cur_main_page().unwrap().raw(), root.raw());
+        menu.set_page(Some(&display_page));
+        assert_eq!(menu.get_cur_main_page().unwrap().raw(), display_page.raw());
+        menu.set_sidebar_page(Some(&root));
+        assert_eq!(menu.get_cur_sidebar_page().unwrap().raw(), root.raw());
+    }
+}
@@ -1,3 +1,9 @@
+use crate::draw::ImageDsc;
+use crate::lv_core::obj::NativeObject;
+use crate::widgets::Meter;
+use crate::{Color, LvError, LvResult};
+use core::ptr::NonNull;
+
 pub enum MeterPart {
     Arc,
     Needle,
@@ -15,3 +21,269 @@ impl From<MeterPart> for u8 {
         }
     }
 }
+
+/// A scale of a `Meter`, with its ticks, labels and value range. The scale is
+/// owned by the meter it was added to, and can only be used with that meter.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MeterScale {
+    raw: NonNull<lvgl_sys::lv_meter_scale_t>,
+    meter: NonNull<lvgl_sys::lv_obj_t>,
+}
+
+/// A needle of a `Meter`, pointing at one value of its scale.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MeterNeedle {
+    raw: NonNull<lvgl_sys::lv_meter_indicator_t>,
+    meter: NonNull<lvgl_sys::lv_obj_t>,
+}
+
+/// An arc or a run of recolored ticks of a `Meter`, highlighting a range of
+/// values of its scale.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MeterRange {
+    raw: NonNull<lvgl_sys::lv_meter_indicator_t>,
+    meter: NonNull<lvgl_sys::lv_obj_t>,
+}
+
+impl<'a> Meter<'a> {
+    /// Adds a scale. Configure it with `set_scale_ticks()`,
+    /// `set_scale_major_ticks()` and `set_scale_range()`.
+    pub fn add_scale(&mut self) -> LvResult<MeterScale> {
+        let meter = self.core.raw();
+        let raw = unsafe { lvgl_sys::lv_meter_add_scale(meter.as_ptr()) };
+        Ok(MeterScale {
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+            meter,
+        })
+    }
+
+    /// Draws `count` ticks on the scale, `width` pixels wide and `len` pixels
+    /// long.
+    pub fn set_scale_ticks(
+        &mut self,
+        scale: &MeterScale,
+        count: u16,
+        width: u16,
+        len: u16,
+        color: Color,
+    ) -> LvResult<()> {
+        let scale = self.check(scale.meter, scale.raw)?;
+        unsafe {
+            lvgl_sys::lv_meter_set_scale_ticks(
+                self.core.raw().as_ptr(),
+                scale,
+                count,
+                width,
+                len,
+                color.into(),
+            )
+        }
+        Ok(())
+    }
+
+    /// Makes every `nth` tick of the scale a major tick, drawn with the given
+    /// size and color and labeled with its value `label_gap` pixels away.
+    pub fn set_scale_major_ticks(
+        &mut self,
+        scale: &MeterScale,
+        nth: u16,
+        width: u16,
+        len: u16,
+        color: Color,
+        label_gap: i16,
+    ) -> LvResult<()> {
+        let scale = self.check(scale.meter, scale.raw)?;
+        unsafe {
+            lvgl_sys::lv_meter_set_scale_major_ticks(
+                self.core.raw().as_ptr(),
+                scale,
+                nth,
+                width,
+                len,
+                color.into(),
+                label_gap,
+            )
+        }
+        Ok(())
+    }
+
+    /// Sets the values at the ends of the scale, and the angle the scale
+    /// spans and starts at, in degrees clockwise from 3 o'clock.
+    pub fn set_scale_range(
+        &mut self,
+        scale: &MeterScale,
+        min: i32,
+        max: i32,
+        angle_range: u32,
+        rotation: u32,
+    ) -> LvResult<()> {
+        let scale = self.check(scale.meter, scale.raw)?;
+        unsafe {
+            lvgl_sys::lv_meter_set_scale_range(
+                self.core.raw().as_ptr(),
+                scale,
+                min,
+                max,
+                angle_range,
+                rotation,
+            )
+        }
+        Ok(())
+    }
+
+    /// Adds a needle drawn as a line. `r_mod` lengthens (or, if negative,
+    /// shortens) it relative to the radius of the scale.
+    pub fn add_needle_line(
+        &mut self,
+        scale: &MeterScale,
+        width: u16,
+        color: Color,
+        r_mod: i16,
+    ) -> LvResult<MeterNeedle> {
+        let meter = self.core.raw();
+        let scale = self.check(scale.meter, scale.raw)?;
+        let raw = unsafe {
+            lvgl_sys::lv_meter_add_needle_line(meter.as_ptr(), scale, width, color.into(), r_mod)
+        };
+        Ok(MeterNeedle {
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+            meter,
+        })
+    }
+
+    /// Adds a needle drawn as an image pointing to the right, rotated around
+    /// the pivot point given in image coordinates. The image must outlive the
+    /// meter.
+    pub fn add_needle_img(
+        &mut self,
+        scale: &MeterScale,
+        src: &'a ImageDsc<'a>,
+        pivot_x: i16,
+        pivot_y: i16,
+    ) -> LvResult<MeterNeedle> {
+        let meter = self.core.raw();
+        let scale = self.check(scale.meter, scale.raw)?;
+        let raw = unsafe {
+            lvgl_sys::lv_meter_add_needle_img(
+                meter.as_ptr(),
+                scale,
+                src.raw() as *const _,
+                pivot_x as lvgl_sys::lv_coord_t,
+                pivot_y as lvgl_sys::lv_coord_t,
+            )
+        };
+        Ok(MeterNeedle {
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+            meter,
+        })
+    }
+
+    /// Adds an arc along the scale, `width` pixels wide, covering the range
+    /// set with `set_range_value()`.
+    pub fn add_arc(
+        &mut self,
+        scale: &MeterScale,
+        width: u16,
+        color: Color,
+        r_mod: i16,
+    ) -> LvResult<MeterRange> {
+        let meter = self.core.raw();
+        let scale = self.check(scale.meter, scale.raw)?;
+        let raw = unsafe {
+            lvgl_sys::lv_meter_add_arc(meter.as_ptr(), scale, width, color.into(), r_mod)
+        };
+        Ok(MeterRange {
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+            meter,
+        })
+    }
+
+    /// Recolors the ticks of the scale in the range set with
+    /// `set_range_value()`, fading from `color_start` to `color_end`. With
+    /// `local`, the fade spans the range instead of the whole scale.
+    pub fn add_scale_lines(
+        &mut self,
+        scale: &MeterScale,
+        color_start: Color,
+        color_end: Color,
+        local: bool,
+        width_mod: i16,
+    ) -> LvResult<MeterRange> {
+        let meter = self.core.raw();
+        let scale = self.check(scale.meter, scale.raw)?;
+        let raw = unsafe {
+            lvgl_sys::lv_meter_add_scale_lines(
+                meter.as_ptr(),
+                scale,
+                color_start.into(),
+                color_end.into(),
+                local,
+                width_mod,
+            )
+        };
+        Ok(MeterRange {
+            raw: NonNull::new(raw).ok_or(LvError::LvOOMemory)?,
+            meter,
+        })
+    }
+
+    /// Points the needle at `value`.
+    pub fn set_needle_value(&mut self, needle: &MeterNeedle, value: i32) -> LvResult<()> {
+        let needle = self.check(needle.meter, needle.raw)?;
+        unsafe { lvgl_sys::lv_meter_set_indicator_value(self.core.raw().as_ptr(), needle, value) }
+        Ok(())
+    }
+
+    /// Sets the range of values an arc or scale lines indicator covers.
+    pub fn set_range_value(&mut self, range: &MeterRange, start: i32, end: i32) -> LvResult<()> {
+        let range = self.check(range.meter, range.raw)?;
+        let meter = self.core.raw().as_ptr();
+        unsafe {
+            lvgl_sys::lv_meter_set_indicator_start_value(meter, range, start);
+            lvgl_sys::lv_meter_set_indicator_end_value(meter, range, end);
+        }
+        Ok(())
+    }
+
+    fn check<T>(&self, meter: NonNull<lvgl_sys::lv_obj_t>, raw: NonNull<T>) -> LvResult<*mut T> {
+        if meter == self.core.raw() {
+            Ok(raw.as_ptr())
+        } else {
+            Err(LvError::InvalidArgument)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn gauge() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut meter = Meter::create(&mut screen).unwrap();
+        let grey = Color::from_rgb((128, 128, 128));
+        let red = Color::from_rgb((255, 0, 0));
+
+        let scale = meter.add_scale().unwrap();
+        meter.set_scale_ticks(&scale, 41, 2, 10, grey).unwrap();
+        meter
+            .set_scale_major_ticks(&scale, 8, 4, 15, grey, 10)
+            .unwrap();
+        meter.set_scale_range(&scale, 0, 100, 270, 135).unwrap();
+        let redline = meter.add_arc(&scale, 3, red, 0).unwrap();
+        meter.set_range_value(&redline, 80, 100).unwrap();
+        let needle = meter.add_needle_line(&scale, 4, grey, -10).unwrap();
+        meter.set_needle_value(&needle, 42).unwrap();
+        assert_eq!(unsafe { needle.raw.as_ref().end_value }, 42);
+
+        let mut other = Meter::create(&mut screen).unwrap();
+        assert_eq!(
+            other.set_needle_value(&needle, 0),
+            Err(LvError::InvalidArgument)
+        );
+    }
+}
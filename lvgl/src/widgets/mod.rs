@@ -4,22 +4,152 @@
 //! associated information, namely its parent widget and its styling data. A
 //! widget with no parent will have a screen as its parent. Style data is
 //! inherited from parent objects by default.
+//!
+//! Only widgets enabled in `lv_conf.h` are available; each of them sets the
+//! `lv_widget` cfg, e.g. `#[cfg(lv_widget = "arc")]`.
+//!
+//! Widgets derived from another widget in LVGL dereference to it, so e.g.
+//! the `Btnmatrix` methods are available on a `Keyboard`, and the `Textarea`
+//! ones on a `Spinbox`.
+//!
+//! The generated wrappers available in this build, and the cargo feature
+//! enabling each of them:
+//!
+#![doc = include_str!(concat!(env!("OUT_DIR"), "/widgets.md"))]
 
+#[cfg(lv_widget = "animimg")]
+mod animimg;
+#[cfg(lv_widget = "arc")]
 mod arc;
+#[cfg(lv_widget = "bar")]
 mod bar;
+#[cfg(lv_widget = "canvas")]
+mod canvas;
+#[cfg(lv_widget = "chart")]
+mod chart;
+#[cfg(lv_widget = "checkbox")]
+mod checkbox;
+#[cfg(lv_widget = "dropdown")]
+mod dropdown;
+#[cfg(lv_widget = "gif")]
+mod gif;
+#[cfg(lv_widget = "img")]
+mod img;
+#[cfg(lv_widget = "keyboard")]
 mod keyboard;
+#[cfg(lv_widget = "label")]
 mod label;
+#[cfg(lv_widget = "led")]
+mod led;
+#[cfg(lv_widget = "line")]
+mod line;
+#[cfg(lv_widget = "list")]
+mod list;
+#[cfg(lv_widget = "lottie")]
+mod lottie;
+#[cfg(lv_widget = "menu")]
+mod menu;
+#[cfg(lv_widget = "meter")]
 mod meter;
+#[cfg(lv_widget = "msgbox")]
+mod msgbox;
+#[cfg(lv_widget = "qrcode")]
+mod qrcode;
+#[cfg(lv_widget = "roller")]
+mod roller;
+#[cfg(lv_widget = "slider")]
 mod slider;
+#[cfg(lv_widget = "spangroup")]
+mod spangroup;
+#[cfg(lv_widget = "spinbox")]
+mod spinbox;
+#[cfg(lv_widget = "switch")]
+mod switch;
+#[cfg(lv_widget = "table")]
 mod table;
+#[cfg(lv_widget = "tabview")]
+mod tabview;
+#[cfg(lv_widget = "textarea")]
+mod textarea;
+#[cfg(any(lv_widget = "label", lv_widget = "textarea"))]
+mod text_writer;
+#[cfg(lv_widget = "tileview")]
+mod tileview;
 
 include!(concat!(env!("OUT_DIR"), "/generated.rs"));
 
 use crate::NativeObject;
+#[cfg(lv_widget = "animimg")]
+pub use animimg::*;
+#[cfg(lv_widget = "arc")]
 pub use arc::*;
+#[cfg(lv_widget = "bar")]
 pub use bar::*;
+#[cfg(lv_widget = "canvas")]
+pub use canvas::*;
+#[cfg(lv_widget = "chart")]
+pub use chart::*;
+#[cfg(lv_widget = "checkbox")]
+pub use checkbox::*;
+#[cfg(lv_widget = "dropdown")]
+pub use dropdown::*;
+#[cfg(lv_widget = "gif")]
+pub use gif::*;
+#[cfg(lv_widget = "img")]
+pub use img::*;
+#[cfg(lv_widget = "keyboard")]
 pub use keyboard::*;
+#[cfg(lv_widget = "label")]
 pub use label::*;
+#[cfg(lv_widget = "led")]
+pub use led::*;
+#[cfg(lv_widget = "line")]
+pub use line::*;
+#[cfg(lv_widget = "list")]
+pub use list::*;
+#[cfg(lv_widget = "lottie")]
+pub use lottie::*;
+#[cfg(lv_widget = "menu")]
+pub use menu::*;
+#[cfg(lv_widget = "meter")]
 pub use meter::*;
+#[cfg(lv_widget = "msgbox")]
+pub use msgbox::*;
+#[cfg(lv_widget = "qrcode")]
+pub use qrcode::*;
+#[cfg(lv_widget = "roller")]
+pub use roller::*;
+#[cfg(lv_widget = "slider")]
 pub use slider::*;
+#[cfg(lv_widget = "spangroup")]
+pub use spangroup::*;
+#[cfg(lv_widget = "spinbox")]
+pub use spinbox::*;
+#[cfg(lv_widget = "switch")]
+pub use switch::*;
+#[cfg(lv_widget = "table")]
 pub use table::*;
+#[cfg(lv_widget = "tabview")]
+pub use tabview::*;
+#[cfg(lv_widget = "textarea")]
+pub use textarea::*;
+#[cfg(any(lv_widget = "label", lv_widget = "textarea"))]
+pub use text_writer::TextWriter;
+#[cfg(lv_widget = "tileview")]
+pub use tileview::*;
+
+/// Has `get` write the selected option of a dropdown or roller into `buf`,
+/// and returns it. The text is cut at a character boundary if `buf` is too
+/// small.
+#[cfg(any(lv_widget = "dropdown", lv_widget = "roller"))]
+fn selected_str(buf: &mut [u8], get: impl FnOnce(*mut cty::c_char, u32)) -> &str {
+    if buf.is_empty() {
+        return "";
+    }
+    get(buf.as_mut_ptr() as *mut _, buf.len() as u32);
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    match core::str::from_utf8(&buf[..len]) {
+        Ok(s) => s,
+        Err(e) => unsafe { core::str::from_utf8_unchecked(&buf[..e.valid_up_to()]) },
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{LvError, LvResult, NativeObject, Widget};
+use core::ffi::c_char;
+use core::{mem, ptr};
+use cstr_core::CStr;
+
+define_object!(
+    /// A message box with a title, a text and a row of buttons. Equivalent
+    /// to an `lv_msgbox` widget.
+    MsgBox
+);
+
+impl<'a> MsgBox<'a> {
+    /// Creates a message box as a child of `parent`.
+    ///
+    /// The texts are copied, so they don't need to outlive the message box.
+    /// They must not contain NUL characters. An empty `title` leaves out the
+    /// title.
+    pub fn create(
+        parent: &mut impl NativeObject,
+        title: &str,
+        text: &str,
+        buttons: &[&str],
+        add_close_btn: bool,
+    ) -> LvResult<Self> {
+        create(parent.raw().as_ptr(), title, text, buttons, add_close_btn)
+    }
+
+    /// Creates a modal message box on the top layer of the default display,
+    /// blocking input to the screen below until it is closed. See
+    /// `create()`.
+    pub fn new(title: &str, text: &str, buttons: &[&str], add_close_btn: bool) -> LvResult<Self> {
+        create(ptr::null_mut(), title, text, buttons, add_close_btn)
+    }
+
+    /// Calls `f` with the index of the button clicked.
+    pub fn on_button_clicked<F>(&mut self, f: F)
+    where
+        F: FnMut(MsgBox<'a>, u16) + 'a,
+    {
+        unsafe {
+            crate::support::add_closure_cb(self.core.raw().as_ptr(), button_clicked_cb::<'a, F>, f)
+        }
+    }
+
+    /// Returns the index of the button clicked last, if any.
+    pub fn get_active_btn(&self) -> Option<u16> {
+        match unsafe { lvgl_sys::lv_msgbox_get_active_btn(self.core.raw().as_ptr()) } {
+            id if id as u32 == lvgl_sys::LV_BTNMATRIX_BTN_NONE => None,
+            id => Some(id),
+        }
+    }
+
+    /// Returns the text of the button clicked last, if any.
+    pub fn get_active_btn_text(&self) -> Option<&CStr> {
+        let text = unsafe { lvgl_sys::lv_msgbox_get_active_btn_text(self.core.raw().as_ptr()) };
+        (!text.is_null()).then(|| unsafe { CStr::from_ptr(text) })
+    }
+
+    /// Closes (deletes) the message box, on the next call to
+    /// `task_handler()` when called from one of its event callbacks, e.g.
+    /// the one registered with `on_button_clicked()`.
+    pub fn close(self) {
+        self.del()
+    }
+
+    /// Closes the message box on the next call to `task_handler()`.
+    pub fn close_async(self) {
+        unsafe { lvgl_sys::lv_msgbox_close_async(self.core.raw().as_ptr()) }
+    }
+}
+
+/// Creates the message box, copying the texts into one LVGL allocation
+/// that is freed with the message box. The button matrix keeps pointers to
+/// the button texts and to the array of them, which therefore have to stay
+/// alive for as long as the message box exists.
+fn create<'a>(
+    parent: *mut lvgl_sys::lv_obj_t,
+    title: &str,
+    text: &str,
+    buttons: &[&str],
+    add_close_btn: bool,
+) -> LvResult<MsgBox<'a>> {
+    let texts = || [title, text].into_iter().chain(buttons.iter().copied());
+    if texts().any(|t| t.contains('\0')) {
+        return Err(LvError::InvalidArgument);
+    }
+    // The map ends with an empty string, which points at the last NUL byte
+    let map_len = (buttons.len() + 1) * mem::size_of::<*const c_char>();
+    let text_len: usize = texts().map(|t| t.len() + 1).sum();
+    let size = map_len + text_len + 1;
+
+    unsafe {
+        let block = lvgl_sys::lv_mem_alloc(size as _) as *mut u8;
+        if block.is_null() {
+            return Err(LvError::LvOOMemory);
+        }
+        let map = block as *mut *const c_char;
+        let mut cursor = block.add(map_len);
+        let mut copy = |t: &str| {
+            let start = cursor;
+            ptr::copy_nonoverlapping(t.as_ptr(), cursor, t.len());
+            *cursor.add(t.len()) = 0;
+            cursor = cursor.add(t.len() + 1);
+            start as *const c_char
+        };
+        let title_ptr = copy(title);
+        let text_ptr = copy(text);
+        for (i, button) in buttons.iter().enumerate() {
+            *map.add(i) = copy(button);
+        }
+        *map.add(buttons.len()) = copy("");
+
+        let raw = lvgl_sys::lv_msgbox_create(
+            parent,
+            if title.is_empty() {
+                ptr::null()
+            } else {
+                title_ptr
+            },
+            text_ptr,
+            map as *mut *const c_char,
+            add_close_btn,
+        );
+        let Some(raw) = ptr::NonNull::new(raw) else {
+            lvgl_sys::lv_mem_free(block as *mut _);
+            return Err(LvError::InvalidReference);
+        };
+        crate::support::free_on_delete(raw.as_ptr(), block as *mut _, ptr::null_mut());
+        Ok(MsgBox::from_raw(raw).unwrap())
+    }
+}
+
+unsafe extern "C" fn button_clicked_cb<'a, F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(MsgBox<'a>, u16) + 'a,
+{
+    let event = &mut *event;
+    let Some(f) = crate::support::event_closure::<F>(event) else {
+        return;
+    };
+    match event.code {
+        lvgl_sys::lv_event_code_t_LV_EVENT_VALUE_CHANGED => {
+            let mbox = event.current_target;
+            let Some(msgbox) = ptr::NonNull::new(mbox).and_then(|raw| MsgBox::from_raw(raw)) else {
+                return;
+            };
+            if let Some(id) = msgbox.get_active_btn() {
+                crate::support::dispatching(mbox, || f(msgbox, id));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn texts_outlive_arguments() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let msgbox = {
+            let buttons = [&*"Apply".to_uppercase(), "Cancel"];
+            MsgBox::create(&mut screen, "Settings", "Apply changes?", &buttons, true).unwrap()
+        };
+
+        let btns = unsafe { lvgl_sys::lv_msgbox_get_btns(msgbox.raw().as_ptr()) };
+        let first = unsafe { CStr::from_ptr(lvgl_sys::lv_btnmatrix_get_btn_text(btns, 0)) };
+        assert_eq!(first.to_str(), Ok("APPLY"));
+        assert_eq!(msgbox.get_active_btn(), None);
+        assert!(MsgBox::create(&mut screen, "", "a\0b", &[], false).is_err());
+        msgbox.close();
+    }
+}
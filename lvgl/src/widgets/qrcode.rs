@@ -0,0 +1,83 @@
+use crate::{Color, LvError, LvResult, NativeObject, Widget};
+use core::ptr::NonNull;
+
+define_object!(
+    /// A QR code, e.g. for pairing a device with a phone app or pointing to a
+    /// provisioning page. Equivalent to an `lv_qrcode` widget.
+    QrCode
+);
+
+impl<'a> QrCode<'a> {
+    /// Creates a `size` by `size` pixel QR code as a child of `parent`,
+    /// drawing its modules in `dark` on `light`.
+    pub fn create(
+        parent: &mut impl NativeObject,
+        size: i16,
+        dark: Color,
+        light: Color,
+    ) -> LvResult<Self> {
+        let raw = unsafe {
+            lvgl_sys::lv_qrcode_create(
+                parent.raw().as_ptr(),
+                size as lvgl_sys::lv_coord_t,
+                dark.into(),
+                light.into(),
+            )
+        };
+        match NonNull::new(raw) {
+            Some(raw) => Ok(unsafe { Self::from_raw(raw) }.unwrap()),
+            None => Err(LvError::LvOOMemory),
+        }
+    }
+
+    /// Creates a QR code on the active screen of the default display. See
+    /// `create()`.
+    pub fn new(size: i16, dark: Color, light: Color) -> LvResult<Self> {
+        let mut parent = crate::display::get_scr_act()?;
+        Self::create(&mut parent, size, dark, light)
+    }
+
+    /// Encodes `data`, e.g. a URL or a pairing code, and redraws the QR code
+    /// with it. The smallest version fitting the data is picked, scaled to
+    /// the size of the widget.
+    ///
+    /// Fails with `LvError::InvalidArgument` if the data is too long to fit
+    /// in a QR code.
+    pub fn update(&mut self, data: &[u8]) -> LvResult<()> {
+        let res = unsafe {
+            lvgl_sys::lv_qrcode_update(
+                self.core.raw().as_ptr(),
+                data.as_ptr() as *const cty::c_void,
+                data.len() as u32,
+            )
+        };
+        if res as u32 == lvgl_sys::LV_RES_OK {
+            Ok(())
+        } else {
+            Err(LvError::InvalidArgument)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn encode_url() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut qr = QrCode::create(
+            &mut screen,
+            100,
+            Color::from_rgb((0, 0, 0)),
+            Color::from_rgb((255, 255, 255)),
+        )
+        .unwrap();
+        qr.update(b"https://example.com/pair?id=42").unwrap();
+        assert!(qr.update(&[b'x'; 8000]).is_err());
+        qr.del();
+    }
+}
@@ -0,0 +1,77 @@
+use crate::lv_core::obj::NativeObject;
+use crate::widgets::Roller;
+use crate::AnimationState;
+use cstr_core::CStr;
+
+/// How a roller behaves when scrolled past its first or last option.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RollerMode {
+    /// Scrolling stops at the first and last option.
+    Normal,
+    /// The options repeat endlessly, e.g. for an hour or minute picker.
+    Infinite,
+}
+
+impl From<RollerMode> for lvgl_sys::lv_roller_mode_t {
+    fn from(mode: RollerMode) -> Self {
+        (match mode {
+            RollerMode::Normal => lvgl_sys::LV_ROLLER_MODE_NORMAL,
+            RollerMode::Infinite => lvgl_sys::LV_ROLLER_MODE_INFINITE,
+        }) as lvgl_sys::lv_roller_mode_t
+    }
+}
+
+impl Roller<'_> {
+    /// Sets the options as a newline-separated list, e.g. `"Mon\nTue\nWed"`.
+    /// The roller keeps its own copy of the text.
+    pub fn set_options(&mut self, options: &CStr, mode: RollerMode) {
+        unsafe {
+            lvgl_sys::lv_roller_set_options(
+                self.core.raw().as_ptr(),
+                options.as_ptr(),
+                mode.into(),
+            );
+        }
+    }
+
+    /// Selects the option at `index`, scrolling to it with an animation if
+    /// `anim` is `AnimationState::ON`.
+    pub fn set_selected(&mut self, index: u16, anim: AnimationState) {
+        unsafe {
+            lvgl_sys::lv_roller_set_selected(self.core.raw().as_ptr(), index, anim.into());
+        }
+    }
+
+    /// Writes the text of the selected option into `buf` and returns it. The
+    /// text is cut at a character boundary if `buf` is too small.
+    pub fn get_selected_str<'b>(&self, buf: &'b mut [u8]) -> &'b str {
+        super::selected_str(buf, |buf, len| unsafe {
+            lvgl_sys::lv_roller_get_selected_str(self.core.raw().as_ptr(), buf, len)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn infinite_roller_selection() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut roller = Roller::create(&mut screen).unwrap();
+
+        let options = CStr::from_bytes_with_nul(b"00\n15\n30\n45\0").unwrap();
+        roller.set_options(options, RollerMode::Infinite);
+        roller.set_selected(2, AnimationState::OFF);
+
+        assert_eq!(
+            unsafe { lvgl_sys::lv_roller_get_option_cnt(roller.raw().as_ptr()) },
+            4
+        );
+        let mut buf = [0u8; 8];
+        assert_eq!(roller.get_selected_str(&mut buf), "30");
+    }
+}
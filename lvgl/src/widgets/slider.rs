@@ -1,6 +1,38 @@
 use crate::lv_core::obj::NativeObject;
+use crate::misc::scale;
 use crate::widgets::Slider;
-use crate::AnimationState;
+use crate::{AnimationState, WidgetValue};
+
+/// How the indicator of a `Slider` is drawn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SliderMode {
+    /// From the minimum to the value.
+    Normal,
+    /// From zero to the value, for ranges spanning negative and positive
+    /// values.
+    Symmetrical,
+    /// From the left value to the value, each with a knob of its own.
+    Range,
+}
+
+impl From<SliderMode> for lvgl_sys::lv_bar_mode_t {
+    fn from(mode: SliderMode) -> Self {
+        (match mode {
+            SliderMode::Normal => lvgl_sys::LV_BAR_MODE_NORMAL,
+            SliderMode::Symmetrical => lvgl_sys::LV_BAR_MODE_SYMMETRICAL,
+            SliderMode::Range => lvgl_sys::LV_BAR_MODE_RANGE,
+        }) as lvgl_sys::lv_bar_mode_t
+    }
+}
+
+/// One of the knobs of a `Slider` in `SliderMode::Range`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SliderKnob {
+    /// The knob of the left value.
+    Left,
+    /// The knob of the value.
+    Right,
+}
 
 impl Slider<'_> {
     /// Set a new value on the slider
@@ -12,4 +44,125 @@ impl Slider<'_> {
     pub fn get_value(&self) -> i32 {
         unsafe { lvgl_sys::lv_bar_get_value(self.core.raw().as_ptr()) }
     }
+
+    /// Sets the value as a fraction of the slider's range, `0.0` being the
+    /// minimum and `1.0` the maximum.
+    pub fn set_value_f32(&self, value: f32, anim: AnimationState) {
+        let (min, max) = self.range();
+        self.set_value(scale::denormalize(value, min, max), anim);
+    }
+
+    /// Gets the current value as a fraction of the slider's range.
+    pub fn get_value_f32(&self) -> f32 {
+        let (min, max) = self.range();
+        scale::normalize(self.get_value(), min, max)
+    }
+
+    /// Sets the minimum and maximum value of the slider.
+    pub fn set_range(&mut self, min: i32, max: i32) {
+        unsafe { lvgl_sys::lv_bar_set_range(self.core.raw().as_ptr(), min, max) }
+    }
+
+    /// Gets the minimum value of the slider.
+    pub fn get_min_value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_bar_get_min_value(self.core.raw().as_ptr()) }
+    }
+
+    /// Gets the maximum value of the slider.
+    pub fn get_max_value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_bar_get_max_value(self.core.raw().as_ptr()) }
+    }
+
+    /// Sets how the indicator is drawn. `SliderMode::Range` adds a second
+    /// knob for the left value.
+    pub fn set_mode(&mut self, mode: SliderMode) {
+        unsafe { lvgl_sys::lv_bar_set_mode(self.core.raw().as_ptr(), mode.into()) }
+    }
+
+    /// Gets how the indicator is drawn.
+    pub fn get_mode(&self) -> SliderMode {
+        match unsafe { lvgl_sys::lv_bar_get_mode(self.core.raw().as_ptr()) } as u32 {
+            lvgl_sys::LV_BAR_MODE_SYMMETRICAL => SliderMode::Symmetrical,
+            lvgl_sys::LV_BAR_MODE_RANGE => SliderMode::Range,
+            _ => SliderMode::Normal,
+        }
+    }
+
+    /// Sets the left value, where the indicator starts in
+    /// `SliderMode::Range`.
+    pub fn set_left_value(&mut self, value: i32, anim: AnimationState) {
+        unsafe { lvgl_sys::lv_bar_set_start_value(self.core.raw().as_ptr(), value, anim.into()) }
+    }
+
+    /// Gets the left value.
+    pub fn get_left_value(&self) -> i32 {
+        unsafe { lvgl_sys::lv_bar_get_start_value(self.core.raw().as_ptr()) }
+    }
+
+    /// Returns the knob being dragged, if any. In a `ValueChanged` event,
+    /// this tells whether the value or the left value changed.
+    pub fn dragged_knob(&self) -> Option<SliderKnob> {
+        unsafe {
+            let slider = &mut *(self.core.raw().as_ptr() as *mut lvgl_sys::lv_slider_t);
+            if slider.dragging() == 0 {
+                return None;
+            }
+            if slider.value_to_set == core::ptr::addr_of_mut!(slider.bar.start_value) {
+                Some(SliderKnob::Left)
+            } else {
+                Some(SliderKnob::Right)
+            }
+        }
+    }
+
+    fn range(&self) -> (i32, i32) {
+        (self.get_min_value(), self.get_max_value())
+    }
+}
+
+/// The value the knob is at. In `SliderMode::Range`, `dragged_knob()` tells
+/// which of the values changed.
+impl WidgetValue for Slider<'_> {
+    type Value = i32;
+
+    fn value(&self) -> i32 {
+        self.get_value()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn value_f32_uses_range() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let slider = Slider::create(&mut screen).unwrap();
+        unsafe { lvgl_sys::lv_bar_set_range(slider.core.raw().as_ptr(), -50, 50) };
+
+        slider.set_value_f32(0.75, AnimationState::OFF);
+        assert_eq!(slider.get_value(), 25);
+        assert_eq!(slider.get_value_f32(), 0.75);
+    }
+
+    #[test]
+    fn range_mode() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut slider = Slider::create(&mut screen).unwrap();
+        slider.set_mode(SliderMode::Range);
+        slider.set_range(0, 1000);
+        slider.set_value(800, AnimationState::OFF);
+        slider.set_left_value(200, AnimationState::OFF);
+
+        assert_eq!(slider.get_mode(), SliderMode::Range);
+        assert_eq!((slider.get_min_value(), slider.get_max_value()), (0, 1000));
+        assert_eq!(slider.get_left_value(), 200);
+        assert_eq!(slider.get_value(), 800);
+        assert_eq!(slider.dragged_knob(), None);
+    }
 }
@@ -0,0 +1,206 @@
+use crate::font::Font;
+use crate::style::Opacity;
+use crate::widgets::Spangroup;
+use crate::{Color, LvError, LvResult, NativeObject, TextAlign};
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ptr::NonNull;
+use cstr_core::CStr;
+
+/// How a `Spangroup` sizes itself to its text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpanMode {
+    /// Keeps the size set on the object.
+    Fixed,
+    /// Grows to fit the text on one line.
+    Expand,
+    /// Keeps the width, breaking lines and growing in height to fit.
+    Break,
+}
+
+impl From<SpanMode> for lvgl_sys::lv_span_mode_t {
+    fn from(mode: SpanMode) -> Self {
+        (match mode {
+            SpanMode::Fixed => lvgl_sys::LV_SPAN_MODE_FIXED,
+            SpanMode::Expand => lvgl_sys::LV_SPAN_MODE_EXPAND,
+            SpanMode::Break => lvgl_sys::LV_SPAN_MODE_BREAK,
+        }) as lvgl_sys::lv_span_mode_t
+    }
+}
+
+/// What a `Spangroup` does with text that doesn't fit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SpanOverflow {
+    /// Cuts the text off at the edge.
+    Clip,
+    /// Ends the last line with "...".
+    Ellipsis,
+}
+
+impl From<SpanOverflow> for lvgl_sys::lv_span_overflow_t {
+    fn from(overflow: SpanOverflow) -> Self {
+        (match overflow {
+            SpanOverflow::Clip => lvgl_sys::LV_SPAN_OVERFLOW_CLIP,
+            SpanOverflow::Ellipsis => lvgl_sys::LV_SPAN_OVERFLOW_ELLIPSIS,
+        }) as lvgl_sys::lv_span_overflow_t
+    }
+}
+
+/// A run of text in a `Spangroup`, styled independently of the other runs.
+/// Equivalent to an `lv_span_t`.
+///
+/// Spans belong to their group, which frees them when it is deleted. A
+/// `Span` borrows the group, so it can't outlive it; the group is laid out
+/// again when the `Span` is dropped.
+pub struct Span<'g> {
+    raw: NonNull<lvgl_sys::lv_span_t>,
+    group: NonNull<lvgl_sys::lv_obj_t>,
+    _group: PhantomData<&'g mut lvgl_sys::lv_obj_t>,
+}
+
+impl Span<'_> {
+    /// Sets the text of the span. The text is copied.
+    pub fn set_text(&mut self, text: &CStr) {
+        unsafe { lvgl_sys::lv_span_set_text(self.raw.as_ptr(), text.as_ptr()) }
+    }
+
+    /// Returns the text of the span.
+    pub fn get_text(&self) -> &CStr {
+        let text = unsafe { self.raw.as_ref().txt };
+        if text.is_null() {
+            unsafe { CStr::from_bytes_with_nul_unchecked(b"\0") }
+        } else {
+            unsafe { CStr::from_ptr(text) }
+        }
+    }
+
+    /// Sets the color of the text.
+    pub fn set_text_color(&mut self, color: Color) {
+        unsafe { lvgl_sys::lv_style_set_text_color(self.style(), color.into()) }
+    }
+
+    /// Sets the opacity of the text.
+    pub fn set_text_opa(&mut self, opa: Opacity) {
+        unsafe { lvgl_sys::lv_style_set_text_opa(self.style(), opa.into()) }
+    }
+
+    /// Sets the font of the text.
+    pub fn set_text_font(&mut self, font: Font) {
+        unsafe { lvgl_sys::lv_style_set_text_font(self.style(), font.into()) }
+    }
+
+    /// Sets the decoration of the text, a combination of
+    /// `LV_TEXT_DECOR_UNDERLINE` and `LV_TEXT_DECOR_STRIKETHROUGH`.
+    pub fn set_text_decor(&mut self, decor: u8) {
+        unsafe { lvgl_sys::lv_style_set_text_decor(self.style(), decor) }
+    }
+
+    /// Sets the space between the letters of the text.
+    pub fn set_text_letter_space(&mut self, space: i16) {
+        unsafe { lvgl_sys::lv_style_set_text_letter_space(self.style(), space as _) }
+    }
+
+    /// Removes the span from its group and frees it.
+    pub fn delete(self) {
+        let span = ManuallyDrop::new(self);
+        unsafe { lvgl_sys::lv_spangroup_del_span(span.group.as_ptr(), span.raw.as_ptr()) }
+    }
+
+    fn style(&mut self) -> *mut lvgl_sys::lv_style_t {
+        unsafe { &mut (*self.raw.as_ptr()).style }
+    }
+}
+
+impl Drop for Span<'_> {
+    fn drop(&mut self) {
+        unsafe { lvgl_sys::lv_spangroup_refr_mode(self.group.as_ptr()) }
+    }
+}
+
+impl Spangroup<'_> {
+    /// Appends a new, empty span to the group.
+    pub fn new_span(&mut self) -> LvResult<Span<'_>> {
+        let group = self.core.raw();
+        let span = unsafe { lvgl_sys::lv_spangroup_new_span(group.as_ptr()) };
+        Ok(Span {
+            raw: NonNull::new(span).ok_or(LvError::LvOOMemory)?,
+            group,
+            _group: PhantomData,
+        })
+    }
+
+    /// Appends a span with the given text and color.
+    pub fn add_text(&mut self, text: &CStr, color: Color) -> LvResult<()> {
+        let mut span = self.new_span()?;
+        span.set_text(text);
+        span.set_text_color(color);
+        Ok(())
+    }
+
+    /// Returns the span at `index`. Negative indices count from the end, so
+    /// -1 is the last span.
+    pub fn span(&mut self, index: i32) -> Option<Span<'_>> {
+        let group = self.core.raw();
+        let span = unsafe { lvgl_sys::lv_spangroup_get_child(group.as_ptr(), index) };
+        Some(Span {
+            raw: NonNull::new(span)?,
+            group,
+            _group: PhantomData,
+        })
+    }
+
+    /// Sets how the group sizes itself to its text.
+    pub fn set_mode(&mut self, mode: SpanMode) {
+        unsafe { lvgl_sys::lv_spangroup_set_mode(self.core.raw().as_ptr(), mode.into()) }
+    }
+
+    /// Sets what happens to text that doesn't fit.
+    pub fn set_overflow(&mut self, overflow: SpanOverflow) {
+        unsafe { lvgl_sys::lv_spangroup_set_overflow(self.core.raw().as_ptr(), overflow.into()) }
+    }
+
+    /// Sets the alignment of the lines of text.
+    pub fn set_text_align(&mut self, align: TextAlign) {
+        unsafe { lvgl_sys::lv_spangroup_set_align(self.core.raw().as_ptr(), align.into()) }
+    }
+
+    /// Sets the indentation of the first line.
+    pub fn set_indent(&mut self, indent: i16) {
+        unsafe { lvgl_sys::lv_spangroup_set_indent(self.core.raw().as_ptr(), indent as _) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Display, Widget};
+
+    #[test]
+    fn spans_are_freed_with_group() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut group = Spangroup::create(&mut screen).unwrap();
+        group.set_mode(SpanMode::Break);
+
+        let temperature = CStr::from_bytes_with_nul(b"21.5 \xc2\xb0C\0").unwrap();
+        group
+            .add_text(
+                CStr::from_bytes_with_nul(b"Outside: \0").unwrap(),
+                Color::from_rgb((128, 128, 128)),
+            )
+            .unwrap();
+        group
+            .add_text(temperature, Color::from_rgb((255, 0, 0)))
+            .unwrap();
+        {
+            let mut last = group.span(-1).unwrap();
+            assert_eq!(last.get_text(), temperature);
+            last.set_text_letter_space(2);
+        }
+        group.span(0).unwrap().delete();
+        assert_eq!(group.span(0).unwrap().get_text(), temperature);
+        assert!(group.span(1).is_none());
+        group.del();
+    }
+}
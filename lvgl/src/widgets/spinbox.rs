@@ -0,0 +1,64 @@
+use crate::widgets::Spinbox;
+use crate::{Direction, NativeObject};
+
+impl Spinbox<'_> {
+    /// Sets how much the value changes per increment or decrement. Steps are
+    /// powers of ten, so the cursor lands on the matching digit.
+    pub fn set_step(&mut self, step: u32) {
+        unsafe { lvgl_sys::lv_spinbox_set_step(self.core.raw().as_ptr(), step) }
+    }
+
+    /// Sets the direction the edited digit moves in when the spinbox is
+    /// clicked or stepped with an encoder, `Direction::LEFT` or
+    /// `Direction::RIGHT`.
+    pub fn set_digit_step_direction(&mut self, direction: Direction) {
+        unsafe {
+            lvgl_sys::lv_spinbox_set_digit_step_direction(
+                self.core.raw().as_ptr(),
+                direction.into(),
+            )
+        }
+    }
+
+    /// Gets the minimum and maximum value of the spinbox.
+    pub fn get_range(&self) -> (i32, i32) {
+        let spinbox = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_spinbox_t) };
+        (spinbox.range_min, spinbox.range_max)
+    }
+
+    /// Gets the number of digits shown and the position of the decimal
+    /// point, counted in digits from the left.
+    pub fn get_digit_format(&self) -> (u8, u8) {
+        let spinbox = unsafe { &*(self.core.raw().as_ptr() as *const lvgl_sys::lv_spinbox_t) };
+        (spinbox.digit_count() as u8, spinbox.dec_point_pos() as u8)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn step_and_range() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut spinbox = Spinbox::create(&mut screen).unwrap();
+        let raw = spinbox.core.raw().as_ptr();
+        unsafe {
+            lvgl_sys::lv_spinbox_set_range(raw, -1000, 1000);
+            lvgl_sys::lv_spinbox_set_digit_format(raw, 4, 2);
+        }
+        spinbox.set_step(10);
+        spinbox.set_digit_step_direction(Direction::LEFT);
+
+        assert_eq!(spinbox.get_range(), (-1000, 1000));
+        assert_eq!(spinbox.get_digit_format(), (4, 2));
+        unsafe {
+            lvgl_sys::lv_spinbox_increment(raw);
+            assert_eq!(lvgl_sys::lv_spinbox_get_value(raw), 10);
+            assert_eq!(lvgl_sys::lv_spinbox_get_step(raw), 10);
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use crate::widgets::Switch;
+use crate::{State, Widget, WidgetValue};
+
+impl Switch<'_> {
+    /// Returns whether the switch is on.
+    pub fn is_checked(&self) -> bool {
+        self.has_state(State::CHECKED)
+    }
+
+    /// Turns the switch on or off. Like other setters, this doesn't send a
+    /// `ValueChanged` event.
+    pub fn set_checked(&mut self, checked: bool) {
+        if checked {
+            self.add_state(State::CHECKED);
+        } else {
+            self.clear_state(State::CHECKED);
+        }
+    }
+
+    /// Turns the switch off if it is on and the other way round, returning
+    /// whether it is on now. The knob slides over with the switch's
+    /// `anim_time`.
+    pub fn toggle(&mut self) -> bool {
+        let checked = !self.is_checked();
+        self.set_checked(checked);
+        checked
+    }
+}
+
+/// Whether the switch is on.
+impl WidgetValue for Switch<'_> {
+    type Value = bool;
+
+    fn value(&self) -> bool {
+        self.is_checked()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn toggle_flips_checked() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut switch = Switch::create(&mut screen).unwrap();
+
+        assert!(switch.toggle());
+        assert!(switch.is_checked());
+        assert!(!switch.toggle());
+        assert!(!switch.is_checked());
+    }
+}
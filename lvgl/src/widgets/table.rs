@@ -1,14 +1,80 @@
 use crate::lv_core::obj::NativeObject;
 use crate::widgets::Table;
+use crate::{Color, LvError, LvResult, TextAlign};
 use core::mem::MaybeUninit;
+use cstr_core::CStr;
 
-impl Table<'_> {
+bitflags! {
+    /// Per-cell control flags of a table.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct TableCellCtrl: u8 {
+        /// Merges the cell with its right neighbour.
+        const MERGE_RIGHT = lvgl_sys::LV_TABLE_CELL_CTRL_MERGE_RIGHT as u8;
+        /// Cuts the text at the cell border instead of wrapping it.
+        const TEXT_CROP = lvgl_sys::LV_TABLE_CELL_CTRL_TEXT_CROP as u8;
+        /// Application-defined flags, e.g. for highlighting cells in
+        /// `on_cell_draw()`.
+        const CUSTOM_1 = lvgl_sys::LV_TABLE_CELL_CTRL_CUSTOM_1 as u8;
+        const CUSTOM_2 = lvgl_sys::LV_TABLE_CELL_CTRL_CUSTOM_2 as u8;
+        const CUSTOM_3 = lvgl_sys::LV_TABLE_CELL_CTRL_CUSTOM_3 as u8;
+        const CUSTOM_4 = lvgl_sys::LV_TABLE_CELL_CTRL_CUSTOM_4 as u8;
+    }
+}
+
+/// A table cell about to be drawn. Changes made through it only affect the
+/// current drawing of the cell.
+pub struct CellDraw<'a> {
+    dsc: &'a mut lvgl_sys::lv_obj_draw_part_dsc_t,
+    row: u16,
+    col: u16,
+}
+
+impl CellDraw<'_> {
+    /// The row of the cell.
+    pub fn row(&self) -> u16 {
+        self.row
+    }
+
+    /// The column of the cell.
+    pub fn col(&self) -> u16 {
+        self.col
+    }
+
+    /// Fills the background of the cell with `color`.
+    pub fn set_bg_color(&mut self, color: Color) {
+        if let Some(rect) = unsafe { self.dsc.rect_dsc.as_mut() } {
+            rect.bg_color = color.into();
+            rect.bg_opa = lvgl_sys::LV_OPA_COVER as lvgl_sys::lv_opa_t;
+        }
+    }
+
+    /// Sets the text color of the cell.
+    pub fn set_text_color(&mut self, color: Color) {
+        if let Some(label) = unsafe { self.dsc.label_dsc.as_mut() } {
+            label.color = color.into();
+        }
+    }
+
+    /// Sets the alignment of the text within the cell.
+    pub fn set_text_align(&mut self, align: TextAlign) {
+        if let Some(label) = unsafe { self.dsc.label_dsc.as_mut() } {
+            label.align = align.into();
+        }
+    }
+}
+
+impl<'a> Table<'a> {
     /// Sets the column width. Row height cannot be set manually and is
     /// calculated by LVGL based on styling parameters.
     pub fn set_col_width(&mut self, column: u16, width: i16) {
         unsafe { lvgl_sys::lv_table_set_col_width(self.core.raw().as_ptr(), column, width) }
     }
 
+    /// Returns the width of a column.
+    pub fn get_col_width(&self, column: u16) -> i16 {
+        unsafe { lvgl_sys::lv_table_get_col_width(self.core.raw().as_ptr(), column) }
+    }
+
     /// Returns the selected cell as a tuple of (row, column).
     pub fn get_selected_cell(&self) -> (u16, u16) {
         let mut row = MaybeUninit::<u16>::uninit();
@@ -23,4 +89,151 @@ impl Table<'_> {
             (row.assume_init(), col.assume_init())
         }
     }
+
+    /// Sets the text of a cell from a Rust string, which needs no NUL
+    /// terminator. The table grows as needed to contain the cell.
+    pub fn set_cell_str(&mut self, row: u16, col: u16, text: &str) -> LvResult<()> {
+        let len: cty::c_int = text.len().try_into().map_err(|_| LvError::InvalidArgument)?;
+        unsafe {
+            lvgl_sys::lv_table_set_cell_value_fmt(
+                self.core.raw().as_ptr(),
+                row,
+                col,
+                b"%.*s\0".as_ptr() as *const cty::c_char,
+                len,
+                text.as_ptr() as *const cty::c_char,
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns the text of a cell, which is empty if the cell was never set.
+    pub fn get_cell_value(&self, row: u16, col: u16) -> &CStr {
+        unsafe {
+            CStr::from_ptr(lvgl_sys::lv_table_get_cell_value(
+                self.core.raw().as_ptr(),
+                row,
+                col,
+            ))
+        }
+    }
+
+    /// Replaces the content of the table with `rows`. The table is resized
+    /// to the number of rows and the length of the longest row.
+    ///
+    /// ```ignore
+    /// let log = [["12:00", "21.5 °C"], ["12:05", "21.7 °C"]];
+    /// table.set_rows(log)?;
+    /// ```
+    pub fn set_rows<I, R>(&mut self, rows: I) -> LvResult<()>
+    where
+        I: IntoIterator<Item = R>,
+        R: IntoIterator,
+        R::Item: AsRef<str>,
+    {
+        let obj = self.core.raw().as_ptr();
+        let mut row_cnt = 0;
+        let mut col_cnt = 0;
+        for (row, cells) in rows.into_iter().enumerate() {
+            let row: u16 = row.try_into().map_err(|_| LvError::InvalidArgument)?;
+            let mut len = 0;
+            for (col, text) in cells.into_iter().enumerate() {
+                len = col.try_into().map_err(|_| LvError::InvalidArgument)?;
+                self.set_cell_str(row, len, text.as_ref())?;
+                len += 1;
+            }
+            // Clear what is left of the previous content in this row
+            for col in len..unsafe { lvgl_sys::lv_table_get_col_cnt(obj) } {
+                self.set_cell_str(row, col, "")?;
+            }
+            row_cnt = row + 1;
+            col_cnt = col_cnt.max(len);
+        }
+        unsafe {
+            lvgl_sys::lv_table_set_row_cnt(obj, row_cnt);
+            lvgl_sys::lv_table_set_col_cnt(obj, col_cnt);
+        }
+        Ok(())
+    }
+
+    /// Sets control flags on a cell.
+    pub fn add_cell_ctrl(&mut self, row: u16, col: u16, ctrl: TableCellCtrl) {
+        unsafe {
+            lvgl_sys::lv_table_add_cell_ctrl(self.core.raw().as_ptr(), row, col, ctrl.bits());
+        }
+    }
+
+    /// Clears control flags of a cell.
+    pub fn clear_cell_ctrl(&mut self, row: u16, col: u16, ctrl: TableCellCtrl) {
+        unsafe {
+            lvgl_sys::lv_table_clear_cell_ctrl(self.core.raw().as_ptr(), row, col, ctrl.bits());
+        }
+    }
+
+    /// Returns whether all of `ctrl` are set on a cell.
+    pub fn has_cell_ctrl(&self, row: u16, col: u16, ctrl: TableCellCtrl) -> bool {
+        unsafe { lvgl_sys::lv_table_has_cell_ctrl(self.core.raw().as_ptr(), row, col, ctrl.bits()) }
+    }
+
+    /// Calls `f` before every cell is drawn, to style cells individually,
+    /// e.g. to highlight out-of-range readings.
+    pub fn on_cell_draw<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut CellDraw) + 'a,
+    {
+        unsafe { crate::support::add_closure_cb(self.core.raw().as_ptr(), cell_draw_cb::<F>, f) }
+    }
+}
+
+unsafe extern "C" fn cell_draw_cb<F>(event: *mut lvgl_sys::lv_event_t)
+where
+    F: FnMut(&mut CellDraw),
+{
+    let event = &mut *event;
+    let Some(f) = crate::support::event_closure::<F>(event) else {
+        return;
+    };
+    match event.code {
+        lvgl_sys::lv_event_code_t_LV_EVENT_DRAW_PART_BEGIN => {
+            let dsc = &mut *(event.param as *mut lvgl_sys::lv_obj_draw_part_dsc_t);
+            if dsc.class_p != &lvgl_sys::lv_table_class as *const _
+                || dsc.part != lvgl_sys::LV_PART_ITEMS
+            {
+                return;
+            }
+            let col_cnt = lvgl_sys::lv_table_get_col_cnt(event.current_target).max(1) as u32;
+            let (row, col) = ((dsc.id / col_cnt) as u16, (dsc.id % col_cnt) as u16);
+            f(&mut CellDraw { dsc, row, col });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn set_rows_resizes_table() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut table = Table::create(&mut screen).unwrap();
+        table
+            .set_rows([["a", "b", "c"], ["d", "e", "f"], ["g", "h", "i"]])
+            .unwrap();
+
+        table
+            .set_rows([vec!["time", "temp"], vec!["12:00"]])
+            .unwrap();
+        let obj = table.raw().as_ptr();
+        unsafe {
+            assert_eq!(lvgl_sys::lv_table_get_row_cnt(obj), 2);
+            assert_eq!(lvgl_sys::lv_table_get_col_cnt(obj), 2);
+        }
+        assert_eq!(table.get_cell_value(0, 1).to_str(), Ok("temp"));
+        assert_eq!(table.get_cell_value(1, 0).to_str(), Ok("12:00"));
+        assert_eq!(table.get_cell_value(1, 1).to_str(), Ok(""));
+    }
 }
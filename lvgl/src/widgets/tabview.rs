@@ -0,0 +1,102 @@
+use crate::{AnimationState, Direction, LvError, LvResult, NativeObject, Obj, Widget};
+use core::ptr::NonNull;
+use cstr_core::CStr;
+
+define_object!(
+    /// A container with one page per tab and a row of tab buttons. Equivalent
+    /// to an `lv_tabview` widget.
+    Tabview
+);
+
+impl<'a> Tabview<'a> {
+    /// Creates a tab view as a child of `parent`, with the tab buttons on
+    /// the `tab_pos` side, `tab_size` pixels high (or wide).
+    pub fn create(
+        parent: &mut impl NativeObject,
+        tab_pos: Direction,
+        tab_size: i16,
+    ) -> LvResult<Self> {
+        let raw = unsafe {
+            lvgl_sys::lv_tabview_create(
+                parent.raw().as_ptr(),
+                tab_pos.into(),
+                tab_size as lvgl_sys::lv_coord_t,
+            )
+        };
+        match NonNull::new(raw) {
+            Some(raw) => Ok(unsafe { Self::from_raw(raw) }.unwrap()),
+            None => Err(LvError::InvalidReference),
+        }
+    }
+
+    /// Adds a tab named `name`, returning its page to add widgets to.
+    pub fn add_tab(&mut self, name: &CStr) -> LvResult<Obj<'a>> {
+        let page = unsafe { lvgl_sys::lv_tabview_add_tab(self.core.raw().as_ptr(), name.as_ptr()) };
+        match NonNull::new(page) {
+            Some(page) => Ok(unsafe { Obj::from_raw(page) }.unwrap()),
+            None => Err(LvError::LvOOMemory),
+        }
+    }
+
+    /// Renames the tab at `index`.
+    pub fn rename_tab(&mut self, index: u32, name: &CStr) {
+        unsafe {
+            lvgl_sys::lv_tabview_rename_tab(self.core.raw().as_ptr(), index, name.as_ptr());
+        }
+    }
+
+    /// Switches to the tab at `index`.
+    pub fn set_active_tab(&mut self, index: u32, anim: AnimationState) {
+        unsafe { lvgl_sys::lv_tabview_set_act(self.core.raw().as_ptr(), index, anim.into()) }
+    }
+
+    /// Returns the index of the tab shown.
+    pub fn get_active_tab(&self) -> u16 {
+        unsafe { lvgl_sys::lv_tabview_get_tab_act(self.core.raw().as_ptr()) }
+    }
+
+    /// Returns the container holding the pages of all tabs.
+    pub fn get_content(&self) -> LvResult<Obj<'a>> {
+        let content = unsafe { lvgl_sys::lv_tabview_get_content(self.core.raw().as_ptr()) };
+        NonNull::new(content)
+            .and_then(|c| unsafe { Obj::from_raw(c) })
+            .ok_or(LvError::InvalidReference)
+    }
+
+    /// Returns the button matrix holding the tab buttons, e.g. to style it.
+    pub fn get_tab_btns(&self) -> LvResult<Obj<'a>> {
+        let btns = unsafe { lvgl_sys::lv_tabview_get_tab_btns(self.core.raw().as_ptr()) };
+        NonNull::new(btns)
+            .and_then(|b| unsafe { Obj::from_raw(b) })
+            .ok_or(LvError::InvalidReference)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn tabs_are_pages() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut tabview = Tabview::create(&mut screen, Direction::LEFT, 60).unwrap();
+
+        let overview = tabview
+            .add_tab(CStr::from_bytes_with_nul(b"Overview\0").unwrap())
+            .unwrap();
+        tabview
+            .add_tab(CStr::from_bytes_with_nul(b"Settings\0").unwrap())
+            .unwrap();
+        let content = tabview.get_content().unwrap();
+        assert_eq!(
+            unsafe { lvgl_sys::lv_obj_get_parent(overview.raw().as_ptr()) },
+            content.raw().as_ptr()
+        );
+
+        tabview.set_active_tab(1, AnimationState::OFF);
+        assert_eq!(tabview.get_active_tab(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{LvError, LvResult};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// Size of the buffer text is collected in before it's given to a widget.
+const WRITER_BUFFER: usize = 128;
+
+/// The widgets a `TextWriter` can write to.
+#[derive(Clone, Copy)]
+pub(crate) enum TextTarget {
+    #[cfg(lv_widget = "label")]
+    Label,
+    #[cfg(lv_widget = "textarea")]
+    Textarea,
+}
+
+/// Replaces the text of a label or text area with text written with
+/// `core::fmt::Write`, see `Label::writer()` and `Textarea::writer()`.
+///
+/// Text is collected in a buffer on the stack and set as the widget's text
+/// in a single call when the writer is dropped, so e.g. a reading can be
+/// formatted into a label every frame without a heap. Text that doesn't fit
+/// the buffer is appended to the widget in pieces, so text written before a
+/// failed write may already have been set.
+pub struct TextWriter<'a> {
+    obj: *mut lvgl_sys::lv_obj_t,
+    target: TextTarget,
+    buffer: [u8; WRITER_BUFFER],
+    len: usize,
+    /// Whether the widget's text was replaced already, so that further text
+    /// is appended to it.
+    replaced: bool,
+    _widget: PhantomData<&'a mut lvgl_sys::lv_obj_t>,
+}
+
+impl TextWriter<'_> {
+    /// # Safety
+    ///
+    /// `obj` must point to a widget of the `target` kind that outlives the
+    /// writer.
+    pub(crate) unsafe fn new(obj: *mut lvgl_sys::lv_obj_t, target: TextTarget) -> Self {
+        Self {
+            obj,
+            target,
+            buffer: [0; WRITER_BUFFER],
+            len: 0,
+            replaced: false,
+            _widget: PhantomData,
+        }
+    }
+
+    /// Replaces the text with `args`, leaving it as it is if they fail to
+    /// format or contain a NUL character.
+    ///
+    /// # Safety
+    ///
+    /// As for `new()`.
+    pub(crate) unsafe fn set_fmt(
+        obj: *mut lvgl_sys::lv_obj_t,
+        target: TextTarget,
+        args: fmt::Arguments,
+    ) -> LvResult<()> {
+        // Checked first, as long text is set in pieces while it is written
+        fmt::write(&mut NulCheck, args).map_err(|_| LvError::InvalidArgument)?;
+        fmt::write(&mut Self::new(obj, target), args).map_err(|_| LvError::InvalidArgument)
+    }
+
+    fn flush(&mut self) {
+        self.buffer[self.len] = 0;
+        let text = self.buffer.as_ptr() as *const cty::c_char;
+        unsafe {
+            match (self.target, self.replaced) {
+                #[cfg(lv_widget = "label")]
+                (TextTarget::Label, false) => lvgl_sys::lv_label_set_text(self.obj, text),
+                #[cfg(lv_widget = "label")]
+                (TextTarget::Label, true) => {
+                    lvgl_sys::lv_label_ins_text(self.obj, lvgl_sys::LV_LABEL_POS_LAST, text)
+                }
+                #[cfg(lv_widget = "textarea")]
+                (TextTarget::Textarea, false) => lvgl_sys::lv_textarea_set_text(self.obj, text),
+                // The cursor is at the end after setting the text
+                #[cfg(lv_widget = "textarea")]
+                (TextTarget::Textarea, true) => lvgl_sys::lv_textarea_add_text(self.obj, text),
+            }
+        }
+        self.replaced = true;
+        self.len = 0;
+    }
+}
+
+impl fmt::Write for TextWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Whole characters only, as LVGL counts the text it appends to in
+        // characters rather than bytes
+        for c in s.chars() {
+            if c == '\0' {
+                return Err(fmt::Error);
+            }
+            if self.len + c.len_utf8() >= WRITER_BUFFER {
+                self.flush();
+            }
+            self.len += c.encode_utf8(&mut self.buffer[self.len..]).len();
+        }
+        Ok(())
+    }
+}
+
+/// Formats without writing anything, failing on NUL characters.
+struct NulCheck;
+
+impl fmt::Write for NulCheck {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if s.contains('\0') {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for TextWriter<'_> {
+    fn drop(&mut self) {
+        // Also replaces the text if nothing was written
+        if self.len > 0 || !self.replaced {
+            self.flush();
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::lv_core::obj::NativeObject;
+use crate::widgets::text_writer::TextTarget;
+use crate::widgets::{TextWriter, Textarea};
+use crate::LvResult;
+use core::fmt;
+use cstr_core::CStr;
+
+impl Textarea<'_> {
+    /// Limits the number of characters that can be entered. `0` means no
+    /// limit.
+    pub fn set_max_length(&mut self, max: u32) {
+        unsafe { lvgl_sys::lv_textarea_set_max_length(self.core.raw().as_ptr(), max) }
+    }
+
+    /// Returns the maximum number of characters, `0` if unlimited.
+    pub fn get_max_length(&self) -> u32 {
+        unsafe { lvgl_sys::lv_textarea_get_max_length(self.core.raw().as_ptr()) }
+    }
+
+    /// Returns the current text. In password mode this is the actual text,
+    /// not the bullets shown on screen.
+    pub fn get_text(&self) -> &CStr {
+        unsafe { CStr::from_ptr(lvgl_sys::lv_textarea_get_text(self.core.raw().as_ptr())) }
+    }
+
+    /// Returns the text shown while the text area is empty.
+    pub fn get_placeholder_text(&self) -> &CStr {
+        unsafe {
+            CStr::from_ptr(lvgl_sys::lv_textarea_get_placeholder_text(
+                self.core.raw().as_ptr(),
+            ))
+        }
+    }
+
+    /// Sets the text to formatted arguments, e.g.
+    /// `textarea.set_text_fmt(format_args!("{:.2}", value))`. Fails, leaving
+    /// the text as it is, if the text contains a NUL character.
+    pub fn set_text_fmt(&mut self, args: fmt::Arguments) -> LvResult<()> {
+        unsafe { TextWriter::set_fmt(self.core.raw().as_ptr(), TextTarget::Textarea, args) }
+    }
+
+    /// Returns a writer replacing the text with what is written to it with
+    /// `write!()`. The text is set once the writer is dropped.
+    pub fn writer(&mut self) -> TextWriter<'_> {
+        unsafe { TextWriter::new(self.core.raw().as_ptr(), TextTarget::Textarea) }
+    }
+
+    /// Moves the cursor to the end of the text.
+    pub fn set_cursor_to_end(&mut self) {
+        unsafe {
+            lvgl_sys::lv_textarea_set_cursor_pos(
+                self.core.raw().as_ptr(),
+                lvgl_sys::LV_TEXTAREA_CURSOR_LAST as i32,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn password_text_is_kept() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut textarea = Textarea::create(&mut screen).unwrap();
+
+        textarea.set_password_mode(true);
+        textarea.set_one_line(true);
+        textarea.set_max_length(6);
+        textarea.add_text(CStr::from_bytes_with_nul(b"secret!\0").unwrap());
+
+        assert_eq!(textarea.get_text().to_bytes(), b"secret");
+        assert_eq!(textarea.get_max_length(), 6);
+    }
+
+    #[test]
+    fn writer_replaces_text() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut textarea = Textarea::create(&mut screen).unwrap();
+
+        textarea.add_text(CStr::from_bytes_with_nul(b"old\0").unwrap());
+        textarea
+            .set_text_fmt(format_args!("{:.2}", 1.0 / 3.0))
+            .unwrap();
+        assert_eq!(textarea.get_text().to_bytes(), b"0.33");
+
+        {
+            let mut writer = textarea.writer();
+            for i in 0..100 {
+                fmt::Write::write_fmt(&mut writer, format_args!("{i},")).unwrap();
+            }
+        }
+        let text = textarea.get_text().to_str().unwrap();
+        assert_eq!(text.len(), 290);
+        assert!(text.starts_with("0,1,2,"));
+        assert!(text.ends_with("98,99,"));
+    }
+}
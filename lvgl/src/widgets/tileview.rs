@@ -0,0 +1,62 @@
+use crate::widgets::Tileview;
+use crate::{AnimationState, Direction, LvError, LvResult, NativeObject, Obj};
+use core::ptr::NonNull;
+
+impl<'a> Tileview<'a> {
+    /// Adds a tile at column `col` and row `row` of the grid, from which the
+    /// user can swipe in the directions of `dir`. Returns the tile to add
+    /// widgets to.
+    pub fn add_tile(&mut self, col: u8, row: u8, dir: Direction) -> LvResult<Obj<'a>> {
+        let tile = unsafe {
+            lvgl_sys::lv_tileview_add_tile(self.core.raw().as_ptr(), col, row, dir.into())
+        };
+        match NonNull::new(tile) {
+            Some(tile) => Ok(unsafe { Obj::from_raw(tile) }.unwrap()),
+            None => Err(LvError::LvOOMemory),
+        }
+    }
+
+    /// Scrolls to `tile`, which must have been returned by `add_tile()` on
+    /// this tile view.
+    pub fn set_tile(&mut self, tile: &Obj<'a>, anim: AnimationState) {
+        unsafe {
+            lvgl_sys::lv_obj_set_tile(self.core.raw().as_ptr(), tile.raw().as_ptr(), anim.into())
+        }
+    }
+
+    /// Scrolls to the tile at column `col` and row `row`.
+    pub fn set_tile_id(&mut self, col: u32, row: u32, anim: AnimationState) {
+        unsafe { lvgl_sys::lv_obj_set_tile_id(self.core.raw().as_ptr(), col, row, anim.into()) }
+    }
+
+    /// Returns the tile shown.
+    pub fn get_tile_act(&self) -> LvResult<Obj<'a>> {
+        let tile = unsafe { lvgl_sys::lv_tileview_get_tile_act(self.core.raw().as_ptr()) };
+        NonNull::new(tile)
+            .and_then(|t| unsafe { Obj::from_raw(t) })
+            .ok_or(LvError::InvalidReference)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Display;
+
+    #[test]
+    fn switch_tiles() {
+        crate::tests::initialize_test(true);
+        let display = Display::default();
+        let mut screen = display.get_scr_act().unwrap();
+        let mut tileview = Tileview::create(&mut screen).unwrap();
+
+        let home = tileview.add_tile(0, 0, Direction::RIGHT).unwrap();
+        let details = tileview
+            .add_tile(1, 0, Direction::LEFT | Direction::BOTTOM)
+            .unwrap();
+        tileview.set_tile(&details, AnimationState::OFF);
+        assert_eq!(tileview.get_tile_act().unwrap().raw(), details.raw());
+        tileview.set_tile_id(0, 0, AnimationState::OFF);
+        assert_eq!(tileview.get_tile_act().unwrap().raw(), home.raw());
+    }
+}
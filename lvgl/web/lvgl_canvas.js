@@ -0,0 +1,49 @@
+// Emscripten JS library backing the `web` module of the lvgl crate. Link it
+// with `-C link-arg=--js-library=<path to this file>`.
+
+mergeInto(LibraryManager.library, {
+  lvgl_canvas_init__deps: ['$UTF8ToString'],
+  lvgl_canvas_init: function (selector, width, height) {
+    const canvas = document.querySelector(UTF8ToString(selector));
+    if (!canvas || !canvas.getContext) {
+      return 0;
+    }
+    canvas.width = width;
+    canvas.height = height;
+    // Keeps touches from scrolling or zooming the page
+    canvas.style.touchAction = 'none';
+    const state = { ctx: canvas.getContext('2d'), x: 0, y: 0, pressed: false };
+    const move = (event) => {
+      // The canvas may be scaled by CSS
+      const rect = canvas.getBoundingClientRect();
+      state.x = Math.round((event.clientX - rect.left) * canvas.width / rect.width);
+      state.y = Math.round((event.clientY - rect.top) * canvas.height / rect.height);
+    };
+    canvas.addEventListener('pointerdown', (event) => {
+      canvas.setPointerCapture(event.pointerId);
+      move(event);
+      state.pressed = true;
+    });
+    canvas.addEventListener('pointermove', move);
+    const release = (event) => {
+      move(event);
+      state.pressed = false;
+    };
+    canvas.addEventListener('pointerup', release);
+    canvas.addEventListener('pointercancel', release);
+    Module.lvglCanvas = state;
+    return 1;
+  },
+
+  lvgl_canvas_blit: function (rgba, x, y, width, height) {
+    const pixels = new Uint8ClampedArray(HEAPU8.buffer, rgba, width * height * 4);
+    Module.lvglCanvas.ctx.putImageData(new ImageData(pixels, width, height), x, y);
+  },
+
+  lvgl_canvas_pointer: function (x, y) {
+    const state = Module.lvglCanvas;
+    HEAP32[x >> 2] = state.x;
+    HEAP32[y >> 2] = state.y;
+    return state.pressed ? 1 : 0;
+  },
+});